@@ -20,13 +20,22 @@ if let currentSSID = interface.ssid() {
 do {
     let networks = try interface.scanForNetworks(withSSID: nil)
 
-    // Output in a parseable format: SSID|BSSID|CHANNEL|RSSI|SECURITY
+    // Output in a parseable format: SSID|BSSID|CHANNEL|RSSI|SECURITY|BAND
     for network in networks {
         let ssid = network.ssid ?? "<Hidden>"
         let bssid = network.bssid ?? ""
         let channel = network.wlanChannel?.channelNumber ?? 0
         let rssi = network.rssiValue
 
+        // 5 GHz and 6 GHz reuse channel numbers, so the band can't be derived from the channel
+        var band = ""
+        switch network.wlanChannel?.channelBand {
+        case .band2GHz?: band = "2.4GHz"
+        case .band5GHz?: band = "5GHz"
+        case .band6GHz?: band = "6GHz"
+        default: break
+        }
+
         // Determine security
         var security = "Unknown"
         if network.supportsSecurity(.none) || network.supportsSecurity(.dynamicWEP) == false && network.supportsSecurity(.enterprise) == false && network.supportsSecurity(.personal) == false {
@@ -40,14 +49,28 @@ do {
         if network.supportsSecurity(.wpaPersonal) || network.supportsSecurity(.wpaEnterprise) {
             security = "WPA"
         }
-        if network.supportsSecurity(.wpa2Personal) || network.supportsSecurity(.wpa2Enterprise) {
+        let wpa2 = network.supportsSecurity(.wpa2Personal) || network.supportsSecurity(.wpa2Enterprise)
+        let wpa3 = network.supportsSecurity(.wpa3Personal) || network.supportsSecurity(.wpa3Enterprise)
+        if wpa2 {
             security = "WPA2"
         }
-        if network.supportsSecurity(.wpa3Personal) || network.supportsSecurity(.wpa3Enterprise) || network.supportsSecurity(.wpa3Transition) {
+        // Transition mode accepts WPA2 and WPA3 clients; only WPA3-only networks are plain "WPA3"
+        if network.supportsSecurity(.wpa3Transition) || (wpa2 && wpa3) {
+            security = "WPA2/WPA3"
+        } else if wpa3 {
             security = "WPA3"
         }
+        // 802.1X networks need per-user credentials, so report them apart from personal ones
+        let enterprise = network.supportsSecurity(.enterprise) || network.supportsSecurity(.wpaEnterprise) || network.supportsSecurity(.wpa2Enterprise) || network.supportsSecurity(.wpa3Enterprise)
+        let personal = network.supportsSecurity(.personal) || network.supportsSecurity(.wpaPersonal) || network.supportsSecurity(.wpa2Personal) || network.supportsSecurity(.wpa3Personal) || network.supportsSecurity(.wpa3Transition)
+        if enterprise && !personal {
+            security = "Enterprise"
+        }
+        if network.supportsSecurity(.OWE) || network.supportsSecurity(.OWETransition) {
+            security = "OWE"
+        }
 
-        print("\(ssid)|\(bssid)|\(channel)|\(rssi)|\(security)")
+        print("\(ssid)|\(bssid)|\(channel)|\(rssi)|\(security)|\(band)")
     }
 } catch {
     fputs("Error scanning: \(error.localizedDescription)\n", stderr)
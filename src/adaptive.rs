@@ -0,0 +1,138 @@
+//! Adaptive auto-refresh: scan more often while the surroundings change
+//! and back off while they stay put.
+
+use crate::scanner::Network;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// A signal moving by more than this between scans counts as a change
+pub const SIGNAL_CHANGE_DB: i32 = 5;
+
+/// Share of networks (percent) that must change for the interval to halve
+const BUSY_PERCENT: usize = 20;
+
+/// At or below this share (percent) of changed networks the interval grows
+const STABLE_PERCENT: usize = 5;
+
+/// Networks that appeared, disappeared or moved more than `SIGNAL_CHANGE_DB`
+/// since the previous scan, out of every network seen in either scan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ScanChange {
+    pub changed: usize,
+    pub total: usize,
+}
+
+impl ScanChange {
+    /// Compare a scan with the previous one's signal by BSSID
+    pub fn between(previous: &HashMap<String, i32>, current: &[Network]) -> Self {
+        let mut changed = 0;
+        let mut still_seen = 0;
+        for network in current {
+            match previous.get(&network.mac) {
+                Some(&dbm) => {
+                    still_seen += 1;
+                    if (network.signal_dbm - dbm).abs() > SIGNAL_CHANGE_DB {
+                        changed += 1;
+                    }
+                }
+                None => changed += 1,
+            }
+        }
+        let disappeared = previous.len().saturating_sub(still_seen);
+        ScanChange {
+            changed: changed + disappeared,
+            total: current.len() + disappeared,
+        }
+    }
+}
+
+/// Bounds the adaptive interval moves within
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdaptiveInterval {
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl AdaptiveInterval {
+    pub fn new(min: Duration, max: Duration) -> Self {
+        Self {
+            min: min.min(max),
+            max: max.max(min),
+        }
+    }
+
+    /// Halve the interval after a busy scan, stretch it by half after a
+    /// stable one and leave it alone in between
+    pub fn next(&self, current: Duration, change: ScanChange) -> Duration {
+        let next = if change.total == 0 {
+            current
+        } else if change.changed * 100 >= change.total * BUSY_PERCENT {
+            current / 2
+        } else if change.changed * 100 <= change.total * STABLE_PERCENT {
+            current * 3 / 2
+        } else {
+            current
+        };
+        next.clamp(self.min, self.max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn network(mac: &str, signal_dbm: i32) -> Network {
+        Network { mac: mac.to_string(), ..Network::test("Cafe", 6, signal_dbm) }
+    }
+
+    fn previous() -> HashMap<String, i32> {
+        (0..10).map(|i| (format!("AA:BB:CC:00:00:{:02X}", i), -60)).collect()
+    }
+
+    #[test]
+    fn test_change_metric() {
+        let mut current: Vec<Network> = (0..10)
+            .map(|i| network(&format!("AA:BB:CC:00:00:{:02X}", i), -60))
+            .collect();
+        assert_eq!(ScanChange::between(&previous(), &current), ScanChange { changed: 0, total: 10 });
+
+        // Small moves don't count, one big move, one new AP and one gone do
+        current[0].signal_dbm = -63;
+        current[1].signal_dbm = -70;
+        current[2] = network("AA:BB:CC:00:00:FF", -50);
+        assert_eq!(ScanChange::between(&previous(), &current), ScanChange { changed: 3, total: 11 });
+    }
+
+    #[test]
+    fn test_large_changes_shrink_the_interval() {
+        let adaptive = AdaptiveInterval::new(Duration::from_secs(5), Duration::from_secs(120));
+        let current: Vec<Network> = (0..10)
+            .map(|i| network(&format!("AA:BB:CC:00:00:{:02X}", i), if i < 5 { -80 } else { -60 }))
+            .collect();
+        let change = ScanChange::between(&previous(), &current);
+        assert_eq!(adaptive.next(Duration::from_secs(30), change), Duration::from_secs(15));
+        // Never below the minimum
+        assert_eq!(adaptive.next(Duration::from_secs(8), change), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_stability_grows_the_interval() {
+        let adaptive = AdaptiveInterval::new(Duration::from_secs(5), Duration::from_secs(120));
+        let current: Vec<Network> = (0..10)
+            .map(|i| network(&format!("AA:BB:CC:00:00:{:02X}", i), -62))
+            .collect();
+        let change = ScanChange::between(&previous(), &current);
+        assert_eq!(adaptive.next(Duration::from_secs(30), change), Duration::from_secs(45));
+        // Never above the maximum
+        assert_eq!(adaptive.next(Duration::from_secs(100), change), Duration::from_secs(120));
+        // A moderate change keeps the interval
+        let moderate = ScanChange { changed: 1, total: 10 };
+        assert_eq!(adaptive.next(Duration::from_secs(30), moderate), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_bounds_are_ordered() {
+        let adaptive = AdaptiveInterval::new(Duration::from_secs(60), Duration::from_secs(10));
+        assert_eq!((adaptive.min, adaptive.max), (Duration::from_secs(10), Duration::from_secs(60)));
+    }
+}
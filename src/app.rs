@@ -1,22 +1,106 @@
-use crate::components::{Component, DetailPanel, DeviceDetail, DeviceTable, NetworkTable, SignalChart, StatusBar};
-use crate::connection::{connect_to_network, get_current_connection, import_known_networks};
-use crate::db::{ConnectionRecord, Database, ScanResultRecord};
-use crate::ip::get_all_ips;
-use crate::scanner::{get_scan_detected_connection, scan_networks, FrequencyBand, Network, SecurityType};
-use crate::scoring::calculate_all_scores;
-use crate::speedtest::{run_speed_test, SpeedTestResult};
-use chrono::Utc;
+use crate::adaptive::{AdaptiveInterval, ScanChange};
+use crate::captive::{detect_captive_portal, CaptiveStatus};
+use crate::components::{
+    ChannelChart, Component, DetailPanel, DeviceDetail, DeviceTable, HistorySummary, KnownNetworksTable, NetworkTable,
+    SignalChart, SignalMeter, SpeedChart, StatusBar,
+};
+use crate::connection::{
+    connect_to_network, get_current_connection, import_known_networks, is_interface_connected, reimport_known_networks,
+    remove_preferred_network, DEFAULT_INTERFACE,
+};
+use crate::config::Config;
+use crate::db::{ConnectionRecord, Database, KnownNetwork, NetworkSummary, RoamEvent, ScanResultRecord, SuccessRate};
+use crate::ip::{get_all_ips, IpAddresses};
+use crate::keymap::{KeyAction, KeyView, Keymap};
+use crate::logger::ScanLogger;
+use crate::mac::{format_mac, MacFormat};
+use crate::scanner::{
+    dbm_to_percent, enable_demo_mode, find_ssid_conflicts, get_scan_detected_connection, parse_current_channel,
+    scan_networks, strongest_on_channel, FrequencyBand, Network, ScanError, SecurityType, SsidConflict, HIDDEN_SSID,
+};
+use crate::scoring::{
+    calculate_all_scores_weighted, score_breakdown_weighted, signal_std_dev, stability_penalty, ScoreBreakdown,
+    ScoringProfile, STABILITY_WINDOW,
+};
+use crate::speedtest::{format_speed, run_speed_test, SpeedTestResult, SpeedUnits};
+use chrono::{DateTime, Utc};
 use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ipnetwork::IpNetwork;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::Frame;
-use std::collections::{HashMap, VecDeque};
+use std::cell::Cell;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
 static SCANNED_DEVICES: Mutex<Option<Vec<crate::network_map::Device>>> = Mutex::new(None);
 
+/// Log entries from background threads, picked up by `poll_background_log`
+static BACKGROUND_LOG: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Lines kept in the in-app diagnostics log
+pub const LOG_CAPACITY: usize = 200;
+
 const SIGNAL_HISTORY_SIZE: usize = 30;
 
+/// Number of past speed tests shown in the speed history chart
+const SPEED_HISTORY_SIZE: usize = 20;
+
+/// One past speed test: when it ran, then download and upload in Mbps
+pub type SpeedSample = (DateTime<Utc>, f64, f64);
+
+/// How often device liveness is re-checked while the devices view is open
+const LIVENESS_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often the ARP cache is sampled for device activity
+const ARP_POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// An ARP cache snapshot as (IP, MAC) pairs, and when it was taken
+pub type ArpSnapshot = (Vec<(String, String)>, DateTime<Utc>);
+
+/// Nearby-only threshold used when `n` is pressed without `--min-signal`
+pub const DEFAULT_MIN_SIGNAL_DBM: i32 = -75;
+
+/// Default time between automatic device scans in the devices view
+pub const DEFAULT_DEVICE_SCAN_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// How long a fetched public IP is reused before asking the lookup service again
+const PUBLIC_IP_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Minimum time between speed tests
+const SPEEDTEST_MIN_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How far back roam events are kept and counted
+const ROAM_WINDOW: chrono::TimeDelta = chrono::TimeDelta::hours(1);
+
+/// Time between scans while the signal meter is open (scans themselves
+/// take a few seconds, so this means back to back)
+const METER_SCAN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Readings kept for the signal meter's trend line
+const METER_SAMPLES: usize = 120;
+
+/// Rows kept by the top-N table limit unless --max-display says otherwise
+pub const DEFAULT_MAX_DISPLAY: usize = 20;
+
+/// Smallest terminal (columns, rows) the normal layout is drawn in
+pub const MIN_TERMINAL_SIZE: (u16, u16) = (60, 20);
+
+/// PageUp/PageDown step until a table has been rendered
+const DEFAULT_PAGE_SIZE: usize = 10;
+
+/// Auto-refresh intervals selectable with +/- (seconds)
+const INTERVAL_STEPS: [u64; 7] = [5, 10, 15, 30, 60, 120, 300];
+
+/// Keep an auto-refresh interval within the selectable range
+pub fn clamp_interval(secs: u64) -> u64 {
+    secs.clamp(INTERVAL_STEPS[0], INTERVAL_STEPS[INTERVAL_STEPS.len() - 1])
+}
+
 fn parse_device_type(s: &str) -> crate::network_map::DeviceType {
     match s {
         "Router" => crate::network_map::DeviceType::Router,
@@ -46,34 +130,331 @@ pub enum SortField {
     Name,
 }
 
+impl SortField {
+    /// Best score or strongest signal first, names A to Z
+    pub fn sort(&self, networks: &mut [Network]) {
+        match self {
+            SortField::Score => networks.sort_by_key(|n| Reverse(n.score)),
+            SortField::Signal => networks.sort_by_key(|n| Reverse(n.signal_dbm)),
+            SortField::Name => networks.sort_by(|a, b| a.ssid.cmp(&b.ssid)),
+        }
+    }
+}
+
+/// Sort order of the device table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeviceSortField {
+    #[default]
+    LastSeen,
+    Ip,
+    Name,
+    Vendor,
+    Type,
+}
+
+impl DeviceSortField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DeviceSortField::LastSeen => "Last seen",
+            DeviceSortField::Ip => "IP",
+            DeviceSortField::Name => "Name",
+            DeviceSortField::Vendor => "Vendor",
+            DeviceSortField::Type => "Type",
+        }
+    }
+}
+
+/// Device text edited in the rename dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeviceField {
+    #[default]
+    Name,
+    Notes,
+    Tags,
+}
+
+impl DeviceField {
+    pub fn title(&self) -> &'static str {
+        match self {
+            DeviceField::Name => " Rename Device ",
+            DeviceField::Notes => " Device Notes ",
+            DeviceField::Tags => " Device Tags ",
+        }
+    }
+
+    pub fn prompt(&self) -> &'static str {
+        match self {
+            DeviceField::Name => "Enter a custom name for this device:",
+            DeviceField::Notes => "Notes for this device (empty to remove):",
+            DeviceField::Tags => "Tags, e.g. #trusted #kids (empty to remove):",
+        }
+    }
+
+    fn max_len(&self) -> usize {
+        match self {
+            DeviceField::Name => 32,
+            DeviceField::Notes => 120,
+            DeviceField::Tags => 64,
+        }
+    }
+}
+
+/// Which networks the table shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NetworkFilter {
+    #[default]
+    All,
+    OpenOnly,
+    SecuredOnly,
+    KnownOnly,
+}
+
+impl NetworkFilter {
+    pub fn label(&self) -> &'static str {
+        match self {
+            NetworkFilter::All => "All",
+            NetworkFilter::OpenOnly => "Open only",
+            NetworkFilter::SecuredOnly => "Secured only",
+            NetworkFilter::KnownOnly => "Known only",
+        }
+    }
+}
+
+/// Which frequency band the networks table shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BandFilter {
+    #[default]
+    All,
+    Band2_4,
+    Band5,
+    Band6,
+}
+
+impl BandFilter {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BandFilter::All => "All",
+            BandFilter::Band2_4 => "2.4 GHz",
+            BandFilter::Band5 => "5 GHz",
+            BandFilter::Band6 => "6 GHz",
+        }
+    }
+
+    pub fn matches(&self, band: FrequencyBand) -> bool {
+        match self {
+            BandFilter::All => true,
+            BandFilter::Band2_4 => band == FrequencyBand::Band2_4GHz,
+            BandFilter::Band5 => band == FrequencyBand::Band5GHz,
+            BandFilter::Band6 => band == FrequencyBand::Band6GHz,
+        }
+    }
+}
+
+/// How many networks the last scan saw on each band
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BandCounts {
+    pub band_2_4: usize,
+    pub band_5: usize,
+    pub band_6: usize,
+}
+
+impl BandCounts {
+    /// e.g. "2.4GHz: 18 | 5GHz: 22 | 6GHz: 3"
+    pub fn summary(&self) -> String {
+        format!("2.4GHz: {} | 5GHz: {} | 6GHz: {}", self.band_2_4, self.band_5, self.band_6)
+    }
+}
+
+/// How signal strength is shown in the table and detail panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SignalUnits {
+    Dbm,
+    Percent,
+    #[default]
+    Both,
+}
+
+impl SignalUnits {
+    pub const ALL: [SignalUnits; 3] = [SignalUnits::Dbm, SignalUnits::Percent, SignalUnits::Both];
+
+    /// Name accepted by --signal-units
+    pub fn name(&self) -> &'static str {
+        match self {
+            SignalUnits::Dbm => "dbm",
+            SignalUnits::Percent => "percent",
+            SignalUnits::Both => "both",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|units| units.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Full label, e.g. "-62 dBm (46%)"
+    pub fn format(&self, dbm: i32) -> String {
+        match self {
+            SignalUnits::Dbm => format!("{} dBm", dbm),
+            SignalUnits::Percent => format!("{}%", dbm_to_percent(dbm)),
+            SignalUnits::Both => format!("{} dBm ({}%)", dbm, dbm_to_percent(dbm)),
+        }
+    }
+
+    /// Compact label for table cells, e.g. "-62 46%"
+    pub fn format_short(&self, dbm: i32) -> String {
+        match self {
+            SignalUnits::Dbm => dbm.to_string(),
+            SignalUnits::Percent => format!("{}%", dbm_to_percent(dbm)),
+            SignalUnits::Both => format!("{} {}%", dbm, dbm_to_percent(dbm)),
+        }
+    }
+}
+
+/// Live readings of one network for the signal meter (site surveys)
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeterState {
+    pub bssid: String,
+    pub ssid: String,
+    /// Latest readings, oldest first (at most `METER_SAMPLES`)
+    pub samples: VecDeque<i32>,
+    /// Weakest and strongest reading since the meter was opened
+    pub min: i32,
+    pub max: i32,
+    sum: i64,
+    count: usize,
+    /// The last scan didn't see the network (out of range)
+    pub missed: bool,
+}
+
+impl MeterState {
+    fn new(network: &Network) -> Self {
+        let mut meter = Self {
+            bssid: network.mac.clone(),
+            ssid: network.ssid.clone(),
+            samples: VecDeque::new(),
+            min: network.signal_dbm,
+            max: network.signal_dbm,
+            sum: 0,
+            count: 0,
+            missed: false,
+        };
+        meter.record(network.signal_dbm);
+        meter
+    }
+
+    fn record(&mut self, dbm: i32) {
+        self.samples.push_back(dbm);
+        while self.samples.len() > METER_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.min = self.min.min(dbm);
+        self.max = self.max.max(dbm);
+        self.sum += i64::from(dbm);
+        self.count += 1;
+        self.missed = false;
+    }
+
+    /// Most recent reading
+    pub fn current(&self) -> i32 {
+        self.samples.back().copied().unwrap_or(self.min)
+    }
+
+    /// Mean of every reading since the meter was opened
+    pub fn average(&self) -> f64 {
+        self.sum as f64 / self.count.max(1) as f64
+    }
+
+    /// Number of readings since the meter was opened
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+/// Work a key press hands back to the event loop instead of doing itself:
+/// background jobs, and blocking calls the loop renders before running
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppAction {
+    /// Start a background WiFi scan
+    Scan,
+    /// Switch to simulated networks and rescan
+    EnableDemo,
+    /// Connect to the selected network (`do_connect`, blocks)
+    Connect,
+    /// Run a speed test on the connected network (`confirm_speedtest`, blocks)
+    SpeedTest,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum AppView {
     #[default]
     WifiNetworks,
     NetworkDevices,
+    KnownNetworks,
 }
 
 pub struct App {
     pub networks: Vec<Network>,
     pub selected_index: usize,
+    /// Rows the last rendered table had room for: the PageUp/PageDown step
+    pub page_size: Cell<usize>,
     /// Signal history keyed by BSSID (MAC address)
     pub signal_history: HashMap<String, VecDeque<i32>>,
+    /// BSSIDs whose signal history was already backfilled from the database
+    pub signal_history_backfilled: HashSet<String>,
+    /// Signal of each BSSID in the latest scan that saw it and in the one
+    /// before, this session only (`signal_history` also holds stored samples)
+    pub scan_signals: HashMap<String, (i32, Option<i32>)>,
     pub scan_mode: ScanMode,
     pub auto_interval: Duration,
+    /// Retune `auto_interval` after every scan from how much changed (--adaptive)
+    pub adaptive_interval: Option<AdaptiveInterval>,
+    /// Signal per BSSID in the previous scan, for the adaptive change metric
+    pub previous_scan_signals: HashMap<String, i32>,
     pub last_scan: Instant,
+    /// How long the last successful scan took (None until one finishes)
+    pub last_scan_duration: Option<Duration>,
+    /// When the scan in flight started
+    scan_started: Option<Instant>,
     pub is_scanning: bool,
     pub sort_by: SortField,
+    /// Keep the connected network first in the table whatever the sort order
+    pub pin_connected: bool,
     pub should_quit: bool,
     pub show_help: bool,
+    /// Help overlay scroll offset, in lines from the top
+    pub help_scroll: usize,
+    /// Lines the help overlay showed on the last frame, for clamping its scroll
+    help_page_size: Cell<usize>,
+    /// Show the channel usage chart in place of the detail panel
+    pub show_channel_chart: bool,
+    /// Signal meter replacing the WiFi view while open
+    pub meter: Option<MeterState>,
+    /// Show historical averages at this location in place of the detail panel
+    pub show_history_summary: bool,
+    /// Historical summaries at the current location, keyed by uppercase BSSID
+    pub network_summaries: HashMap<String, NetworkSummary>,
     pub error_message: Option<String>,
+    /// What to do about the error in `error_message`, when it came from a scan
+    pub error_advice: Option<&'static str>,
+    /// Timestamped diagnostics (errors and warnings), oldest first, capped at `LOG_CAPACITY`
+    pub log: VecDeque<String>,
+    /// Show the diagnostics log overlay
+    pub show_log: bool,
+    /// Log overlay scroll offset, in lines up from the newest entry
+    pub log_scroll: usize,
     /// Database connection (None if persistence disabled)
     pub db: Option<Database>,
+    /// Wireless interface used for connection detection, connect and IP lookup
+    pub interface: String,
     /// Current location ID for persistence
     pub current_location_id: Option<i64>,
     /// Current location name for display
     pub current_location_name: Option<String>,
+    /// How networks are ranked here; a location's saved profile replaces it
+    pub scoring_profile: ScoringProfile,
     /// BSSID of currently connected network (None if not connected)
     pub connected_bssid: Option<String>,
+    /// `connected_bssid` was read from the WiFi hardware, not guessed
+    pub connected_bssid_verified: bool,
     /// SSID of currently connected network
     pub connected_ssid: Option<String>,
     /// Show connection confirmation popup
@@ -88,10 +469,24 @@ pub struct App {
     pub cached_speed_test: Option<(String, SpeedTestResult)>,
     /// Cached recent IPs for selected network
     pub cached_recent_ips: Option<(String, Vec<String>)>,
+    /// Cached speed test history (timestamp, down, up) for selected network, oldest first
+    pub cached_speed_history: Option<(String, Vec<SpeedSample>)>,
+    /// Selection (index, BSSID) the caches above were last loaded for
+    cached_selection: Option<(usize, Option<String>)>,
+    /// Latest captive portal check per network, keyed by uppercase BSSID
+    pub captive_portals: HashMap<String, CaptiveStatus>,
     /// Current local IP address (for connected network)
     pub current_local_ip: Option<String>,
     /// Current public IP address (for connected network)
     pub current_public_ip: Option<String>,
+    /// Current local IPv6 address (None on hosts without IPv6)
+    pub current_local_ipv6: Option<String>,
+    /// Current public IPv6 address (None on hosts without IPv6)
+    pub current_public_ipv6: Option<String>,
+    /// When the public IPs were last fetched (rate limits the lookup service)
+    pub last_public_ip_fetch: Option<Instant>,
+    /// When the last speed test was started (rate limits speed tests)
+    pub last_speedtest: Option<Instant>,
     /// Speed test running in background (network MAC, start time)
     pub speedtest_running: Option<(String, Instant)>,
     /// Channel to receive speed test result
@@ -102,16 +497,98 @@ pub struct App {
     pub devices: Vec<crate::network_map::Device>,
     /// Selected device index
     pub selected_device_index: usize,
+    pub device_sort_by: DeviceSortField,
+    /// Show only devices running a detected AI agent
+    pub agent_filter: bool,
     /// Device scan in progress
     pub device_scan_progress: Option<crate::network_map::ScanProgress>,
     /// Channel to receive device scan progress
     pub device_scan_receiver: Option<std::sync::mpsc::Receiver<crate::network_map::ScanProgress>>,
+    /// Set to stop the running network device scan early
+    device_scan_cancel: Arc<AtomicBool>,
+    /// Devices (uppercase MAC) not in the database before the last device scan
+    pub new_device_macs: HashSet<String>,
+    /// MAC of the device being deep scanned (None for a full network scan)
+    pub deep_scan_target: Option<String>,
+    /// Gateway IP to select once the device scan started by `show_gateway` finishes
+    pub pending_gateway_ip: Option<String>,
+    /// Channel to receive results from a background WiFi scan
+    pub scan_receiver: Option<std::sync::mpsc::Receiver<Result<Vec<Network>, ScanError>>>,
+    /// Channel to receive refreshed devices from a liveness check
+    pub liveness_receiver: Option<std::sync::mpsc::Receiver<Vec<crate::network_map::Device>>>,
+    /// When device liveness was last checked
+    pub last_liveness_check: Option<Instant>,
+    /// When each device's ARP entry last refreshed
+    pub arp_activity: crate::network_map::ArpActivity,
+    /// Channel to receive an ARP cache snapshot and when it was taken
+    pub arp_poll_receiver: Option<std::sync::mpsc::Receiver<ArpSnapshot>>,
+    pub last_arp_poll: Option<Instant>,
+    /// Our own liveness pings or device scan ran since the last ARP
+    /// snapshot, so entries it refreshed aren't the devices' doing
+    pub arp_probed: bool,
+    /// Rescan devices on a timer while the devices view is open
+    pub device_auto_scan: bool,
+    pub device_scan_interval: Duration,
+    /// When the last device scan started or finished
+    pub last_device_scan: Option<Instant>,
+    /// Subnet to discover devices in instead of the /24 around the local IP
+    pub target_subnet: Option<IpNetwork>,
+    /// TCP ports probed on each device (`COMMON_PORTS` unless --ports/--ports-file)
+    pub scan_ports: Vec<u16>,
     /// Show device detail panel
     pub show_device_detail: bool,
     /// Show rename dialog
     pub show_rename_dialog: bool,
     /// Rename dialog input buffer
     pub rename_input: String,
+    /// What the rename dialog edits: the name, notes or tags
+    pub rename_field: DeviceField,
+    /// Show the network note dialog
+    pub show_note_dialog: bool,
+    /// Note dialog input buffer
+    pub note_input: String,
+    /// Notes attached to networks, keyed by uppercase BSSID
+    pub network_notes: HashMap<String, String>,
+    /// Show network search input
+    pub show_filter_input: bool,
+    /// Case-insensitive SSID filter for the networks table
+    pub filter_query: String,
+    /// Security/known filter for the networks table
+    pub network_filter: NetworkFilter,
+    /// Frequency band filter for the networks table
+    pub band_filter: BandFilter,
+    /// Hide networks weaker than this from the table (they still count toward congestion)
+    pub min_signal_dbm: Option<i32>,
+    /// Threshold restored when the nearby-only filter is toggled back on
+    pub min_signal_threshold: i32,
+    /// Show only the first `max_display` rows of the sorted table (scoring still sees every network)
+    pub limit_display: bool,
+    pub max_display: usize,
+    pub signal_units: SignalUnits,
+    /// How speed test results are shown (always stored in Mbps)
+    pub speed_units: SpeedUnits,
+    /// How BSSIDs and device MACs are displayed (storage stays canonical)
+    pub mac_format: MacFormat,
+    /// Optional CSV log that every scan is appended to
+    pub scan_logger: Option<ScanLogger>,
+    /// Known networks (loaded from the database when the view is opened)
+    pub known_networks: Vec<KnownNetwork>,
+    /// SSIDs of `known_networks`, for the per-row known check while filtering
+    known_ssids: HashSet<String>,
+    /// Selected known network index
+    pub selected_known_index: usize,
+    /// Show forget network confirmation popup
+    pub show_forget_popup: bool,
+    /// Only report what connecting would do, without changing networks
+    pub dry_run: bool,
+    /// Up at the first row jumps to the last and down at the last to the first
+    pub wrap_navigation: bool,
+    /// Config file the auto-refresh interval is saved to when changed
+    pub config_path: Option<PathBuf>,
+    /// Key bindings from the `[keys]` config section
+    pub keymap: Keymap,
+    /// Roam events within `ROAM_WINDOW`, oldest first
+    pub roam_events: Vec<RoamEvent>,
 }
 
 impl App {
@@ -119,50 +596,215 @@ impl App {
         Self {
             networks: Vec::new(),
             selected_index: 0,
+            page_size: Cell::new(DEFAULT_PAGE_SIZE),
             signal_history: HashMap::new(),
+            signal_history_backfilled: HashSet::new(),
+            scan_signals: HashMap::new(),
             scan_mode: if start_auto {
                 ScanMode::Auto
             } else {
                 ScanMode::Manual
             },
             auto_interval,
+            adaptive_interval: None,
+            previous_scan_signals: HashMap::new(),
             last_scan: Instant::now() - auto_interval, // Trigger immediate scan
+            last_scan_duration: None,
+            scan_started: None,
             is_scanning: false,
             sort_by: SortField::Score,
+            pin_connected: false,
             should_quit: false,
             show_help: false,
+            help_scroll: 0,
+            help_page_size: Cell::new(0),
+            show_channel_chart: false,
+            meter: None,
+            show_history_summary: false,
+            network_summaries: HashMap::new(),
             error_message: None,
+            error_advice: None,
+            log: VecDeque::new(),
+            show_log: false,
+            log_scroll: 0,
             db: None,
+            interface: DEFAULT_INTERFACE.to_string(),
             current_location_id: None,
             current_location_name: None,
+            scoring_profile: ScoringProfile::default(),
             connected_bssid: None,
+            connected_bssid_verified: false,
             connected_ssid: None,
             show_connect_popup: false,
             show_speedtest_popup: false,
             status_message: None,
             cached_connection_history: None,
             cached_speed_test: None,
+            cached_speed_history: None,
+            cached_selection: None,
             cached_recent_ips: None,
+            captive_portals: HashMap::new(),
             current_local_ip: None,
             current_public_ip: None,
+            current_local_ipv6: None,
+            current_public_ipv6: None,
+            last_public_ip_fetch: None,
+            last_speedtest: None,
             speedtest_running: None,
             speedtest_receiver: None,
             current_view: AppView::default(),
             devices: Vec::new(),
             selected_device_index: 0,
+            agent_filter: false,
+            device_sort_by: DeviceSortField::default(),
             device_scan_progress: None,
             device_scan_receiver: None,
+            device_scan_cancel: Arc::new(AtomicBool::new(false)),
+            new_device_macs: HashSet::new(),
+            deep_scan_target: None,
+            scan_receiver: None,
+            liveness_receiver: None,
+            last_liveness_check: None,
+            arp_activity: crate::network_map::ArpActivity::default(),
+            arp_poll_receiver: None,
+            last_arp_poll: None,
+            arp_probed: false,
+            device_auto_scan: false,
+            device_scan_interval: DEFAULT_DEVICE_SCAN_INTERVAL,
+            last_device_scan: None,
+            target_subnet: None,
+            scan_ports: crate::network_map::COMMON_PORTS.to_vec(),
             show_device_detail: false,
+            pending_gateway_ip: None,
             show_rename_dialog: false,
             rename_input: String::new(),
+            rename_field: DeviceField::default(),
+            show_note_dialog: false,
+            note_input: String::new(),
+            network_notes: HashMap::new(),
+            show_filter_input: false,
+            filter_query: String::new(),
+            network_filter: NetworkFilter::default(),
+            band_filter: BandFilter::default(),
+            min_signal_dbm: None,
+            min_signal_threshold: DEFAULT_MIN_SIGNAL_DBM,
+            limit_display: false,
+            max_display: DEFAULT_MAX_DISPLAY,
+            signal_units: SignalUnits::default(),
+            speed_units: SpeedUnits::default(),
+            mac_format: MacFormat::default(),
+            scan_logger: None,
+            known_networks: Vec::new(),
+            known_ssids: HashSet::new(),
+            selected_known_index: 0,
+            show_forget_popup: false,
+            dry_run: false,
+            wrap_navigation: false,
+            config_path: None,
+            keymap: Keymap::default(),
+            roam_events: Vec::new(),
         }
     }
 
-    /// Configure the app with database persistence
+    /// Configure the app with database persistence, switching to the
+    /// location's saved scoring profile if it has one
     pub fn with_database(mut self, db: Database, location_id: i64, location_name: String) -> Self {
+        if let Ok(Some(profile)) = db.get_location_profile(location_id) {
+            self.scoring_profile = profile;
+        }
         self.db = Some(db);
         self.current_location_id = Some(location_id);
         self.current_location_name = Some(location_name);
+        self.load_known_networks();
+        self
+    }
+
+    /// Rank networks with this profile's weights (used without a database,
+    /// or at locations that haven't saved a profile)
+    pub fn with_scoring_profile(mut self, profile: ScoringProfile) -> Self {
+        self.scoring_profile = profile;
+        self
+    }
+
+    /// Configure the wireless interface (e.g. a USB adapter instead of en0)
+    pub fn with_interface(mut self, interface: String) -> Self {
+        self.interface = interface;
+        self
+    }
+
+    /// Report connection attempts instead of performing them
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Wrap the selection around at the ends of the network and device tables
+    pub fn with_wrap_navigation(mut self, wrap: bool) -> Self {
+        self.wrap_navigation = wrap;
+        self
+    }
+
+    pub fn with_device_scan_interval(mut self, interval: Duration) -> Self {
+        self.device_scan_interval = interval;
+        self
+    }
+
+    pub fn with_target_subnet(mut self, subnet: IpNetwork) -> Self {
+        self.target_subnet = Some(subnet);
+        self
+    }
+
+    pub fn with_scan_ports(mut self, ports: Vec<u16>) -> Self {
+        self.scan_ports = ports;
+        self
+    }
+
+    /// Start with the nearby-only filter on at this threshold
+    pub fn with_min_signal(mut self, dbm: i32) -> Self {
+        self.min_signal_dbm = Some(dbm);
+        self.min_signal_threshold = dbm;
+        self
+    }
+
+    /// Adjust the auto-refresh interval between `min` and `max` as the surroundings change
+    pub fn with_adaptive_interval(mut self, min: Duration, max: Duration) -> Self {
+        let adaptive = AdaptiveInterval::new(min, max);
+        self.auto_interval = self.auto_interval.clamp(adaptive.min, adaptive.max);
+        self.adaptive_interval = Some(adaptive);
+        self
+    }
+
+    /// Start with the table limited to the top `count` networks
+    pub fn with_max_display(mut self, count: usize) -> Self {
+        self.limit_display = true;
+        self.max_display = count.max(1);
+        self
+    }
+
+    pub fn with_signal_units(mut self, units: SignalUnits) -> Self {
+        self.signal_units = units;
+        self
+    }
+
+    pub fn with_speed_units(mut self, units: SpeedUnits) -> Self {
+        self.speed_units = units;
+        self
+    }
+
+    /// Save interval changes to this config file
+    pub fn with_config_path(mut self, path: PathBuf) -> Self {
+        self.config_path = Some(path);
+        self
+    }
+
+    pub fn with_keymap(mut self, keymap: Keymap) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
+    /// Configure the app to append every scan to a CSV file
+    pub fn with_csv_log(mut self, logger: ScanLogger) -> Self {
+        self.scan_logger = Some(logger);
         self
     }
 
@@ -194,19 +836,100 @@ impl App {
                 }
             }
 
+            // Restore captive portal results from earlier connections
+            for (bssid, status) in db.get_captive_statuses()? {
+                if let Some(status) = CaptiveStatus::from_name(&status) {
+                    self.captive_portals.insert(bssid.to_uppercase(), status);
+                }
+            }
+
+            for (bssid, note) in db.get_network_notes()? {
+                self.network_notes.insert(bssid.to_uppercase(), note);
+            }
+
             self.sort_networks();
         }
         Ok(())
     }
 
+    /// The user's note for a network, if any
+    pub fn network_note(&self, bssid: &str) -> Option<&str> {
+        self.network_notes.get(&bssid.to_uppercase()).map(String::as_str)
+    }
+
+    /// Open the note dialog for the selected network, prefilled with its note
+    pub fn start_edit_note(&mut self) {
+        if let Some(network) = self.selected_network() {
+            self.note_input = self.network_note(&network.mac).unwrap_or_default().to_string();
+            self.show_note_dialog = true;
+        }
+    }
+
+    pub fn cancel_note(&mut self) {
+        self.show_note_dialog = false;
+        self.note_input.clear();
+    }
+
+    /// Save the note for the selected network; an empty note removes it
+    pub fn confirm_note(&mut self) {
+        if let Some(bssid) = self.selected_network().map(|n| n.mac.to_uppercase()) {
+            let note = self.note_input.trim().to_string();
+
+            // Persist to database
+            if let Some(ref db) = self.db {
+                match db.set_network_note(&bssid, &note) {
+                    Ok(true) => {}
+                    Ok(false) => self.log(format!("Note for {} not saved: network not in database", bssid)),
+                    Err(e) => self.log(format!("Failed to save note for {}: {}", bssid, e)),
+                }
+            }
+
+            if note.is_empty() {
+                self.network_notes.remove(&bssid);
+            } else {
+                self.network_notes.insert(bssid, note);
+            }
+        }
+        self.show_note_dialog = false;
+        self.note_input.clear();
+    }
+
+    pub fn note_input_char(&mut self, c: char) {
+        if self.note_input.chars().count() < 80 {
+            self.note_input.push(c);
+        }
+    }
+
+    pub fn note_input_backspace(&mut self) {
+        self.note_input.pop();
+    }
+
+    /// Latest captive portal check for a network, if it has been connected to
+    pub fn captive_status(&self, bssid: &str) -> Option<CaptiveStatus> {
+        self.captive_portals.get(&bssid.to_uppercase()).copied()
+    }
+
+    /// Factors behind a network's score with the location's profile weights,
+    /// before any stability penalty
+    pub fn score_breakdown(&self, network: &Network) -> ScoreBreakdown {
+        let captive = self.captive_status(&network.mac) == Some(CaptiveStatus::CaptivePortal);
+        score_breakdown_weighted(network, &self.networks, captive, &self.scoring_profile.weights())
+    }
+
     /// Initialize connection state on startup (fast - no network calls)
     pub fn init_connection_state(&mut self) -> Result<()> {
+        // Recent roams from earlier runs
+        if let Some(ref db) = self.db {
+            self.roam_events = db.get_roam_events_since(Utc::now() - ROAM_WINDOW)?;
+        }
+
         // Detect current WiFi connection
         self.refresh_current_connection()?;
 
         // Get local IP immediately (fast, no network call)
         if self.connected_ssid.is_some() {
-            self.current_local_ip = crate::ip::get_local_ip().ok();
+            self.current_local_ip = crate::ip::get_interface_ip(&self.interface).ok();
+            self.current_local_ipv6 = crate::ip::get_interface_ipv6(&self.interface).ok();
             // Public IP will be fetched lazily when viewing detail panel
         }
 
@@ -215,12 +938,13 @@ impl App {
             // Only import if we haven't imported before
             let count = db.get_known_networks_count()?;
             if count == 0 {
-                let imported = import_known_networks(db)?;
+                let imported = import_known_networks(db, &self.interface)?;
                 if imported > 0 {
                     self.status_message = Some(format!("Imported {} known networks", imported));
                 }
             }
         }
+        self.load_known_networks();
 
         // Load connection data for the initially selected network
         self.load_selected_network_data();
@@ -228,11 +952,50 @@ impl App {
         Ok(())
     }
 
-    /// Fetch public IP lazily (called when viewing connected network details)
+    /// Fetch public IP lazily (called when viewing connected network details).
+    /// Results (including failures) are reused for `PUBLIC_IP_CACHE_TTL`.
     pub fn fetch_public_ip_if_needed(&mut self) {
-        // Only fetch if connected and we don't have it yet
-        if self.connected_ssid.is_some() && self.current_public_ip.is_none() {
-            self.current_public_ip = crate::ip::get_public_ip();
+        if self.connected_ssid.is_none() || self.public_ip_age().is_some_and(|age| age < PUBLIC_IP_CACHE_TTL) {
+            return;
+        }
+
+        let (v4, v6) = crate::ip::get_public_ips();
+        let (v4, source) = match v4 {
+            Ok((ip, endpoint)) => (Some(ip), Ok(endpoint)),
+            Err(e) => (None, Err(e)),
+        };
+        self.log_public_ip_source(&source);
+        self.current_public_ip = v4;
+        self.current_public_ipv6 = v6;
+        self.last_public_ip_fetch = Some(Instant::now());
+    }
+
+    /// Log which endpoint answered the public IPv4 lookup, or why none did
+    fn log_public_ip_source(&mut self, source: &Result<String>) {
+        match source {
+            Ok(endpoint) => self.log(format!("Public IP from {}", endpoint)),
+            Err(e) => self.log(e.to_string()),
+        }
+    }
+
+    /// Time since the public IPs were last fetched
+    pub fn public_ip_age(&self) -> Option<Duration> {
+        self.last_public_ip_fetch.map(|t| t.elapsed())
+    }
+
+    /// Time left before another speed test is allowed
+    pub fn speedtest_cooldown(&self) -> Option<Duration> {
+        let elapsed = self.last_speedtest?.elapsed();
+        SPEEDTEST_MIN_INTERVAL.checked_sub(elapsed).filter(|d| !d.is_zero())
+    }
+
+    /// Addresses of the current connection
+    fn current_ips(&self) -> IpAddresses {
+        IpAddresses {
+            local_v4: self.current_local_ip.clone(),
+            local_v6: self.current_local_ipv6.clone(),
+            public_v4: self.current_public_ip.clone(),
+            public_v6: self.current_public_ipv6.clone(),
         }
     }
 
@@ -240,16 +1003,14 @@ impl App {
     pub fn refresh_current_connection(&mut self) -> Result<()> {
         // Method 1: Use connection info detected during scan (most reliable on modern macOS)
         if let Some(scan_conn) = get_scan_detected_connection() {
-            self.connected_ssid = Some(scan_conn.ssid);
-            self.connected_bssid = scan_conn.bssid;
+            self.set_connection(scan_conn.ssid, scan_conn.bssid, true);
             return Ok(());
         }
 
         // Method 2: Try system APIs (works on older macOS)
-        match get_current_connection() {
+        match get_current_connection(&self.interface) {
             Ok(Some(conn)) => {
-                self.connected_ssid = Some(conn.ssid);
-                self.connected_bssid = conn.bssid;
+                self.set_connection(conn.ssid, conn.bssid, conn.bssid_verified);
             }
             Ok(None) => {
                 // Couldn't determine SSID via system APIs (macOS privacy restrictions)
@@ -264,34 +1025,69 @@ impl App {
         Ok(())
     }
 
+    /// Update the reported connection, recording a roam when the BSSID changed
+    /// but the SSID did not. Only BSSIDs read from the WiFi hardware count
+    /// (`verified`): a guessed one changing, like the gateway's MAC or the
+    /// strongest network on the channel, isn't a roam.
+    fn set_connection(&mut self, ssid: String, bssid: Option<String>, verified: bool) {
+        if verified
+            && self.connected_bssid_verified
+            && self.connected_ssid.as_deref() == Some(ssid.as_str())
+            && let Some(from) = self.connected_bssid.clone()
+            && let Some(ref to) = bssid
+            && !from.eq_ignore_ascii_case(to)
+        {
+            self.record_roam(RoamEvent {
+                ssid: ssid.clone(),
+                from_bssid: from.to_uppercase(),
+                to_bssid: to.to_uppercase(),
+                roamed_at: Utc::now(),
+            });
+        }
+
+        self.connected_ssid = Some(ssid);
+        self.connected_bssid_verified = verified && bssid.is_some();
+        self.connected_bssid = bssid;
+    }
+
+    fn record_roam(&mut self, event: RoamEvent) {
+        if let Some(ref db) = self.db {
+            let _ = db.insert_roam_event(&event);
+        }
+        self.roam_events.push(event);
+
+        let cutoff = Utc::now() - ROAM_WINDOW;
+        self.roam_events.retain(|e| e.roamed_at >= cutoff);
+    }
+
+    /// Number of roams between BSSIDs of `ssid` in the last hour
+    pub fn roams_in_last_hour(&self, ssid: &str) -> usize {
+        let cutoff = Utc::now() - ROAM_WINDOW;
+        self.roam_events
+            .iter()
+            .filter(|e| e.ssid == ssid && e.roamed_at >= cutoff)
+            .count()
+    }
+
     /// Try to detect connected network using channel from system_profiler
     /// On modern macOS, we may not be able to get SSID directly due to privacy restrictions
     fn detect_connected_by_signal(&mut self) {
         // Check if we have an IP (indicating we're connected to something)
-        if let Ok(output) = std::process::Command::new("ipconfig")
-            .args(["getifaddr", "en0"])
-            .output()
-        {
-            let ip = String::from_utf8_lossy(&output.stdout);
-            if ip.trim().is_empty() {
-                // No IP, not connected
-                self.connected_ssid = None;
-                self.connected_bssid = None;
-                return;
-            }
-        } else {
+        if !is_interface_connected(&self.interface) {
+            // No IP, not connected
+            self.connected_ssid = None;
+            self.connected_bssid = None;
+            self.connected_bssid_verified = false;
             return;
         }
 
         // Method 1: Get current channel from system_profiler and match
-        if let Some(channel) = get_current_channel() {
-            // Find the network on this channel with the strongest signal
-            if let Some(network) = self.networks.iter()
-                .filter(|n| n.channel as u32 == channel)
-                .max_by_key(|n| n.signal_dbm)
-            {
+        if let Some((channel, band)) = get_current_channel() {
+            // Find the network on this channel and band with the strongest signal
+            if let Some(network) = strongest_on_channel(&self.networks, channel, band) {
                 self.connected_ssid = Some(network.ssid.clone());
                 self.connected_bssid = Some(network.mac.clone());
+                self.connected_bssid_verified = false;
                 return;
             }
         }
@@ -303,6 +1099,7 @@ impl App {
         {
             self.connected_ssid = Some(strongest.ssid.clone());
             self.connected_bssid = Some(strongest.mac.clone());
+            self.connected_bssid_verified = false;
         }
     }
 
@@ -339,22 +1136,17 @@ impl App {
         false
     }
 
-    /// Check if a network is known (previously connected)
+    /// Check if a network is known (previously connected), against the
+    /// SSIDs cached by `load_known_networks`
     pub fn is_known_network(&self, ssid: &str) -> bool {
-        if let Some(ref db) = self.db {
-            db.is_known_network(ssid).unwrap_or(false)
-        } else {
-            false
-        }
+        self.known_ssids.contains(ssid)
     }
 
     /// Show the connection confirmation popup (or speed test popup if connected)
     pub fn show_connect_dialog(&mut self) {
-        if self.networks.is_empty() {
+        let Some(network) = self.selected_network() else {
             return;
-        }
-
-        let network = &self.networks[self.selected_index];
+        };
 
         // Check if already connected - offer speed test instead
         if self.is_connected(network) {
@@ -379,11 +1171,15 @@ impl App {
     pub fn confirm_speedtest(&mut self) -> Result<()> {
         self.show_speedtest_popup = false;
 
-        if self.networks.is_empty() {
+        let Some(network) = self.selected_network().cloned() else {
             return Ok(());
-        }
+        };
 
-        let network = self.networks[self.selected_index].clone();
+        if let Some(wait) = self.speedtest_cooldown() {
+            self.status_message = Some(format!("Speed test rate limited, try again in {}s", wait.as_secs() + 1));
+            return Ok(());
+        }
+        self.last_speedtest = Some(Instant::now());
 
         // Create channel for result
         let (tx, rx) = std::sync::mpsc::channel();
@@ -394,8 +1190,11 @@ impl App {
 
         // Spawn background thread for speed test
         std::thread::spawn(move || {
-            if let Ok(result) = run_speed_test() {
-                let _ = tx.send(result);
+            match run_speed_test() {
+                Ok(result) => {
+                    let _ = tx.send(result);
+                }
+                Err(e) => log_background(format!("Speed test error: {}", e)),
             }
         });
 
@@ -417,10 +1216,11 @@ impl App {
                             if let Ok(Some(network_id)) = db.get_network_id_by_bssid(&mac) {
                                 let _ = db.insert_connection(
                                     network_id,
-                                    self.current_local_ip.as_deref(),
-                                    self.current_public_ip.as_deref(),
+                                    &self.current_ips(),
                                     Some(result.download_mbps),
                                     Some(result.upload_mbps),
+                                    result.latency_ms,
+                                    self.captive_status(&mac).map(|c| c.to_string()).as_deref(),
                                 );
                             }
                         }
@@ -428,8 +1228,9 @@ impl App {
                         // Cache and display the result
                         self.cached_speed_test = Some((mac, result.clone()));
                         self.status_message = Some(format!(
-                            "Speed test complete: ↓{:.1} Mbps  ↑{:.1} Mbps",
-                            result.download_mbps, result.upload_mbps
+                            "Speed test complete: ↓{}  ↑{}",
+                            format_speed(result.download_mbps, self.speed_units),
+                            format_speed(result.upload_mbps, self.speed_units)
                         ));
 
                         // Refresh connection history cache
@@ -447,6 +1248,7 @@ impl App {
                 Err(std::sync::mpsc::TryRecvError::Disconnected) => {
                     // Thread died without sending result
                     self.status_message = Some("Speed test failed".to_string());
+                    self.log("Speed test failed");
                     self.speedtest_running = None;
                     self.speedtest_receiver = None;
                 }
@@ -482,16 +1284,37 @@ impl App {
         }
     }
 
+    /// "Last scan: 3s ago (took 1.2s)", once a scan has finished
+    pub fn last_scan_summary(&self) -> Option<String> {
+        let took = self.last_scan_duration?;
+        let secs = self.last_scan.elapsed().as_secs();
+        let ago = if secs < 60 { format!("{}s", secs) } else { format!("{}m", secs / 60) };
+        Some(format!("Last scan: {} ago (took {:.1}s)", ago, took.as_secs_f64()))
+    }
+
     /// Execute the connection (dialog already dismissed by caller)
     pub fn do_connect(&mut self) -> Result<()> {
-        if self.networks.is_empty() {
+        let Some(network) = self.selected_network().cloned() else {
             return Ok(());
-        }
+        };
 
-        let network = self.networks[self.selected_index].clone();
+        if self.dry_run {
+            let outcome = if self.is_known_network(&network.ssid) {
+                "known network, should auto-connect"
+            } else {
+                "new network, may require a password"
+            };
+            self.status_message = Some(format!(
+                "Dry run: would run networksetup -setairportnetwork {} \"{}\" ({})",
+                self.interface, network.ssid, outcome
+            ));
+            return Ok(());
+        }
 
         // Try command-line connection first
-        match connect_to_network(&network.ssid) {
+        let result = connect_to_network(&network.ssid, &self.interface);
+        self.record_connection_attempt(&network, &result);
+        match result {
             Ok(true) => {
                 // Connection verified - refresh state and gather stats
                 self.refresh_current_connection()?;
@@ -516,55 +1339,92 @@ impl App {
         Ok(())
     }
 
+    /// Log a connect attempt and its outcome, so flaky networks show a low success rate
+    fn record_connection_attempt(&mut self, network: &Network, result: &Result<bool>) {
+        let Some(ref db) = self.db else { return };
+        let Ok(Some(network_id)) = db.get_network_id_by_bssid(&network.mac) else { return };
+        let (succeeded, reason) = match result {
+            Ok(true) => (true, None),
+            Ok(false) => (false, Some("command-line connect failed".to_string())),
+            Err(e) => (false, Some(e.to_string())),
+        };
+        if let Err(e) = db.record_connection_attempt(network_id, succeeded, reason.as_deref()) {
+            self.log(format!("Failed to record connection attempt: {}", e));
+        }
+    }
+
     /// Legacy method for compatibility
     pub fn confirm_connect(&mut self) -> Result<()> {
         self.show_connect_popup = false;
         self.do_connect()
     }
 
-    /// Called after successful connection - gather IPs, run speed test, persist
+    /// Called after successful connection - check for a captive portal,
+    /// gather IPs, run speed test, persist
     fn on_connect_success(&mut self, network: &Network) -> Result<()> {
         self.status_message = Some(format!("Connected to {}! Gathering stats...", network.ssid));
 
-        // Get IPs
-        let (local_ip, public_ip) = get_all_ips();
-
-        // Run speed test
-        self.status_message = Some("Running speed test...".to_string());
-        let speed_result = run_speed_test().ok();
+        // Check for a captive portal before measuring anything
+        let captive = detect_captive_portal();
+        self.captive_portals.insert(network.mac.to_uppercase(), captive);
+
+        // Get IPs (both families)
+        let (ips, source) = get_all_ips(&self.interface);
+        self.log_public_ip_source(&source);
+        self.current_local_ip = ips.local_v4.clone();
+        self.current_local_ipv6 = ips.local_v6.clone();
+        self.current_public_ip = ips.public_v4.clone();
+        self.current_public_ipv6 = ips.public_v6.clone();
+        self.last_public_ip_fetch = Some(Instant::now());
+
+        // Run speed test (meaningless until a portal has been signed in to)
+        let speed_result = if captive == CaptiveStatus::Open && self.speedtest_cooldown().is_none() {
+            self.status_message = Some("Running speed test...".to_string());
+            self.last_speedtest = Some(Instant::now());
+            run_speed_test().ok()
+        } else {
+            None
+        };
 
         // Persist connection to database
-        if let Some(ref db) = self.db {
-            if let Some(network_id) = db.get_network_id_by_bssid(&network.mac)? {
-                let (download, upload) = speed_result
-                    .as_ref()
-                    .map(|r| (Some(r.download_mbps), Some(r.upload_mbps)))
-                    .unwrap_or((None, None));
-
-                db.insert_connection(
-                    network_id,
-                    local_ip.as_deref(),
-                    public_ip.as_deref(),
-                    download,
-                    upload,
-                )?;
-
-                // Cache the speed test result
-                if let Some(result) = speed_result {
-                    self.cached_speed_test = Some((network.mac.clone(), result.clone()));
-                    self.status_message = Some(format!(
-                        "Connected! ↓{:.1} Mbps ↑{:.1} Mbps",
-                        result.download_mbps, result.upload_mbps
-                    ));
-                } else {
-                    self.status_message = Some(format!("Connected to {}", network.ssid));
-                }
-            }
-        } else {
-            self.status_message = Some(format!("Connected to {}", network.ssid));
+        if let Some(ref db) = self.db
+            && let Some(network_id) = db.get_network_id_by_bssid(&network.mac)?
+        {
+            let (download, upload, latency) = speed_result
+                .as_ref()
+                .map(|r| (Some(r.download_mbps), Some(r.upload_mbps), r.latency_ms))
+                .unwrap_or((None, None, None));
+
+            db.insert_connection(
+                network_id,
+                &ips,
+                download,
+                upload,
+                latency,
+                Some(&captive.to_string()),
+            )?;
         }
 
-        Ok(())
+        // Cache the speed test result
+        if let Some(result) = speed_result {
+            self.cached_speed_test = Some((network.mac.clone(), result.clone()));
+            self.load_speed_history(&network.mac);
+            self.status_message = Some(format!(
+                "Connected! ↓{} ↑{}",
+                format_speed(result.download_mbps, self.speed_units),
+                format_speed(result.upload_mbps, self.speed_units)
+            ));
+        } else {
+            self.status_message = Some(match captive {
+                CaptiveStatus::CaptivePortal => {
+                    format!("Connected to {} - captive portal, sign in via browser", network.ssid)
+                }
+                CaptiveStatus::NoInternet => format!("Connected to {} - no internet access", network.ssid),
+                CaptiveStatus::Open => format!("Connected to {}", network.ssid),
+            });
+        }
+
+        Ok(())
     }
 
     /// Get connection history for a network (cached)
@@ -599,6 +1459,20 @@ impl App {
         None
     }
 
+    /// Connect attempts to a network and how many succeeded
+    pub fn get_connection_success(&self, bssid: &str) -> Option<SuccessRate> {
+        let db = self.db.as_ref()?;
+        let network_id = db.get_network_id_by_bssid(bssid).ok()??;
+        db.get_success_rate(network_id).ok()?
+    }
+
+    /// Strongest signal ever recorded for a network
+    pub fn get_peak_signal(&self, bssid: &str) -> Option<i32> {
+        let db = self.db.as_ref()?;
+        let network_id = db.get_network_id_by_bssid(bssid).ok()??;
+        db.get_peak_signal(network_id).ok()?
+    }
+
     /// Get recent IPs for a network (cached)
     pub fn get_recent_ips(&mut self, bssid: &str) -> Option<&Vec<String>> {
         // Check cache
@@ -626,11 +1500,12 @@ impl App {
         self.cached_connection_history = None;
         self.cached_speed_test = None;
         self.cached_recent_ips = None;
+        self.cached_speed_history = None;
     }
 
     /// Load connection data for the currently selected network
     pub fn load_selected_network_data(&mut self) {
-        if let Some(network) = self.networks.get(self.selected_index) {
+        if let Some(network) = self.selected_network() {
             let bssid = network.mac.clone();
             let is_connected = self.is_connected(network);
 
@@ -638,6 +1513,10 @@ impl App {
             let _ = self.get_connection_history(&bssid);
             // Load recent IPs
             let _ = self.get_recent_ips(&bssid);
+            // Load past speed test results
+            self.load_speed_history(&bssid);
+            // Fill in older signal samples from previous sessions
+            self.backfill_signal_history(&bssid);
 
             // If viewing the connected network, fetch public IP lazily
             if is_connected {
@@ -646,6 +1525,61 @@ impl App {
         }
     }
 
+    /// Load recent speed test results for a network into the cache
+    pub fn load_speed_history(&mut self, bssid: &str) {
+        let Some(ref db) = self.db else { return };
+        if let Ok(Some(network_id)) = db.get_network_id_by_bssid(bssid)
+            && let Ok(history) = db.get_speed_history(network_id, SPEED_HISTORY_SIZE)
+        {
+            self.cached_speed_history = Some((bssid.to_string(), history));
+        }
+    }
+
+    /// Speed test history for the selected network, oldest first
+    pub fn selected_speed_history(&self) -> &[SpeedSample] {
+        match (&self.cached_speed_history, self.selected_network()) {
+            (Some((bssid, history)), Some(network)) if *bssid == network.mac => history,
+            _ => &[],
+        }
+    }
+
+    /// Standard deviation of the newest signal samples for a BSSID, in dB
+    pub fn signal_stability(&self, bssid: &str) -> Option<f32> {
+        let history = self.signal_history.get(bssid)?;
+        let recent: Vec<i32> = history.iter().rev().take(STABILITY_WINDOW).copied().collect();
+        signal_std_dev(&recent)
+    }
+
+    /// Change in dB since the previous scan that saw a BSSID (None after one scan)
+    pub fn signal_delta(&self, bssid: &str) -> Option<i32> {
+        let (latest, previous) = self.scan_signals.get(bssid)?;
+        Some(latest - (*previous)?)
+    }
+
+    /// Prepend persisted signal samples when the in-memory history is short
+    fn backfill_signal_history(&mut self, bssid: &str) {
+        if self.signal_history_backfilled.contains(bssid) {
+            return;
+        }
+        let Some(ref db) = self.db else { return };
+
+        let history = self.signal_history.entry(bssid.to_string()).or_default();
+        if history.len() < SIGNAL_HISTORY_SIZE {
+            let Ok(stored) = db.get_signal_history(bssid, SIGNAL_HISTORY_SIZE) else {
+                return;
+            };
+            // The newest stored samples are the ones already recorded this session
+            let older = stored.len().saturating_sub(history.len());
+            for &dbm in stored[..older].iter().rev() {
+                history.push_front(dbm);
+            }
+            while history.len() > SIGNAL_HISTORY_SIZE {
+                history.pop_front();
+            }
+        }
+        self.signal_history_backfilled.insert(bssid.to_string());
+    }
+
     /// Set status message
     pub fn set_status(&mut self, msg: String) {
         self.status_message = Some(msg);
@@ -657,29 +1591,318 @@ impl App {
     }
 
     pub fn set_error(&mut self, msg: String) {
+        self.log(&msg);
         self.error_message = Some(msg);
+        self.error_advice = None;
+    }
+
+    /// Show a scan failure along with advice specific to its cause
+    pub fn set_scan_error(&mut self, error: &ScanError) {
+        self.log(error.to_string());
+        self.error_message = Some(error.to_string());
+        self.error_advice = Some(error.advice());
+    }
+
+    /// Add a timestamped entry to the diagnostics log (use instead of
+    /// `eprintln!`, which would draw over the TUI)
+    pub fn log(&mut self, msg: impl AsRef<str>) {
+        self.push_log_entry(timestamped(msg.as_ref()));
+    }
+
+    fn push_log_entry(&mut self, entry: String) {
+        if self.log.len() >= LOG_CAPACITY {
+            self.log.pop_front();
+        }
+        self.log.push_back(entry);
+    }
+
+    /// Move log entries written by background threads into the log
+    pub fn poll_background_log(&mut self) {
+        let entries = std::mem::take(&mut *BACKGROUND_LOG.lock().unwrap());
+        for entry in entries {
+            self.push_log_entry(entry);
+        }
+    }
+
+    pub fn toggle_log(&mut self) {
+        self.show_log = !self.show_log;
+        self.log_scroll = 0;
+    }
+
+    /// Scroll the log overlay towards older entries
+    pub fn log_scroll_up(&mut self) {
+        self.log_scroll = (self.log_scroll + 1).min(self.log.len().saturating_sub(1));
+    }
+
+    /// Scroll the log overlay towards the newest entry
+    pub fn log_scroll_down(&mut self) {
+        self.log_scroll = self.log_scroll.saturating_sub(1);
     }
 
     pub fn clear_error(&mut self) {
         self.error_message = None;
+        self.error_advice = None;
+    }
+
+    /// Number of scanned networks that don't broadcast an SSID
+    pub fn hidden_count(&self) -> usize {
+        self.networks.iter().filter(|n| n.ssid == HIDDEN_SSID).count()
+    }
+
+    /// SSIDs advertised by APs with mismatched security or vendors (possible evil twins)
+    pub fn ssid_conflicts(&self) -> Vec<SsidConflict> {
+        find_ssid_conflicts(&self.networks)
+    }
+
+    /// The conflict a network is part of, if any
+    pub fn ssid_conflict(&self, bssid: &str) -> Option<SsidConflict> {
+        self.ssid_conflicts().into_iter().find(|c| c.contains(bssid))
+    }
+
+    /// Other BSSIDs broadcasting this network's SSID on a different band
+    /// (dual-band routers, band steering), by band then strongest first
+    pub fn sibling_bands(&self, network: &Network) -> Vec<&Network> {
+        if network.ssid == HIDDEN_SSID || network.ssid.is_empty() {
+            return Vec::new();
+        }
+        let mut siblings: Vec<&Network> = self
+            .networks
+            .iter()
+            .filter(|n| {
+                n.ssid == network.ssid && n.frequency_band != network.frequency_band && n.mac != network.mac
+            })
+            .collect();
+        siblings.sort_by_key(|n| (n.frequency_band, -n.signal_dbm));
+        siblings
+    }
+
+    /// Number of scanned networks hidden by the nearby-only filter
+    pub fn below_min_signal_count(&self) -> usize {
+        match self.min_signal_dbm {
+            Some(min) => self.networks.iter().filter(|n| n.signal_dbm < min).count(),
+            None => 0,
+        }
+    }
+
+    /// Networks shown in the table (after applying the SSID, network and signal filters,
+    /// then the top-N limit)
+    pub fn visible_networks(&self) -> Vec<&Network> {
+        let limit = if self.limit_display { self.max_display } else { usize::MAX };
+        self.filtered_networks().take(limit).collect()
+    }
+
+    /// Networks passing the filters, before the top-N limit is applied
+    pub fn filtered_count(&self) -> usize {
+        self.filtered_networks().count()
+    }
+
+    fn filtered_networks(&self) -> impl Iterator<Item = &Network> {
+        let query = self.filter_query.to_lowercase();
+        self.networks
+            .iter()
+            .filter(move |n| query.is_empty() || n.ssid.to_lowercase().contains(&query))
+            .filter(|n| self.matches_network_filter(n))
+            .filter(|n| self.band_filter.matches(n.frequency_band))
+            .filter(|n| self.min_signal_dbm.is_none_or(|min| n.signal_dbm >= min))
+    }
+
+    fn matches_network_filter(&self, network: &Network) -> bool {
+        match self.network_filter {
+            NetworkFilter::All => true,
+            NetworkFilter::OpenOnly => network.security == SecurityType::Open,
+            NetworkFilter::SecuredOnly => network.security != SecurityType::Open,
+            NetworkFilter::KnownOnly => self.is_known_network(&network.ssid),
+        }
+    }
+
+    /// Cycle All -> Open only -> Secured only -> Known only, keeping the selection if still visible
+    pub fn cycle_network_filter(&mut self) {
+        let selected_mac = self.selected_network().map(|n| n.mac.clone());
+        self.network_filter = match self.network_filter {
+            NetworkFilter::All => NetworkFilter::OpenOnly,
+            NetworkFilter::OpenOnly => NetworkFilter::SecuredOnly,
+            NetworkFilter::SecuredOnly => NetworkFilter::KnownOnly,
+            NetworkFilter::KnownOnly => NetworkFilter::All,
+        };
+        self.reselect(selected_mac);
+    }
+
+    /// Cycle All -> 2.4 GHz -> 5 GHz -> 6 GHz, keeping the selection if still visible
+    pub fn cycle_band_filter(&mut self) {
+        let selected_mac = self.selected_network().map(|n| n.mac.clone());
+        self.band_filter = match self.band_filter {
+            BandFilter::All => BandFilter::Band2_4,
+            BandFilter::Band2_4 => BandFilter::Band5,
+            BandFilter::Band5 => BandFilter::Band6,
+            BandFilter::Band6 => BandFilter::All,
+        };
+        self.reselect(selected_mac);
+    }
+
+    /// Networks per band across the whole scan, ignoring every filter
+    pub fn band_counts(&self) -> BandCounts {
+        let mut counts = BandCounts::default();
+        for network in &self.networks {
+            match network.frequency_band {
+                FrequencyBand::Band2_4GHz => counts.band_2_4 += 1,
+                FrequencyBand::Band5GHz => counts.band_5 += 1,
+                FrequencyBand::Band6GHz => counts.band_6 += 1,
+                FrequencyBand::Unknown => {}
+            }
+        }
+        counts
+    }
+
+    /// Toggle keeping the connected network at the top regardless of sort order
+    pub fn toggle_pin_connected(&mut self) {
+        let selected_mac = self.selected_network().map(|n| n.mac.clone());
+        self.pin_connected = !self.pin_connected;
+        self.sort_networks();
+        self.status_message = Some(
+            if self.pin_connected {
+                "Connected network pinned to the top"
+            } else {
+                "Connected network sorted with the others"
+            }
+            .to_string(),
+        );
+        self.reselect(selected_mac);
+    }
+
+    /// Toggle showing only the top `max_display` networks, keeping the selection if still visible
+    pub fn toggle_limit_display(&mut self) {
+        let selected_mac = self.selected_network().map(|n| n.mac.clone());
+        self.limit_display = !self.limit_display;
+        self.status_message = Some(if self.limit_display {
+            format!("Showing the top {} networks", self.max_display)
+        } else {
+            "Showing all networks".to_string()
+        });
+        self.reselect(selected_mac);
+    }
+
+    /// Toggle hiding networks below the signal threshold, keeping the selection if still visible
+    pub fn toggle_min_signal(&mut self) {
+        let selected_mac = self.selected_network().map(|n| n.mac.clone());
+        self.min_signal_dbm = match self.min_signal_dbm {
+            Some(_) => None,
+            None => Some(self.min_signal_threshold),
+        };
+        self.status_message = Some(match self.min_signal_dbm {
+            Some(min) => format!("Showing networks at {} dBm or stronger", min),
+            None => "Showing networks at any signal strength".to_string(),
+        });
+        self.reselect(selected_mac);
+    }
+
+    /// Switch to the next MAC address display format
+    pub fn cycle_mac_format(&mut self) {
+        self.mac_format = self.mac_format.next();
+        self.status_message = Some(format!("MAC format: {}", self.mac_format.label()));
+    }
+
+    pub fn cycle_speed_units(&mut self) {
+        self.speed_units = self.speed_units.next();
+        self.status_message = Some(format!("Speeds in {}", self.speed_units.label()));
+    }
+
+    /// A MAC address in the current display format
+    pub fn display_mac(&self, mac: &str) -> String {
+        format_mac(mac, self.mac_format)
+    }
+
+    /// Select the network with this MAC in the visible list, or clamp if it is hidden
+    fn reselect(&mut self, mac: Option<String>) {
+        if let Some(idx) = mac.and_then(|mac| self.visible_networks().iter().position(|n| n.mac == mac)) {
+            self.selected_index = idx;
+        }
+        self.clamp_selection();
+    }
+
+    /// Currently selected network (index into the visible networks)
+    pub fn selected_network(&self) -> Option<&Network> {
+        self.visible_networks().get(self.selected_index).copied()
+    }
+
+    /// Keep the selection within the bounds of the visible networks
+    fn clamp_selection(&mut self) {
+        let visible = self.visible_networks().len();
+        self.selected_index = self.selected_index.min(visible.saturating_sub(1));
     }
 
     pub fn navigate_up(&mut self) {
-        if !self.networks.is_empty() && self.selected_index > 0 {
-            self.selected_index -= 1;
-            self.clear_connection_cache();
-            self.load_selected_network_data();
+        if self.selected_index > 0 {
+            self.select_network_at(self.selected_index - 1);
+        } else if self.wrap_navigation {
+            self.select_network_at(usize::MAX);
         }
     }
 
     pub fn navigate_down(&mut self) {
-        if !self.networks.is_empty() && self.selected_index < self.networks.len() - 1 {
-            self.selected_index += 1;
+        if self.selected_index + 1 < self.visible_networks().len() {
+            self.select_network_at(self.selected_index + 1);
+        } else if self.wrap_navigation {
+            self.select_network_at(0);
+        }
+    }
+
+    /// Select the visible network at `index` (clamped to the list),
+    /// reloading its connection data if the selection changed
+    fn select_network_at(&mut self, index: usize) {
+        let index = index.min(self.visible_networks().len().saturating_sub(1));
+        if index != self.selected_index {
+            self.selected_index = index;
             self.clear_connection_cache();
             self.load_selected_network_data();
         }
     }
 
+    pub fn navigate_page_up(&mut self) {
+        self.select_network_at(self.selected_index.saturating_sub(self.page_size.get()));
+    }
+
+    pub fn navigate_page_down(&mut self) {
+        self.select_network_at(self.selected_index.saturating_add(self.page_size.get()));
+    }
+
+    pub fn navigate_first(&mut self) {
+        self.select_network_at(0);
+    }
+
+    pub fn navigate_last(&mut self) {
+        self.select_network_at(usize::MAX);
+    }
+
+    pub fn start_filter(&mut self) {
+        self.show_filter_input = true;
+    }
+
+    /// Close the search input, keeping the current filter
+    pub fn confirm_filter(&mut self) {
+        self.show_filter_input = false;
+    }
+
+    /// Close the search input and show all networks again
+    pub fn clear_filter(&mut self) {
+        let selected_mac = self.selected_network().map(|n| n.mac.clone());
+        self.show_filter_input = false;
+        self.filter_query.clear();
+        self.reselect(selected_mac);
+    }
+
+    pub fn filter_input_char(&mut self, c: char) {
+        if self.filter_query.len() < 32 {
+            self.filter_query.push(c);
+            self.selected_index = 0;
+            self.clamp_selection();
+        }
+    }
+
+    pub fn filter_input_backspace(&mut self) {
+        self.filter_query.pop();
+        self.clamp_selection();
+    }
+
     pub fn toggle_scan_mode(&mut self) {
         self.scan_mode = match self.scan_mode {
             ScanMode::Auto => ScanMode::Manual,
@@ -687,6 +1910,37 @@ impl App {
         };
     }
 
+    /// Step the auto-refresh interval up (`+`)
+    pub fn increase_interval(&mut self) {
+        let current = self.auto_interval.as_secs();
+        let next = INTERVAL_STEPS.iter().copied().find(|&s| s > current);
+        self.set_auto_interval(next.unwrap_or(current));
+    }
+
+    /// Step the auto-refresh interval down (`-`)
+    pub fn decrease_interval(&mut self) {
+        let current = self.auto_interval.as_secs();
+        let prev = INTERVAL_STEPS.iter().rev().copied().find(|&s| s < current);
+        self.set_auto_interval(prev.unwrap_or(current));
+    }
+
+    fn set_auto_interval(&mut self, secs: u64) {
+        let secs = clamp_interval(secs);
+        self.auto_interval = Duration::from_secs(secs);
+        self.status_message = Some(format!("Auto-refresh every {}s", secs));
+
+        // Remember the interval for the next run
+        if let Some(ref path) = self.config_path {
+            let result = Config::load(path).and_then(|mut config| {
+                config.interval_secs = Some(secs);
+                config.save(path)
+            });
+            if let Err(e) = result {
+                self.status_message = Some(format!("Auto-refresh every {}s (not saved: {})", secs, e));
+            }
+        }
+    }
+
     pub fn cycle_sort(&mut self) {
         self.sort_by = match self.sort_by {
             SortField::Score => SortField::Signal,
@@ -698,28 +1952,404 @@ impl App {
 
     pub fn toggle_help(&mut self) {
         self.show_help = !self.show_help;
+        self.help_scroll = 0;
+    }
+
+    /// Scroll the help overlay by `lines` (negative scrolls up), stopping
+    /// once its last line is in view
+    pub fn help_scroll_by(&mut self, lines: isize) {
+        let max = self.help_lines().len().saturating_sub(self.help_page_size.get());
+        self.help_scroll = self.help_scroll.saturating_add_signed(lines).min(max);
+    }
+
+    pub fn toggle_channel_chart(&mut self) {
+        self.show_channel_chart = !self.show_channel_chart;
+        self.show_history_summary = false;
+    }
+
+    /// Open the signal meter for the selected network, or close it
+    pub fn toggle_meter(&mut self) {
+        self.meter = match self.meter {
+            Some(_) => None,
+            None => self.selected_network().map(MeterState::new),
+        };
+        if let Some(meter) = &self.meter {
+            self.status_message = Some(format!("Signal meter: {} (scanning continuously)", meter.ssid));
+        }
+    }
+
+    pub fn toggle_history_summary(&mut self) {
+        self.show_history_summary = !self.show_history_summary;
+        self.show_channel_chart = false;
+        if self.show_history_summary {
+            self.load_network_summaries();
+        }
+    }
+
+    /// Reload per-network averages for the current location
+    pub fn load_network_summaries(&mut self) {
+        let (Some(db), Some(location_id)) = (&self.db, self.current_location_id) else {
+            return;
+        };
+        match db.get_networks_at_location(location_id) {
+            Ok(summaries) => {
+                self.network_summaries = summaries
+                    .into_iter()
+                    .map(|s| (s.bssid.to_uppercase(), s))
+                    .collect();
+            }
+            Err(e) => self.set_error(format!("Failed to load history: {}", e)),
+        }
+    }
+
+    /// Historical summary of a network at the current location
+    pub fn network_summary(&self, bssid: &str) -> Option<&NetworkSummary> {
+        self.network_summaries.get(&bssid.to_uppercase())
     }
 
     pub fn quit(&mut self) {
         self.should_quit = true;
     }
 
+    /// Abandon in-flight background work and flush the database before exit.
+    /// Background threads finish on their own; their results are discarded.
+    pub fn shutdown(&mut self) -> Result<()> {
+        self.scan_receiver = None;
+        self.is_scanning = false;
+        self.cancel_device_scan();
+        self.speedtest_receiver = None;
+        self.liveness_receiver = None;
+        self.arp_poll_receiver = None;
+
+        if let Some(db) = self.db.take() {
+            db.checkpoint()?;
+        }
+        Ok(())
+    }
+
     pub fn switch_view(&mut self) {
         self.current_view = match self.current_view {
             AppView::WifiNetworks => AppView::NetworkDevices,
-            AppView::NetworkDevices => AppView::WifiNetworks,
+            AppView::NetworkDevices => AppView::KnownNetworks,
+            AppView::KnownNetworks => AppView::WifiNetworks,
         };
+
+        // Show previously seen devices until a scan is run
+        if self.current_view == AppView::NetworkDevices
+            && self.devices.is_empty()
+            && self.device_scan_progress.is_none()
+        {
+            self.load_devices_from_db();
+        }
+
+        if self.current_view == AppView::KnownNetworks {
+            self.load_known_networks();
+        }
+    }
+
+    /// Reload known networks from the database
+    pub fn load_known_networks(&mut self) {
+        if let Some(ref db) = self.db {
+            match db.get_known_networks() {
+                Ok(networks) => self.known_networks = networks,
+                Err(e) => self.set_error(format!("Failed to load known networks: {}", e)),
+            }
+        }
+        self.known_ssids = self.known_networks.iter().map(|n| n.ssid.clone()).collect();
+        if self.selected_known_index >= self.known_networks.len() {
+            self.selected_known_index = self.known_networks.len().saturating_sub(1);
+        }
+    }
+
+    /// Run the known network import again so networks saved since startup
+    /// show up, reporting how many were new
+    pub fn refresh_known_networks(&mut self) {
+        let Some(db) = &self.db else {
+            self.set_status("No database: known networks can't be imported".to_string());
+            return;
+        };
+        match reimport_known_networks(db, &self.interface) {
+            Ok(0) => self.set_status("Known networks up to date".to_string()),
+            Ok(1) => self.set_status("Imported 1 new known network".to_string()),
+            Ok(added) => self.set_status(format!("Imported {} new known networks", added)),
+            Err(e) => self.set_error(format!("Failed to import known networks: {}", e)),
+        }
+        self.load_known_networks();
+    }
+
+    pub fn known_navigate_up(&mut self) {
+        if self.selected_known_index > 0 {
+            self.selected_known_index -= 1;
+        }
+    }
+
+    pub fn known_navigate_down(&mut self) {
+        if self.selected_known_index + 1 < self.known_networks.len() {
+            self.selected_known_index += 1;
+        }
+    }
+
+    /// Select the known network at `index`, clamped to the list
+    fn select_known_at(&mut self, index: usize) {
+        self.selected_known_index = index.min(self.known_networks.len().saturating_sub(1));
+    }
+
+    pub fn known_navigate_page_up(&mut self) {
+        self.select_known_at(self.selected_known_index.saturating_sub(self.page_size.get()));
+    }
+
+    pub fn known_navigate_page_down(&mut self) {
+        self.select_known_at(self.selected_known_index.saturating_add(self.page_size.get()));
+    }
+
+    pub fn selected_known_network(&self) -> Option<&KnownNetwork> {
+        self.known_networks.get(self.selected_known_index)
+    }
+
+    /// Show forget confirmation for the selected known network
+    pub fn show_forget_dialog(&mut self) {
+        if self.selected_known_network().is_some() {
+            self.show_forget_popup = true;
+        }
+    }
+
+    pub fn cancel_forget_dialog(&mut self) {
+        self.show_forget_popup = false;
+    }
+
+    /// Forget the selected known network: remove it from the system's
+    /// preferred networks list, then from the database
+    pub fn confirm_forget(&mut self) -> Result<()> {
+        self.show_forget_popup = false;
+
+        let Some(ssid) = self.selected_known_network().map(|n| n.ssid.clone()) else {
+            return Ok(());
+        };
+
+        // The row goes only once macOS has really forgotten the network;
+        // otherwise it would be imported again on the next refresh. A
+        // network macOS no longer lists counts as already forgotten.
+        remove_preferred_network(&ssid, &self.interface)?;
+
+        if let Some(ref db) = self.db {
+            db.delete_known_network(&ssid)?;
+        }
+        self.load_known_networks();
+        self.status_message = Some(format!("Forgot {}", ssid));
+        Ok(())
+    }
+
+    /// One-line summary of the selected network or device, for the clipboard
+    pub fn selection_summary(&self) -> Option<String> {
+        match self.current_view {
+            AppView::WifiNetworks => self.selected_network().map(|n| {
+                format!(
+                    "SSID: {} | BSSID: {} | Channel: {} ({}) | Signal: {} dBm | Security: {} | Score: {}/100",
+                    n.ssid,
+                    self.display_mac(&n.mac),
+                    n.channel,
+                    n.frequency_band,
+                    n.signal_dbm,
+                    n.security,
+                    n.score
+                )
+            }),
+            AppView::NetworkDevices => self.selected_device().map(|d| {
+                let open_ports: Vec<String> = d
+                    .services
+                    .iter()
+                    .filter(|s| s.state == crate::network_map::PortState::Open)
+                    .map(|s| format!("{}/{}", s.port, s.protocol))
+                    .collect();
+                let ports = if open_ports.is_empty() { "none".to_string() } else { open_ports.join(", ") };
+                format!(
+                    "Name: {} | IP: {} | MAC: {} | Open ports: {}",
+                    d.display_name(),
+                    d.ip_address,
+                    self.display_mac(&d.mac_address),
+                    ports
+                )
+            }),
+            AppView::KnownNetworks => None,
+        }
+    }
+
+    /// Copy the selection summary to the system clipboard. Without a clipboard
+    /// (headless or SSH sessions) the summary is shown in the status bar instead.
+    pub fn yank_selection(&mut self) {
+        let Some(summary) = self.selection_summary() else {
+            return;
+        };
+
+        let copied = arboard::Clipboard::new().and_then(|mut clipboard| clipboard.set_text(summary.clone()));
+        self.status_message = Some(match copied {
+            Ok(()) => "Copied to clipboard".to_string(),
+            Err(_) => summary,
+        });
+    }
+
+    /// Devices shown in the table, as indexes into `devices`
+    pub fn visible_device_indices(&self) -> Vec<usize> {
+        self.devices
+            .iter()
+            .enumerate()
+            .filter(|(_, d)| !self.agent_filter || !d.detected_agents.is_empty())
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Devices with at least one detected AI agent
+    pub fn agent_device_count(&self) -> usize {
+        self.devices.iter().filter(|d| !d.detected_agents.is_empty()).count()
+    }
+
+    /// Table row of the selected device, None when it's filtered out
+    pub fn selected_device_row(&self) -> Option<usize> {
+        self.visible_device_indices()
+            .iter()
+            .position(|&idx| idx == self.selected_device_index)
+    }
+
+    /// The selected device, if it's shown in the table
+    pub fn selected_device(&self) -> Option<&crate::network_map::Device> {
+        self.selected_device_row()?;
+        self.devices.get(self.selected_device_index)
+    }
+
+    pub fn toggle_agent_filter(&mut self) {
+        self.agent_filter = !self.agent_filter;
+        self.clamp_device_selection();
+        self.status_message = Some(if self.agent_filter {
+            format!("Showing {} AI-agent devices", self.agent_device_count())
+        } else {
+            "Showing all devices".to_string()
+        });
+    }
+
+    /// Move the selection to the first shown device if the filter hides it
+    fn clamp_device_selection(&mut self) {
+        if self.selected_device_row().is_none() {
+            self.select_device_at(0);
+        }
     }
 
     pub fn device_navigate_up(&mut self) {
-        if !self.devices.is_empty() && self.selected_device_index > 0 {
-            self.selected_device_index -= 1;
+        match self.selected_device_row() {
+            Some(0) if self.wrap_navigation => self.select_device_at(usize::MAX),
+            Some(row) => self.select_device_at(row.saturating_sub(1)),
+            None => {}
         }
     }
 
     pub fn device_navigate_down(&mut self) {
-        if !self.devices.is_empty() && self.selected_device_index < self.devices.len() - 1 {
-            self.selected_device_index += 1;
+        match self.selected_device_row() {
+            Some(row) if self.wrap_navigation && row + 1 >= self.visible_device_indices().len() => {
+                self.select_device_at(0)
+            }
+            Some(row) => self.select_device_at(row + 1),
+            None => {}
+        }
+    }
+
+    /// Select the device in table row `row`, clamped to the shown devices
+    fn select_device_at(&mut self, row: usize) {
+        let visible = self.visible_device_indices();
+        if let Some(&idx) = visible.get(row.min(visible.len().saturating_sub(1))) {
+            self.selected_device_index = idx;
+        }
+    }
+
+    pub fn device_navigate_page_up(&mut self) {
+        let row = self.selected_device_row().unwrap_or(0);
+        self.select_device_at(row.saturating_sub(self.page_size.get()));
+    }
+
+    pub fn device_navigate_page_down(&mut self) {
+        let row = self.selected_device_row().unwrap_or(0);
+        self.select_device_at(row.saturating_add(self.page_size.get()));
+    }
+
+    pub fn device_navigate_first(&mut self) {
+        self.select_device_at(0);
+    }
+
+    pub fn device_navigate_last(&mut self) {
+        self.select_device_at(usize::MAX);
+    }
+
+    pub fn cycle_device_sort(&mut self) {
+        self.device_sort_by = match self.device_sort_by {
+            DeviceSortField::LastSeen => DeviceSortField::Ip,
+            DeviceSortField::Ip => DeviceSortField::Name,
+            DeviceSortField::Name => DeviceSortField::Vendor,
+            DeviceSortField::Vendor => DeviceSortField::Type,
+            DeviceSortField::Type => DeviceSortField::LastSeen,
+        };
+        self.sort_devices();
+    }
+
+    /// Order devices by `device_sort_by`. IPs compare numerically (.2 before .10);
+    /// devices without a vendor sort last. The selection follows its device
+    /// to the new row.
+    pub fn sort_devices(&mut self) {
+        let selected_mac = self.devices.get(self.selected_device_index).map(|d| d.mac_address.clone());
+        match self.device_sort_by {
+            DeviceSortField::LastSeen => self.devices.sort_by_key(|d| Reverse(d.last_seen)),
+            DeviceSortField::Ip => self.devices.sort_by_key(|d| d.ip_address.parse::<std::net::IpAddr>().ok()),
+            DeviceSortField::Name => self.devices.sort_by_key(|d| d.display_name().to_lowercase()),
+            DeviceSortField::Vendor => self
+                .devices
+                .sort_by_key(|d| (d.vendor.is_none(), d.vendor.as_deref().map(str::to_lowercase))),
+            DeviceSortField::Type => self.devices.sort_by_key(|d| d.device_type.to_string()),
+        }
+        if let Some(mac) = selected_mac
+            && let Some(index) = self.devices.iter().position(|d| d.mac_address == mac)
+        {
+            self.selected_device_index = index;
+        }
+        self.clamp_device_selection();
+    }
+
+    /// Jump to the router of the connected network in the devices view
+    pub fn show_gateway(&mut self) {
+        if self.connected_ssid.is_none() {
+            self.status_message = Some("Not connected to a network".to_string());
+            return;
+        }
+        match crate::network_map::gateway_device() {
+            Ok(Some(gateway)) => self.focus_gateway(gateway),
+            Ok(None) => self.status_message = Some("No default gateway found".to_string()),
+            Err(e) => self.set_error(format!("Failed to find the gateway: {}", e)),
+        }
+    }
+
+    /// Select `gateway` in the devices view with its details open, adding it
+    /// if no device has its address yet. With no devices known at all a
+    /// device scan is started and the gateway is selected again when it ends.
+    pub fn focus_gateway(&mut self, gateway: crate::network_map::Device) {
+        self.current_view = AppView::NetworkDevices;
+        // The router rarely runs an agent, so don't hide it
+        self.agent_filter = false;
+        if self.devices.is_empty() && self.device_scan_progress.is_none() {
+            self.load_devices_from_db();
+            if self.devices.is_empty() {
+                self.pending_gateway_ip = Some(gateway.ip_address.clone());
+                self.start_device_scan();
+            }
+        }
+
+        let ip = gateway.ip_address.clone();
+        if !self.devices.iter().any(|d| d.ip_address == ip) {
+            self.devices.push(gateway);
+            self.sort_devices();
+        }
+        self.select_device_by_ip(&ip);
+        self.show_device_detail = true;
+    }
+
+    fn select_device_by_ip(&mut self, ip: &str) {
+        if let Some(index) = self.devices.iter().position(|d| d.ip_address == ip) {
+            self.selected_device_index = index;
         }
     }
 
@@ -728,9 +2358,19 @@ impl App {
     }
 
     pub fn start_rename_device(&mut self) {
-        if !self.devices.is_empty() {
-            let device = &self.devices[self.selected_device_index];
-            self.rename_input = device.custom_name.clone().unwrap_or_default();
+        self.start_device_edit(DeviceField::Name);
+    }
+
+    /// Open the rename dialog on the selected device's name, notes or tags
+    pub fn start_device_edit(&mut self, field: DeviceField) {
+        let current = self.selected_device().map(|d| match field {
+            DeviceField::Name => d.custom_name.clone().unwrap_or_default(),
+            DeviceField::Notes => d.notes.clone().unwrap_or_default(),
+            DeviceField::Tags => d.tags_text(),
+        });
+        if let Some(current) = current {
+            self.rename_input = current;
+            self.rename_field = field;
             self.show_rename_dialog = true;
         }
     }
@@ -741,13 +2381,31 @@ impl App {
     }
 
     pub fn confirm_rename(&mut self) {
-        if !self.devices.is_empty() && !self.rename_input.is_empty() {
+        if self.selected_device().is_some() {
             let device = &mut self.devices[self.selected_device_index];
-            device.custom_name = Some(self.rename_input.clone());
-
-            // Persist to database
-            if let Some(ref db) = self.db {
-                let _ = db.update_device_name(&device.mac_address, &self.rename_input);
+            let db = self.db.as_ref();
+            // Names can't be cleared; notes and tags are removed by saving them empty
+            match self.rename_field {
+                DeviceField::Name if !self.rename_input.is_empty() => {
+                    device.custom_name = Some(self.rename_input.clone());
+                    if let Some(db) = db {
+                        let _ = db.update_device_name(&device.mac_address, &self.rename_input);
+                    }
+                }
+                DeviceField::Name => {}
+                DeviceField::Notes => {
+                    let notes = self.rename_input.trim();
+                    device.notes = Some(notes.to_string()).filter(|n| !n.is_empty());
+                    if let Some(db) = db {
+                        let _ = db.update_device_notes(&device.mac_address, notes);
+                    }
+                }
+                DeviceField::Tags => {
+                    device.tags = crate::network_map::parse_tags(&self.rename_input);
+                    if let Some(db) = db {
+                        let _ = db.update_device_tags(&device.mac_address, &device.tags);
+                    }
+                }
             }
         }
         self.show_rename_dialog = false;
@@ -755,7 +2413,7 @@ impl App {
     }
 
     pub fn rename_input_char(&mut self, c: char) {
-        if self.rename_input.len() < 32 {
+        if self.rename_input.chars().count() < self.rename_field.max_len() {
             self.rename_input.push(c);
         }
     }
@@ -772,11 +2430,122 @@ impl App {
 
         let (tx, rx) = std::sync::mpsc::channel();
         self.device_scan_receiver = Some(rx);
+        self.last_device_scan = Some(Instant::now());
+        self.arp_probed = true;
+        let target_subnet = self.target_subnet;
+        let ports = self.scan_ports.clone();
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.device_scan_cancel = cancel.clone();
+
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            rt.block_on(async {
+                use crate::network_map::{
+                    discover_and_scan_devices, identify_all_devices, merge_randomized_macs, PingSweepConfig,
+                    PortScanConfig, ScanPhase, ScanProgress,
+                };
+
+                let (progress_tx, mut progress_rx) = tokio::sync::mpsc::channel(10);
+
+                // Forward progress to main thread
+                let tx_clone = tx.clone();
+                let forward_handle = tokio::spawn(async move {
+                    while let Some(progress) = progress_rx.recv().await {
+                        let _ = tx_clone.send(progress);
+                    }
+                });
+
+                // Phases 1 and 2: ping sweep, port scanning each host as it answers
+                let mut devices = match discover_and_scan_devices(
+                    Some(progress_tx.clone()),
+                    &PingSweepConfig::default(),
+                    target_subnet.as_ref(),
+                    &ports,
+                    &PortScanConfig::default(),
+                    &cancel,
+                ).await {
+                    Ok(d) => d,
+                    Err(e) => {
+                        if !cancel.load(Ordering::Relaxed) {
+                            log_background(format!("Device discovery error: {}", e));
+                        }
+                        drop(progress_tx);
+                        let _ = forward_handle.await;
+                        return;
+                    }
+                };
+
+                // Phase 3: Identify devices
+                let _ = progress_tx.send(ScanProgress {
+                    phase: ScanPhase::Identification,
+                    devices_found: devices.len(),
+                    current_device: None,
+                    ports_scanned: 0,
+                    total_ports: 0,
+                    percent: None,
+                }).await;
+
+                identify_all_devices(&mut devices);
+                let devices = merge_randomized_macs(devices);
+                if cancel.load(Ordering::Relaxed) {
+                    drop(progress_tx);
+                    let _ = forward_handle.await;
+                    return;
+                }
+
+                // Send complete signal
+                let _ = progress_tx.send(ScanProgress {
+                    phase: ScanPhase::Complete,
+                    devices_found: devices.len(),
+                    current_device: None,
+                    ports_scanned: 0,
+                    total_ports: 0,
+                    percent: None,
+                }).await;
+
+                // Store devices for main thread to pick up
+                SCANNED_DEVICES.lock().unwrap().replace(devices);
+
+                // Drop progress_tx to signal forwarding task to exit, then wait for it
+                drop(progress_tx);
+                let _ = forward_handle.await;
+            });
+        });
+
+        self.device_scan_progress = Some(crate::network_map::ScanProgress {
+            phase: crate::network_map::ScanPhase::Discovery,
+            devices_found: 0,
+            current_device: None,
+            ports_scanned: 0,
+            total_ports: 0,
+            percent: None,
+        });
+    }
+
+    /// Start a full (1-65535) port scan of the selected device. Progress is
+    /// reported through the same channel as a network device scan.
+    pub fn start_deep_scan(&mut self) {
+        if self.device_scan_progress.is_some() {
+            return; // Already scanning
+        }
+        let Some(target) = self.selected_device().cloned() else {
+            return;
+        };
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.device_scan_receiver = Some(rx);
+        self.deep_scan_target = Some(target.mac_address.clone());
+        self.arp_probed = true;
+
+        // The scan thread hands back the whole list with the target updated
+        let mut devices = self.devices.clone();
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.device_scan_cancel = cancel.clone();
 
         std::thread::spawn(move || {
             let rt = tokio::runtime::Runtime::new().unwrap();
             rt.block_on(async {
-                use crate::network_map::{discover_devices_with_options, identify_all_devices, scan_devices_ports, ScanPhase, ScanProgress};
+                use crate::network_map::{deep_scan_device, identify_device, PortScanConfig, Protocol, ScanPhase, ScanProgress};
 
                 let (progress_tx, mut progress_rx) = tokio::sync::mpsc::channel(10);
 
@@ -788,57 +2557,57 @@ impl App {
                     }
                 });
 
-                // Phase 1: Discover devices (with ping sweep to find all devices)
-                let mut devices = match discover_devices_with_options(Some(progress_tx.clone()), true).await {
-                    Ok(d) => d,
-                    Err(e) => {
-                        eprintln!("Device discovery error: {}", e);
-                        drop(progress_tx);
-                        let _ = forward_handle.await;
-                        return;
+                let mut device = target;
+                let udp_services: Vec<_> = device
+                    .services
+                    .iter()
+                    .filter(|s| s.protocol == Protocol::Udp)
+                    .cloned()
+                    .collect();
+
+                if let Err(e) = deep_scan_device(&mut device, Some(progress_tx.clone()), &PortScanConfig::default(), &cancel).await {
+                    if !cancel.load(Ordering::Relaxed) {
+                        log_background(format!("Deep scan error: {}", e));
                     }
-                };
-
-                // Phase 2: Scan ports
-                if let Err(e) = scan_devices_ports(&mut devices, Some(progress_tx.clone())).await {
-                    eprintln!("Port scan error: {}", e);
+                }
+                if cancel.load(Ordering::Relaxed) {
+                    drop(progress_tx);
+                    let _ = forward_handle.await;
+                    return;
                 }
 
-                // Phase 3: Identify devices
-                let _ = progress_tx.send(ScanProgress {
-                    phase: ScanPhase::Identification,
-                    devices_found: devices.len(),
-                    current_device: None,
-                    ports_scanned: 0,
-                    total_ports: 0,
-                }).await;
+                // The deep scan is TCP only, so keep UDP services from earlier scans
+                device.services.extend(udp_services);
+                identify_device(&mut device);
 
-                identify_all_devices(&mut devices);
+                if let Some(slot) = devices.iter_mut().find(|d| d.mac_address == device.mac_address) {
+                    *slot = device;
+                }
 
-                // Send complete signal
                 let _ = progress_tx.send(ScanProgress {
                     phase: ScanPhase::Complete,
-                    devices_found: devices.len(),
+                    devices_found: 1,
                     current_device: None,
                     ports_scanned: 0,
                     total_ports: 0,
+                    percent: None,
                 }).await;
 
                 // Store devices for main thread to pick up
                 SCANNED_DEVICES.lock().unwrap().replace(devices);
 
-                // Drop progress_tx to signal forwarding task to exit, then wait for it
                 drop(progress_tx);
                 let _ = forward_handle.await;
             });
         });
 
         self.device_scan_progress = Some(crate::network_map::ScanProgress {
-            phase: crate::network_map::ScanPhase::Discovery,
-            devices_found: 0,
+            phase: crate::network_map::ScanPhase::PortScan,
+            devices_found: 1,
             current_device: None,
             ports_scanned: 0,
-            total_ports: 0,
+            total_ports: 65535,
+            percent: None,
         });
     }
 
@@ -849,11 +2618,32 @@ impl App {
                 if matches!(progress.phase, crate::network_map::ScanPhase::Complete) {
                     if let Some(devices) = SCANNED_DEVICES.lock().unwrap().take() {
                         self.devices = devices;
-                        self.persist_devices();
+                        self.sort_devices();
+                        self.clamp_device_selection();
+                        // New devices stay highlighted until the next scan completes
+                        self.new_device_macs = self.persist_devices().into_iter().collect();
+                        if let Some(ip) = self.pending_gateway_ip.take() {
+                            self.select_device_by_ip(&ip);
+                        }
                     }
                     self.device_scan_progress = None;
                     self.device_scan_receiver = None;
-                    self.status_message = Some(format!("Found {} devices", self.devices.len()));
+                    self.arp_probed = true;
+                    self.last_device_scan = Some(Instant::now());
+                    let new_devices = self.new_device_macs.len();
+                    self.status_message = Some(match self.deep_scan_target.take() {
+                        Some(mac) => {
+                            let open_ports = self
+                                .devices
+                                .iter()
+                                .find(|d| d.mac_address == mac)
+                                .map_or(0, |d| d.services.len());
+                            format!("Deep scan found {} open ports", open_ports)
+                        }
+                        None if new_devices == 1 => "⚠ 1 new device detected".to_string(),
+                        None if new_devices > 1 => format!("⚠ {} new devices detected", new_devices),
+                        None => format!("Found {} devices", self.devices.len()),
+                    });
                     return;
                 }
                 // Only update progress if it's advancing (don't let late port scan messages
@@ -886,16 +2676,26 @@ impl App {
 
     /// Cancel ongoing device scan
     pub fn cancel_device_scan(&mut self) {
+        self.device_scan_cancel.store(true, Ordering::Relaxed);
         self.device_scan_progress = None;
         self.device_scan_receiver = None;
+        self.arp_probed = true;
+        self.deep_scan_target = None;
+        self.pending_gateway_ip = None;
     }
 
-    /// Persist scanned devices to database
-    fn persist_devices(&self) {
-        let Some(ref db) = self.db else { return };
+    /// Persist scanned devices to database. Returns the MACs (uppercase) of
+    /// devices that weren't in the database before this scan.
+    fn persist_devices(&self) -> Vec<String> {
+        let Some(ref db) = self.db else { return Vec::new() };
         let network_bssid = self.connected_bssid.as_deref();
+        let mut new_macs = Vec::new();
 
         for device in &self.devices {
+            if let Ok(false) = db.device_exists(&device.mac_address) {
+                new_macs.push(device.mac_address.to_uppercase());
+            }
+
             let device_id = match db.upsert_device(
                 &device.mac_address,
                 &device.ip_address,
@@ -922,6 +2722,13 @@ impl App {
                 }
             }
         }
+
+        new_macs
+    }
+
+    /// Whether a device first appeared in the most recent device scan
+    pub fn is_new_device(&self, mac: &str) -> bool {
+        self.new_device_macs.contains(&mac.to_uppercase())
     }
 
     /// Load devices from database
@@ -934,7 +2741,7 @@ impl App {
             Err(_) => return,
         };
 
-        self.devices = records
+        let devices = records
             .into_iter()
             .map(|r| {
                 let mut device = crate::network_map::Device::new(r.mac_address, r.ip_address.unwrap_or_default());
@@ -945,18 +2752,174 @@ impl App {
                     .map(parse_device_type)
                     .unwrap_or_default();
                 device.custom_name = r.custom_name;
+                device.notes = r.notes;
+                device.tags = r.tags.as_deref().map(crate::network_map::parse_tags).unwrap_or_default();
                 device.first_seen = r.first_seen;
                 device.last_seen = r.last_seen;
                 device.is_online = false;
                 device
             })
             .collect();
+        // Phones rotate MACs, which leaves one stored row per MAC
+        self.devices = crate::network_map::merge_randomized_macs(devices);
+        self.sort_devices();
+        self.clamp_device_selection();
+
+        // Stored devices may be long gone - find out which are still around
+        self.start_liveness_check();
+    }
+
+    /// Re-check device reachability in the background
+    pub fn start_liveness_check(&mut self) {
+        if self.liveness_receiver.is_some() || self.devices.is_empty() {
+            return;
+        }
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.liveness_receiver = Some(rx);
+        self.last_liveness_check = Some(Instant::now());
+        self.arp_probed = true;
+
+        let mut devices = self.devices.clone();
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            rt.block_on(crate::network_map::refresh_device_liveness(&mut devices));
+            let _ = tx.send(devices);
+        });
+    }
+
+    pub fn toggle_device_auto_scan(&mut self) {
+        self.device_auto_scan = !self.device_auto_scan;
+        self.status_message = Some(if self.device_auto_scan {
+            format!("Device auto-scan every {}s", self.device_scan_interval.as_secs())
+        } else {
+            "Device auto-scan off".to_string()
+        });
+    }
+
+    /// Whether a popup or dialog is covering the screen
+    pub fn dialog_open(&self) -> bool {
+        self.show_help
+            || self.show_log
+            || self.show_connect_popup
+            || self.show_speedtest_popup
+            || self.show_forget_popup
+            || self.show_rename_dialog
+            || self.show_note_dialog
+            || self.show_filter_input
+            || self.meter.is_some()
+            || self.error_message.is_some()
+    }
+
+    /// Whether an automatic device scan should start now. Only runs while the
+    /// devices view is open, no scan is running and no dialog is open.
+    pub fn device_scan_due(&self) -> bool {
+        self.device_auto_scan
+            && self.current_view == AppView::NetworkDevices
+            && self.device_scan_receiver.is_none()
+            && !self.dialog_open()
+            && self
+                .last_device_scan
+                .is_none_or(|t| t.elapsed() >= self.device_scan_interval)
+    }
+
+    /// Seconds until the next automatic device scan (None when off or scanning)
+    pub fn next_device_scan_secs(&self) -> Option<u64> {
+        if !self.device_auto_scan || self.device_scan_receiver.is_some() {
+            return None;
+        }
+        let elapsed = self.last_device_scan.map_or(self.device_scan_interval, |t| t.elapsed());
+        Some(self.device_scan_interval.saturating_sub(elapsed).as_secs())
+    }
+
+    /// Whether the devices view is due for another liveness check
+    pub fn liveness_check_due(&self) -> bool {
+        self.current_view == AppView::NetworkDevices
+            && self.device_scan_progress.is_none()
+            && self.liveness_receiver.is_none()
+            && !self.devices.is_empty()
+            && self
+                .last_liveness_check
+                .is_none_or(|t| t.elapsed() >= LIVENESS_INTERVAL)
+    }
+
+    /// Apply a finished liveness check (matched by MAC, since a device scan
+    /// may have replaced the list in the meantime)
+    pub fn check_liveness_result(&mut self) {
+        let Some(ref rx) = self.liveness_receiver else { return };
+        match rx.try_recv() {
+            Ok(refreshed) => {
+                for checked in refreshed {
+                    if let Some(device) = self
+                        .devices
+                        .iter_mut()
+                        .find(|d| d.mac_address == checked.mac_address)
+                    {
+                        device.is_online = checked.is_online;
+                        device.last_seen = device.last_seen.max(checked.last_seen);
+                    }
+                }
+                self.liveness_receiver = None;
+                self.arp_probed = true;
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => {}
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                self.liveness_receiver = None;
+            }
+        }
+    }
+
+    /// Whether the ARP cache is due for another activity sample. Polling
+    /// starts once devices are known and runs in every view.
+    pub fn arp_poll_due(&self) -> bool {
+        !self.devices.is_empty()
+            && self.arp_poll_receiver.is_none()
+            && self.last_arp_poll.is_none_or(|t| t.elapsed() >= ARP_POLL_INTERVAL)
+    }
+
+    /// Read the ARP cache in the background
+    pub fn start_arp_poll(&mut self) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.arp_poll_receiver = Some(rx);
+        self.last_arp_poll = Some(Instant::now());
+
+        std::thread::spawn(move || {
+            if let Ok(entries) = crate::network_map::arp_entries() {
+                let _ = tx.send((entries, Utc::now()));
+            }
+        });
+    }
+
+    /// Fold a finished ARP snapshot into the activity times
+    pub fn check_arp_poll_result(&mut self) {
+        let Some(ref rx) = self.arp_poll_receiver else { return };
+        match rx.try_recv() {
+            Ok((entries, taken_at)) => {
+                // Pings from a check still running, or one that finished
+                // since the last snapshot, re-resolve idle devices' entries
+                let probing = self.liveness_receiver.is_some() || self.device_scan_receiver.is_some();
+                if self.arp_probed || probing {
+                    self.arp_activity.record_baseline(&entries);
+                } else {
+                    self.arp_activity.record_snapshot(&entries, taken_at);
+                }
+                self.arp_probed = false;
+                self.arp_poll_receiver = None;
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => {}
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                self.arp_poll_receiver = None;
+            }
+        }
     }
 
     pub fn should_scan(&self) -> bool {
         if self.is_scanning {
             return false;
         }
+        if self.meter.is_some() {
+            return self.last_scan.elapsed() >= METER_SCAN_INTERVAL;
+        }
         match self.scan_mode {
             ScanMode::Auto => self.last_scan.elapsed() >= self.auto_interval,
             ScanMode::Manual => false,
@@ -969,19 +2932,76 @@ impl App {
         }
     }
 
-    pub async fn perform_scan(&mut self) -> Result<()> {
+    /// Scan and apply the results in place (used at startup and by tests;
+    /// the event loop uses `start_scan` so rendering isn't blocked)
+    pub async fn perform_scan(&mut self) -> Result<(), ScanError> {
         self.is_scanning = true;
-        let mut scanned_networks = scan_networks().await?;
-        calculate_all_scores(&mut scanned_networks);
+        self.scan_started = Some(Instant::now());
+        let scanned_networks = match scan_networks().await {
+            Ok(networks) => networks,
+            Err(e) => {
+                self.is_scanning = false;
+                return Err(e);
+            }
+        };
+        self.apply_scan_results(scanned_networks);
+        Ok(())
+    }
 
-        // Persist to database if available
-        if let (Some(db), Some(location_id)) = (&self.db, self.current_location_id)
-            && let Err(e) = self.persist_scan_results(db, location_id, &scanned_networks)
-        {
-            // Log error but don't fail the scan
-            eprintln!("Failed to persist scan: {}", e);
+    /// Start a WiFi scan on a background thread; results are picked up by `poll_scan_result`
+    pub fn start_scan(&mut self) {
+        if self.scan_receiver.is_some() {
+            return; // Already scanning
+        }
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.scan_receiver = Some(rx);
+        self.is_scanning = true;
+        self.scan_started = Some(Instant::now());
+
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let _ = tx.send(rt.block_on(scan_networks()));
+        });
+    }
+
+    /// Apply a finished background scan. Returns true if new networks arrived.
+    pub fn poll_scan_result(&mut self) -> bool {
+        let Some(ref rx) = self.scan_receiver else {
+            return false;
+        };
+
+        match rx.try_recv() {
+            Ok(Ok(networks)) => {
+                self.scan_receiver = None;
+                self.apply_scan_results(networks);
+                true
+            }
+            Ok(Err(e)) => {
+                self.scan_receiver = None;
+                self.is_scanning = false;
+                // Wait a full interval before retrying in auto mode
+                self.last_scan = Instant::now();
+                self.set_scan_error(&e);
+                false
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => false,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                self.scan_receiver = None;
+                self.is_scanning = false;
+                false
+            }
         }
+    }
 
+    /// Score, persist and merge freshly scanned networks
+    fn apply_scan_results(&mut self, mut scanned_networks: Vec<Network>) {
+        let captive_bssids: HashSet<String> = self
+            .captive_portals
+            .iter()
+            .filter(|(_, status)| **status == CaptiveStatus::CaptivePortal)
+            .map(|(bssid, _)| bssid.clone())
+            .collect();
         // Update signal history (keyed by BSSID/MAC address for uniqueness)
         for network in &scanned_networks {
             let history = self
@@ -992,10 +3012,53 @@ impl App {
             while history.len() > SIGNAL_HISTORY_SIZE {
                 history.pop_front();
             }
+
+            let previous = self.scan_signals.get(&network.mac).map(|(latest, _)| *latest);
+            self.scan_signals.insert(network.mac.clone(), (network.signal_dbm, previous));
+        }
+
+        calculate_all_scores_weighted(&mut scanned_networks, &captive_bssids, &self.scoring_profile.weights());
+        // A jittery signal suggests a loaded or interfered AP
+        for network in &mut scanned_networks {
+            if let Some(std_dev) = self.signal_stability(&network.mac) {
+                network.score = network.score.saturating_sub(stability_penalty(std_dev));
+            }
+        }
+
+        // Persist to database if available
+        if let (Some(db), Some(location_id)) = (&self.db, self.current_location_id)
+            && let Err(e) = self.persist_scan_results(db, location_id, &scanned_networks)
+        {
+            // Log error but don't fail the scan
+            self.log(format!("Failed to persist scan: {}", e));
+        }
+
+        // Append to the CSV log if configured
+        if let Some(logger) = &self.scan_logger
+            && let Err(e) = logger.log_scan(&scanned_networks, Utc::now())
+        {
+            let msg = format!("Warning: Failed to write CSV log {}: {}", logger.path().display(), e);
+            self.log(msg);
+        }
+
+        if let Some(meter) = &mut self.meter {
+            match scanned_networks.iter().find(|n| n.mac == meter.bssid) {
+                Some(network) => meter.record(network.signal_dbm),
+                None => meter.missed = true,
+            }
+        }
+
+        // Scan sooner while networks come, go or move, later while they hold still
+        if let Some(adaptive) = self.adaptive_interval {
+            if !self.previous_scan_signals.is_empty() {
+                let change = ScanChange::between(&self.previous_scan_signals, &scanned_networks);
+                self.auto_interval = adaptive.next(self.auto_interval, change);
+            }
+            self.previous_scan_signals = scanned_networks.iter().map(|n| (n.mac.clone(), n.signal_dbm)).collect();
         }
 
         // Preserve selection if possible (by MAC address for stability)
-        let selected_mac = self.networks.get(self.selected_index).map(|n| n.mac.clone());
+        let selected_mac = self.selected_network().map(|n| n.mac.clone());
 
         // Merge scanned networks with existing (accumulate, don't replace)
         let now = Utc::now();
@@ -1017,66 +3080,448 @@ impl App {
             }
         }
 
-        self.sort_networks();
+        self.sort_networks();
+
+        // Try to maintain selection by MAC address
+        if let Some(mac) = selected_mac
+            && let Some(idx) = self.visible_networks().iter().position(|n| n.mac == mac)
+        {
+            self.selected_index = idx;
+        }
+
+        // Clamp selection index to the filtered view
+        self.clamp_selection();
+
+        self.last_scan = Instant::now();
+        self.last_scan_duration = self.scan_started.take().map(|started| started.elapsed());
+        self.is_scanning = false;
+
+        // Include this scan in the averages
+        if self.show_history_summary {
+            self.load_network_summaries();
+        }
+
+        // Load connection data for the selected network
+        self.load_selected_network_data();
+    }
+
+    /// Persist scan results to the database
+    fn persist_scan_results(
+        &self,
+        db: &Database,
+        location_id: i64,
+        networks: &[Network],
+    ) -> Result<()> {
+        let scan_id = db.create_scan(location_id)?;
+
+        let records: Vec<ScanResultRecord> = networks
+            .iter()
+            .map(|n| ScanResultRecord {
+                bssid: n.mac.clone(),
+                ssid: n.ssid.clone(),
+                channel: n.channel,
+                signal_dbm: n.signal_dbm,
+                security: n.security.as_str().to_string(),
+                frequency_band: n.frequency_band.as_str().to_string(),
+                score: n.score,
+            })
+            .collect();
+
+        db.record_scan_results(scan_id, &records)?;
+        Ok(())
+    }
+
+    fn sort_networks(&mut self) {
+        self.sort_by.sort(&mut self.networks);
+
+        // Move the connected network (all its BSSIDs) to the top, keeping the sort order otherwise
+        if self.pin_connected {
+            let (mut pinned, rest): (Vec<Network>, Vec<Network>) = std::mem::take(&mut self.networks)
+                .into_iter()
+                .partition(|n| self.is_connected(n));
+            pinned.extend(rest);
+            self.networks = pinned;
+        }
+    }
+
+    /// Apply a key press: dialogs first, then the current view's bindings.
+    /// State changes happen here; work the event loop must run (scans and
+    /// blocking network calls) comes back as an action.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<AppAction> {
+        // Handle Ctrl+C
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit();
+            return None;
+        }
+
+        // Handle popup keys first
+        if self.show_connect_popup {
+            self.handle_connect_popup_key(key.code)
+        } else if self.show_speedtest_popup {
+            self.handle_speedtest_popup_key(key.code)
+        } else if self.show_forget_popup {
+            self.handle_forget_popup_key(key.code);
+            None
+        } else if self.show_log {
+            self.handle_log_key(key.code);
+            None
+        } else if self.show_help {
+            self.handle_help_key(key);
+            None
+        } else if self.meter.is_some() {
+            self.handle_meter_key(key);
+            None
+        } else {
+            // Normal key handling based on current view
+            match self.current_view {
+                AppView::WifiNetworks => self.handle_wifi_key(key),
+                AppView::NetworkDevices => {
+                    self.handle_devices_key(key);
+                    None
+                }
+                AppView::KnownNetworks => {
+                    self.handle_known_key(key);
+                    None
+                }
+            }
+        }
+    }
+
+    fn handle_connect_popup_key(&mut self, code: KeyCode) -> Option<AppAction> {
+        match code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                // Dismiss dialog immediately; the caller renders, then connects
+                self.show_connect_popup = false;
+                self.status_message = Some("Connecting...".to_string());
+                return Some(AppAction::Connect);
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => self.cancel_connect_dialog(),
+            _ => {}
+        }
+        None
+    }
+
+    fn handle_speedtest_popup_key(&mut self, code: KeyCode) -> Option<AppAction> {
+        match code {
+            // The caller renders first, then runs confirm_speedtest
+            KeyCode::Char('y') | KeyCode::Char('Y') => return Some(AppAction::SpeedTest),
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => self.cancel_speedtest_dialog(),
+            _ => {}
+        }
+        None
+    }
+
+    fn handle_forget_popup_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                if let Err(e) = self.confirm_forget() {
+                    self.set_error(format!("Forget failed: {}", e));
+                }
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => self.cancel_forget_dialog(),
+            _ => {}
+        }
+    }
+
+    fn handle_log_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('l') | KeyCode::Esc => self.toggle_log(),
+            KeyCode::Up | KeyCode::Char('k') => self.log_scroll_up(),
+            KeyCode::Down | KeyCode::Char('j') => self.log_scroll_down(),
+            _ => {}
+        }
+    }
+
+    /// The help overlay scrolls with the navigation keys of the current view
+    fn handle_help_key(&mut self, key: KeyEvent) {
+        if key.code == KeyCode::Esc {
+            self.toggle_help();
+            return;
+        }
+        let page = self.help_page_size.get().max(1) as isize;
+        match self.keymap.action_for(self.key_view(), key) {
+            Some(KeyAction::Help) => self.toggle_help(),
+            Some(KeyAction::Up) => self.help_scroll_by(-1),
+            Some(KeyAction::Down) => self.help_scroll_by(1),
+            Some(KeyAction::PageUp) => self.help_scroll_by(-page),
+            Some(KeyAction::PageDown) => self.help_scroll_by(page),
+            Some(KeyAction::First) => self.help_scroll = 0,
+            Some(KeyAction::Last) => self.help_scroll_by(isize::MAX),
+            Some(KeyAction::Log) => self.toggle_log(),
+            Some(KeyAction::Quit) => self.quit(),
+            _ => {}
+        }
+    }
+
+    /// The keymap view matching the current tab
+    fn key_view(&self) -> KeyView {
+        match self.current_view {
+            AppView::WifiNetworks => KeyView::Networks,
+            AppView::NetworkDevices => KeyView::Devices,
+            AppView::KnownNetworks => KeyView::Known,
+        }
+    }
+
+    /// The meter hides the table, so only closing it, the log and quitting work
+    fn handle_meter_key(&mut self, key: KeyEvent) {
+        if key.code == KeyCode::Esc {
+            self.toggle_meter();
+            return;
+        }
+        match self.keymap.action_for(KeyView::Networks, key) {
+            Some(KeyAction::Meter) => self.toggle_meter(),
+            Some(KeyAction::Log) => self.toggle_log(),
+            Some(KeyAction::Quit) => self.quit(),
+            _ => {}
+        }
+    }
+
+    /// Keymap actions that work in every view; false for the WiFi-only ones
+    fn handle_global_action(&mut self, action: KeyAction) -> bool {
+        match (action, self.current_view) {
+            (KeyAction::Quit, _) => self.quit(),
+            (KeyAction::SwitchView, _) => self.switch_view(),
+            (KeyAction::Up, AppView::WifiNetworks) => self.navigate_up(),
+            (KeyAction::Up, AppView::NetworkDevices) => self.device_navigate_up(),
+            (KeyAction::Up, AppView::KnownNetworks) => self.known_navigate_up(),
+            (KeyAction::Down, AppView::WifiNetworks) => self.navigate_down(),
+            (KeyAction::Down, AppView::NetworkDevices) => self.device_navigate_down(),
+            (KeyAction::Down, AppView::KnownNetworks) => self.known_navigate_down(),
+            (KeyAction::PageUp, AppView::WifiNetworks) => self.navigate_page_up(),
+            (KeyAction::PageUp, AppView::NetworkDevices) => self.device_navigate_page_up(),
+            (KeyAction::PageUp, AppView::KnownNetworks) => self.known_navigate_page_up(),
+            (KeyAction::PageDown, AppView::WifiNetworks) => self.navigate_page_down(),
+            (KeyAction::PageDown, AppView::NetworkDevices) => self.device_navigate_page_down(),
+            (KeyAction::PageDown, AppView::KnownNetworks) => self.known_navigate_page_down(),
+            (KeyAction::First, AppView::WifiNetworks) => self.navigate_first(),
+            (KeyAction::First, AppView::NetworkDevices) => self.device_navigate_first(),
+            (KeyAction::First, AppView::KnownNetworks) => self.select_known_at(0),
+            (KeyAction::Last, AppView::WifiNetworks) => self.navigate_last(),
+            (KeyAction::Last, AppView::NetworkDevices) => self.device_navigate_last(),
+            (KeyAction::Last, AppView::KnownNetworks) => self.select_known_at(usize::MAX),
+            (KeyAction::Log, _) => self.toggle_log(),
+            (KeyAction::Help, _) => self.toggle_help(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// WiFi Networks view keys, including its search and note inputs
+    fn handle_wifi_key(&mut self, key: KeyEvent) -> Option<AppAction> {
+        if self.show_filter_input {
+            match key.code {
+                KeyCode::Enter => self.confirm_filter(),
+                KeyCode::Esc => self.clear_filter(),
+                KeyCode::Backspace => self.filter_input_backspace(),
+                KeyCode::Char(c) => self.filter_input_char(c),
+                _ => {}
+            }
+            return None;
+        }
+        if self.show_note_dialog {
+            match key.code {
+                KeyCode::Enter => self.confirm_note(),
+                KeyCode::Esc => self.cancel_note(),
+                KeyCode::Backspace => self.note_input_backspace(),
+                KeyCode::Char(c) => self.note_input_char(c),
+                _ => {}
+            }
+            return None;
+        }
+        if key.code == KeyCode::Esc && !self.filter_query.is_empty() {
+            self.clear_filter();
+            return None;
+        }
+
+        let action = self.keymap.action_for(KeyView::Networks, key)?;
+        if self.handle_global_action(action) {
+            return None;
+        }
+        match action {
+            KeyAction::Connect => self.show_connect_dialog(),
+            KeyAction::Scan => return Some(AppAction::Scan),
+            KeyAction::Demo => {
+                self.clear_error();
+                return Some(AppAction::EnableDemo);
+            }
+            KeyAction::AutoScan => self.toggle_scan_mode(),
+            KeyAction::IntervalUp => self.increase_interval(),
+            KeyAction::IntervalDown => self.decrease_interval(),
+            KeyAction::Sort => self.cycle_sort(),
+            KeyAction::ChannelChart => self.toggle_channel_chart(),
+            KeyAction::History => self.toggle_history_summary(),
+            KeyAction::Copy => self.yank_selection(),
+            KeyAction::Filter => self.cycle_network_filter(),
+            KeyAction::Band => self.cycle_band_filter(),
+            KeyAction::Gateway => self.show_gateway(),
+            KeyAction::Nearby => self.toggle_min_signal(),
+            KeyAction::PinConnected => self.toggle_pin_connected(),
+            KeyAction::TopN => self.toggle_limit_display(),
+            KeyAction::Meter => self.toggle_meter(),
+            KeyAction::Search => self.start_filter(),
+            KeyAction::Note => self.start_edit_note(),
+            KeyAction::MacFormat => self.cycle_mac_format(),
+            KeyAction::SpeedUnits => self.cycle_speed_units(),
+            // Handled by handle_global_action
+            KeyAction::Quit
+            | KeyAction::SwitchView
+            | KeyAction::Up
+            | KeyAction::Down
+            | KeyAction::PageUp
+            | KeyAction::PageDown
+            | KeyAction::First
+            | KeyAction::Last
+            | KeyAction::Log
+            | KeyAction::Help => {}
+            // Not bound in this view
+            KeyAction::DeviceDetail
+            | KeyAction::DeviceScan
+            | KeyAction::DeepScan
+            | KeyAction::Rename
+            | KeyAction::DeviceNotes
+            | KeyAction::DeviceTags
+            | KeyAction::DeviceSort
+            | KeyAction::AgentFilter
+            | KeyAction::Forget
+            | KeyAction::Reimport => {}
+        }
+        None
+    }
+
+    /// Network Devices view keys, including the rename dialog
+    fn handle_devices_key(&mut self, key: KeyEvent) {
+        if self.show_rename_dialog {
+            match key.code {
+                KeyCode::Enter => self.confirm_rename(),
+                KeyCode::Esc => self.cancel_rename(),
+                KeyCode::Backspace => self.rename_input_backspace(),
+                KeyCode::Char(c) => self.rename_input_char(c),
+                _ => {}
+            }
+            return;
+        }
+        if self.device_scan_progress.is_some() {
+            // Only cancelling is possible while a scan runs
+            if key.code == KeyCode::Esc {
+                self.cancel_device_scan();
+            }
+            return;
+        }
+        let Some(action) = self.keymap.action_for(KeyView::Devices, key) else {
+            return;
+        };
+        if self.handle_global_action(action) {
+            return;
+        }
+        match action {
+            KeyAction::DeviceDetail => self.toggle_device_detail(),
+            KeyAction::DeviceScan => self.start_device_scan(),
+            KeyAction::DeepScan => self.start_deep_scan(),
+            KeyAction::Rename => self.start_rename_device(),
+            KeyAction::DeviceNotes => self.start_device_edit(DeviceField::Notes),
+            KeyAction::DeviceTags => self.start_device_edit(DeviceField::Tags),
+            KeyAction::Copy => self.yank_selection(),
+            KeyAction::DeviceSort => self.cycle_device_sort(),
+            KeyAction::AgentFilter => self.toggle_agent_filter(),
+            KeyAction::AutoScan => self.toggle_device_auto_scan(),
+            KeyAction::MacFormat => self.cycle_mac_format(),
+            _ => {}
+        }
+    }
+
+    fn handle_known_key(&mut self, key: KeyEvent) {
+        let Some(action) = self.keymap.action_for(KeyView::Known, key) else {
+            return;
+        };
+        if self.handle_global_action(action) {
+            return;
+        }
+        match action {
+            KeyAction::Forget => self.show_forget_dialog(),
+            KeyAction::Reimport => self.refresh_known_networks(),
+            _ => {}
+        }
+    }
+
+    /// Run an action that doesn't block; returns false for the blocking ones
+    /// (connect, speed test), which the event loop runs after rendering
+    pub fn run_action(&mut self, action: AppAction) -> bool {
+        match action {
+            AppAction::Scan => self.start_scan(),
+            AppAction::EnableDemo => {
+                enable_demo_mode();
+                self.start_scan();
+            }
+            AppAction::Connect | AppAction::SpeedTest => return false,
+        }
+        true
+    }
 
-        // Try to maintain selection by MAC address
-        if let Some(mac) = selected_mac
-            && let Some(idx) = self.networks.iter().position(|n| n.mac == mac)
-        {
-            self.selected_index = idx;
+    /// Reload the per-network caches (connection history, IPs, speed
+    /// history) when the selected network changed since the last call
+    pub fn sync_selection_cache(&mut self) {
+        let current = (self.selected_index, self.selected_network().map(|n| n.mac.clone()));
+        if self.cached_selection.as_ref() == Some(&current) {
+            return;
         }
+        self.clear_connection_cache();
 
-        // Clamp selection index
-        if !self.networks.is_empty() {
-            self.selected_index = self.selected_index.min(self.networks.len() - 1);
-        } else {
-            self.selected_index = 0;
+        // Load connection data for newly selected network
+        if let Some(ref mac) = current.1 {
+            let _ = self.get_connection_history(mac);
+            let _ = self.get_recent_ips(mac);
+            self.load_speed_history(mac);
         }
+        self.cached_selection = Some(current);
+    }
 
-        self.last_scan = Instant::now();
-        self.is_scanning = false;
+    /// Periodic work on every tick: collect background results and start due scans
+    pub fn handle_tick(&mut self) {
+        // Collect log entries from background threads
+        self.poll_background_log();
 
-        // Load connection data for the selected network
-        self.load_selected_network_data();
+        // Check for background speed test completion
+        self.check_speedtest_result();
 
-        Ok(())
-    }
+        // Check for device scan progress
+        self.check_device_scan_progress();
 
-    /// Persist scan results to the database
-    fn persist_scan_results(
-        &self,
-        db: &Database,
-        location_id: i64,
-        networks: &[Network],
-    ) -> Result<()> {
-        let scan_id = db.create_scan(location_id)?;
+        // Keep device online status fresh while the devices view is open
+        self.check_liveness_result();
+        if self.liveness_check_due() {
+            self.start_liveness_check();
+        }
 
-        let records: Vec<ScanResultRecord> = networks
-            .iter()
-            .map(|n| ScanResultRecord {
-                bssid: n.mac.clone(),
-                ssid: n.ssid.clone(),
-                channel: n.channel,
-                signal_dbm: n.signal_dbm,
-                security: format!("{:?}", n.security),
-                frequency_band: format!("{:?}", n.frequency_band),
-                score: n.score,
-            })
-            .collect();
+        // Sample the ARP cache for device activity
+        self.check_arp_poll_result();
+        if self.arp_poll_due() {
+            self.start_arp_poll();
+        }
 
-        db.record_scan_results(scan_id, &records)?;
-        Ok(())
-    }
+        // Periodic device inventory refresh (devices view only)
+        if self.device_scan_due() {
+            self.start_device_scan();
+        }
 
-    fn sort_networks(&mut self) {
-        match self.sort_by {
-            SortField::Score => self.networks.sort_by(|a, b| b.score.cmp(&a.score)),
-            SortField::Signal => self.networks.sort_by(|a, b| b.signal_dbm.cmp(&a.signal_dbm)),
-            SortField::Name => self.networks.sort_by(|a, b| a.ssid.cmp(&b.ssid)),
+        // Apply a finished background WiFi scan
+        if self.poll_scan_result() {
+            self.clear_error();
+            let _ = self.refresh_current_connection();
+        }
+
+        // Check for auto-scan
+        if self.should_scan() {
+            self.start_scan();
         }
     }
 
     pub fn render(&self, frame: &mut Frame) {
+        let (min_width, min_height) = MIN_TERMINAL_SIZE;
+        if frame.area().width < min_width || frame.area().height < min_height {
+            self.render_too_small(frame);
+            return;
+        }
+
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
@@ -1091,21 +3536,40 @@ impl App {
 
         // Main content based on current view
         match self.current_view {
+            AppView::WifiNetworks if self.meter.is_some() => {
+                SignalMeter.render(frame, chunks[1], self);
+            }
             AppView::WifiNetworks => {
                 let main_chunks = Layout::default()
                     .direction(Direction::Horizontal)
                     .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
                     .split(chunks[1]);
 
+                self.page_size.set(table_page_size(main_chunks[0]));
                 NetworkTable.render(frame, main_chunks[0], self);
 
+                // Speed trend only takes space once the network has been speed tested
+                let speed_height = if self.selected_speed_history().is_empty() { 0 } else { 5 };
                 let detail_chunks = Layout::default()
                     .direction(Direction::Vertical)
-                    .constraints([Constraint::Min(10), Constraint::Length(5)])
+                    .constraints([
+                        Constraint::Min(10),
+                        Constraint::Length(5),
+                        Constraint::Length(speed_height),
+                    ])
                     .split(main_chunks[1]);
 
-                DetailPanel.render(frame, detail_chunks[0], self);
+                if self.show_channel_chart {
+                    ChannelChart.render(frame, detail_chunks[0], self);
+                } else if self.show_history_summary {
+                    HistorySummary.render(frame, detail_chunks[0], self);
+                } else {
+                    DetailPanel.render(frame, detail_chunks[0], self);
+                }
                 SignalChart.render(frame, detail_chunks[1], self);
+                if speed_height > 0 {
+                    SpeedChart.render(frame, detail_chunks[2], self);
+                }
             }
             AppView::NetworkDevices => {
                 let main_chunks = Layout::default()
@@ -1113,9 +3577,14 @@ impl App {
                     .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
                     .split(chunks[1]);
 
+                self.page_size.set(table_page_size(main_chunks[0]));
                 DeviceTable.render(frame, main_chunks[0], self);
                 DeviceDetail.render(frame, main_chunks[1], self);
             }
+            AppView::KnownNetworks => {
+                self.page_size.set(table_page_size(chunks[1]));
+                KnownNetworksTable.render(frame, chunks[1], self);
+            }
         }
 
         // Status bar
@@ -1134,48 +3603,106 @@ impl App {
         if self.show_rename_dialog {
             self.render_rename_dialog(frame);
         }
+        if self.show_note_dialog {
+            self.render_note_dialog(frame);
+        }
+        if self.show_forget_popup {
+            self.render_forget_popup(frame);
+        }
+        if self.show_filter_input {
+            self.render_filter_input(frame);
+        }
         if let Some(ref progress) = self.device_scan_progress {
             self.render_scan_progress_overlay(frame, progress);
         }
         if let Some(ref error) = self.error_message {
             self.render_error_overlay(frame, error);
         }
+        if self.show_log {
+            self.render_log_overlay(frame);
+        }
+    }
+
+    fn render_log_overlay(&self, frame: &mut Frame) {
+        use crate::theme::Theme;
+        use ratatui::text::{Line, Span};
+        use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+        let area = centered_rect(80, 70, frame.area());
+        let visible = area.height.saturating_sub(2) as usize;
+
+        // Newest entries at the bottom; scrolling walks back from there
+        let end = self.log.len().saturating_sub(self.log_scroll);
+        let start = end.saturating_sub(visible);
+        let lines: Vec<Line> = if self.log.is_empty() {
+            vec![Line::from(Span::styled("No log entries yet", Theme::help_style()))]
+        } else {
+            self.log.range(start..end).map(|entry| Line::from(entry.as_str())).collect()
+        };
+
+        let title = format!(" Log ({}/{}) ", end, self.log.len());
+        let paragraph = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Theme::border_style())
+                .title(Span::styled(title, Theme::title_style()))
+                .title_bottom(Line::from(" ↑/↓ scroll · l/Esc close ").right_aligned()),
+        );
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(paragraph, area);
     }
 
     fn render_connect_popup(&self, frame: &mut Frame) {
-        use ratatui::style::{Color, Style};
+        use crate::theme::Theme;
+        use ratatui::style::Color;
         use ratatui::text::{Line, Span};
         use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 
-        let area = centered_rect(40, 25, frame.area());
+        let area = centered_rect(40, 30, frame.area());
 
         let ssid = self
-            .networks
-            .get(self.selected_index)
+            .selected_network()
             .map(|n| n.ssid.as_str())
             .unwrap_or("Unknown");
 
-        let popup_text = vec![
+        // Saved networks connect without prompting for a password
+        let saved_status = if self.is_known_network(ssid) {
+            Span::styled("Known network — will auto-connect", Theme::fg(Color::Green))
+        } else {
+            Span::styled("New network — may require password", Theme::fg(Color::Yellow))
+        };
+
+        let mut popup_text = vec![
             Line::from(""),
             Line::from(format!("Connect to \"{}\"?", ssid)),
             Line::from(""),
+            Line::from(saved_status),
+        ];
+        if self.dry_run {
+            popup_text.push(Line::from(Span::styled(
+                "(dry run — nothing will change)",
+                Theme::fg(Color::Gray),
+            )));
+        }
+        popup_text.extend([
             Line::from(""),
             Line::from(vec![
-                Span::styled("[Y]", Style::default().fg(Color::Green)),
+                Span::styled("[Y]", Theme::fg(Color::Green)),
                 Span::raw("es    "),
-                Span::styled("[N]", Style::default().fg(Color::Red)),
+                Span::styled("[N]", Theme::fg(Color::Red)),
                 Span::raw("o"),
             ]),
-        ];
+        ]);
 
         let paragraph = Paragraph::new(popup_text)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::Cyan))
+                    .border_style(Theme::fg(Color::Cyan))
                     .title(Span::styled(
                         " Connect to Network? ",
-                        Style::default().fg(Color::Cyan),
+                        Theme::fg(Color::Cyan),
                     )),
             )
             .alignment(ratatui::layout::Alignment::Center);
@@ -1185,15 +3712,15 @@ impl App {
     }
 
     fn render_speedtest_popup(&self, frame: &mut Frame) {
-        use ratatui::style::{Color, Style};
+        use crate::theme::Theme;
+        use ratatui::style::Color;
         use ratatui::text::{Line, Span};
         use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 
         let area = centered_rect(45, 30, frame.area());
 
         let ssid = self
-            .networks
-            .get(self.selected_index)
+            .selected_network()
             .map(|n| n.ssid.as_str())
             .unwrap_or("Unknown");
 
@@ -1203,13 +3730,13 @@ impl App {
             Line::from(""),
             Line::from(Span::styled(
                 "(~10 seconds: 5s download + 5s upload)",
-                Style::default().fg(Color::Gray),
+                Theme::fg(Color::Gray),
             )),
             Line::from(""),
             Line::from(vec![
-                Span::styled("[Y]", Style::default().fg(Color::Green)),
+                Span::styled("[Y]", Theme::fg(Color::Green)),
                 Span::raw("es    "),
-                Span::styled("[N]", Style::default().fg(Color::Red)),
+                Span::styled("[N]", Theme::fg(Color::Red)),
                 Span::raw("o"),
             ]),
         ];
@@ -1218,10 +3745,10 @@ impl App {
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::Yellow))
+                    .border_style(Theme::fg(Color::Yellow))
                     .title(Span::styled(
                         " Speed Test? ",
-                        Style::default().fg(Color::Yellow),
+                        Theme::fg(Color::Yellow),
                     )),
             )
             .alignment(ratatui::layout::Alignment::Center);
@@ -1231,41 +3758,51 @@ impl App {
     }
 
     fn render_error_overlay(&self, frame: &mut Frame, error: &str) {
-        use ratatui::style::{Color, Style};
+        use crate::theme::Theme;
+        use ratatui::style::Color;
         use ratatui::text::{Line, Span};
         use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
 
         let area = centered_rect(70, 50, frame.area());
 
-        let error_text = vec![
+        let mut error_text = vec![
             Line::from(""),
             Line::from(Span::styled(
                 "WiFi Scan Failed",
-                Style::default().fg(Color::Red),
+                Theme::fg(Color::Red),
             )),
             Line::from(""),
             Line::from(error.to_string()),
             Line::from(""),
+        ];
+        if let Some(advice) = self.error_advice {
+            error_text.push(Line::from(Span::styled(
+                format!("Tip: {}", advice),
+                Theme::fg(Color::Yellow),
+            )));
+            error_text.push(Line::from(""));
+        }
+        error_text.extend([
             Line::from(""),
             Line::from(Span::styled(
-                "Tip: Run with --demo flag to see the app with simulated networks:",
-                Style::default().fg(Color::Yellow),
+                "Run with --demo flag to see the app with simulated networks:",
+                Theme::fg(Color::Yellow),
             )),
             Line::from(""),
             Line::from("  cargo run -- --demo"),
             Line::from(""),
             Line::from(""),
             Line::from("Press 'd' to switch to demo mode, or 'q' to quit"),
-        ];
+        ]);
 
         let paragraph = Paragraph::new(error_text)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::Red))
+                    .border_style(Theme::fg(Color::Red))
                     .title(Span::styled(
                         " Error ",
-                        Style::default().fg(Color::Red),
+                        Theme::fg(Color::Red),
                     )),
             )
             .wrap(Wrap { trim: true });
@@ -1275,20 +3812,27 @@ impl App {
     }
 
     fn render_header_with_tabs(&self, frame: &mut Frame, area: Rect) {
-        use ratatui::style::{Color, Modifier, Style};
+        use crate::theme::Theme;
+        use ratatui::style::{Color, Modifier};
         use ratatui::text::{Line, Span};
         use ratatui::widgets::Paragraph;
 
         let wifi_style = if matches!(self.current_view, AppView::WifiNetworks) {
-            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+            Theme::fg(Color::Cyan).add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Gray)
+            Theme::fg(Color::Gray)
         };
 
         let devices_style = if matches!(self.current_view, AppView::NetworkDevices) {
-            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+            Theme::fg(Color::Cyan).add_modifier(Modifier::BOLD)
+        } else {
+            Theme::fg(Color::Gray)
+        };
+
+        let known_style = if matches!(self.current_view, AppView::KnownNetworks) {
+            Theme::fg(Color::Cyan).add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::Gray)
+            Theme::fg(Color::Gray)
         };
 
         let line = Line::from(vec![
@@ -1296,8 +3840,10 @@ impl App {
             Span::styled("[WiFi Networks]", wifi_style),
             Span::raw("  "),
             Span::styled("[Network Devices]", devices_style),
-            Span::raw("                              "),
-            Span::styled("Tab", Style::default().fg(Color::DarkGray)),
+            Span::raw("  "),
+            Span::styled("[Known Networks]", known_style),
+            Span::raw("                "),
+            Span::styled("Tab", Theme::fg(Color::DarkGray)),
             Span::raw(" to switch"),
         ]);
 
@@ -1305,27 +3851,90 @@ impl App {
         frame.render_widget(paragraph, area);
     }
 
-    fn render_help_overlay(&self, frame: &mut Frame) {
-        use ratatui::text::{Line, Span};
-        use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+    /// Help overlay contents: each view's bindings as currently mapped,
+    /// then the score legend
+    fn help_lines(&self) -> Vec<ratatui::text::Line<'static>> {
         use crate::theme::Theme;
+        use ratatui::text::{Line, Span};
 
-        let area = centered_rect(50, 60, frame.area());
+        const SECTIONS: &[(&str, &[(KeyAction, &str)])] = &[
+            (
+                "Everywhere",
+                &[
+                    (KeyAction::Up, "Move up"),
+                    (KeyAction::Down, "Move down"),
+                    (KeyAction::PageUp, "Jump a page up"),
+                    (KeyAction::PageDown, "Jump a page down"),
+                    (KeyAction::First, "First row"),
+                    (KeyAction::Last, "Last row"),
+                    (KeyAction::SwitchView, "Switch view"),
+                    (KeyAction::Log, "Toggle diagnostics log"),
+                    (KeyAction::Help, "Toggle this help"),
+                    (KeyAction::Quit, "Quit"),
+                ],
+            ),
+            (
+                "WiFi Networks",
+                &[
+                    (KeyAction::Connect, "Connect to network"),
+                    (KeyAction::Scan, "Refresh scan"),
+                    (KeyAction::Demo, "Load demo networks"),
+                    (KeyAction::AutoScan, "Toggle auto/manual mode"),
+                    (KeyAction::IntervalUp, "Lengthen auto-refresh interval"),
+                    (KeyAction::IntervalDown, "Shorten auto-refresh interval"),
+                    (KeyAction::Search, "Search networks by SSID"),
+                    (KeyAction::Filter, "Cycle filter (all/open/secured/known)"),
+                    (KeyAction::Band, "Cycle band filter (all/2.4/5/6 GHz)"),
+                    (KeyAction::Nearby, "Toggle nearby only (hide weak signals)"),
+                    (KeyAction::TopN, "Show only the top N networks"),
+                    (KeyAction::Sort, "Cycle sort order"),
+                    (KeyAction::PinConnected, "Pin connected network to the top"),
+                    (KeyAction::Gateway, "Show the connected network's router"),
+                    (KeyAction::ChannelChart, "Toggle channel usage chart"),
+                    (KeyAction::History, "Toggle historical summary"),
+                    (KeyAction::Meter, "Signal meter for the selected network"),
+                    (KeyAction::Note, "Edit note for network"),
+                    (KeyAction::Copy, "Copy selection to clipboard"),
+                    (KeyAction::MacFormat, "Cycle MAC address format"),
+                    (KeyAction::SpeedUnits, "Toggle speed units (Mbps / MB/s)"),
+                ],
+            ),
+            (
+                "Network Devices",
+                &[
+                    (KeyAction::DeviceDetail, "Show device details"),
+                    (KeyAction::DeviceScan, "Scan for devices"),
+                    (KeyAction::DeepScan, "Scan every port of the device"),
+                    (KeyAction::AutoScan, "Toggle device auto-scan"),
+                    (KeyAction::Rename, "Rename device"),
+                    (KeyAction::DeviceNotes, "Edit device notes"),
+                    (KeyAction::DeviceTags, "Edit device tags"),
+                    (KeyAction::DeviceSort, "Cycle device sort"),
+                    (KeyAction::AgentFilter, "Show only AI-agent devices"),
+                    (KeyAction::Copy, "Copy selection to clipboard"),
+                    (KeyAction::MacFormat, "Cycle MAC address format"),
+                ],
+            ),
+            (
+                "Known Networks",
+                &[
+                    (KeyAction::Forget, "Forget network"),
+                    (KeyAction::Reimport, "Re-import saved networks"),
+                ],
+            ),
+        ];
 
-        let help_text = vec![
-            Line::from(""),
-            Line::from(Span::styled("Keyboard Shortcuts", Theme::title_style())),
-            Line::from(""),
-            Line::from("\u{2191}/\u{2193} or j/k   Navigate networks"),
-            Line::from("Enter          Connect to network"),
-            Line::from("r              Refresh scan"),
-            Line::from("a              Toggle auto/manual mode"),
-            Line::from("s              Cycle sort order"),
-            Line::from("?              Toggle this help"),
-            Line::from("q / Esc        Quit"),
-            Line::from(""),
+        let mut lines = vec![Line::from("")];
+        for (title, bindings) in SECTIONS {
+            lines.push(Line::from(Span::styled(*title, Theme::title_style())));
+            for (action, description) in *bindings {
+                lines.push(Line::from(format!("{:<14} {}", self.keymap.describe(*action), description)));
+            }
+            lines.push(Line::from(""));
+        }
+
+        lines.extend([
             Line::from(Span::styled("Score Legend", Theme::title_style())),
-            Line::from(""),
             Line::from(vec![
                 Span::styled("80-100", Theme::score_style(90)),
                 Span::raw("  Excellent"),
@@ -1342,15 +3951,38 @@ impl App {
                 Span::styled("0-39  ", Theme::score_style(20)),
                 Span::raw("  Poor"),
             ]),
-            Line::from(""),
-            Line::from("Press ? to close"),
-        ];
+        ]);
+        lines
+    }
+
+    fn render_help_overlay(&self, frame: &mut Frame) {
+        use ratatui::text::{Line, Span};
+        use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+        use crate::theme::Theme;
 
+        let area = centered_rect(50, 60, frame.area());
+        let visible = area.height.saturating_sub(2) as usize;
+        self.help_page_size.set(visible);
+
+        let lines = self.help_lines();
+        let total = lines.len();
+        let start = self.help_scroll.min(total.saturating_sub(visible));
+        let end = (start + visible).min(total);
+        let help_text: Vec<Line> = lines.into_iter().skip(start).take(visible).collect();
+
+        let title = format!(" Help ({}-{}/{}) ", start + 1, end, total);
+        let footer = format!(
+            " {}/{} scroll · {} close ",
+            self.keymap.describe(KeyAction::Up),
+            self.keymap.describe(KeyAction::Down),
+            self.keymap.describe(KeyAction::Help),
+        );
         let paragraph = Paragraph::new(help_text).block(
             Block::default()
                 .borders(Borders::ALL)
                 .style(Theme::border_style())
-                .title(Span::styled(" Help ", Theme::title_style())),
+                .title(Span::styled(title, Theme::title_style()))
+                .title_bottom(Line::from(footer).right_aligned()),
         );
 
         frame.render_widget(Clear, area);
@@ -1358,25 +3990,70 @@ impl App {
     }
 
     fn render_rename_dialog(&self, frame: &mut Frame) {
-        use ratatui::style::{Color, Style};
+        use crate::theme::Theme;
+        use ratatui::style::Color;
         use ratatui::text::{Line, Span};
-        use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+        use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
 
         let area = centered_rect(50, 25, frame.area());
 
         let lines = vec![
             Line::from(""),
-            Line::from("Enter a custom name for this device:"),
+            Line::from(self.rename_field.prompt()),
             Line::from(""),
             Line::from(Span::styled(
                 format!("{}_", self.rename_input),
-                Style::default().fg(Color::Cyan),
+                Theme::fg(Color::Cyan),
+            )),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("[Enter]", Theme::fg(Color::Green)),
+                Span::raw(" Save  "),
+                Span::styled("[Esc]", Theme::fg(Color::Red)),
+                Span::raw(" Cancel"),
+            ]),
+        ];
+
+        let paragraph = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Theme::fg(Color::Cyan))
+                    .title(Span::styled(self.rename_field.title(), Theme::fg(Color::Cyan))),
+            )
+            .alignment(ratatui::layout::Alignment::Center)
+            .wrap(Wrap { trim: false });
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(paragraph, area);
+    }
+
+    fn render_note_dialog(&self, frame: &mut Frame) {
+        use crate::theme::Theme;
+        use ratatui::style::Color;
+        use ratatui::text::{Line, Span};
+        use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+
+        let area = centered_rect(60, 30, frame.area());
+
+        let ssid = self
+            .selected_network()
+            .map(|n| n.ssid.as_str())
+            .unwrap_or("Unknown");
+
+        let lines = vec![
+            Line::from(""),
+            Line::from(format!("Note for \"{}\" (empty to remove):", ssid)),
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("{}_", self.note_input),
+                Theme::fg(Color::Cyan),
             )),
             Line::from(""),
             Line::from(vec![
-                Span::styled("[Enter]", Style::default().fg(Color::Green)),
+                Span::styled("[Enter]", Theme::fg(Color::Green)),
                 Span::raw(" Save  "),
-                Span::styled("[Esc]", Style::default().fg(Color::Red)),
+                Span::styled("[Esc]", Theme::fg(Color::Red)),
                 Span::raw(" Cancel"),
             ]),
         ];
@@ -1385,8 +4062,90 @@ impl App {
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::Cyan))
-                    .title(Span::styled(" Rename Device ", Style::default().fg(Color::Cyan))),
+                    .border_style(Theme::fg(Color::Cyan))
+                    .title(Span::styled(" Network Note ", Theme::fg(Color::Cyan))),
+            )
+            .alignment(ratatui::layout::Alignment::Center)
+            .wrap(Wrap { trim: false });
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(paragraph, area);
+    }
+
+    fn render_forget_popup(&self, frame: &mut Frame) {
+        use crate::theme::Theme;
+        use ratatui::style::Color;
+        use ratatui::text::{Line, Span};
+        use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+        let area = centered_rect(45, 30, frame.area());
+
+        let ssid = self
+            .selected_known_network()
+            .map(|n| n.ssid.as_str())
+            .unwrap_or("Unknown");
+
+        let popup_text = vec![
+            Line::from(""),
+            Line::from(format!("Forget \"{}\"?", ssid)),
+            Line::from(""),
+            Line::from(Span::styled(
+                "(Also removes it from the system's preferred networks)",
+                Theme::fg(Color::Gray),
+            )),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("[Y]", Theme::fg(Color::Green)),
+                Span::raw("es    "),
+                Span::styled("[N]", Theme::fg(Color::Red)),
+                Span::raw("o"),
+            ]),
+        ];
+
+        let paragraph = Paragraph::new(popup_text)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Theme::fg(Color::Red))
+                    .title(Span::styled(" Forget Network? ", Theme::fg(Color::Red))),
+            )
+            .alignment(ratatui::layout::Alignment::Center);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(paragraph, area);
+    }
+
+    fn render_filter_input(&self, frame: &mut Frame) {
+        use crate::theme::Theme;
+        use ratatui::style::Color;
+        use ratatui::text::{Line, Span};
+        use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+        let area = centered_rect(40, 20, frame.area());
+
+        let lines = vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("{}_", self.filter_query),
+                Theme::fg(Color::Cyan),
+            )),
+            Line::from(""),
+            Line::from(format!("{} matching", self.filtered_count())),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("[Enter]", Theme::fg(Color::Green)),
+                Span::raw(" Apply  "),
+                Span::styled("[Esc]", Theme::fg(Color::Red)),
+                Span::raw(" Clear"),
+            ]),
+        ];
+
+        let paragraph = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Theme::fg(Color::Cyan))
+                    .title(Span::styled(" Search SSID ", Theme::fg(Color::Cyan))),
             )
             .alignment(ratatui::layout::Alignment::Center);
 
@@ -1394,15 +4153,54 @@ impl App {
         frame.render_widget(paragraph, area);
     }
 
+    /// Shown instead of the normal layout, whose constraints can't be met in a tiny window
+    fn render_too_small(&self, frame: &mut Frame) {
+        use crate::theme::Theme;
+        use ratatui::layout::Alignment;
+        use ratatui::widgets::{Paragraph, Wrap};
+
+        let (min_width, min_height) = MIN_TERMINAL_SIZE;
+        let area = frame.area();
+        let message = format!(
+            "Terminal too small — resize to at least {}×{} (now {}×{})",
+            min_width, min_height, area.width, area.height
+        );
+        // Rough wrapped height, enough to centre the text vertically
+        let lines = message.chars().count().div_ceil(area.width.max(1) as usize) + 1;
+        let height = (lines as u16).min(area.height);
+        let paragraph = Paragraph::new(message)
+            .style(Theme::warning_style())
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+
+        let text_area = Rect {
+            y: area.y + (area.height - height) / 2,
+            height,
+            ..area
+        };
+        frame.render_widget(paragraph, text_area);
+    }
+
     fn render_scan_progress_overlay(&self, frame: &mut Frame, progress: &crate::network_map::ScanProgress) {
         use crate::network_map::ScanPhase;
-        use ratatui::style::{Color, Style};
+        use crate::theme::Theme;
+        use ratatui::style::Color;
         use ratatui::text::{Line, Span};
         use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 
         let area = centered_rect(40, 20, frame.area());
 
         let phase_str = format!("{}", progress.phase);
+        let title = if self.deep_scan_target.is_some() {
+            " Deep Port Scan "
+        } else {
+            " Scanning Network "
+        };
+
+        let bar = |pct: usize| {
+            let filled = pct.min(100) / 5;
+            format!("[{}{}] {}%", "\u{2588}".repeat(filled), "\u{2591}".repeat(20 - filled), pct)
+        };
 
         // Phase-specific progress display
         let (progress_bar, detail_line) = match progress.phase {
@@ -1412,20 +4210,35 @@ impl App {
                     .duration_since(std::time::UNIX_EPOCH)
                     .unwrap()
                     .as_millis() / 250) as usize % 4;
-                (
-                    format!("  {}  Scanning ARP cache...", spinner[idx]),
-                    format!("{} devices found so far", progress.devices_found),
-                )
+                if progress.total_ports > 0 {
+                    // Ping sweep: progress counts addresses rather than ports
+                    (
+                        match progress.percent {
+                            // Hosts that replied are already being port scanned
+                            Some(pct) => bar(usize::from(pct)),
+                            None => format!("  {}  Ping sweep...", spinner[idx]),
+                        },
+                        format!("Pinged {}/{} addresses", progress.ports_scanned, progress.total_ports),
+                    )
+                } else {
+                    (
+                        format!("  {}  Scanning ARP cache...", spinner[idx]),
+                        format!("{} devices found so far", progress.devices_found),
+                    )
+                }
             }
             ScanPhase::PortScan => {
                 let device_str = progress.current_device.as_deref().unwrap_or("...");
                 if progress.total_ports > 0 {
-                    let pct = (progress.ports_scanned * 100) / progress.total_ports;
-                    let filled = pct / 5;
-                    let empty = 20 - filled;
+                    let pct = progress
+                        .percent
+                        .map_or((progress.ports_scanned * 100) / progress.total_ports, usize::from);
                     (
-                        format!("[{}{}] {}%", "\u{2588}".repeat(filled), "\u{2591}".repeat(empty), pct),
-                        format!("Scanning: {}", device_str),
+                        bar(pct),
+                        format!(
+                            "Scanning: {} ({}/{} ports)",
+                            device_str, progress.ports_scanned, progress.total_ports
+                        ),
                     )
                 } else {
                     (
@@ -1455,22 +4268,22 @@ impl App {
 
         let lines = vec![
             Line::from(""),
-            Line::from(Span::styled(&phase_str, Style::default().fg(Color::Cyan))),
+            Line::from(Span::styled(&phase_str, Theme::fg(Color::Cyan))),
             Line::from(""),
             Line::from(progress_bar),
             Line::from(""),
             Line::from(format!("Devices found: {}", progress.devices_found)),
             Line::from(detail_line),
             Line::from(""),
-            Line::from(Span::styled("[Esc] Cancel", Style::default().fg(Color::Gray))),
+            Line::from(Span::styled("[Esc] Cancel", Theme::fg(Color::Gray))),
         ];
 
         let paragraph = Paragraph::new(lines)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::Yellow))
-                    .title(Span::styled(" Scanning Network ", Style::default().fg(Color::Yellow))),
+                    .border_style(Theme::fg(Color::Yellow))
+                    .title(Span::styled(title, Theme::fg(Color::Yellow))),
             )
             .alignment(ratatui::layout::Alignment::Center);
 
@@ -1479,6 +4292,22 @@ impl App {
     }
 }
 
+/// Prefix a log message with the local time
+fn timestamped(msg: &str) -> String {
+    format!("{} {}", chrono::Local::now().format("%H:%M:%S"), msg)
+}
+
+/// Log from a background thread; the entry is timestamped now and shows up
+/// in the app's log on the next `poll_background_log`
+fn log_background(msg: String) {
+    BACKGROUND_LOG.lock().unwrap().push(timestamped(&msg));
+}
+
+/// Data rows visible in a bordered table with a one-line header
+fn table_page_size(area: Rect) -> usize {
+    usize::from(area.height.saturating_sub(3)).max(1)
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -1499,40 +4328,12 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         .split(popup_layout[1])[1]
 }
 
-/// Get the current WiFi channel from system_profiler
-fn get_current_channel() -> Option<u32> {
+/// Get the current WiFi channel and band from system_profiler
+fn get_current_channel() -> Option<(u8, FrequencyBand)> {
     let output = std::process::Command::new("system_profiler")
         .args(["SPAirPortDataType"])
         .output()
         .ok()?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut in_current_network = false;
-
-    for line in stdout.lines() {
-        let trimmed = line.trim();
-
-        if trimmed.contains("Current Network Information:") {
-            in_current_network = true;
-            continue;
-        }
-
-        if in_current_network && trimmed.starts_with("Channel:") {
-            // Parse "Channel: 37 (6GHz, 160MHz)" format
-            let channel_part = trimmed.strip_prefix("Channel:")?.trim();
-            let channel_num = channel_part
-                .split_whitespace()
-                .next()?
-                .parse::<u32>()
-                .ok()?;
-            return Some(channel_num);
-        }
-
-        // Stop if we've moved past the current network section
-        if in_current_network && (trimmed.starts_with("Other Local") || trimmed.is_empty() && line.len() < 10) {
-            break;
-        }
-    }
-
-    None
+    parse_current_channel(&String::from_utf8_lossy(&output.stdout))
 }
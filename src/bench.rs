@@ -0,0 +1,113 @@
+//! `bench` subcommand: time scan parsing, scoring and sorting on demo data.
+//! Nothing touches the WiFi hardware or the network, and the demo signals
+//! come from a fixed seed, so runs are comparable.
+
+use crate::app::SortField;
+use crate::scanner::{demo_networks, parse_swift_scanner_output};
+use crate::scoring::calculate_all_scores;
+use std::time::{Duration, Instant};
+
+/// Seed for the demo signal jitter, fixed so every run parses the same scan
+const DEMO_SEED: u64 = 42;
+
+/// One sample per iteration for each stage
+#[derive(Debug, Clone, Default)]
+pub struct BenchTimings {
+    pub parse: Vec<Duration>,
+    pub score: Vec<Duration>,
+    pub sort: Vec<Duration>,
+}
+
+impl BenchTimings {
+    pub fn stages(&self) -> [(&'static str, &[Duration]); 3] {
+        [("parse", &self.parse), ("score", &self.score), ("sort", &self.sort)]
+    }
+}
+
+/// Spread of one stage's samples, in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Percentiles {
+    pub min: f64,
+    pub median: f64,
+    pub p95: f64,
+    pub max: f64,
+}
+
+impl Percentiles {
+    /// Nearest-rank percentiles, or None without samples
+    pub fn of(samples: &[Duration]) -> Option<Self> {
+        let mut ms: Vec<f64> = samples.iter().map(|d| d.as_nanos() as f64 / 1_000_000.0).collect();
+        ms.sort_by(f64::total_cmp);
+        let rank = |percent: usize| ms[(ms.len() * percent).div_ceil(100).saturating_sub(1)];
+        Some(Percentiles {
+            min: *ms.first()?,
+            median: rank(50),
+            p95: rank(95),
+            max: *ms.last()?,
+        })
+    }
+}
+
+/// The demo networks as the Swift scanner prints them: SSID|BSSID|CHANNEL|RSSI|SECURITY
+pub fn demo_scan_output() -> String {
+    demo_networks(DEMO_SEED)
+        .iter()
+        .map(|n| format!("{}|{}|{}|{}|{}\n", n.ssid, n.mac, n.channel, n.signal_dbm, n.security.as_str()))
+        .collect()
+}
+
+/// Parse, score and sort the demo scan `iterations` times
+pub fn run(iterations: usize) -> BenchTimings {
+    let output = demo_scan_output();
+    let mut timings = BenchTimings::default();
+
+    for _ in 0..iterations {
+        let start = Instant::now();
+        let mut networks = parse_swift_scanner_output(&output);
+        timings.parse.push(start.elapsed());
+
+        let start = Instant::now();
+        calculate_all_scores(&mut networks);
+        timings.score.push(start.elapsed());
+
+        let start = Instant::now();
+        SortField::Score.sort(&mut networks);
+        timings.sort.push(start.elapsed());
+
+        std::hint::black_box(&networks);
+    }
+    timings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles() {
+        let samples: Vec<Duration> = (1..=100).rev().map(Duration::from_millis).collect();
+        let p = Percentiles::of(&samples).unwrap();
+        assert_eq!((p.min, p.median, p.p95, p.max), (1.0, 50.0, 95.0, 100.0));
+
+        let single = Percentiles::of(&[Duration::from_micros(1500)]).unwrap();
+        assert_eq!((single.min, single.median, single.p95, single.max), (1.5, 1.5, 1.5, 1.5));
+        assert!(Percentiles::of(&[]).is_none());
+    }
+
+    #[test]
+    fn test_demo_output_round_trips() {
+        let parsed = parse_swift_scanner_output(&demo_scan_output());
+        let demo = demo_networks(DEMO_SEED);
+        assert_eq!(parsed.len(), demo.len());
+        for (parsed, demo) in parsed.iter().zip(&demo) {
+            assert_eq!((&parsed.ssid, &parsed.mac, parsed.signal_dbm), (&demo.ssid, &demo.mac, demo.signal_dbm));
+            assert_eq!(parsed.security, demo.security);
+        }
+    }
+
+    #[test]
+    fn test_run_collects_every_iteration() {
+        let timings = run(5);
+        assert!(timings.stages().iter().all(|(_, samples)| samples.len() == 5));
+    }
+}
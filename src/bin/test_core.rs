@@ -1,6 +1,5 @@
 //! Test binary to verify core functionality without TUI
 
-use chrono::Utc;
 use std::time::Duration;
 use wifi_analyzer::app::{App, ScanMode, SortField};
 use wifi_analyzer::scanner::{enable_demo_mode, scan_networks, FrequencyBand, Network, SecurityType};
@@ -46,34 +45,18 @@ async fn main() {
     println!("2. Testing Scoring System...");
     let mut test_networks = vec![
         Network {
-            ssid: "StrongOpen5G".to_string(),
             mac: "AA:BB:CC:DD:EE:FF".to_string(),
-            channel: 36,
-            signal_dbm: -40,
             security: SecurityType::Open,
-            frequency_band: FrequencyBand::Band5GHz,
-            score: 0,
-            last_seen: Utc::now(),
+            ..Network::test("StrongOpen5G", 36, -40)
         },
         Network {
-            ssid: "WeakSecured24".to_string(),
             mac: "11:22:33:44:55:66".to_string(),
-            channel: 6,
-            signal_dbm: -85,
-            security: SecurityType::WPA2,
-            frequency_band: FrequencyBand::Band2_4GHz,
-            score: 0,
-            last_seen: Utc::now(),
+            ..Network::test("WeakSecured24", 6, -85)
         },
         Network {
-            ssid: "MediumOpen".to_string(),
             mac: "AA:11:BB:22:CC:33".to_string(),
-            channel: 6,
-            signal_dbm: -60,
             security: SecurityType::Open,
-            frequency_band: FrequencyBand::Band2_4GHz,
-            score: 0,
-            last_seen: Utc::now(),
+            ..Network::test("MediumOpen", 6, -60)
         },
     ];
 
@@ -103,6 +86,9 @@ async fn main() {
         (SecurityType::WPA, "WPA"),
         (SecurityType::WPA2, "WPA2"),
         (SecurityType::WPA3, "WPA3"),
+        (SecurityType::WPA2WPA3, "WPA2/WPA3"),
+        (SecurityType::OWE, "OWE"),
+        (SecurityType::Enterprise, "Enterprise"),
         (SecurityType::Unknown, "Unknown"),
     ];
     let mut display_pass = true;
@@ -164,14 +150,9 @@ async fn main() {
     let mut bars_pass = true;
     for (dbm, expected_bars) in signal_tests {
         let net = Network {
-            ssid: "test".to_string(),
             mac: String::new(),
-            channel: 1,
-            signal_dbm: dbm,
             security: SecurityType::Open,
-            frequency_band: FrequencyBand::Band2_4GHz,
-            score: 0,
-            last_seen: Utc::now(),
+            ..Network::test("test", 1, dbm)
         };
         let bars = net.signal_bars();
         let filled_count = bars.chars().filter(|c| *c == '▓').count();
@@ -1,8 +1,21 @@
 //! Quick test for the database
 
-use wifi_analyzer::db::{Database, ScanResultRecord};
+use wifi_analyzer::db::{Database, DbError, ScanResultRecord};
 
 fn main() {
+    // Child mode for test 6: open the file as a second process and report how it went
+    let args: Vec<String> = std::env::args().collect();
+    if let [_, flag, path] = args.as_slice()
+        && flag == "--open-second"
+    {
+        match Database::open(path) {
+            Ok(_) => println!("OPENED"),
+            Err(e) if e.downcast_ref::<DbError>().is_some() => println!("LOCKED"),
+            Err(e) => println!("ERROR {}", e),
+        }
+        return;
+    }
+
     // Clean up any existing test database
     let _ = std::fs::remove_file("test_db.duckdb");
     let _ = std::fs::remove_file("test_db.duckdb.wal");
@@ -94,6 +107,27 @@ fn main() {
         }
     }
 
+    // Test 6: A second process opening the same file gets a lock error
+    println!("6. Opening the database from a second process...");
+    let child = std::env::current_exe().and_then(|exe| {
+        std::process::Command::new(exe)
+            .args(["--open-second", "test_db.duckdb"])
+            .output()
+    });
+    match child {
+        Ok(output) if String::from_utf8_lossy(&output.stdout).trim() == "LOCKED" => {
+            println!("   SUCCESS: Second instance reported DbError::Locked\n");
+        }
+        Ok(output) => {
+            println!("   FAILED: Second instance said {}\n", String::from_utf8_lossy(&output.stdout).trim());
+            return;
+        }
+        Err(e) => {
+            println!("   FAILED: Could not start second instance: {}\n", e);
+            return;
+        }
+    }
+
     println!("All database tests passed!");
 
     // Cleanup
@@ -0,0 +1,359 @@
+//! Test binary that scripts key sequences through the headless event driver
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+use std::time::Duration;
+use wifi_analyzer::app::{App, AppAction, AppView};
+use wifi_analyzer::config::Config;
+use wifi_analyzer::event::{drive, key, Event};
+use wifi_analyzer::keymap::Keymap;
+use wifi_analyzer::network_map::Device;
+use wifi_analyzer::scanner::{enable_demo_mode, Network};
+
+/// Type a string into whichever input has focus
+fn typed(text: &str) -> Vec<Event> {
+    text.chars().map(|c| key(KeyCode::Char(c))).collect()
+}
+
+#[tokio::main]
+async fn main() {
+    println!("=== WiFi Analyzer Driver Tests ===\n");
+
+    let mut all_passed = true;
+    enable_demo_mode();
+
+    let mut app = App::new(Duration::from_secs(5), false);
+    app.perform_scan().await.unwrap();
+
+    // Test 1: Tab cycles through the three views and back
+    println!("1. Testing view switching...");
+    let mut views = vec![app.current_view];
+    for _ in 0..3 {
+        drive(&mut app, &[key(KeyCode::Tab)]);
+        views.push(app.current_view);
+    }
+    let expected = [
+        AppView::WifiNetworks,
+        AppView::NetworkDevices,
+        AppView::KnownNetworks,
+        AppView::WifiNetworks,
+    ];
+    if views == expected {
+        println!("   ✓ WiFi → Devices → Known → WiFi");
+    } else {
+        println!("   ✗ Unexpected view sequence: {:?}", views);
+        all_passed = false;
+    }
+
+    // Test 2: Arrow keys and j/k move the selection, clamped at the top
+    println!("2. Testing navigation...");
+    drive(&mut app, &[key(KeyCode::Down), key(KeyCode::Char('j')), key(KeyCode::Up)]);
+    let after_moves = app.selected_index;
+    drive(&mut app, &[key(KeyCode::Char('k')), key(KeyCode::Char('k')), key(KeyCode::Char('k'))]);
+    if after_moves == 1 && app.selected_index == 0 {
+        println!("   ✓ Down, j, Up selects row 1; k stops at row 0");
+    } else {
+        println!("   ✗ Selection {} then {}", after_moves, app.selected_index);
+        all_passed = false;
+    }
+
+    // Test 3: Enter opens the connect dialog, n cancels, y hands back a connect action
+    println!("3. Testing connect dialog flow...");
+    drive(&mut app, &[key(KeyCode::Enter)]);
+    let opened = app.show_connect_popup;
+    let cancelled = drive(&mut app, &[key(KeyCode::Char('n'))]);
+    let closed = !app.show_connect_popup;
+    let confirmed = drive(&mut app, &[key(KeyCode::Enter), key(KeyCode::Char('y'))]);
+    if opened && closed && cancelled.is_empty()
+        && confirmed == vec![AppAction::Connect]
+        && !app.show_connect_popup
+        && app.status_message.as_deref() == Some("Connecting...")
+    {
+        println!("   ✓ Dialog opens, cancels, and confirms with a Connect action");
+    } else {
+        println!("   ✗ Unexpected dialog state (actions {:?})", confirmed);
+        all_passed = false;
+    }
+
+    // Test 4: Search input captures keys until Enter
+    println!("4. Testing search input...");
+    let ssid = app.networks[2].ssid.clone();
+    let query: String = ssid.chars().take(5).collect();
+    let mut events = vec![key(KeyCode::Char('/'))];
+    events.extend(typed(&query));
+    events.push(key(KeyCode::Enter));
+    drive(&mut app, &events);
+    let matches = app
+        .visible_networks()
+        .iter()
+        .all(|n| n.ssid.to_lowercase().contains(&query.to_lowercase()));
+    if app.filter_query == query && !app.show_filter_input && matches && app.current_view == AppView::WifiNetworks {
+        println!("   ✓ Typing \"{}\" filtered the table without triggering shortcuts", query);
+    } else {
+        println!("   ✗ Filter \"{}\" not applied", app.filter_query);
+        all_passed = false;
+    }
+    drive(&mut app, &[key(KeyCode::Esc)]);
+
+    // Test 5: The log overlay takes j/k for scrolling instead of navigation
+    println!("5. Testing log overlay keys...");
+    let before = app.selected_index;
+    drive(&mut app, &[key(KeyCode::Char('l')), key(KeyCode::Char('j'))]);
+    let open = app.show_log;
+    drive(&mut app, &[key(KeyCode::Esc)]);
+    if open && !app.show_log && app.selected_index == before && !app.should_quit {
+        println!("   ✓ Log opens on l, swallows j, and closes on Esc without quitting");
+    } else {
+        println!("   ✗ Log overlay leaked keys to the table");
+        all_passed = false;
+    }
+
+    // Test 6: r and d hand back scan actions instead of spawning scans
+    println!("6. Testing scan actions...");
+    let actions = drive(&mut app, &[key(KeyCode::Char('r')), key(KeyCode::Char('d'))]);
+    if actions == [AppAction::Scan, AppAction::EnableDemo] && !app.is_scanning {
+        println!("   ✓ Scan and demo mode returned for the event loop to run");
+    } else {
+        println!("   ✗ Expected [Scan, EnableDemo], got {:?} (scanning: {})", actions, app.is_scanning);
+        all_passed = false;
+    }
+
+    // Test 7: Sort and interval keys change state without returning actions
+    println!("7. Testing sort and interval keys...");
+    let sort_before = app.sort_by;
+    let interval_before = app.auto_interval;
+    let actions = drive(&mut app, &[key(KeyCode::Char('s')), key(KeyCode::Char('+'))]);
+    if actions.is_empty() && app.sort_by != sort_before && app.auto_interval > interval_before {
+        println!("   ✓ Sort cycled to {:?}, interval raised to {}s", app.sort_by, app.auto_interval.as_secs());
+    } else {
+        println!("   ✗ Sort or interval unchanged");
+        all_passed = false;
+    }
+    drive(&mut app, &[key(KeyCode::Char('-'))]);
+
+    // Test 8: ? toggles help in every view
+    println!("8. Testing help toggle...");
+    let mut toggled = true;
+    for _ in 0..3 {
+        drive(&mut app, &[key(KeyCode::Char('?'))]);
+        toggled &= app.show_help;
+        drive(&mut app, &[key(KeyCode::Char('?')), key(KeyCode::Tab)]);
+        toggled &= !app.show_help;
+    }
+    if toggled && app.current_view == AppView::WifiNetworks {
+        println!("   ✓ Help opens and closes in all three views");
+    } else {
+        println!("   ✗ Help toggle inconsistent across views");
+        all_passed = false;
+    }
+
+    // Test 9: A keymap from the [keys] config section remaps scan and sort
+    println!("9. Testing custom keymap...");
+    let config: Config = toml::from_str("[keys]\nscan = \"F5\"\nsort = \"o\"\n").unwrap();
+    let keymap = Keymap::from_config(&config.keys).unwrap();
+    let mut remapped = App::new(Duration::from_secs(5), false).with_keymap(keymap);
+    remapped.perform_scan().await.unwrap();
+    let sort_before = remapped.sort_by;
+    let old_keys = drive(&mut remapped, &[key(KeyCode::Char('r')), key(KeyCode::Char('s'))]);
+    let unchanged = old_keys.is_empty() && remapped.sort_by == sort_before;
+    let new_keys = drive(&mut remapped, &[key(KeyCode::F(5)), key(KeyCode::Char('o'))]);
+    if unchanged && new_keys == [AppAction::Scan] && remapped.sort_by != sort_before {
+        println!("   ✓ F5 scans and o sorts; r and s do nothing");
+    } else {
+        println!("   ✗ Remapped keys not applied (actions: {:?} then {:?})", old_keys, new_keys);
+        all_passed = false;
+    }
+    let conflicting: Config = toml::from_str("[keys]\nscan = \"s\"\n").unwrap();
+    match Keymap::from_config(&conflicting.keys) {
+        Err(e) if e.to_string().contains("bound to both scan and sort") => {
+            println!("   ✓ Conflict reported: {}", e)
+        }
+        other => {
+            println!("   ✗ Expected a scan/sort conflict, got {:?}", other.map(|_| ()));
+            all_passed = false;
+        }
+    }
+
+    // Test 10: Ctrl+C quits and nothing after it is applied
+    println!("10. Testing Ctrl+C...");
+    let ctrl_c = Event::Key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+    let chart_before = app.show_channel_chart;
+    drive(&mut app, &[ctrl_c, key(KeyCode::Char('c'))]);
+    if app.should_quit && app.show_channel_chart == chart_before {
+        println!("   ✓ Quit requested and remaining events dropped");
+    } else {
+        println!("   ✗ Ctrl+C didn't stop the driver");
+        all_passed = false;
+    }
+
+    // Test 11: PageUp/PageDown jump by the rendered table height, Home/End go to the ends
+    println!("11. Testing paging...");
+    let mut paged = App::new(Duration::from_secs(5), false);
+    paged.networks = (0..50)
+        .map(|i| Network {
+            mac: format!("02:00:00:00:00:{:02X}", i),
+            score: 50,
+            ..Network::test(&format!("Net_{:02}", i), 6, -40 - i as i32)
+        })
+        .collect();
+    paged.devices = (0..12)
+        .map(|i| Device::new(format!("02:00:00:00:01:{:02X}", i), format!("192.168.1.{}", i + 10)))
+        .collect();
+    // 30 rows: 1 tab line + 1 status line + 28 for the table, less borders and header
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+    let _ = terminal.draw(|frame| paged.render(frame));
+    let page = paged.page_size.get();
+    let mut stops = Vec::new();
+    for code in [KeyCode::PageDown, KeyCode::PageDown, KeyCode::PageUp, KeyCode::End, KeyCode::PageDown] {
+        drive(&mut paged, &[key(code)]);
+        stops.push(paged.selected_index);
+    }
+    drive(&mut paged, &[key(KeyCode::Home), key(KeyCode::PageUp)]);
+    let network_top = paged.selected_index;
+
+    drive(&mut paged, &[key(KeyCode::Tab)]);
+    let _ = terminal.draw(|frame| paged.render(frame));
+    drive(&mut paged, &[key(KeyCode::PageDown)]);
+    let device_stop = paged.selected_device_index;
+    drive(&mut paged, &[key(KeyCode::End), key(KeyCode::Up), key(KeyCode::Home)]);
+    let device_top = paged.selected_device_index;
+    if page == 25
+        && stops == [25, 49, 24, 49, 49]
+        && network_top == 0
+        && device_stop == 11
+        && device_top == 0
+    {
+        println!("   ✓ Pages of {} rows, clamped at both ends in both tables", page);
+    } else {
+        println!(
+            "   ✗ Page {} stops {:?} top {} devices {} / {}",
+            page, stops, network_top, device_stop, device_top
+        );
+        all_passed = false;
+    }
+
+    // Test 12: Wrap navigation jumps between the ends only when enabled
+    println!("12. Testing wrap navigation...");
+    for wrap in [false, true] {
+        let mut wrapping = App::new(Duration::from_secs(5), false).with_wrap_navigation(wrap);
+        wrapping.networks = paged.networks[..5].to_vec();
+        wrapping.devices = paged.devices[..4].to_vec();
+
+        // A stale cache entry shows whether the selected network's data was reloaded
+        wrapping.cached_connection_history = Some(("stale".to_string(), Vec::new()));
+        drive(&mut wrapping, &[key(KeyCode::Up)]);
+        let top_up = wrapping.selected_index;
+        let reloaded = wrapping.cached_connection_history.is_none();
+        drive(&mut wrapping, &[key(KeyCode::End), key(KeyCode::Down)]);
+        let bottom_down = wrapping.selected_index;
+
+        drive(&mut wrapping, &[key(KeyCode::Tab), key(KeyCode::Up)]);
+        let device_up = wrapping.selected_device_index;
+        drive(&mut wrapping, &[key(KeyCode::End), key(KeyCode::Down)]);
+        let device_down = wrapping.selected_device_index;
+
+        let expected = if wrap { (4, 0, 3, 0) } else { (0, 4, 0, 3) };
+        if (top_up, bottom_down, device_up, device_down) == expected && reloaded == wrap {
+            println!("   ✓ Wrap {}: up from top → {}, down from bottom → {}", if wrap { "on" } else { "off" }, top_up, bottom_down);
+        } else {
+            println!(
+                "   ✗ Wrap {}: networks {} / {}, devices {} / {}, reloaded {}",
+                wrap, top_up, bottom_down, device_up, device_down, reloaded
+            );
+            all_passed = false;
+        }
+    }
+
+    // Test 13: e and t edit a device's notes and tags through the rename dialog
+    println!("13. Testing device notes and tags...");
+    let mut tagged = App::new(Duration::from_secs(5), false);
+    tagged.devices = paged.devices[..2].to_vec();
+    drive(&mut tagged, &[key(KeyCode::Tab), key(KeyCode::Char('e'))]);
+    let notes_dialog = tagged.show_rename_dialog;
+    drive(&mut tagged, &typed("kid's tablet"));
+    drive(&mut tagged, &[key(KeyCode::Enter), key(KeyCode::Char('t'))]);
+    drive(&mut tagged, &typed("#trusted, kids #Trusted"));
+    drive(&mut tagged, &[key(KeyCode::Enter)]);
+    let device = &tagged.devices[tagged.selected_device_index];
+    let saved = (device.notes.clone(), device.tags.clone(), device.custom_name.clone());
+
+    // Reopening shows the tags as typed; saving them empty removes them
+    drive(&mut tagged, &[key(KeyCode::Char('t'))]);
+    let prefilled = tagged.rename_input.clone();
+    let clear: Vec<Event> = prefilled.chars().map(|_| key(KeyCode::Backspace)).collect();
+    drive(&mut tagged, &clear);
+    drive(&mut tagged, &[key(KeyCode::Enter)]);
+    let cleared = tagged.devices[tagged.selected_device_index].tags.is_empty();
+    if notes_dialog
+        && saved == (Some("kid's tablet".to_string()), vec!["trusted".to_string(), "kids".to_string()], None)
+        && prefilled == "#trusted #kids"
+        && cleared
+        && !tagged.show_rename_dialog
+    {
+        println!("   ✓ Notes and tags saved, shown as \"{}\", and cleared", prefilled);
+    } else {
+        println!("   ✗ Saved {:?}, prefilled {:?}, cleared {}", saved, prefilled, cleared);
+        all_passed = false;
+    }
+
+    // Test 14: Sorting with the AI-agent filter on keeps the selected device
+    println!("14. Testing device sort under the agent filter...");
+    let mut filtered = App::new(Duration::from_secs(5), false);
+    filtered.devices = [40, 30, 20, 10]
+        .iter()
+        .map(|host| Device::new(format!("AA:BB:CC:00:00:{:02X}", host), format!("192.168.1.{}", host)))
+        .collect();
+    filtered.devices[1].detected_agents = vec!["Ollama".to_string()];
+    filtered.devices[3].detected_agents = vec!["OpenClaw".to_string()];
+    drive(&mut filtered, &[key(KeyCode::Tab), key(KeyCode::Char('i'))]);
+    let before = filtered.selected_device().map(|d| d.ip_address.clone());
+    // Last seen -> IP puts .20 (no agent) where .30 was
+    drive(&mut filtered, &[key(KeyCode::Char('o'))]);
+    let after = filtered.selected_device().map(|d| d.ip_address.clone());
+    if filtered.agent_filter && before.as_deref() == Some("192.168.1.30") && after == before {
+        println!("   ✓ i filters, o re-sorts, and .30 stays selected");
+    } else {
+        println!("   ✗ Selected {:?} before sorting, {:?} after", before, after);
+        all_passed = false;
+    }
+
+    // Test 15: The help overlay shows the active keymap and scrolls to its end
+    println!("15. Testing help overlay...");
+    let mut helped = App::new(Duration::from_secs(5), false).with_keymap(Keymap::from_config(&config.keys).unwrap());
+    let screen = |app: &App| {
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+        let _ = terminal.draw(|frame| app.render(frame));
+        terminal.backend().buffer().content.iter().map(|cell| cell.symbol()).collect::<String>()
+    };
+    drive(&mut helped, &[key(KeyCode::Char('?'))]);
+    let top = screen(&helped);
+    drive(&mut helped, &[key(KeyCode::End)]);
+    let bottom = screen(&helped);
+    let end_scroll = helped.help_scroll;
+    drive(&mut helped, &[key(KeyCode::Down), key(KeyCode::Char('j'))]);
+    let clamped = helped.help_scroll == end_scroll;
+    drive(&mut helped, &[key(KeyCode::Home)]);
+    if top.contains("F5             Refresh scan")
+        && !top.contains("Poor")
+        && bottom.contains("Poor")
+        && end_scroll > 0
+        && clamped
+        && helped.help_scroll == 0
+        && helped.show_help
+    {
+        println!("   ✓ Remapped scan key listed; End scrolls to the legend and stops there");
+    } else {
+        println!("   ✗ Help overlay stopped at line {} (clamped: {})", end_scroll, clamped);
+        all_passed = false;
+    }
+
+    println!();
+    println!("=== Driver Test Summary ===");
+    if all_passed {
+        println!("✓ All driver tests PASSED!");
+    } else {
+        println!("✗ Some driver tests FAILED");
+        std::process::exit(1);
+    }
+}
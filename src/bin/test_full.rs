@@ -39,8 +39,8 @@ async fn main() {
             ssid: n.ssid.clone(),
             channel: n.channel,
             signal_dbm: n.signal_dbm,
-            security: format!("{:?}", n.security),
-            frequency_band: format!("{:?}", n.frequency_band),
+            security: n.security.as_str().to_string(),
+            frequency_band: n.frequency_band.as_str().to_string(),
             score: n.score,
         })
         .collect();
@@ -2,7 +2,7 @@
 
 use chrono::Utc;
 use wifi_analyzer::db::{Database, ScanResultRecord};
-use wifi_analyzer::scanner::{enable_demo_mode, FrequencyBand, Network, SecurityType};
+use wifi_analyzer::scanner::{enable_demo_mode, Network, SecurityType};
 use wifi_analyzer::app::App;
 use std::time::Duration;
 
@@ -74,14 +74,10 @@ fn main() {
 
     // Simulate adding a new network (like from a scan)
     let new_network = Network {
-        ssid: "NewNetwork3".to_string(),
         mac: "AA:BB:CC:DD:EE:03".to_string(),
-        channel: 11,
-        signal_dbm: -55,
         security: SecurityType::WPA3,
-        frequency_band: FrequencyBand::Band2_4GHz,
         score: 85,
-        last_seen: Utc::now(),
+        ..Network::test("NewNetwork3", 11, -55)
     };
 
     // Manually add to simulate merge
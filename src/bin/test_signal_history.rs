@@ -0,0 +1,73 @@
+//! Test that signal history is backfilled from the database after a restart
+
+use std::time::Duration;
+use wifi_analyzer::app::App;
+use wifi_analyzer::components::SignalChart;
+use wifi_analyzer::db::{Database, ScanResultRecord};
+
+fn main() {
+    println!("=== Testing Signal History Backfill ===\n");
+
+    // 1. Record several scans of the same network in a fresh database
+    println!("1. Recording scans...");
+    let db = Database::open_in_memory().expect("Failed to open in-memory db");
+    let location_id = db.create_or_get_location("test_room").expect("Failed to create location");
+
+    let signals = [-80, -72, -65, -58, -50];
+    for signal_dbm in signals {
+        let scan_id = db.create_scan(location_id).expect("Failed to create scan");
+        let record = ScanResultRecord {
+            bssid: "AA:BB:CC:DD:EE:01".to_string(),
+            ssid: "HistoryNet".to_string(),
+            channel: 6,
+            signal_dbm,
+            security: "WPA2".to_string(),
+            frequency_band: "Band2_4GHz".to_string(),
+            score: 70,
+        };
+        db.record_scan_results(scan_id, &[record]).expect("Failed to record results");
+    }
+    println!("   Recorded {} scans", signals.len());
+
+    // 2. Start a fresh app (empty in-memory history) and select the network
+    println!("\n2. Loading into a fresh App...");
+    let mut app = App::new(Duration::from_secs(5), false)
+        .with_database(db, location_id, "test_room".to_string());
+    app.load_networks_from_db().expect("Failed to load networks from DB");
+    app.load_selected_network_data();
+
+    let history: Vec<i32> = app
+        .signal_history
+        .get("AA:BB:CC:DD:EE:01")
+        .map(|h| h.iter().copied().collect())
+        .unwrap_or_default();
+
+    if history == signals {
+        println!("   ✓ History backfilled oldest-to-newest: {:?}", history);
+    } else {
+        println!("   ✗ Expected {:?}, got {:?}", signals, history);
+        std::process::exit(1);
+    }
+
+    // 3. The chart renders the backfilled points
+    println!("\n3. Checking chart data...");
+    let data = SignalChart::data(&app);
+    if data.len() == signals.len() && data.first() < data.last() {
+        println!("   ✓ Chart has {} points: {:?}", data.len(), data);
+    } else {
+        println!("   ✗ Unexpected chart data: {:?}", data);
+        std::process::exit(1);
+    }
+
+    // 4. Selecting again must not duplicate samples
+    app.load_selected_network_data();
+    let len_after = app.signal_history.get("AA:BB:CC:DD:EE:01").map_or(0, |h| h.len());
+    if len_after == signals.len() {
+        println!("   ✓ Re-selecting does not duplicate history");
+    } else {
+        println!("   ✗ History grew to {} after re-selecting", len_after);
+        std::process::exit(1);
+    }
+
+    println!("\n=== All tests passed! ===");
+}
@@ -3,8 +3,10 @@
 use ratatui::backend::TestBackend;
 use ratatui::Terminal;
 use std::time::Duration;
-use wifi_analyzer::app::App;
-use wifi_analyzer::scanner::enable_demo_mode;
+use wifi_analyzer::app::{App, AppView, BandFilter, DeviceSortField, NetworkFilter, SignalUnits, LOG_CAPACITY};
+use wifi_analyzer::network_map::{Device, DeviceType};
+use wifi_analyzer::db::RoamEvent;
+use wifi_analyzer::scanner::{enable_demo_mode, FrequencyBand, Network, SecurityType};
 
 #[tokio::main]
 async fn main() {
@@ -142,6 +144,744 @@ async fn main() {
         all_passed = false;
     }
 
+    // Test 8: SSID search filter
+    println!("8. Testing SSID search filter...");
+    app.start_filter();
+    for c in "hotel".chars() {
+        app.filter_input_char(c);
+    }
+    app.confirm_filter();
+    let _ = terminal.draw(|frame| app.render(frame));
+    let content = buffer_to_string(terminal.backend().buffer());
+
+    let hidden_names = ["CoffeeShop", "Airport", "Starbucks", "Library"];
+    let leaked: Vec<_> = hidden_names.iter().filter(|name| content.contains(*name)).collect();
+    if content.contains("Hotel_Guest") && leaked.is_empty() && app.visible_networks().len() == 1 {
+        println!("   ✓ Filter shows only matching SSIDs");
+    } else {
+        println!("   ✗ Filter mismatch (leaked: {:?})", leaked);
+        all_passed = false;
+    }
+
+    app.navigate_down();
+    if app.selected_index == 0 {
+        println!("   ✓ Selection clamped to filtered list");
+    } else {
+        println!("   ✗ Selection escaped filtered list: {}", app.selected_index);
+        all_passed = false;
+    }
+
+    app.clear_filter();
+    if app.filter_query.is_empty() && app.visible_networks().len() == app.networks.len() {
+        println!("   ✓ Clearing filter restores all networks");
+    } else {
+        println!("   ✗ Clearing filter did not restore all networks");
+        all_passed = false;
+    }
+
+    // Test 9: Channel usage chart
+    println!("9. Testing channel usage chart...");
+    app.toggle_channel_chart();
+    match terminal.draw(|frame| app.render(frame)) {
+        Ok(_) => {
+            let content = buffer_to_string(terminal.backend().buffer());
+            if content.contains("Channels 2.4 GHz") && content.contains("best ch") {
+                println!("   ✓ Channel chart replaces detail panel");
+            } else {
+                println!("   ✗ Channel chart not rendered");
+                all_passed = false;
+            }
+        }
+        Err(e) => {
+            println!("   ✗ Channel chart render failed: {}", e);
+            all_passed = false;
+        }
+    }
+    app.toggle_channel_chart();
+
+    // Test 10: Security/known network filter
+    println!("10. Testing network filter...");
+    app.cycle_network_filter(); // Open only
+    let open_only = app
+        .visible_networks()
+        .iter()
+        .all(|n| n.security == SecurityType::Open);
+    app.cycle_network_filter(); // Secured only
+    let secured_only = app
+        .visible_networks()
+        .iter()
+        .all(|n| n.security != SecurityType::Open);
+    if open_only && secured_only {
+        println!("   ✓ Open/secured filters applied");
+    } else {
+        println!("   ✗ Open/secured filters leaked networks");
+        all_passed = false;
+    }
+
+    app.cycle_network_filter(); // Known only (no database, so nothing is known)
+    let _ = terminal.draw(|frame| app.render(frame));
+    let content = buffer_to_string(terminal.backend().buffer());
+    if content.contains("No networks match filter") && content.contains("Filter: Known only") {
+        println!("   ✓ Empty filter shows a message and the status bar shows the filter");
+    } else {
+        println!("   ✗ Empty filter message or status missing");
+        all_passed = false;
+    }
+
+    app.cycle_network_filter(); // Back to all
+    if app.network_filter == NetworkFilter::All && app.visible_networks().len() == app.networks.len() {
+        println!("   ✓ Filter cycles back to all networks");
+    } else {
+        println!("   ✗ Filter did not cycle back to all networks");
+        all_passed = false;
+    }
+
+    // Test 11: Background scan keeps the app responsive
+    println!("11. Testing background scan...");
+    app.start_scan();
+    let scanning_while_pending = app.is_scanning;
+    let _ = terminal.draw(|frame| app.render(frame)); // Rendering works mid-scan
+    let mut arrived = false;
+    for _ in 0..100 {
+        if app.poll_scan_result() {
+            arrived = true;
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    if scanning_while_pending && arrived && !app.is_scanning {
+        println!("   ✓ Background scan delivered results");
+    } else {
+        println!(
+            "   ✗ Background scan failed (scanning={}, arrived={}, still scanning={})",
+            scanning_while_pending, arrived, app.is_scanning
+        );
+        all_passed = false;
+    }
+
+    // Test 12: Known networks tab
+    println!("12. Testing known networks view...");
+    app.switch_view(); // Network devices
+    app.switch_view(); // Known networks
+    let _ = terminal.draw(|frame| app.render(frame));
+    let content = buffer_to_string(terminal.backend().buffer());
+    if app.current_view == AppView::KnownNetworks && content.contains("Known Networks (0)") {
+        println!("   ✓ Known networks tab rendered");
+    } else {
+        println!("   ✗ Known networks tab not rendered");
+        all_passed = false;
+    }
+
+    app.show_forget_dialog(); // Nothing selected, so no popup
+    app.switch_view();
+    if !app.show_forget_popup && app.current_view == AppView::WifiNetworks {
+        println!("   ✓ Tab cycles back to WiFi networks");
+    } else {
+        println!("   ✗ View cycle or forget popup state wrong");
+        all_passed = false;
+    }
+
+    // Test 13: Speed test rate limiting
+    println!("13. Testing speed test rate limit...");
+    let no_cooldown_initially = app.speedtest_cooldown().is_none();
+    app.last_speedtest = Some(std::time::Instant::now());
+    let cooldown = app.speedtest_cooldown();
+    if no_cooldown_initially && cooldown.is_some_and(|d| d.as_secs() >= 55) {
+        println!("   ✓ Speed tests are limited to one per minute");
+    } else {
+        println!("   ✗ Unexpected speed test cooldown: {:?}", cooldown);
+        all_passed = false;
+    }
+
+    // Test 14: Vendor column (wide terminal so every column gets its width)
+    println!("14. Testing vendor column...");
+    let mut wide_terminal = Terminal::new(TestBackend::new(180, 30)).unwrap();
+    let _ = wide_terminal.draw(|frame| app.render(frame));
+    let content = buffer_to_string(wide_terminal.backend().buffer());
+    let library_row = content.lines().find(|line| line.contains("Library_Public"));
+    if content.contains("Vendor") && library_row.is_some_and(|row| row.contains("Cisco")) {
+        println!("   ✓ Known OUI shows its vendor");
+    } else {
+        println!("   ✗ Vendor column missing or wrong (row: {:?})", library_row);
+        all_passed = false;
+    }
+
+    // Test 15: Roaming note for the connected network
+    println!("15. Testing roam count...");
+    for _ in 0..10 {
+        app.navigate_up();
+    }
+    let connected_ssid = app.selected_network().map(|n| n.ssid.clone()).unwrap_or_default();
+    app.connected_ssid = Some(connected_ssid.clone());
+    for (from, to) in [("AA:00:00:00:00:01", "AA:00:00:00:00:02"), ("AA:00:00:00:00:02", "AA:00:00:00:00:01")] {
+        app.roam_events.push(RoamEvent {
+            ssid: connected_ssid.clone(),
+            from_bssid: from.to_string(),
+            to_bssid: to.to_string(),
+            roamed_at: chrono::Utc::now(),
+        });
+    }
+    let _ = terminal.draw(|frame| app.render(frame));
+    let content = buffer_to_string(terminal.backend().buffer());
+    if app.roams_in_last_hour(&connected_ssid) == 2 && content.contains("Roamed 2× in last hour") {
+        println!("   ✓ Detail panel shows recent roams");
+    } else {
+        println!("   ✗ Roam count missing");
+        all_passed = false;
+    }
+
+    // Test 16: Shutdown while a scan is still running
+    println!("16. Testing shutdown during a scan...");
+    app.start_scan();
+    app.quit();
+    match app.shutdown() {
+        Ok(()) if !app.is_scanning && app.scan_receiver.is_none() && !app.poll_scan_result() => {
+            println!("   ✓ Shutdown abandons the in-flight scan");
+        }
+        Ok(()) => {
+            println!("   ✗ Scan still pending after shutdown");
+            all_passed = false;
+        }
+        Err(e) => {
+            println!("   ✗ Shutdown failed: {}", e);
+            all_passed = false;
+        }
+    }
+
+    // Test 17: Signal shown in dBm and percent
+    println!("17. Testing signal units...");
+    let expected = app
+        .selected_network()
+        .map(|n| format!("Signal: {} dBm ({}%)", n.signal_dbm, n.signal_percent()))
+        .unwrap_or_default();
+    app.signal_units = SignalUnits::Both;
+    let _ = terminal.draw(|frame| app.render(frame));
+    let both = buffer_to_string(terminal.backend().buffer());
+    app.signal_units = SignalUnits::Percent;
+    let _ = terminal.draw(|frame| app.render(frame));
+    let percent = buffer_to_string(terminal.backend().buffer());
+    if both.contains(&expected) && !percent.contains(" dBm") {
+        println!("   ✓ Detail panel follows --signal-units");
+    } else {
+        println!("   ✗ Expected \"{}\" with both units and no dBm with percent", expected);
+        all_passed = false;
+    }
+    app.signal_units = SignalUnits::default();
+
+    // Test 18: Hidden networks counted in the table title
+    println!("18. Testing hidden network count...");
+    let _ = terminal.draw(|frame| app.render(frame));
+    let content = buffer_to_string(terminal.backend().buffer());
+    let expected = format!("({} hidden)", app.hidden_count());
+    if app.hidden_count() == 1 && content.contains(&expected) {
+        println!("   ✓ Table title shows {}", expected);
+    } else {
+        println!("   ✗ Hidden count missing (count {})", app.hidden_count());
+        all_passed = false;
+    }
+
+    // Test 19: Yank the selected network
+    println!("19. Testing copy to clipboard...");
+    app.current_view = AppView::WifiNetworks;
+    let summary = app.selection_summary().unwrap_or_default();
+    let bssid = app.selected_network().map(|n| n.mac.clone()).unwrap_or_default();
+    app.yank_selection();
+    // Headless machines have no clipboard, so the summary lands in the status bar
+    let status = app.status_message.clone().unwrap_or_default();
+    if summary.contains(&bssid) && (status == "Copied to clipboard" || status == summary) {
+        println!("   ✓ Selection copied ({})", status);
+    } else {
+        println!("   ✗ Unexpected summary {:?} / status {:?}", summary, status);
+        all_passed = false;
+    }
+
+    // Test 20: Device sort compares IPs numerically
+    println!("20. Testing device sort by IP...");
+    app.devices = ["192.168.1.10", "192.168.1.2", "192.168.1.100"]
+        .iter()
+        .enumerate()
+        .map(|(i, ip)| Device::new(format!("AA:BB:CC:DD:EE:0{}", i), ip.to_string()))
+        .collect();
+    while app.device_sort_by != DeviceSortField::Ip {
+        app.cycle_device_sort();
+    }
+    let ips: Vec<String> = app.devices.iter().map(|d| d.ip_address.clone()).collect();
+    app.current_view = AppView::NetworkDevices;
+    let _ = terminal.draw(|frame| app.render(frame));
+    let content = buffer_to_string(terminal.backend().buffer());
+    app.current_view = AppView::WifiNetworks;
+    if ips == ["192.168.1.2", "192.168.1.10", "192.168.1.100"] && content.contains("by IP") {
+        println!("   ✓ 192.168.1.2 sorts before 192.168.1.10");
+    } else {
+        println!("   ✗ Wrong IP order: {:?}", ips);
+        all_passed = false;
+    }
+
+    // Test 21: Periodic device scans only while the devices view is idle
+    println!("21. Testing device auto-scan scheduling...");
+    app.clear_error();
+    app.current_view = AppView::NetworkDevices;
+    app.device_auto_scan = true;
+    app.last_device_scan = None;
+    let due_when_idle = app.device_scan_due();
+    app.toggle_help();
+    let due_with_help = app.device_scan_due();
+    app.toggle_help();
+    app.last_device_scan = Some(std::time::Instant::now());
+    let due_after_scan = app.device_scan_due();
+    let countdown = app.next_device_scan_secs();
+    let _ = terminal.draw(|frame| app.render(frame));
+    let content = buffer_to_string(terminal.backend().buffer());
+    app.current_view = AppView::WifiNetworks;
+    let due_elsewhere = app.device_scan_due();
+    app.device_auto_scan = false;
+    if due_when_idle && !due_with_help && !due_after_scan && !due_elsewhere
+        && countdown.is_some_and(|s| s > 0)
+        && content.contains("next device scan in")
+    {
+        println!("   ✓ Auto-scan waits for its interval and pauses behind dialogs");
+    } else {
+        println!(
+            "   ✗ Unexpected schedule: idle={} help={} after={} elsewhere={} countdown={:?}",
+            due_when_idle, due_with_help, due_after_scan, due_elsewhere, countdown
+        );
+        all_passed = false;
+    }
+
+    // Test 22: Nearby-only filter hides weak networks from the table
+    println!("22. Testing nearby-only signal threshold...");
+    app.clear_filter();
+    app.network_filter = NetworkFilter::All;
+    let total = app.visible_networks().len();
+    app.min_signal_threshold = -70;
+    app.toggle_min_signal();
+    let weak = app.networks.iter().filter(|n| n.signal_dbm < -70).count();
+    let all_strong = app.visible_networks().iter().all(|n| n.signal_dbm >= -70);
+    let visible = app.visible_networks().len();
+    let _ = terminal.draw(|frame| app.render(frame));
+    let content = buffer_to_string(terminal.backend().buffer());
+    let expected = format!("({} hidden below -70 dBm)", weak);
+    app.toggle_min_signal();
+    if weak > 0 && all_strong && visible + weak == total
+        && app.visible_networks().len() == total
+        && content.contains(&expected)
+    {
+        println!("   ✓ {} weak networks hidden and counted in the title", weak);
+    } else {
+        println!("   ✗ Threshold not applied (weak {}, visible {}, title {:?})", weak, visible, expected);
+        all_passed = false;
+    }
+
+    // Test 23: Signal chart placeholder until there are two samples
+    println!("23. Testing signal chart empty state...");
+    let mut fresh = App::new(Duration::from_secs(5), false);
+    let _ = terminal.draw(|frame| fresh.render(frame));
+    let empty = buffer_to_string(terminal.backend().buffer());
+    fresh.perform_scan().await.unwrap();
+    let _ = terminal.draw(|frame| fresh.render(frame));
+    let one_point = buffer_to_string(terminal.backend().buffer());
+    fresh.perform_scan().await.unwrap();
+    let _ = terminal.draw(|frame| fresh.render(frame));
+    let two_points = buffer_to_string(terminal.backend().buffer());
+    let placeholder = "Collecting signal data…";
+    if empty.contains(placeholder) && one_point.contains(placeholder)
+        && !two_points.contains(placeholder) && two_points.contains("min ")
+    {
+        println!("   ✓ Placeholder shown until the chart has two points, then min/max labels");
+    } else {
+        println!("   ✗ Unexpected signal chart states");
+        all_passed = false;
+    }
+
+    // Test 24: Diagnostics log is capped and shown in an overlay
+    println!("24. Testing diagnostics log...");
+    let mut logged = App::new(Duration::from_secs(5), false);
+    for i in 0..LOG_CAPACITY + 5 {
+        logged.log(format!("entry {}", i));
+    }
+    let oldest_dropped = logged.log.front().is_some_and(|e| e.ends_with(" entry 5"));
+    logged.toggle_log();
+    let _ = terminal.draw(|frame| logged.render(frame));
+    let overlay = buffer_to_string(terminal.backend().buffer());
+    let newest = format!("entry {}", LOG_CAPACITY + 4);
+    logged.toggle_log();
+    if logged.log.len() == LOG_CAPACITY && oldest_dropped && overlay.contains(&newest)
+        && !logged.show_log
+    {
+        println!("   ✓ Log keeps the newest {} entries and shows them in the overlay", LOG_CAPACITY);
+    } else {
+        println!("   ✗ Unexpected log state ({} entries)", logged.log.len());
+        all_passed = false;
+    }
+
+    // Test 25: MAC display format applies to the detail panel only
+    println!("25. Testing MAC display format...");
+    let mut formatted = App::new(Duration::from_secs(5), false);
+    formatted.perform_scan().await.unwrap();
+    let canonical = formatted.selected_network().map(|n| n.mac.clone()).unwrap_or_default();
+    // Colon upper -> colon lower -> hyphen upper -> hyphen lower -> bare upper
+    for _ in 0..4 {
+        formatted.cycle_mac_format();
+    }
+    let _ = terminal.draw(|frame| formatted.render(frame));
+    let content = buffer_to_string(terminal.backend().buffer());
+    let bare = canonical.replace(':', "");
+    let stored = formatted.selected_network().map(|n| n.mac.clone()).unwrap_or_default();
+    if content.contains(&format!("MAC: {}", bare)) && stored == canonical {
+        println!("   ✓ Detail panel shows {} while the network keeps {}", bare, canonical);
+    } else {
+        println!("   ✗ Expected bare MAC {} in the detail panel", bare);
+        all_passed = false;
+    }
+
+    // Test 26: Pinning puts a low-score connected network first and keeps the selection
+    println!("26. Testing connected network pinning...");
+    let mut pinned = App::new(Duration::from_secs(5), false);
+    pinned.perform_scan().await.unwrap();
+    let weakest = pinned.networks.last().cloned().unwrap();
+    pinned.connected_ssid = Some(weakest.ssid.clone());
+    pinned.connected_bssid = Some(weakest.mac.clone());
+    pinned.navigate_down();
+    let selected_before = pinned.selected_network().map(|n| n.mac.clone());
+    pinned.toggle_pin_connected();
+    let first_is_connected = pinned.networks.first().is_some_and(|n| pinned.is_connected(n));
+    let selection_kept = pinned.selected_network().map(|n| n.mac.clone()) == selected_before;
+    pinned.toggle_pin_connected();
+    let unpinned = pinned.networks.first().is_some_and(|n| n.mac != weakest.mac);
+    if first_is_connected && selection_kept && unpinned {
+        println!("   ✓ {} (score {}) pinned first, selection followed by MAC", weakest.ssid, weakest.score);
+    } else {
+        println!(
+            "   ✗ Pinning failed (first connected: {}, selection kept: {}, restored: {})",
+            first_is_connected, selection_kept, unpinned
+        );
+        all_passed = false;
+    }
+
+    // Test 27: Same SSID on 2.4 and 5 GHz shows up as a sibling band
+    println!("27. Testing dual-band siblings...");
+    let band_network = |mac: &str, channel: u8, signal_dbm: i32| Network {
+        mac: mac.to_string(),
+        score: 70,
+        ..Network::test("HomeNet", channel, signal_dbm)
+    };
+    let mut dual = App::new(Duration::from_secs(5), false);
+    dual.networks = vec![
+        band_network("C8:7F:54:00:00:01", 6, -48),
+        band_network("C8:7F:54:00:00:05", 149, -58),
+        band_network("C8:7F:54:00:00:09", 11, -70),
+    ];
+    let siblings: Vec<String> = dual.sibling_bands(&dual.networks[0]).iter().map(|n| n.mac.clone()).collect();
+    let from_5ghz = dual.sibling_bands(&dual.networks[1]).len();
+    let _ = terminal.draw(|frame| dual.render(frame));
+    let content = buffer_to_string(terminal.backend().buffer());
+    if siblings == ["C8:7F:54:00:00:05"] && from_5ghz == 2 && content.contains("Also on 5 GHz (ch 149") {
+        println!("   ✓ 2.4 GHz network lists its 5 GHz sibling in the detail panel");
+    } else {
+        println!("   ✗ Unexpected siblings {:?} ({} from 5 GHz)", siblings, from_5ghz);
+        all_passed = false;
+    }
+
+    // Test 28: Signal meter shows the selected network's dBm and tracks readings
+    println!("28. Testing signal meter...");
+    let mut metered = App::new(Duration::from_secs(5), false);
+    metered.perform_scan().await.unwrap();
+    metered.navigate_down();
+    let target = metered.selected_network().cloned().unwrap();
+    metered.toggle_meter();
+    metered.perform_scan().await.unwrap();
+    let current = metered.meter.as_ref().map(|m| m.current()).unwrap_or_default();
+    let readings = metered.meter.as_ref().map(|m| m.count()).unwrap_or_default();
+    let _ = terminal.draw(|frame| metered.render(frame));
+    let content = buffer_to_string(terminal.backend().buffer());
+    let follows_target = metered.meter.as_ref().is_some_and(|m| m.bssid == target.mac);
+    metered.toggle_meter();
+    if follows_target
+        && readings == 2
+        && content.contains("Signal Meter")
+        && content.contains(&format!("{} dBm", current))
+        && !content.contains("Signal History")
+        && metered.meter.is_none()
+    {
+        println!("   ✓ Meter shows {} at {} dBm in place of the table", target.ssid, current);
+    } else {
+        println!("   ✗ Meter missing {} dBm ({} readings)", current, readings);
+        all_passed = false;
+    }
+
+    // Test 29: Tiny terminals get a resize message instead of the layout
+    println!("29. Testing minimum terminal size guard...");
+    let mut small_terminal = Terminal::new(TestBackend::new(40, 10)).unwrap();
+    match small_terminal.draw(|frame| app.render(frame)) {
+        Ok(_) => {
+            let content = buffer_to_string(small_terminal.backend().buffer());
+            if content.contains("Terminal too small") && !content.contains("WiFi Networks") {
+                println!("   ✓ 40x10 terminal shows the resize message");
+            } else {
+                println!("   ✗ Resize message missing at 40x10");
+                all_passed = false;
+            }
+        }
+        Err(e) => {
+            println!("   ✗ Small terminal render failed: {}", e);
+            all_passed = false;
+        }
+    }
+
+    // Test 30: Top-N limit trims the table but keeps every network for scoring
+    println!("30. Testing top-N display limit...");
+    let mut crowded = App::new(Duration::from_secs(5), false);
+    crowded.networks = (0..137)
+        .map(|i| Network {
+            mac: format!("02:00:00:00:{:02X}:{:02X}", i / 256, i % 256),
+            score: 50,
+            ..Network::test(&format!("Apt_{}", i), [1, 6, 11][i % 3], -40 - (i as i32 % 50))
+        })
+        .collect();
+    crowded.selected_index = 120;
+    crowded.toggle_limit_display();
+    let shown = crowded.visible_networks().len();
+    let selection_valid = crowded.selected_network().is_some();
+    let _ = terminal.draw(|frame| crowded.render(frame));
+    let content = buffer_to_string(terminal.backend().buffer());
+    crowded.toggle_limit_display();
+    if shown == 20
+        && selection_valid
+        && crowded.networks.len() == 137
+        && content.contains("showing top 20 of 137")
+        && crowded.visible_networks().len() == 137
+    {
+        println!("   ✓ 20 of 137 networks shown, selection clamped into the list");
+    } else {
+        println!("   ✗ Showed {} networks (selection valid: {})", shown, selection_valid);
+        all_passed = false;
+    }
+
+    // Test 31: Detail panel shows how much the selected AP's signal jitters
+    println!("31. Testing signal stability...");
+    let mut jittery = App::new(Duration::from_secs(5), false);
+    jittery.perform_scan().await.unwrap();
+    let bssid = jittery.selected_network().map(|n| n.mac.clone()).unwrap();
+    jittery.signal_history.insert(bssid.clone(), [-50, -70, -50, -70].into_iter().collect());
+    let stability = jittery.signal_stability(&bssid);
+    let _ = terminal.draw(|frame| jittery.render(frame));
+    let content = buffer_to_string(terminal.backend().buffer());
+    if stability == Some(10.0) && content.contains("Signal stability: ±10 dB") {
+        println!("   ✓ Alternating -50/-70 dBm shows ±10 dB");
+    } else {
+        println!("   ✗ Expected ±10 dB, got {:?}", stability);
+        all_passed = false;
+    }
+
+    // Test 32: Band totals count the whole scan while the band filter narrows the table
+    println!("32. Testing band filter and band totals...");
+    let mut banded = App::new(Duration::from_secs(5), false);
+    banded.networks = [
+        (1, FrequencyBand::Band2_4GHz),
+        (6, FrequencyBand::Band2_4GHz),
+        (11, FrequencyBand::Band2_4GHz),
+        (36, FrequencyBand::Band5GHz),
+        (149, FrequencyBand::Band5GHz),
+        (37, FrequencyBand::Band6GHz),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(i, (channel, frequency_band))| Network {
+        mac: format!("02:00:00:00:00:{:02X}", i),
+        frequency_band,
+        score: 60,
+        ..Network::test(&format!("Band_{}", i), channel, -50 - i as i32)
+    })
+    .collect();
+    banded.cycle_band_filter();
+    banded.cycle_band_filter();
+    let shown = banded.visible_networks().len();
+    let _ = terminal.draw(|frame| banded.render(frame));
+    let content = buffer_to_string(terminal.backend().buffer());
+    if banded.band_filter == BandFilter::Band5
+        && shown == 2
+        && content.contains("2.4GHz: 3 | 5GHz: 2 | 6GHz: 1")
+        && content.contains("[Band: 5 GHz]")
+    {
+        println!("   ✓ 5 GHz filter shows 2 networks, totals still cover all 6");
+    } else {
+        println!("   ✗ Showed {} networks with {:?}", shown, banded.band_counts());
+        all_passed = false;
+    }
+
+    // Test 33: The connected network's gateway is added to the devices view and selected
+    println!("33. Testing jump to the gateway device...");
+    let mut routed = App::new(Duration::from_secs(5), false);
+    routed.connected_ssid = Some("HomeNet".to_string());
+    routed.devices = vec![
+        Device::new("AA:BB:CC:00:00:20".to_string(), "192.168.1.20".to_string()),
+        Device::new("AA:BB:CC:00:00:30".to_string(), "192.168.1.30".to_string()),
+    ];
+    let mut gateway = Device::new("C8:7F:54:BF:29:1C".to_string(), "192.168.1.1".to_string());
+    gateway.device_type = DeviceType::Router;
+    routed.focus_gateway(gateway.clone());
+    routed.focus_gateway(gateway);
+    let selected_ip = routed.devices.get(routed.selected_device_index).map(|d| d.ip_address.clone());
+    let _ = terminal.draw(|frame| routed.render(frame));
+    let content = buffer_to_string(terminal.backend().buffer());
+    if routed.current_view == AppView::NetworkDevices
+        && routed.devices.len() == 3
+        && selected_ip.as_deref() == Some("192.168.1.1")
+        && routed.show_device_detail
+        && content.contains("192.168.1.1")
+    {
+        println!("   ✓ Gateway added once and selected in the devices view");
+    } else {
+        println!(
+            "   ✗ View {:?}, {} devices, selected {:?}",
+            routed.current_view,
+            routed.devices.len(),
+            selected_ip
+        );
+        all_passed = false;
+    }
+
+    // Test 34: Signal arrows show the change between the last two scans
+    println!("34. Testing signal delta since the last scan...");
+    let mut moving = App::new(Duration::from_secs(5), false);
+    moving.perform_scan().await.unwrap();
+    let bssid = moving.selected_network().map(|n| n.mac.clone()).unwrap();
+    // Samples from earlier sessions, as backfilled from the database, don't count
+    moving.signal_history.get_mut(&bssid).unwrap().push_front(-30);
+    let first_delta = moving.signal_delta(&bssid);
+    let _ = terminal.draw(|frame| moving.render(frame));
+    let first_content = buffer_to_string(terminal.backend().buffer());
+    // Demo signals jitter by up to ±3 dB, so rescan until this one moves
+    let mut delta = None;
+    for _ in 0..10 {
+        moving.perform_scan().await.unwrap();
+        delta = moving.signal_delta(&bssid);
+        if delta.is_some_and(|d| d != 0) {
+            break;
+        }
+    }
+    let samples: Vec<i32> = moving.signal_history[&bssid].iter().rev().take(2).copied().collect();
+    let _ = terminal.draw(|frame| moving.render(frame));
+    let content = buffer_to_string(terminal.backend().buffer());
+    let arrow = match delta {
+        Some(d) if d > 0 => format!("\u{25b2} +{}", d),
+        Some(d) if d < 0 => format!("\u{25bc} {}", d),
+        _ => "\u{2014} 0".to_string(),
+    };
+    if first_delta.is_none()
+        && !["\u{25b2} +", "\u{25bc} -", "\u{2014} 0"].iter().any(|a| first_content.contains(a))
+        && delta == Some(samples[0] - samples[1])
+        && delta != Some(0)
+        && content.contains(&arrow)
+    {
+        println!("   ✓ No arrow after one scan, then \"{}\"", arrow);
+    } else {
+        println!("   ✗ Deltas {:?} then {:?} for samples {:?}", first_delta, delta, samples);
+        all_passed = false;
+    }
+
+    // Test 35: An empty scan explains what to check instead of showing a blank table
+    println!("35. Testing empty network list guidance...");
+    let empty = App::new(Duration::from_secs(5), false);
+    let _ = terminal.draw(|frame| empty.render(frame));
+    let content = buffer_to_string(terminal.backend().buffer());
+    if empty.networks.is_empty()
+        && content.contains("No networks found")
+        && content.contains("Location permission is granted")
+        && content.contains("Press 'd' for demo, 'r' to rescan.")
+    {
+        println!("   ✓ Guidance shown when no networks were found");
+    } else {
+        println!("   ✗ Empty network list has no guidance:\n{}", content);
+        all_passed = false;
+    }
+
+    // Test 36: The status bar says when the last scan ran and how long it took
+    println!("36. Testing last scan time and duration...");
+    let mut timed = App::new(Duration::from_secs(5), false);
+    let before = timed.last_scan_duration;
+    timed.perform_scan().await.unwrap();
+    let _ = terminal.draw(|frame| timed.render(frame));
+    let content = buffer_to_string(terminal.backend().buffer());
+    match (before, timed.last_scan_duration) {
+        (None, Some(took)) if took < Duration::from_secs(60) && content.contains("Last scan: 0s ago (took ") => {
+            println!("   ✓ Scan took {:?}, shown in the status bar", took);
+        }
+        (before, after) => {
+            println!("   ✗ Duration {:?} then {:?}, status bar missing \"Last scan\"", before, after);
+            all_passed = false;
+        }
+    }
+
+    // Test 37: The AI-agent filter narrows the device table and its navigation
+    println!("37. Testing AI-agent device filter...");
+    let mut agents = App::new(Duration::from_secs(5), false);
+    agents.current_view = AppView::NetworkDevices;
+    agents.devices = (1..=4)
+        .map(|i| {
+            let mut device = Device::new(format!("AA:BB:CC:00:01:{:02X}", i), format!("192.168.1.{}", 100 + i));
+            device.hostname = Some(format!("host-{}", i));
+            device
+        })
+        .collect();
+    agents.devices[1].detected_agents = vec!["Ollama".to_string()];
+    agents.devices[3].detected_agents = vec!["OpenClaw".to_string()];
+    agents.toggle_agent_filter();
+    let first = agents.selected_device().map(|d| d.ip_address.clone());
+    agents.device_navigate_down();
+    agents.device_navigate_down();
+    let last = agents.selected_device().map(|d| d.ip_address.clone());
+    agents.device_navigate_up();
+    let back = agents.selected_device().map(|d| d.ip_address.clone());
+    let _ = terminal.draw(|frame| agents.render(frame));
+    let content = buffer_to_string(terminal.backend().buffer());
+    let filtered_ok = agents.visible_device_indices() == vec![1, 3]
+        && first.as_deref() == Some("192.168.1.102")
+        && last.as_deref() == Some("192.168.1.104")
+        && back.as_deref() == Some("192.168.1.102")
+        && content.contains("2 AI-agent devices of 4")
+        && content.contains("host-2")
+        && !content.contains("host-1")
+        && !content.contains("host-3");
+    agents.toggle_agent_filter();
+    let _ = terminal.draw(|frame| agents.render(frame));
+    let content = buffer_to_string(terminal.backend().buffer());
+    if filtered_ok && agents.visible_device_indices().len() == 4 && content.contains("host-3") {
+        println!("   ✓ Only the 2 agent devices shown and navigated while filtered");
+    } else {
+        println!("   ✗ Filter showed {:?}, selection {:?} -> {:?} -> {:?}", agents.visible_device_indices(), first, last, back);
+        all_passed = false;
+    }
+
+    // Test 38: ARP entries our own pings refreshed don't count as device activity
+    println!("38. Testing ARP activity around liveness pings...");
+    let mut arp = App::new(Duration::from_secs(5), false);
+    let router = ("192.168.1.1".to_string(), "AA:BB:CC:00:00:01".to_string());
+    let tv = ("192.168.1.30".to_string(), "AA:BB:CC:00:00:03".to_string());
+    let deliver = |app: &mut App, entries: Vec<(String, String)>| {
+        let (tx, rx) = std::sync::mpsc::channel();
+        tx.send((entries, chrono::Utc::now())).unwrap();
+        app.arp_poll_receiver = Some(rx);
+        app.check_arp_poll_result();
+    };
+    deliver(&mut arp, vec![router.clone()]);
+    // A liveness check pinged the idle TV back into the cache
+    arp.arp_probed = true;
+    deliver(&mut arp, vec![router.clone(), tv.clone()]);
+    let after_ping = arp.arp_activity.last_active(&tv.1);
+    deliver(&mut arp, vec![router.clone(), tv.clone()]);
+    let after_quiet = arp.arp_activity.last_active(&tv.1);
+    // Its entry expires, then comes back on its own
+    deliver(&mut arp, vec![router.clone()]);
+    deliver(&mut arp, vec![router, tv.clone()]);
+    let after_traffic = arp.arp_activity.last_active(&tv.1);
+    if after_ping.is_none() && after_quiet.is_none() && after_traffic.is_some() && !arp.arp_probed {
+        println!("   ✓ Only the TV's own traffic marks it active");
+    } else {
+        println!("   ✗ Active after ping {:?}, quiet {:?}, traffic {:?}", after_ping, after_quiet, after_traffic);
+        all_passed = false;
+    }
+
     println!();
     println!("=== UI Test Summary ===");
     if all_passed {
@@ -0,0 +1,103 @@
+//! Captive portal detection module
+//!
+//! Checks whether a freshly joined network actually reaches the internet or
+//! intercepts traffic with a sign-in page.
+
+use color_eyre::Result;
+use std::fmt;
+use std::time::Duration;
+
+/// Endpoint that returns an empty 204 when the internet is reachable
+const CHECK_URL: &str = "http://connectivitycheck.gstatic.com/generate_204";
+
+/// Result of a captive portal check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptiveStatus {
+    /// Direct internet access
+    Open,
+    /// Requests are redirected or rewritten by a sign-in page
+    CaptivePortal,
+    /// The check endpoint could not be reached
+    NoInternet,
+}
+
+impl fmt::Display for CaptiveStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CaptiveStatus::Open => write!(f, "Open"),
+            CaptiveStatus::CaptivePortal => write!(f, "CaptivePortal"),
+            CaptiveStatus::NoInternet => write!(f, "NoInternet"),
+        }
+    }
+}
+
+impl CaptiveStatus {
+    /// Parse from display string (stored in DB)
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s {
+            "Open" => Some(CaptiveStatus::Open),
+            "CaptivePortal" => Some(CaptiveStatus::CaptivePortal),
+            "NoInternet" => Some(CaptiveStatus::NoInternet),
+            _ => None,
+        }
+    }
+}
+
+/// Check for a captive portal (safe to call from async context)
+pub fn detect_captive_portal() -> CaptiveStatus {
+    // Use std::thread for blocking HTTP call to avoid Tokio runtime conflicts
+    let handle = std::thread::spawn(check_blocking);
+    match handle.join() {
+        Ok(Ok(status)) => status,
+        _ => CaptiveStatus::NoInternet,
+    }
+}
+
+/// Request the generate-204 endpoint without following redirects
+fn check_blocking() -> Result<CaptiveStatus> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?;
+
+    let response = client.get(CHECK_URL).send()?;
+    let status = response.status().as_u16();
+    let body_len = response.bytes().map(|b| b.len()).unwrap_or(0);
+
+    Ok(classify_response(status, body_len))
+}
+
+/// Classify the check response.
+/// A 204 (or an empty 200) means real internet; a redirect or a page with
+/// content means something intercepted the request.
+fn classify_response(status: u16, body_len: usize) -> CaptiveStatus {
+    match status {
+        204 => CaptiveStatus::Open,
+        200 if body_len == 0 => CaptiveStatus::Open,
+        200..=399 => CaptiveStatus::CaptivePortal,
+        _ => CaptiveStatus::NoInternet,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_response() {
+        assert_eq!(classify_response(204, 0), CaptiveStatus::Open);
+        assert_eq!(classify_response(200, 0), CaptiveStatus::Open);
+        assert_eq!(classify_response(200, 512), CaptiveStatus::CaptivePortal);
+        assert_eq!(classify_response(302, 0), CaptiveStatus::CaptivePortal);
+        assert_eq!(classify_response(307, 0), CaptiveStatus::CaptivePortal);
+        assert_eq!(classify_response(503, 0), CaptiveStatus::NoInternet);
+    }
+
+    #[test]
+    fn test_status_round_trip() {
+        for status in [CaptiveStatus::Open, CaptiveStatus::CaptivePortal, CaptiveStatus::NoInternet] {
+            assert_eq!(CaptiveStatus::from_name(&status.to_string()), Some(status));
+        }
+        assert_eq!(CaptiveStatus::from_name("bogus"), None);
+    }
+}
@@ -0,0 +1,95 @@
+use crate::app::App;
+use crate::components::Component;
+use crate::scanner::FrequencyBand;
+use crate::scoring::{recommend_channel, recommendation_summary, CHANNELS_5GHZ};
+use crate::theme::Theme;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Bar, BarChart, BarGroup, Block, Borders, Paragraph};
+use ratatui::Frame;
+
+/// 2.4GHz channels
+const CHANNELS_2_4GHZ: &[u8] = &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14];
+
+pub struct ChannelChart;
+
+impl ChannelChart {
+    fn render_band(
+        frame: &mut Frame,
+        area: Rect,
+        app: &App,
+        label: &str,
+        channels: &[u8],
+        best: Option<u8>,
+    ) {
+        let selected_channel = app.selected_network().map(|n| n.channel);
+
+        let bars: Vec<Bar> = channels
+            .iter()
+            .map(|&channel| {
+                let count = app.networks.iter().filter(|n| n.channel == channel).count() as u64;
+                let style = if Some(channel) == selected_channel {
+                    Theme::fg(Color::Cyan)
+                } else if Some(channel) == best {
+                    Theme::fg(Color::Green)
+                } else {
+                    Theme::fg(Color::Gray)
+                };
+                Bar::default()
+                    .value(count)
+                    .text_value(if count > 0 { count.to_string() } else { String::new() })
+                    .label(Line::from(channel.to_string()))
+                    .style(style)
+            })
+            .collect();
+
+        let title = match best {
+            Some(channel) => format!(" {} · best ch {} ", label, channel),
+            None => format!(" {} ", label),
+        };
+
+        // Fit every channel in the available width (1 column gap between bars)
+        let inner_width = area.width.saturating_sub(2);
+        let bar_width = (inner_width / channels.len().max(1) as u16)
+            .saturating_sub(1)
+            .clamp(1, 4);
+
+        let chart = BarChart::default()
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .style(Theme::border_style())
+                    .title(Span::styled(title, Theme::title_style())),
+            )
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(bar_width)
+            .bar_gap(1)
+            .value_style(Theme::plain(Style::default().fg(Color::Black).bg(Color::Gray)));
+
+        frame.render_widget(chart, area);
+    }
+}
+
+impl Component for ChannelChart {
+    fn render(&self, frame: &mut Frame, area: Rect, app: &App) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Percentage(50),
+                Constraint::Percentage(50),
+            ])
+            .split(area);
+
+        let best_2_4 = recommend_channel(&app.networks, FrequencyBand::Band2_4GHz).first().copied();
+        let best_5 = recommend_channel(&app.networks, FrequencyBand::Band5GHz).first().copied();
+
+        let summary = Paragraph::new(format!(" {}", recommendation_summary(&app.networks)))
+            .style(Theme::fg(Color::Green));
+        frame.render_widget(summary, chunks[0]);
+
+        Self::render_band(frame, chunks[1], app, "Channels 2.4 GHz", CHANNELS_2_4GHZ, best_2_4);
+        Self::render_band(frame, chunks[2], app, "Channels 5 GHz", CHANNELS_5GHZ, best_5);
+    }
+}
@@ -1,5 +1,8 @@
 use crate::app::App;
+use crate::captive::CaptiveStatus;
 use crate::components::Component;
+use crate::scoring::{experience_score, stability_penalty};
+use crate::speedtest::format_speed;
 use crate::theme::Theme;
 use chrono::Utc;
 use ratatui::layout::Rect;
@@ -12,7 +15,7 @@ pub struct DetailPanel;
 
 impl Component for DetailPanel {
     fn render(&self, frame: &mut Frame, area: Rect, app: &App) {
-        let content = if let Some(network) = app.networks.get(app.selected_index) {
+        let content = if let Some(network) = app.selected_network() {
             let score_style = Theme::score_style(network.score);
             let is_connected = app.is_connected(network);
             let is_known = app.is_known_network(&network.ssid);
@@ -25,7 +28,7 @@ impl Component for DetailPanel {
                 Line::from(""),
                 Line::from(vec![
                     Span::raw("MAC: "),
-                    Span::raw(&network.mac),
+                    Span::raw(app.display_mac(&network.mac)),
                 ]),
             ];
 
@@ -33,12 +36,12 @@ impl Component for DetailPanel {
             let status_line = if is_connected {
                 Line::from(vec![
                     Span::raw("Status: "),
-                    Span::styled("● Connected", Style::default().fg(Color::Green)),
+                    Span::styled("● Connected", Theme::fg(Color::Green)),
                 ])
             } else if is_known {
                 Line::from(vec![
                     Span::raw("Status: "),
-                    Span::styled("Known Network", Style::default().fg(Color::Yellow)),
+                    Span::styled("Known Network", Theme::fg(Color::Yellow)),
                 ])
             } else {
                 Line::from(vec![
@@ -47,6 +50,40 @@ impl Component for DetailPanel {
                 ])
             };
             lines.push(status_line);
+
+            // Mesh networks: the same SSID served by several access points
+            let roams = if is_connected { app.roams_in_last_hour(&network.ssid) } else { 0 };
+            if roams > 0 {
+                lines.push(Line::from(Span::styled(
+                    format!("Roamed {}× in last hour", roams),
+                    Theme::fg(Color::Gray),
+                )));
+            }
+
+            // Captive portal result from the last connection
+            match app.captive_status(&network.mac) {
+                Some(CaptiveStatus::CaptivePortal) => lines.push(Line::from(Span::styled(
+                    "⚠ Captive Portal",
+                    Theme::fg(Color::Yellow),
+                ))),
+                Some(CaptiveStatus::NoInternet) => lines.push(Line::from(Span::styled(
+                    "⚠ No Internet",
+                    Theme::fg(Color::Red),
+                ))),
+                _ => {}
+            }
+
+            // Same SSID from APs that disagree on security or vendor
+            if let Some(conflict) = app.ssid_conflict(&network.mac) {
+                lines.push(Line::from(Span::styled(
+                    "⚠ duplicate SSID (possible evil twin)",
+                    Theme::ssid_conflict_style(),
+                )));
+                lines.push(Line::from(Span::styled(
+                    format!("{} APs: {}", conflict.bssids.len(), conflict.explanation()),
+                    Theme::fg(Color::Gray),
+                )));
+            }
             lines.push(Line::from(""));
 
             // Basic network info
@@ -57,39 +94,132 @@ impl Component for DetailPanel {
             lines.push(Line::from(vec![
                 Span::raw("Signal: "),
                 Span::styled(
-                    format!("{} dBm", network.signal_dbm),
+                    app.signal_units.format(network.signal_dbm),
                     Theme::signal_style(network.signal_dbm),
                 ),
             ]));
+            if let Some(peak) = app.get_peak_signal(&network.mac) {
+                lines.push(Line::from(vec![
+                    Span::raw("Best ever: "),
+                    Span::styled(app.signal_units.format(peak), Theme::fg(Color::Gray)),
+                ]));
+            }
+            if let Some(std_dev) = app.signal_stability(&network.mac) {
+                let style = if stability_penalty(std_dev) > 0 {
+                    Theme::fg(Color::Yellow)
+                } else {
+                    Theme::fg(Color::Gray)
+                };
+                lines.push(Line::from(vec![
+                    Span::raw("Signal stability: "),
+                    Span::styled(format!("±{:.0} dB", std_dev), style),
+                ]));
+            }
             lines.push(Line::from(vec![
                 Span::raw("Security: "),
                 Span::raw(network.security.to_string()),
             ]));
+
+            // Same SSID on other bands: show the strongest AP of each
+            let mut shown_bands = Vec::new();
+            for sibling in app.sibling_bands(network) {
+                if shown_bands.contains(&sibling.frequency_band) {
+                    continue;
+                }
+                shown_bands.push(sibling.frequency_band);
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "Also on {} (ch {}, {})",
+                        sibling.frequency_band,
+                        sibling.channel,
+                        app.signal_units.format(sibling.signal_dbm)
+                    ),
+                    Theme::fg(Color::Gray),
+                )));
+            }
             lines.push(Line::from(""));
             lines.push(Line::from(vec![
                 Span::raw("Score: "),
                 Span::styled(format!("{}/100", network.score), score_style),
             ]));
+            // Two factors per line to fit the panel, then the penalty scoring leaves out
+            let breakdown = app.score_breakdown(network);
+            let [signal, congestion, security, band] = breakdown.factors;
+            let mut total = format!("{}, {} → {}", security, band, breakdown.total());
+            if let Some(std_dev) = app.signal_stability(&network.mac)
+                && stability_penalty(std_dev) > 0
+            {
+                total.push_str(&format!(" −{} unstable", stability_penalty(std_dev)));
+            }
+            for text in [format!("{}, {},", signal, congestion), total] {
+                lines.push(Line::from(Span::styled(format!("  {}", text), Theme::fg(Color::Gray))));
+            }
 
-            // Connection History section (if we have cached data)
-            if let Some((cached_bssid, history)) = &app.cached_connection_history {
-                if cached_bssid == &network.mac && !history.is_empty() {
-                    lines.push(Line::from(""));
-                    lines.push(Line::from(Span::styled(
-                        "─── Connection History ───",
-                        Style::default().fg(Color::Cyan),
-                    )));
+            if let Some(note) = app.network_note(&network.mac) {
+                lines.push(Line::from(vec![
+                    Span::raw("Note: "),
+                    Span::styled(note, Theme::fg(Color::Yellow)),
+                ]));
+            }
 
-                    // Last connected
-                    if let Some(last) = history.first() {
-                        let relative = format_relative_time(last.connected_at);
-                        lines.push(Line::from(format!("Last connected: {}", relative)));
-                    }
+            // Measured experience, once a speed test has been recorded here
+            let last_measured = app
+                .cached_connection_history
+                .as_ref()
+                .filter(|(cached_bssid, _)| cached_bssid == &network.mac)
+                .and_then(|(_, history)| history.iter().find(|c| c.download_mbps.is_some()));
+            if let Some(conn) = last_measured {
+                let experience = experience_score(network, conn);
+                lines.push(Line::from(vec![
+                    Span::raw("Experience: "),
+                    Span::styled(format!("{}/100", experience), Theme::score_style(experience)),
+                    Span::styled(" (measured)", Theme::fg(Color::DarkGray)),
+                ]));
+            }
 
-                    // Connection count
-                    if let Some(count) = app.get_connection_count(&network.mac) {
-                        lines.push(Line::from(format!("Times connected: {}", count)));
-                    }
+            // Connection History section (if we have cached data or past attempts)
+            let history = app
+                .cached_connection_history
+                .as_ref()
+                .filter(|(cached_bssid, history)| cached_bssid == &network.mac && !history.is_empty())
+                .map(|(_, history)| history);
+            let success = app.get_connection_success(&network.mac);
+            if history.is_some() || success.is_some() {
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled(
+                    "─── Connection History ───",
+                    Theme::fg(Color::Cyan),
+                )));
+
+                // Last connected
+                if let Some(last) = history.and_then(|h| h.first()) {
+                    let relative = format_relative_time(last.connected_at);
+                    lines.push(Line::from(format!("Last connected: {}", relative)));
+                }
+
+                // Connection count
+                if history.is_some()
+                    && let Some(count) = app.get_connection_count(&network.mac)
+                {
+                    lines.push(Line::from(format!("Times connected: {}", count)));
+                }
+
+                // Flaky networks stand out by their failed attempts
+                if let Some(success) = success {
+                    let style = if success.percent() < 50 {
+                        Theme::fg(Color::Yellow)
+                    } else {
+                        Style::default()
+                    };
+                    lines.push(Line::from(Span::styled(
+                        format!(
+                            "Connected {}/{} attempts ({}%)",
+                            success.succeeded,
+                            success.attempts,
+                            success.percent()
+                        ),
+                        style,
+                    )));
                 }
             }
 
@@ -99,11 +229,13 @@ impl Component for DetailPanel {
                     lines.push(Line::from(""));
                     lines.push(Line::from(Span::styled(
                         "─── Speed Test ───",
-                        Style::default().fg(Color::Cyan),
+                        Theme::fg(Color::Cyan),
                     )));
                     lines.push(Line::from(format!(
-                        "↓ {:.1} Mbps  ↑ {:.1} Mbps",
-                        speed.download_mbps, speed.upload_mbps
+                        "↓ {}  ↑ {}{}",
+                        format_speed(speed.download_mbps, app.speed_units),
+                        format_speed(speed.upload_mbps, app.speed_units),
+                        format_latency(speed.latency_ms)
                     )));
                 }
             } else if is_connected || is_known {
@@ -115,12 +247,13 @@ impl Component for DetailPanel {
                             lines.push(Line::from(""));
                             lines.push(Line::from(Span::styled(
                                 "─── Last Speed Test ───",
-                                Style::default().fg(Color::Cyan),
+                                Theme::fg(Color::Cyan),
                             )));
                             lines.push(Line::from(format!(
-                                "↓ {:.1} Mbps  ↑ {:.1} Mbps",
-                                conn.download_mbps.unwrap_or(0.0),
-                                conn.upload_mbps.unwrap_or(0.0)
+                                "↓ {}  ↑ {}{}",
+                                format_speed(conn.download_mbps.unwrap_or(0.0), app.speed_units),
+                                format_speed(conn.upload_mbps.unwrap_or(0.0), app.speed_units),
+                                format_latency(conn.latency_ms)
                             )));
                         }
                     }
@@ -133,15 +266,25 @@ impl Component for DetailPanel {
 
             // If this is the connected network, show current IPs
             if is_connected {
-                if app.current_local_ip.is_some() || app.current_public_ip.is_some() {
-                    show_ip_section = true;
-                    if let Some(ref local) = app.current_local_ip {
-                        ip_lines.push(Line::from(format!("Local:  {}", local)));
-                    }
-                    if let Some(ref public) = app.current_public_ip {
-                        ip_lines.push(Line::from(format!("Public: {}", public)));
+                let current = [
+                    ("Local:     ", &app.current_local_ip),
+                    ("Public:    ", &app.current_public_ip),
+                    ("Local v6:  ", &app.current_local_ipv6),
+                    ("Public v6: ", &app.current_public_ipv6),
+                ];
+                for (label, ip) in current {
+                    if let Some(ip) = ip {
+                        show_ip_section = true;
+                        ip_lines.push(Line::from(format!("{}{}", label, ip)));
                     }
                 }
+                if show_ip_section && let Some(age) = app.public_ip_age() {
+                    let refreshed = Utc::now() - chrono::TimeDelta::from_std(age).unwrap_or_default();
+                    ip_lines.push(Line::from(Span::styled(
+                        format!("Public IP cached (refreshed {})", format_relative_time(refreshed)),
+                        Theme::fg(Color::DarkGray),
+                    )));
+                }
             }
 
             // Also show historical IPs if available
@@ -168,7 +311,7 @@ impl Component for DetailPanel {
                 lines.push(Line::from(""));
                 lines.push(Line::from(Span::styled(
                     "─── IP Addresses ───",
-                    Style::default().fg(Color::Cyan),
+                    Theme::fg(Color::Cyan),
                 )));
                 lines.extend(ip_lines);
             }
@@ -178,7 +321,7 @@ impl Component for DetailPanel {
             vec![Line::from("No network selected")]
         };
 
-        let title = if let Some(network) = app.networks.get(app.selected_index) {
+        let title = if let Some(network) = app.selected_network() {
             format!(" {} ", network.ssid)
         } else {
             " Details ".to_string()
@@ -217,3 +360,8 @@ fn format_relative_time(time: chrono::DateTime<Utc>) -> String {
         format!("{} weeks ago", secs / 604800)
     }
 }
+
+/// Latency suffix for a speed test line (empty when it wasn't measured)
+fn format_latency(latency_ms: Option<f64>) -> String {
+    latency_ms.map(|ms| format!("  ⏱ {:.0} ms", ms)).unwrap_or_default()
+}
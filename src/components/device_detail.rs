@@ -1,18 +1,19 @@
 use crate::app::App;
+use crate::components::network_table::format_relative_time;
 use crate::components::Component;
-use crate::network_map::PortState;
+use crate::network_map::{is_randomized_mac, port_risk, CertificateInfo, Device, PortRisk, PortState};
 use crate::theme::Theme;
 use ratatui::layout::Rect;
-use ratatui::style::{Color, Style};
+use ratatui::style::{Color, Modifier};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
 use ratatui::Frame;
 
 pub struct DeviceDetail;
 
 impl Component for DeviceDetail {
     fn render(&self, frame: &mut Frame, area: Rect, app: &App) {
-        if app.devices.is_empty() {
+        let Some(device) = app.selected_device() else {
             let empty = Paragraph::new("No device selected")
                 .block(
                     Block::default()
@@ -22,51 +23,80 @@ impl Component for DeviceDetail {
                 );
             frame.render_widget(empty, area);
             return;
-        }
-
-        let device = &app.devices[app.selected_device_index];
+        };
 
         // Build info lines
         let mut lines = vec![
             Line::from(""),
             Line::from(vec![
-                Span::styled("Name: ", Style::default().fg(Color::Gray)),
+                Span::styled("Name: ", Theme::fg(Color::Gray)),
                 Span::raw(device.display_name()),
             ]),
             Line::from(vec![
-                Span::styled("MAC:  ", Style::default().fg(Color::Gray)),
-                Span::raw(&device.mac_address),
+                Span::styled("MAC:  ", Theme::fg(Color::Gray)),
+                Span::raw(app.display_mac(&device.mac_address)),
+                Span::styled(randomized_note(device), Theme::fg(Color::Gray)),
             ]),
             Line::from(vec![
-                Span::styled("IP:   ", Style::default().fg(Color::Gray)),
+                Span::styled("IP:   ", Theme::fg(Color::Gray)),
                 Span::raw(&device.ip_address),
             ]),
             Line::from(vec![
-                Span::styled("Type: ", Style::default().fg(Color::Gray)),
+                Span::styled("Type: ", Theme::fg(Color::Gray)),
                 Span::raw(format!("{}", device.device_type)),
             ]),
         ];
 
+        if let Some(ref os) = device.os_guess {
+            lines.push(Line::from(vec![
+                Span::styled("OS:   ", Theme::fg(Color::Gray)),
+                Span::raw(format!("{} (guess)", os)),
+            ]));
+        }
+
         if let Some(ref vendor) = device.vendor {
             lines.push(Line::from(vec![
-                Span::styled("Vendor: ", Style::default().fg(Color::Gray)),
+                Span::styled("Vendor: ", Theme::fg(Color::Gray)),
                 Span::raw(vendor),
             ]));
         }
 
         if let Some(ref hostname) = device.hostname {
             lines.push(Line::from(vec![
-                Span::styled("Hostname: ", Style::default().fg(Color::Gray)),
+                Span::styled("Hostname: ", Theme::fg(Color::Gray)),
                 Span::raw(hostname),
             ]));
         }
 
+        if let Some(ref notes) = device.notes {
+            lines.push(Line::from(vec![
+                Span::styled("Notes: ", Theme::fg(Color::Gray)),
+                Span::styled(notes, Theme::fg(Color::Yellow)),
+            ]));
+        }
+
+        if !device.tags.is_empty() {
+            lines.push(Line::from(vec![
+                Span::styled("Tags: ", Theme::fg(Color::Gray)),
+                Span::styled(device.tags_text(), Theme::fg(Color::Cyan)),
+            ]));
+        }
+
+        // ARP cache disagreements
+        if let Some(ref conflict) = device.ip_conflict {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled("\u{26a0} IP conflict", Theme::ssid_conflict_style())));
+            for explanation in conflict.explanations(&device.ip_address) {
+                lines.push(Line::from(format!("  {}", explanation)));
+            }
+        }
+
         // AI Agents
         if !device.detected_agents.is_empty() {
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "Detected AI Agents:",
-                Style::default().fg(Color::Magenta),
+                Theme::fg(Color::Magenta),
             )));
             for agent in &device.detected_agents {
                 lines.push(Line::from(format!("  \u{2022} {}", agent)));
@@ -83,23 +113,48 @@ impl Component for DeviceDetail {
             lines.push(Line::from(""));
             lines.push(Line::from(Span::styled(
                 "Open Services:",
-                Style::default().fg(Color::Cyan),
+                Theme::fg(Color::Cyan),
             )));
 
-            for service in open_services.iter().take(10) {
-                let service_name = service.service_name.as_deref().unwrap_or("Unknown");
+            // Riskiest first, so they're never cut off by the 10-line limit
+            let mut rated: Vec<_> = open_services
+                .iter()
+                .map(|s| (*s, port_risk(s.port, s.banner.as_deref())))
+                .collect();
+            rated.sort_by_key(|(_, risk)| std::cmp::Reverse(*risk));
+            let risky = rated.iter().filter(|(_, risk)| *risk == PortRisk::Risky).count();
+            if risky > 0 {
+                lines.push(Line::from(Span::styled(
+                    format!("  ⚠ {} risky port{}", risky, if risky == 1 { "" } else { "s" }),
+                    Theme::port_risk_style(PortRisk::Risky).add_modifier(Modifier::BOLD),
+                )));
+            }
+
+            for &(service, risk) in rated.iter().take(10) {
+                let service_name = service.display_name().unwrap_or("Unknown");
                 let agent_info = service.detected_agent
                     .as_ref()
                     .map(|a| format!(" [{}]", a))
                     .unwrap_or_default();
+                let risk_info = match risk {
+                    PortRisk::Neutral => String::new(),
+                    _ => format!(" ({})", risk.label()),
+                };
 
-                lines.push(Line::from(format!(
-                    "  {:5} {} {}{}",
-                    service.port,
-                    service.protocol,
-                    service_name,
-                    agent_info
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "  {:5} {} {}{}{}",
+                        service.port,
+                        service.protocol,
+                        service_name,
+                        agent_info,
+                        risk_info
+                    ),
+                    Theme::port_risk_style(risk),
                 )));
+                if let Some(certificate) = &service.certificate {
+                    lines.extend(certificate_lines(certificate));
+                }
             }
 
             if open_services.len() > 10 {
@@ -113,21 +168,73 @@ impl Component for DeviceDetail {
         // Timestamps
         lines.push(Line::from(""));
         lines.push(Line::from(vec![
-            Span::styled("First seen: ", Style::default().fg(Color::Gray)),
+            Span::styled("First seen: ", Theme::fg(Color::Gray)),
             Span::raw(device.first_seen.format("%Y-%m-%d %H:%M").to_string()),
         ]));
         lines.push(Line::from(vec![
-            Span::styled("Last seen:  ", Style::default().fg(Color::Gray)),
+            Span::styled("Last seen:  ", Theme::fg(Color::Gray)),
             Span::raw(device.last_seen.format("%Y-%m-%d %H:%M").to_string()),
         ]));
+        // ARP refreshes show traffic; a scan only shows the device answered
+        let activity = match app.arp_activity.last_active(&device.mac_address) {
+            Some(active) => format!("active {}", format_relative_time(active)),
+            None if device.is_online => "seen in last scan".to_string(),
+            None => "offline".to_string(),
+        };
+        lines.push(Line::from(vec![
+            Span::styled("Activity:   ", Theme::fg(Color::Gray)),
+            Span::raw(activity),
+        ]));
 
-        let paragraph = Paragraph::new(lines).block(
-            Block::default()
-                .borders(Borders::ALL)
-                .style(Theme::border_style())
-                .title(Span::styled(" Device Details ", Theme::title_style())),
-        );
+        let paragraph = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .style(Theme::border_style())
+                    .title(Span::styled(" Device Details ", Theme::title_style())),
+            )
+            .wrap(Wrap { trim: false });
 
         frame.render_widget(paragraph, area);
     }
 }
+
+/// Names, issuer and expiry of a TLS service's certificate, indented under it
+fn certificate_lines(certificate: &CertificateInfo) -> Vec<Line<'static>> {
+    let label = |text: &'static str| Span::styled(text, Theme::fg(Color::Gray));
+    let mut names: Vec<&str> = certificate.common_name.iter().map(String::as_str).collect();
+    for name in &certificate.subject_alt_names {
+        if !names.contains(&name.as_str()) {
+            names.push(name);
+        }
+    }
+
+    let mut lines = Vec::new();
+    if !names.is_empty() {
+        lines.push(Line::from(vec![label("        cert:    "), Span::raw(names.join(", "))]));
+    }
+    if let Some(issuer) = &certificate.issuer {
+        lines.push(Line::from(vec![label("        issuer:  "), Span::raw(issuer.clone())]));
+    }
+    if let Some(not_after) = certificate.not_after {
+        let date = not_after.format("%Y-%m-%d").to_string();
+        let expiry = if certificate.is_expired(chrono::Utc::now()) {
+            Span::styled(format!("{} (expired)", date), Theme::fg(Color::Red).add_modifier(Modifier::BOLD))
+        } else {
+            Span::raw(date)
+        };
+        lines.push(Line::from(vec![label("        expires: "), expiry]));
+    }
+    lines
+}
+
+/// " (randomized, 3 seen)" for a rotating private MAC, counting merged aliases
+fn randomized_note(device: &Device) -> String {
+    if !is_randomized_mac(&device.mac_address) {
+        String::new()
+    } else if device.mac_aliases.is_empty() {
+        " (randomized)".to_string()
+    } else {
+        format!(" (randomized, {} seen)", device.mac_aliases.len() + 1)
+    }
+}
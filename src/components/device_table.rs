@@ -1,4 +1,4 @@
-use crate::app::App;
+use crate::app::{App, DeviceSortField};
 use crate::components::Component;
 use crate::theme::Theme;
 use ratatui::layout::{Constraint, Rect};
@@ -11,17 +11,20 @@ pub struct DeviceTable;
 
 impl Component for DeviceTable {
     fn render(&self, frame: &mut Frame, area: Rect, app: &App) {
+        let sort = app.device_sort_by;
         let header_cells = [
-            Cell::from(Span::styled("Status", Theme::header_style())),
-            Cell::from(Span::styled("Device", Theme::header_style())),
-            Cell::from(Span::styled("IP Address", Theme::header_style())),
-            Cell::from(Span::styled("Vendor", Theme::header_style())),
-            Cell::from(Span::styled("AI", Theme::header_style())),
+            header_cell("Status", false),
+            header_cell("Device", matches!(sort, DeviceSortField::Name | DeviceSortField::Type)),
+            header_cell("IP Address", sort == DeviceSortField::Ip),
+            header_cell("Vendor", sort == DeviceSortField::Vendor),
+            header_cell("AI", false),
         ];
 
         let header = Row::new(header_cells).style(Theme::header_style()).height(1);
 
-        let rows = app.devices.iter().enumerate().map(|(idx, device)| {
+        let visible = app.visible_device_indices();
+        let rows = visible.iter().map(|&idx| {
+            let device = &app.devices[idx];
             let is_selected = idx == app.selected_device_index;
 
             // Selection indicator
@@ -49,8 +52,15 @@ impl Component for DeviceTable {
             };
             let device_cell = Cell::from(truncate(&name_with_type, 24));
 
-            // IP address
-            let ip_cell = Cell::from(device.ip_address.clone());
+            // IP address, marked when another MAC claims it too
+            let ip_cell = if device.ip_conflict.is_some() {
+                Cell::from(Line::from(vec![
+                    Span::styled("\u{26a0} ", Theme::ssid_conflict_style()),
+                    Span::raw(device.ip_address.clone()),
+                ]))
+            } else {
+                Cell::from(device.ip_address.clone())
+            };
 
             // Vendor
             let vendor = device.vendor.as_deref().unwrap_or("Unknown");
@@ -60,7 +70,7 @@ impl Component for DeviceTable {
             let ai_cell = if !device.detected_agents.is_empty() {
                 Cell::from(Span::styled(
                     "[AI]",
-                    Style::default().fg(ratatui::style::Color::Magenta),
+                    Theme::fg(ratatui::style::Color::Magenta),
                 ))
             } else {
                 Cell::from("")
@@ -70,6 +80,8 @@ impl Component for DeviceTable {
 
             if is_selected {
                 row.style(Theme::selected_style())
+            } else if app.is_new_device(&device.mac_address) {
+                row.style(Theme::new_device_style())
             } else {
                 row
             }
@@ -81,14 +93,29 @@ impl Component for DeviceTable {
         } else {
             ""
         };
-        let title = format!(" Network Devices ({} found){} ", device_count, scan_status);
+        let conflicts = match app.devices.iter().filter(|d| d.ip_conflict.is_some()).count() {
+            0 => String::new(),
+            n => format!(", {} with \u{26a0} IP conflict", n),
+        };
+        let shown = if app.agent_filter {
+            format!("{} AI-agent devices of {}", visible.len(), device_count)
+        } else {
+            format!("{} found", device_count)
+        };
+        let title = format!(
+            " Network Devices ({}{}, by {}){} ",
+            shown,
+            conflicts,
+            sort.label(),
+            scan_status
+        );
 
         let table = Table::new(
             rows,
             [
                 Constraint::Length(4),   // Status
                 Constraint::Min(20),     // Device name
-                Constraint::Length(15),  // IP
+                Constraint::Length(17),  // IP (with conflict marker)
                 Constraint::Length(12),  // Vendor
                 Constraint::Length(5),   // AI
             ],
@@ -103,12 +130,20 @@ impl Component for DeviceTable {
         .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
 
         let mut table_state = TableState::default();
-        table_state.select(Some(app.selected_device_index));
+        table_state.select(app.selected_device_row());
 
         frame.render_stateful_widget(table, area, &mut table_state);
     }
 }
 
+fn header_cell(name: &str, is_sorted: bool) -> Cell<'static> {
+    let indicator = if is_sorted { " \u{25bc}" } else { "" };
+    Cell::from(Line::from(vec![
+        Span::styled(name.to_string(), Theme::header_style()),
+        Span::raw(indicator.to_string()),
+    ]))
+}
+
 fn truncate(s: &str, max_len: usize) -> String {
     if s.len() > max_len {
         format!("{}...", &s[..max_len.saturating_sub(3)])
@@ -0,0 +1,84 @@
+use crate::app::App;
+use crate::components::Component;
+use crate::theme::Theme;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::Frame;
+
+/// Historical averages for the selected network at the current location,
+/// to tell a consistently good network from a momentarily strong one
+pub struct HistorySummary;
+
+impl Component for HistorySummary {
+    fn render(&self, frame: &mut Frame, area: Rect, app: &App) {
+        let location = app.current_location_name.as_deref().unwrap_or("this location");
+
+        let lines = match app.selected_network() {
+            None => vec![Line::from("No network selected")],
+            Some(_) if app.db.is_none() => vec![Line::from("History requires persistence")],
+            Some(network) => match app.network_summary(&network.mac) {
+                None => vec![Line::from(format!("No scans of {} recorded at {}", network.ssid, location))],
+                Some(summary) => {
+                    let avg_signal = summary.avg_signal_dbm.round() as i32;
+                    let diff = network.signal_dbm - avg_signal;
+                    let (trend, trend_style) = if diff >= 5 {
+                        ("stronger than usual", Theme::fg(Color::Green))
+                    } else if diff <= -5 {
+                        ("weaker than usual", Theme::fg(Color::Red))
+                    } else {
+                        ("typical", Theme::fg(Color::Gray))
+                    };
+
+                    vec![
+                        Line::from(vec![
+                            Span::raw("SSID: "),
+                            Span::styled(&network.ssid, Theme::title_style()),
+                        ]),
+                        Line::from(format!("Location: {}", location)),
+                        Line::from(""),
+                        Line::from(vec![
+                            Span::raw("Current signal: "),
+                            Span::styled(
+                                format!("{} dBm", network.signal_dbm),
+                                Theme::signal_style(network.signal_dbm),
+                            ),
+                        ]),
+                        Line::from(vec![
+                            Span::raw("Avg signal:     "),
+                            Span::styled(format!("{} dBm", avg_signal), Theme::signal_style(avg_signal)),
+                        ]),
+                        Line::from(vec![
+                            Span::raw("                "),
+                            Span::styled(format!("{:+} dB, {}", diff, trend), trend_style),
+                        ]),
+                        Line::from(""),
+                        Line::from(vec![
+                            Span::raw("Current score:  "),
+                            Span::styled(format!("{}/100", network.score), Theme::score_style(network.score)),
+                        ]),
+                        Line::from(vec![
+                            Span::raw("Avg score:      "),
+                            Span::styled(
+                                format!("{:.0}/100", summary.avg_score),
+                                Theme::score_style(summary.avg_score.round() as u8),
+                            ),
+                        ]),
+                        Line::from(""),
+                        Line::from(format!("Observations:   {}", summary.times_seen)),
+                    ]
+                }
+            },
+        };
+
+        let paragraph = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Theme::border_style())
+                .title(Span::styled(" Historical Summary ", Theme::title_style())),
+        );
+
+        frame.render_widget(paragraph, area);
+    }
+}
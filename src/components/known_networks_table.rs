@@ -0,0 +1,79 @@
+use crate::app::App;
+use crate::components::Component;
+use crate::theme::Theme;
+use chrono::{DateTime, Utc};
+use ratatui::layout::{Constraint, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Span;
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState};
+use ratatui::Frame;
+
+pub struct KnownNetworksTable;
+
+impl Component for KnownNetworksTable {
+    fn render(&self, frame: &mut Frame, area: Rect, app: &App) {
+        let title = format!(" Known Networks ({}) ", app.known_networks.len());
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .style(Theme::border_style())
+            .title(Span::styled(title, Theme::title_style()));
+
+        if app.known_networks.is_empty() {
+            let message = if app.db.is_some() {
+                "No known networks imported"
+            } else {
+                "Known networks require persistence (run without --no-persist)"
+            };
+            frame.render_widget(Paragraph::new(message).block(block), area);
+            return;
+        }
+
+        let header_cells = [
+            Cell::from(Span::styled("SSID", Theme::header_style())),
+            Cell::from(Span::styled("Last Connected", Theme::header_style())),
+            Cell::from(Span::styled("Added", Theme::header_style())),
+        ];
+
+        let header = Row::new(header_cells).style(Theme::header_style()).height(1);
+
+        let rows = app.known_networks.iter().enumerate().map(|(idx, network)| {
+            let is_selected = idx == app.selected_known_index;
+            let select_indicator = if is_selected { "\u{25b6}" } else { " " };
+
+            let row = Row::new([
+                Cell::from(format!("{} {}", select_indicator, network.ssid)),
+                Cell::from(format_timestamp(network.last_connected_at)),
+                Cell::from(format_timestamp(network.added_at)),
+            ]);
+
+            if is_selected {
+                row.style(Theme::selected_style())
+            } else {
+                row
+            }
+        });
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Min(24),     // SSID
+                Constraint::Length(18),  // Last connected
+                Constraint::Length(18),  // Added
+            ],
+        )
+        .header(header)
+        .block(block)
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        let mut table_state = TableState::default();
+        table_state.select(Some(app.selected_known_index));
+
+        frame.render_stateful_widget(table, area, &mut table_state);
+    }
+}
+
+fn format_timestamp(timestamp: Option<DateTime<Utc>>) -> String {
+    timestamp
+        .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
@@ -1,15 +1,25 @@
+mod channel_chart;
 mod detail_panel;
 mod device_detail;
 mod device_table;
+mod history_summary;
+mod known_networks_table;
 mod network_table;
 mod signal_chart;
+mod signal_meter;
+mod speed_chart;
 mod status_bar;
 
+pub use channel_chart::ChannelChart;
 pub use detail_panel::DetailPanel;
 pub use device_detail::DeviceDetail;
 pub use device_table::DeviceTable;
+pub use history_summary::HistorySummary;
+pub use known_networks_table::KnownNetworksTable;
 pub use network_table::NetworkTable;
 pub use signal_chart::SignalChart;
+pub use signal_meter::SignalMeter;
+pub use speed_chart::SpeedChart;
 pub use status_bar::StatusBar;
 
 use crate::app::App;
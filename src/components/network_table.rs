@@ -1,12 +1,13 @@
-use crate::app::{App, SortField};
+use crate::app::{App, BandFilter, NetworkFilter, SignalUnits, SortField};
 use crate::components::Component;
+use crate::network_map::lookup_vendor;
 use crate::scanner::SecurityType;
 use crate::theme::Theme;
 use chrono::Utc;
-use ratatui::layout::{Constraint, Rect};
+use ratatui::layout::{Alignment, Constraint, Rect};
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Cell, Row, Table, TableState};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState, Wrap};
 use ratatui::Frame;
 
 pub struct NetworkTable;
@@ -18,13 +19,17 @@ impl Component for NetworkTable {
             header_cell("Signal", app.sort_by == SortField::Signal),
             header_cell("Score", app.sort_by == SortField::Score),
             header_cell("Security", false),
+            header_cell("Vendor", false),
             header_cell("Channel", false),
             header_cell("Last Seen", false),
         ];
 
         let header = Row::new(header_cells).style(Theme::header_style()).height(1);
 
-        let rows = app.networks.iter().enumerate().map(|(idx, network)| {
+        let visible = app.visible_networks();
+        let conflicts = app.ssid_conflicts();
+
+        let rows = visible.iter().enumerate().map(|(idx, network)| {
             let is_selected = idx == app.selected_index;
             let is_connected = app.is_connected(network);
 
@@ -40,26 +45,39 @@ impl Component for NetworkTable {
 
             // Build SSID cell with both indicators
             let ssid_text = truncate(&network.ssid, 16);
+            // Duplicate SSID with mismatched security/vendor: possible evil twin
+            let conflict_marker = if conflicts.iter().any(|c| c.contains(&network.mac)) {
+                " \u{26a0}"
+            } else {
+                ""
+            };
             let ssid_cell = Cell::from(Line::from(vec![
                 Span::raw(format!("{} ", select_indicator)),
                 Span::styled(connect_indicator, connect_style),
                 Span::raw(format!(" {}", ssid_text)),
+                Span::styled(conflict_marker, Theme::ssid_conflict_style()),
             ]));
 
-            let signal_cell = Cell::from(Span::styled(
-                network.signal_bars(),
+            let mut signal_spans = vec![Span::styled(
+                format!("{} {}", network.signal_bars(), app.signal_units.format_short(network.signal_dbm)),
                 Theme::signal_style(network.signal_dbm),
-            ));
+            )];
+            if let Some(delta) = app.signal_delta(&network.mac) {
+                signal_spans.push(Span::styled(format_delta(delta), Theme::signal_delta_style(delta)));
+            }
+            let signal_cell = Cell::from(Line::from(signal_spans));
 
             let score_cell =
                 Cell::from(Span::styled(format!("{:3}", network.score), Theme::score_style(network.score)));
 
             let security_style = match network.security {
-                SecurityType::Open => Theme::security_open_style(),
+                SecurityType::Open | SecurityType::OWE => Theme::security_open_style(),
                 _ => Theme::security_secured_style(),
             };
             let security_cell = Cell::from(Span::styled(network.security.to_string(), security_style));
 
+            let vendor_cell = Cell::from(truncate(&vendor_label(app, &network.mac), 10));
+
             let channel_cell = Cell::from(format!(
                 "{} ({})",
                 network.channel, network.frequency_band
@@ -67,7 +85,15 @@ impl Component for NetworkTable {
 
             let last_seen_cell = Cell::from(format_relative_time(network.last_seen));
 
-            let row = Row::new([ssid_cell, signal_cell, score_cell, security_cell, channel_cell, last_seen_cell]);
+            let row = Row::new([
+                ssid_cell,
+                signal_cell,
+                score_cell,
+                security_cell,
+                vendor_cell,
+                channel_cell,
+                last_seen_cell,
+            ]);
 
             if is_selected {
                 row.style(Theme::selected_style())
@@ -77,17 +103,61 @@ impl Component for NetworkTable {
         });
 
         let network_count = app.networks.len();
-        let title = format!(" Networks ({} found) ", network_count);
+        let mut active_filters = Vec::new();
+        if app.network_filter != NetworkFilter::All {
+            active_filters.push(app.network_filter.label().to_string());
+        }
+        if app.band_filter != BandFilter::All {
+            active_filters.push(app.band_filter.label().to_string());
+        }
+        if !app.filter_query.is_empty() {
+            active_filters.push(format!("matching \"{}\"", app.filter_query));
+        }
+        let hidden = match app.hidden_count() {
+            0 => String::new(),
+            n => format!(" ({} hidden)", n),
+        };
+        let weak = match app.min_signal_dbm {
+            Some(min) => format!(" ({} hidden below {} dBm)", app.below_min_signal_count(), min),
+            None => String::new(),
+        };
+        let matching = app.filtered_count();
+        let top = if visible.len() < matching {
+            format!(" (showing top {} of {})", visible.len(), matching)
+        } else {
+            String::new()
+        };
+        let title = if active_filters.is_empty() {
+            format!(" Networks ({} found){}{}{} ", network_count, hidden, weak, top)
+        } else {
+            format!(
+                " Networks ({}/{} {}){}{}{} ",
+                matching,
+                network_count,
+                active_filters.join(", "),
+                hidden,
+                weak,
+                top
+            )
+        };
+        let is_filtered_empty = visible.is_empty() && network_count > 0;
+
+        // Bars plus the widest label, e.g. "-100 100%", and the change since the last scan
+        let signal_width = match app.signal_units {
+            SignalUnits::Dbm | SignalUnits::Percent => 16,
+            SignalUnits::Both => 21,
+        };
 
         let table = Table::new(
             rows,
             [
-                Constraint::Min(22),       // SSID
-                Constraint::Length(7),     // Signal bars
+                Constraint::Min(20),       // SSID
+                Constraint::Length(signal_width), // Signal
                 Constraint::Length(5),     // Score
-                Constraint::Length(8),     // Security
-                Constraint::Length(14),    // Channel
-                Constraint::Length(10),    // Last Seen
+                Constraint::Length(9),     // Security
+                Constraint::Length(10),    // Vendor
+                Constraint::Length(12),    // Channel
+                Constraint::Length(8),     // Last Seen
             ],
         )
         .header(header)
@@ -95,7 +165,9 @@ impl Component for NetworkTable {
             Block::default()
                 .borders(Borders::ALL)
                 .style(Theme::border_style())
-                .title(Span::styled(title, Theme::title_style())),
+                .title(Span::styled(title, Theme::title_style()))
+                // Per-band totals count the whole scan, whatever is filtered out
+                .title_bottom(Line::from(format!(" {} ", app.band_counts().summary())).right_aligned()),
         )
         .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
 
@@ -103,6 +175,51 @@ impl Component for NetworkTable {
         table_state.select(Some(app.selected_index));
 
         frame.render_stateful_widget(table, area, &mut table_state);
+
+        // Explain an empty table instead of leaving it blank
+        if is_filtered_empty {
+            let message_area = Rect {
+                x: area.x + 1,
+                y: area.y + 3,
+                width: area.width.saturating_sub(2),
+                height: 1.min(area.height.saturating_sub(4)),
+            };
+            let message = Paragraph::new("No networks match filter")
+                .style(Theme::help_style())
+                .alignment(Alignment::Center);
+            frame.render_widget(message, message_area);
+        } else if network_count == 0 {
+            // Zero results usually mean WiFi is off or Location permission is missing
+            let message_area = Rect {
+                x: area.x + 1,
+                y: area.y + 3,
+                width: area.width.saturating_sub(2),
+                height: area.height.saturating_sub(4),
+            };
+            let text = if app.is_scanning {
+                vec![Line::from("Scanning for networks...")]
+            } else {
+                vec![
+                    Line::from("No networks found \u{2014} check WiFi is on and Location permission is granted."),
+                    Line::from(""),
+                    Line::from("Press 'd' for demo, 'r' to rescan."),
+                ]
+            };
+            let message = Paragraph::new(text)
+                .style(Theme::help_style())
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true });
+            frame.render_widget(message, message_area);
+        }
+    }
+}
+
+/// " ▲ +4", " ▼ -6" or " — 0"
+fn format_delta(delta: i32) -> String {
+    match delta.signum() {
+        1 => format!(" \u{25b2} +{}", delta),
+        -1 => format!(" \u{25bc} {}", delta),
+        _ => " \u{2014} 0".to_string(),
     }
 }
 
@@ -114,16 +231,36 @@ fn header_cell(name: &str, is_sorted: bool) -> Cell<'static> {
     ]))
 }
 
+/// AP vendor from the BSSID's OUI, or the OUI prefix in the current MAC
+/// format when it isn't listed. Synthetic BSSIDs (02: prefix, generated when
+/// macOS hides the real one) have no vendor to look up.
+fn vendor_label(app: &App, mac: &str) -> String {
+    if mac.starts_with("02:") {
+        return "\u{2014}".to_string();
+    }
+    match lookup_vendor(mac) {
+        Some(vendor) => vendor.to_string(),
+        None => oui_prefix(&app.display_mac(mac)),
+    }
+}
+
+/// First three octets of a formatted MAC address, e.g. "A4-C3-61" or "a4c361"
+fn oui_prefix(formatted: &str) -> String {
+    let separated = formatted.chars().nth(2).is_some_and(|c| !c.is_ascii_hexdigit());
+    formatted.chars().take(if separated { 8 } else { 6 }).collect()
+}
+
 fn truncate(s: &str, max_len: usize) -> String {
-    if s.len() > max_len {
-        format!("{}...", &s[..max_len - 3])
+    // Count chars, not bytes: SSIDs and IEEE vendor names may be non-ASCII
+    if s.chars().count() > max_len {
+        format!("{}...", s.chars().take(max_len - 3).collect::<String>())
     } else {
         s.to_string()
     }
 }
 
 /// Format a timestamp as relative time (e.g., "2m ago", "1h ago")
-fn format_relative_time(time: chrono::DateTime<Utc>) -> String {
+pub(super) fn format_relative_time(time: chrono::DateTime<Utc>) -> String {
     let now = Utc::now();
     let duration = now.signed_duration_since(time);
 
@@ -1,50 +1,88 @@
-use crate::app::App;
+use crate::app::{App, SignalUnits};
 use crate::components::Component;
+use crate::scanner::dbm_to_percent;
 use crate::theme::Theme;
-use ratatui::layout::Rect;
-use ratatui::text::Span;
-use ratatui::widgets::{Block, Borders, Sparkline};
+use ratatui::layout::{Alignment, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph, Sparkline};
 use ratatui::Frame;
+use std::collections::VecDeque;
+
+/// Fewer samples than this don't make a trend, so a placeholder is shown instead
+const MIN_CHART_POINTS: usize = 2;
 
 pub struct SignalChart;
 
-impl Component for SignalChart {
-    fn render(&self, frame: &mut Frame, area: Rect, app: &App) {
-        let data: Vec<u64> = if let Some(network) = app.networks.get(app.selected_index) {
-            // Use MAC address (BSSID) as the key for signal history
-            if let Some(history) = app.signal_history.get(&network.mac) {
-                // Convert dBm to positive values for sparkline (0-100 scale)
-                // -30 dBm = 100, -90 dBm = 0
-                history
-                    .iter()
-                    .map(|&dbm| {
-                        let clamped = dbm.clamp(-90, -30);
-                        ((clamped + 90) as f32 / 60.0 * 100.0) as u64
-                    })
-                    .collect()
-            } else {
-                vec![]
-            }
-        } else {
-            vec![]
+impl SignalChart {
+    /// Sparkline values for the selected network (this session's samples
+    /// plus any backfilled from the database), oldest first
+    pub fn data(app: &App) -> Vec<u64> {
+        let Some(history) = Self::history(app) else {
+            return vec![];
         };
+        // Convert dBm to positive values for sparkline (0-100 scale)
+        history.iter().map(|&dbm| dbm_to_percent(dbm) as u64).collect()
+    }
+
+    /// Weakest and strongest samples for the selected network,
+    /// or None until there are enough of them to chart
+    pub fn range(app: &App) -> Option<(i32, i32)> {
+        let history = Self::history(app).filter(|h| h.len() >= MIN_CHART_POINTS)?;
+        Some((*history.iter().min()?, *history.iter().max()?))
+    }
+
+    fn history(app: &App) -> Option<&VecDeque<i32>> {
+        let network = app.selected_network()?;
+        // Use MAC address (BSSID) as the key for signal history
+        app.signal_history.get(&network.mac)
+    }
+}
+
+/// Min/max label; "Both" uses the compact form so the pair fits the border
+fn axis_label(units: SignalUnits, dbm: i32) -> String {
+    match units {
+        SignalUnits::Both => units.format_short(dbm),
+        _ => units.format(dbm),
+    }
+}
 
+impl Component for SignalChart {
+    fn render(&self, frame: &mut Frame, area: Rect, app: &App) {
         let current_dbm = app
-            .networks
-            .get(app.selected_index)
+            .selected_network()
             .map(|n| n.signal_dbm)
             .unwrap_or(-100);
 
-        let title = format!(" Signal History ({} dBm) ", current_dbm);
+        let title = format!(" Signal History ({}) ", app.signal_units.format(current_dbm));
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .style(Theme::border_style())
+            .title(Span::styled(title, Theme::title_style()));
+
+        let Some((min, max)) = Self::range(app) else {
+            // A sparkline of zero or one sample is just an empty box
+            let inner = block.inner(area);
+            frame.render_widget(block, area);
+            let message_area = Rect {
+                y: inner.y + inner.height / 2,
+                height: inner.height.min(1),
+                ..inner
+            };
+            let message = Paragraph::new("Collecting signal data…")
+                .style(Theme::help_style())
+                .alignment(Alignment::Center);
+            frame.render_widget(message, message_area);
+            return;
+        };
 
+        let labels = format!(
+            " min {} · max {} ",
+            axis_label(app.signal_units, min),
+            axis_label(app.signal_units, max)
+        );
         let sparkline = Sparkline::default()
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .style(Theme::border_style())
-                    .title(Span::styled(title, Theme::title_style())),
-            )
-            .data(&data)
+            .block(block.title_bottom(Line::from(labels).right_aligned()))
+            .data(Self::data(app))
             .style(Theme::signal_style(current_dbm));
 
         frame.render_widget(sparkline, area);
@@ -0,0 +1,85 @@
+use crate::app::App;
+use crate::components::Component;
+use crate::scanner::dbm_to_percent;
+use crate::theme::Theme;
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use ratatui::style::Modifier;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Gauge, Paragraph, Sparkline};
+use ratatui::Frame;
+
+/// Full-size live signal gauge for one network, for walking around a site
+pub struct SignalMeter;
+
+impl Component for SignalMeter {
+    fn render(&self, frame: &mut Frame, area: Rect, app: &App) {
+        let Some(meter) = &app.meter else {
+            return;
+        };
+        let current = meter.current();
+
+        let title = format!(" Signal Meter: {} ({}) ", meter.ssid, app.display_mac(&meter.bssid));
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .style(Theme::border_style())
+            .title(Span::styled(title, Theme::title_style()));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints([
+                Constraint::Length(1), // Current reading
+                Constraint::Length(1),
+                Constraint::Length(5), // Gauge
+                Constraint::Length(1),
+                Constraint::Length(1), // Min/avg/max
+                Constraint::Min(3),    // Trend
+                Constraint::Length(1), // Hint
+            ])
+            .split(inner);
+
+        let reading = if meter.missed {
+            Span::styled(
+                format!("{} (not seen in the last scan)", app.signal_units.format(current)),
+                Theme::help_style(),
+            )
+        } else {
+            Span::styled(
+                app.signal_units.format(current),
+                Theme::signal_style(current).add_modifier(Modifier::BOLD),
+            )
+        };
+        frame.render_widget(Paragraph::new(Line::from(reading)).alignment(Alignment::Center), chunks[0]);
+
+        let gauge = Gauge::default()
+            .block(Block::default().borders(Borders::ALL).style(Theme::border_style()))
+            .gauge_style(Theme::signal_style(current))
+            .percent(dbm_to_percent(current).into())
+            .label(format!("{} dBm", current));
+        frame.render_widget(gauge, chunks[2]);
+
+        let stats = format!(
+            "min {} dBm   avg {:.1} dBm   max {} dBm   ({} readings)",
+            meter.min,
+            meter.average(),
+            meter.max,
+            meter.count()
+        );
+        frame.render_widget(Paragraph::new(stats).alignment(Alignment::Center), chunks[4]);
+
+        let data: Vec<u64> = meter.samples.iter().map(|&dbm| dbm_to_percent(dbm) as u64).collect();
+        let trend = Sparkline::default()
+            .block(Block::default().borders(Borders::TOP).style(Theme::border_style()))
+            .data(&data)
+            .max(100)
+            .style(Theme::signal_style(current));
+        frame.render_widget(trend, chunks[5]);
+
+        let hint = Paragraph::new("Scanning continuously · Esc/w close · l log · q quit")
+            .style(Theme::help_style())
+            .alignment(Alignment::Center);
+        frame.render_widget(hint, chunks[6]);
+    }
+}
@@ -0,0 +1,66 @@
+use crate::app::App;
+use crate::components::Component;
+use crate::speedtest::format_speed;
+use crate::theme::Theme;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Color;
+use ratatui::text::Span;
+use ratatui::widgets::{Block, Borders, Sparkline};
+use ratatui::Frame;
+
+/// Download and upload speeds from past speed tests of the selected network,
+/// to spot a network that slows down over the day
+pub struct SpeedChart;
+
+impl SpeedChart {
+    /// Sparkline values (tenths of a Mbps) for download and upload, oldest first
+    pub fn data(app: &App) -> (Vec<u64>, Vec<u64>) {
+        app.selected_speed_history()
+            .iter()
+            .map(|&(_, down, up)| (to_tenths(down), to_tenths(up)))
+            .unzip()
+    }
+}
+
+fn to_tenths(mbps: f64) -> u64 {
+    (mbps.max(0.0) * 10.0).round() as u64
+}
+
+impl Component for SpeedChart {
+    fn render(&self, frame: &mut Frame, area: Rect, app: &App) {
+        let history = app.selected_speed_history();
+        let (down, up) = Self::data(app);
+        let (latest_down, latest_up) = history
+            .last()
+            .map(|&(_, down, up)| (down, up))
+            .unwrap_or((0.0, 0.0));
+
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(area);
+
+        let charts = [
+            (
+                format!(" ↓ {} ({} tests) ", format_speed(latest_down, app.speed_units), history.len()),
+                &down,
+                Color::Cyan,
+            ),
+            (format!(" ↑ {} ", format_speed(latest_up, app.speed_units)), &up, Color::Magenta),
+        ];
+
+        for ((title, data, color), chunk) in charts.into_iter().zip(chunks.iter()) {
+            let sparkline = Sparkline::default()
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .style(Theme::border_style())
+                        .title(Span::styled(title, Theme::title_style())),
+                )
+                .data(data)
+                .style(Theme::fg(color));
+
+            frame.render_widget(sparkline, *chunk);
+        }
+    }
+}
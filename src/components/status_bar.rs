@@ -1,8 +1,8 @@
-use crate::app::{App, AppView, ScanMode};
+use crate::app::{App, AppView, BandFilter, NetworkFilter, ScanMode};
 use crate::components::Component;
 use crate::theme::Theme;
 use ratatui::layout::Rect;
-use ratatui::style::{Color, Style};
+use ratatui::style::Color;
 use ratatui::text::{Line, Span};
 use ratatui::widgets::Paragraph;
 use ratatui::Frame;
@@ -13,26 +13,47 @@ impl Component for StatusBar {
     fn render(&self, frame: &mut Frame, area: Rect, app: &App) {
         // Mode indicator with countdown for auto mode
         let mode_span = match app.scan_mode {
+            _ if app.current_view == AppView::NetworkDevices => {
+                if !app.device_auto_scan {
+                    Span::styled("[Manual]", Theme::manual_mode_style())
+                } else if let Some(secs) = app.next_device_scan_secs() {
+                    Span::styled(
+                        format!("[Auto] next device scan in {}s", secs),
+                        Theme::auto_mode_style(),
+                    )
+                } else {
+                    Span::styled("[Auto]", Theme::auto_mode_style())
+                }
+            }
             ScanMode::Auto => {
+                let label = if app.adaptive_interval.is_some() { "Adaptive" } else { "Auto" };
                 if let Some(secs) = app.get_next_refresh_secs() {
                     Span::styled(
-                        format!("[Auto] next scan in {}s", secs),
+                        format!("[{}] next scan in {}s", label, secs),
                         Theme::auto_mode_style(),
                     )
                 } else {
-                    Span::styled("[Auto]", Theme::auto_mode_style())
+                    Span::styled(format!("[{}]", label), Theme::auto_mode_style())
                 }
             }
             ScanMode::Manual => Span::styled("[Manual]", Theme::manual_mode_style()),
         };
 
+        // When the network list was last refreshed (WiFi view only)
+        let last_scan_span = match app.last_scan_summary() {
+            Some(summary) if app.current_view == AppView::WifiNetworks && !app.is_scanning => {
+                Span::styled(format!(" {}", summary), Theme::help_style())
+            }
+            _ => Span::raw(""),
+        };
+
         // Status message - prioritize speed test progress, then device scan progress
         let status_span = if let Some(speedtest_status) = app.get_speedtest_status() {
-            Span::styled(format!(" {}", speedtest_status), Style::default().fg(Color::Yellow))
+            Span::styled(format!(" {}", speedtest_status), Theme::fg(Color::Yellow))
         } else if let Some(ref progress) = app.device_scan_progress {
             Span::styled(
                 format!(" Scanning: {} devices found", progress.devices_found),
-                Style::default().fg(Color::Yellow),
+                Theme::fg(Color::Yellow),
             )
         } else if app.is_scanning {
             Span::raw(" Scanning...")
@@ -42,19 +63,44 @@ impl Component for StatusBar {
             Span::raw("")
         };
 
+        // Active network filter (WiFi view only)
+        let filter_span = if app.current_view == AppView::WifiNetworks
+            && app.network_filter != NetworkFilter::All
+        {
+            Span::styled(
+                format!(" [Filter: {}]", app.network_filter.label()),
+                Theme::fg(Color::Cyan),
+            )
+        } else {
+            Span::raw("")
+        };
+
+        let band_span = if app.current_view == AppView::WifiNetworks && app.band_filter != BandFilter::All {
+            Span::styled(
+                format!(" [Band: {}]", app.band_filter.label()),
+                Theme::fg(Color::Cyan),
+            )
+        } else {
+            Span::raw("")
+        };
+
         // View-specific shortcuts
         let help_text = match app.current_view {
             AppView::WifiNetworks => Span::styled(
-                " | Tab Devices | ↑↓ Nav | Enter Connect | r Scan | s Sort | ? Help | q Quit",
+                " | Tab Devices | ↑↓ Nav | Enter Connect | r Scan | +/- Interval | s Sort | f Filter | b Band | g Gateway | n Nearby | p Pin | t Top | w Meter | c Channels | h History | y Copy | / Search | e Note | m MAC | u Units | l Log | ? Help | q Quit",
                 Theme::help_style(),
             ),
             AppView::NetworkDevices => Span::styled(
-                " | Tab WiFi | ↑↓ Nav | Enter Details | s Scan | r Rename | ? Help | q Quit",
+                " | Tab Known | ↑↓ Nav | Enter Details | s Scan | S Deep scan | a Auto | o Sort | i AI only | r Rename | e Notes | t Tags | y Copy | m MAC | l Log | ? Help | q Quit",
+                Theme::help_style(),
+            ),
+            AppView::KnownNetworks => Span::styled(
+                " | Tab WiFi | ↑↓ Nav | r Re-import | x Forget | l Log | ? Help | q Quit",
                 Theme::help_style(),
             ),
         };
 
-        let line = Line::from(vec![mode_span, status_span, help_text]);
+        let line = Line::from(vec![mode_span, last_scan_span, filter_span, band_span, status_span, help_text]);
 
         let paragraph = Paragraph::new(line);
         frame.render_widget(paragraph, area);
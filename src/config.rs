@@ -0,0 +1,104 @@
+use crate::keymap::KeyBinding;
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Settings remembered between runs, stored as TOML at
+/// `$XDG_CONFIG_HOME/wifi-analyzer/config.toml` (defaulting to `~/.config`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Last-used auto-refresh interval in seconds
+    pub interval_secs: Option<u64>,
+    /// Wrap table selection around at the first and last rows
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub wrap_navigation: bool,
+    /// Remapped keys by action name, see `Keymap::from_config`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub keys: BTreeMap<String, KeyBinding>,
+}
+
+impl Config {
+    /// Default config file location (None if neither XDG_CONFIG_HOME nor HOME is set)
+    pub fn default_path() -> Option<PathBuf> {
+        let config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+        Some(config_home.join("wifi-analyzer").join("config.toml"))
+    }
+
+    /// Read the config, treating a missing file as defaults
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => Ok(toml::from_str(&contents)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write the config, creating its directory if needed
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_file_is_default() {
+        let path = std::env::temp_dir().join("wifi_analyzer_no_such_config.toml");
+        assert_eq!(Config::load(&path).unwrap(), Config::default());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let dir = std::env::temp_dir().join(format!("wifi_analyzer_config_{}", std::process::id()));
+        let path = dir.join("config.toml");
+
+        let config = Config {
+            interval_secs: Some(60),
+            ..Config::default()
+        };
+        config.save(&path).unwrap();
+        let loaded = Config::load(&path).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(loaded, config);
+        assert!(contents.contains("interval_secs = 60"));
+        assert!(!contents.contains("[keys]"));
+        assert!(!contents.contains("wrap_navigation"));
+    }
+
+    #[test]
+    fn test_keys_section() {
+        let config: Config = toml::from_str(
+            r#"
+interval_secs = 30
+wrap_navigation = true
+
+[keys]
+scan = "F5"
+sort = ["o", "ctrl+s"]
+"#,
+        )
+        .unwrap();
+        assert!(config.wrap_navigation);
+        assert_eq!(config.keys["scan"], KeyBinding::One("F5".to_string()));
+        assert_eq!(
+            config.keys["sort"],
+            KeyBinding::Many(vec!["o".to_string(), "ctrl+s".to_string()])
+        );
+
+        // Saving the interval keeps the user's bindings
+        let saved: Config = toml::from_str(&toml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(saved, config);
+    }
+}
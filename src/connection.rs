@@ -8,20 +8,85 @@ use color_eyre::Result;
 use std::process::Command;
 
 use crate::db::Database;
+use crate::mac::canonical_mac;
 
 /// Result of getting current WiFi connection
 #[derive(Debug, Clone)]
 pub struct CurrentConnection {
     pub ssid: String,
     pub bssid: Option<String>,
+    /// Whether `bssid` was read from the WiFi hardware. The gateway's MAC
+    /// stands in when it can't be, and only approximates the AP's.
+    pub bssid_verified: bool,
+}
+
+/// Interface used when no wireless hardware port can be found
+pub const DEFAULT_INTERFACE: &str = "en0";
+
+/// A wireless hardware port as reported by networksetup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WirelessInterface {
+    /// Hardware port name (e.g. "Wi-Fi")
+    pub port: String,
+    /// BSD device name (e.g. "en0")
+    pub device: String,
+}
+
+/// List wireless interfaces (`networksetup -listallhardwareports`)
+pub fn list_wireless_interfaces() -> Vec<WirelessInterface> {
+    match Command::new("networksetup").arg("-listallhardwareports").output() {
+        Ok(output) => parse_hardware_ports(&String::from_utf8_lossy(&output.stdout)),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// First wireless interface, falling back to en0
+pub fn default_wireless_interface() -> String {
+    list_wireless_interfaces()
+        .into_iter()
+        .next()
+        .map(|iface| iface.device)
+        .unwrap_or_else(|| DEFAULT_INTERFACE.to_string())
+}
+
+/// Parse `networksetup -listallhardwareports` output, keeping wireless ports.
+/// Each port is a block of "Hardware Port: Wi-Fi", "Device: en0", "Ethernet Address: ..." lines.
+fn parse_hardware_ports(output: &str) -> Vec<WirelessInterface> {
+    let mut interfaces = Vec::new();
+    let mut port: Option<String> = None;
+
+    for line in output.lines() {
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix("Hardware Port:") {
+            port = Some(name.trim().to_string());
+        } else if let Some(device) = line.strip_prefix("Device:")
+            && let Some(name) = port.take()
+            && is_wireless_port(&name)
+        {
+            interfaces.push(WirelessInterface {
+                port: name,
+                device: device.trim().to_string(),
+            });
+        }
+    }
+
+    interfaces
+}
+
+/// Whether a hardware port name refers to a WiFi adapter
+fn is_wireless_port(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    ["wi-fi", "airport", "wlan", "wireless", "802.11"]
+        .iter()
+        .any(|k| lower.contains(k))
 }
 
 /// Get the currently connected WiFi network on macOS
-pub fn get_current_connection() -> Result<Option<CurrentConnection>> {
+pub fn get_current_connection(interface: &str) -> Result<Option<CurrentConnection>> {
     // Try multiple methods to detect the current connection
 
     // Method 1: Use networksetup (works on older macOS)
-    if let Some(conn) = try_networksetup_method() {
+    if let Some(conn) = try_networksetup_method(interface) {
         return Ok(Some(conn));
     }
 
@@ -30,9 +95,9 @@ pub fn get_current_connection() -> Result<Option<CurrentConnection>> {
         return Ok(Some(conn));
     }
 
-    // Method 3: Check if we have an IP on en0 (we're connected to something)
+    // Method 3: Check if we have an IP on the interface (we're connected to something)
     // In this case, we can't determine the SSID but we know we're connected
-    if is_interface_connected() {
+    if is_interface_connected(interface) {
         // We're connected but can't get SSID due to macOS privacy restrictions
         // The app will need to match based on signal strength or user confirmation
         return Ok(None);
@@ -42,9 +107,9 @@ pub fn get_current_connection() -> Result<Option<CurrentConnection>> {
 }
 
 /// Try using networksetup command (works on older macOS versions)
-fn try_networksetup_method() -> Option<CurrentConnection> {
+fn try_networksetup_method(interface: &str) -> Option<CurrentConnection> {
     let output = Command::new("networksetup")
-        .args(["-getairportnetwork", "en0"])
+        .args(["-getairportnetwork", interface])
         .output()
         .ok()?;
 
@@ -65,38 +130,24 @@ fn try_networksetup_method() -> Option<CurrentConnection> {
         return None;
     }
 
-    // Try to get BSSID
-    let bssid = get_current_bssid();
+    // Try to get BSSID, falling back to the gateway's MAC
+    let (bssid, bssid_verified) = match get_current_bssid() {
+        Some(bssid) => (Some(bssid), true),
+        None => (get_gateway_mac(), false),
+    };
 
-    Some(CurrentConnection { ssid, bssid })
+    Some(CurrentConnection { ssid, bssid, bssid_verified })
 }
 
 /// Try using Swift CoreWLAN script
 fn try_swift_method() -> Option<CurrentConnection> {
-    let script_path = std::env::current_exe()
-        .ok()?
-        .parent()?
-        .parent()?
-        .parent()?
-        .join("scripts/wifi_current.swift");
-
-    // Also try relative to working directory
-    let script_paths = [
-        script_path,
-        std::path::PathBuf::from("scripts/wifi_current.swift"),
-        std::path::PathBuf::from("./scripts/wifi_current.swift"),
-    ];
-
-    for path in &script_paths {
-        if path.exists() {
-            if let Ok(output) = Command::new("swift").arg(path).output() {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                return parse_swift_output(&stdout);
-            }
-        }
+    if !crate::scripts::swift_available() {
+        return None;
     }
-
-    None
+    let script_path = crate::scripts::WIFI_CURRENT.path().ok()?;
+    let output = Command::new("swift").arg(&script_path).output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    parse_swift_output(&stdout)
 }
 
 /// Parse output from wifi_current.swift script
@@ -113,18 +164,18 @@ fn parse_swift_output(output: &str) -> Option<CurrentConnection> {
         } else if let Some(b) = line.strip_prefix("BSSID:") {
             let b = b.trim();
             if !b.is_empty() {
-                bssid = Some(b.to_uppercase());
+                bssid = Some(canonical_mac(b));
             }
         }
     }
 
-    ssid.map(|ssid| CurrentConnection { ssid, bssid })
+    ssid.map(|ssid| CurrentConnection { ssid, bssid, bssid_verified: true })
 }
 
-/// Check if en0 interface has an IP address (indicating connection)
-fn is_interface_connected() -> bool {
+/// Check if the interface has an IP address (indicating connection)
+pub fn is_interface_connected(interface: &str) -> bool {
     if let Ok(output) = Command::new("ipconfig")
-        .args(["getifaddr", "en0"])
+        .args(["getifaddr", interface])
         .output()
     {
         let stdout = String::from_utf8_lossy(&output.stdout);
@@ -134,7 +185,7 @@ fn is_interface_connected() -> bool {
     }
 }
 
-/// Get the BSSID of the current connection using various methods
+/// Get the BSSID of the current connection from the airport utility
 fn get_current_bssid() -> Option<String> {
     // Try airport utility (older macOS)
     let airport_paths = [
@@ -153,7 +204,7 @@ fn get_current_bssid() -> Option<String> {
                         if let Some(bssid) = line.strip_prefix("BSSID:") {
                             let bssid = bssid.trim();
                             if !bssid.is_empty() && bssid != "0:0:0:0:0:0" {
-                                return Some(bssid.to_uppercase());
+                                return Some(canonical_mac(bssid));
                             }
                         }
                     }
@@ -162,11 +213,6 @@ fn get_current_bssid() -> Option<String> {
         }
     }
 
-    // Try to get gateway MAC from ARP table - this is often the router/AP MAC
-    if let Some(gateway_mac) = get_gateway_mac() {
-        return Some(gateway_mac);
-    }
-
     None
 }
 
@@ -206,9 +252,8 @@ pub fn get_gateway_mac() -> Option<String> {
             let parts: Vec<&str> = line.split(" at ").collect();
             if parts.len() >= 2 {
                 let mac_part = parts[1].split_whitespace().next()?;
-                // Normalize MAC format (add leading zeros if needed)
-                let mac = normalize_mac(mac_part);
-                return Some(mac);
+                // arp drops leading zeros ("c8:7f:54:b:29:1c")
+                return Some(canonical_mac(mac_part));
             }
         }
     }
@@ -216,24 +261,9 @@ pub fn get_gateway_mac() -> Option<String> {
     None
 }
 
-/// Normalize MAC address format (ensure uppercase, proper format)
-fn normalize_mac(mac: &str) -> String {
-    mac.split(':')
-        .map(|octet| {
-            if octet.len() == 1 {
-                format!("0{}", octet)
-            } else {
-                octet.to_string()
-            }
-        })
-        .collect::<Vec<_>>()
-        .join(":")
-        .to_uppercase()
-}
-
 /// Connect to a WiFi network by SSID
 /// Note: This only works for known networks (password already saved in keychain)
-pub fn connect_to_network(ssid: &str) -> Result<bool> {
+pub fn connect_to_network(ssid: &str, interface: &str) -> Result<bool> {
     // Try Swift CoreWLAN method first (more reliable on modern macOS)
     if let Some(result) = try_swift_connect(ssid) {
         return Ok(result);
@@ -241,7 +271,7 @@ pub fn connect_to_network(ssid: &str) -> Result<bool> {
 
     // Fallback to networksetup
     let output = Command::new("networksetup")
-        .args(["-setairportnetwork", "en0", ssid])
+        .args(["-setairportnetwork", interface, ssid])
         .output()?;
 
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -256,7 +286,7 @@ pub fn connect_to_network(ssid: &str) -> Result<bool> {
 
     // Quick poll for connection (3 seconds max)
     for _ in 0..3 {
-        if let Ok(Some(conn)) = get_current_connection() {
+        if let Ok(Some(conn)) = get_current_connection(interface) {
             if conn.ssid == ssid {
                 return Ok(true);
             }
@@ -264,7 +294,7 @@ pub fn connect_to_network(ssid: &str) -> Result<bool> {
         std::thread::sleep(std::time::Duration::from_secs(1));
     }
 
-    if let Ok(Some(conn)) = get_current_connection() {
+    if let Ok(Some(conn)) = get_current_connection(interface) {
         Ok(conn.ssid == ssid)
     } else {
         Ok(false)
@@ -273,20 +303,14 @@ pub fn connect_to_network(ssid: &str) -> Result<bool> {
 
 /// Try connecting using Swift CoreWLAN script
 fn try_swift_connect(ssid: &str) -> Option<bool> {
-    // Find the Swift script
-    let script_paths = [
-        std::env::current_exe()
-            .ok()
-            .and_then(|p| p.parent().map(|p| p.join("../scripts/wifi_connect.swift")))
-            .unwrap_or_default(),
-        std::path::PathBuf::from("scripts/wifi_connect.swift"),
-        std::path::PathBuf::from("./scripts/wifi_connect.swift"),
-    ];
-
-    let script_path = script_paths.iter().find(|p| p.exists())?;
+    if !crate::scripts::swift_available() {
+        return None;
+    }
+    // The script is embedded in the binary and extracted to a temp file
+    let script_path = crate::scripts::WIFI_CONNECT.path().ok()?;
 
     let output = Command::new("swift")
-        .arg(script_path)
+        .arg(&script_path)
         .arg(ssid)
         .output()
         .ok()?;
@@ -304,9 +328,9 @@ fn try_swift_connect(ssid: &str) -> Option<bool> {
 }
 
 /// Import known networks from macOS
-pub fn import_known_networks(db: &Database) -> Result<usize> {
+pub fn import_known_networks(db: &Database, interface: &str) -> Result<usize> {
     // Method 1: Use networksetup to list preferred networks (most reliable)
-    if let Ok(count) = import_from_networksetup(db) {
+    if let Ok(count) = import_from_networksetup(db, interface) {
         if count > 0 {
             return Ok(count);
         }
@@ -328,10 +352,25 @@ pub fn import_known_networks(db: &Database) -> Result<usize> {
     Ok(0)
 }
 
+/// Run an import and return how many SSIDs it added that weren't known
+/// before. Known SSIDs are updated in place, so repeating it is harmless.
+fn count_new_known_networks(db: &Database, import: impl FnOnce(&Database) -> Result<usize>) -> Result<usize> {
+    let before = db.get_known_networks_count()?;
+    import(db)?;
+    let after = db.get_known_networks_count()?;
+    Ok((after - before).max(0) as usize)
+}
+
+/// Import known networks again, e.g. after saving a new network in System
+/// Settings. Returns how many were new.
+pub fn reimport_known_networks(db: &Database, interface: &str) -> Result<usize> {
+    count_new_known_networks(db, |db| import_known_networks(db, interface))
+}
+
 /// Import known networks using networksetup command
-fn import_from_networksetup(db: &Database) -> Result<usize> {
+fn import_from_networksetup(db: &Database, interface: &str) -> Result<usize> {
     let output = Command::new("networksetup")
-        .args(["-listpreferredwirelessnetworks", "en0"])
+        .args(["-listpreferredwirelessnetworks", interface])
         .output()?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -424,6 +463,37 @@ pub fn get_known_network_last_connected(db: &Database, ssid: &str) -> Result<Opt
     Ok(None)
 }
 
+/// Remove a network from the system's preferred networks list
+/// (`networksetup -removepreferredwirelessnetwork`). A network that isn't in
+/// the list, because it was removed in System Settings or never made it
+/// there, counts as already forgotten.
+pub fn remove_preferred_network(ssid: &str, interface: &str) -> Result<()> {
+    let output = Command::new("networksetup")
+        .args(["-removepreferredwirelessnetwork", interface, ssid])
+        .output()?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    check_removal(ssid, output.status.success(), &stdout, &stderr)
+}
+
+/// Interpret the output of `networksetup -removepreferredwirelessnetwork`
+fn check_removal(ssid: &str, success: bool, stdout: &str, stderr: &str) -> Result<()> {
+    if stdout.contains("was not found") || stderr.contains("was not found") {
+        return Ok(());
+    }
+    if !success || stderr.contains("Error") {
+        let message = if stderr.trim().is_empty() { stdout.trim() } else { stderr.trim() };
+        return Err(color_eyre::eyre::eyre!(
+            "Could not remove \"{}\" from preferred networks: {}",
+            ssid,
+            message
+        ));
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -431,7 +501,81 @@ mod tests {
     #[test]
     fn test_get_current_connection() {
         // This test just verifies the function doesn't panic
-        let result = get_current_connection();
+        let result = get_current_connection(DEFAULT_INTERFACE);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_removing_a_missing_network_counts_as_forgotten() {
+        let missing = "Network Cafe was not found in the preferred networks list\n";
+        assert!(check_removal("Cafe", true, missing, "").is_ok());
+        assert!(check_removal("Cafe", true, "Removed Cafe from the preferred networks list\n", "").is_ok());
+        let err = check_removal("Cafe", false, "", "** Error: The operation couldn't be completed.\n").unwrap_err();
+        assert!(err.to_string().contains("Could not remove \"Cafe\""));
+    }
+
+    #[test]
+    fn test_swift_bssid_is_canonical() {
+        // CoreWLAN drops leading zeros, like airport does
+        let conn = parse_swift_output("SSID: Home\nBSSID: a4:c3:61:b:2e:f\n").unwrap();
+        assert_eq!(conn.bssid.as_deref(), Some("A4:C3:61:0B:2E:0F"));
+    }
+
+    #[test]
+    fn test_parse_hardware_ports() {
+        let output = r"
+            Hardware Port: Ethernet
+            Device: en0
+            Ethernet Address: 3c:22:fb:00:00:01
+
+            Hardware Port: Wi-Fi
+            Device: en1
+            Ethernet Address: a4:83:e7:00:00:02
+
+            Hardware Port: Thunderbolt Bridge
+            Device: bridge0
+            Ethernet Address: N/A
+
+            Hardware Port: USB 802.11n WLAN
+            Device: en7
+            Ethernet Address: 00:0f:00:00:00:03
+
+            VLAN Configurations
+            ===================
+        ";
+
+        let interfaces = parse_hardware_ports(output);
+        assert_eq!(
+            interfaces,
+            vec![
+                WirelessInterface { port: "Wi-Fi".to_string(), device: "en1".to_string() },
+                WirelessInterface { port: "USB 802.11n WLAN".to_string(), device: "en7".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_hardware_ports_empty() {
+        assert!(parse_hardware_ports("").is_empty());
+    }
+
+    #[test]
+    fn test_reimport_is_idempotent() {
+        let db = Database::open_in_memory().unwrap();
+        let import = |ssids: &'static [&'static str]| {
+            move |db: &Database| -> Result<usize> {
+                for ssid in ssids {
+                    db.import_known_network(ssid, None, None)?;
+                }
+                Ok(ssids.len())
+            }
+        };
+
+        assert_eq!(count_new_known_networks(&db, import(&["Home", "Office"])).unwrap(), 2);
+        assert_eq!(count_new_known_networks(&db, import(&["Home", "Office"])).unwrap(), 0);
+        // A network saved since the last import is picked up
+        assert_eq!(count_new_known_networks(&db, import(&["Home", "Office", "Cafe"])).unwrap(), 1);
+        assert_eq!(db.get_known_networks_count().unwrap(), 3);
+        assert!(db.is_known_network("Cafe").unwrap());
+    }
 }
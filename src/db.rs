@@ -1,7 +1,131 @@
 use chrono::{DateTime, Utc};
+use color_eyre::eyre::eyre;
 use color_eyre::Result;
-use duckdb::{params, Connection};
-use std::path::Path;
+use crate::ip::IpAddresses;
+use crate::scoring::ScoringProfile;
+use duckdb::types::Value;
+use duckdb::{params, params_from_iter, AccessMode, Config, Connection};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Attempts to open a database file that another process has locked
+const LOCK_ATTEMPTS: u32 = 4;
+/// Wait after the first locked attempt, doubled after each further one
+const LOCK_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Tables written to snapshots, parents before the tables referring to them
+const SNAPSHOT_TABLES: &[&str] = &[
+    "locations",
+    "networks",
+    "scans",
+    "scan_results",
+    "connections",
+    "connection_attempts",
+    "known_networks",
+    "devices",
+    "device_services",
+    "device_scans",
+    "roam_events",
+];
+
+/// Version of the snapshot JSON layout, bumped on incompatible changes
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// Portable JSON copy of the whole database, see `Database::export_snapshot`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub tables: Vec<TableSnapshot>,
+}
+
+impl Snapshot {
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Number of rows per table, in snapshot order
+    pub fn row_counts(&self) -> Vec<(String, usize)> {
+        self.tables.iter().map(|t| (t.name.clone(), t.rows.len())).collect()
+    }
+}
+
+/// One table's rows, values in `columns` order. Numbers and booleans stay
+/// as they are; timestamps and text are strings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableSnapshot {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+}
+
+/// How a column's values are written to a snapshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnKind {
+    Integer,
+    Float,
+    Boolean,
+    Text,
+}
+
+/// Database failures callers handle differently from other errors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DbError {
+    /// Another process (usually a second wifi-analyzer) holds the file's
+    /// write lock. DuckDB allows one writer or any number of readers, so
+    /// opening read-only doesn't help while that process runs.
+    Locked { path: PathBuf, detail: String },
+}
+
+impl fmt::Display for DbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DbError::Locked { path, detail } => {
+                write!(f, "Database {} is in use by another process ({})", path.display(), detail)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DbError {}
+
+/// Whether a DuckDB open error means the file is locked by another process
+fn is_lock_error(message: &str) -> bool {
+    let lower = message.to_lowercase();
+    lower.contains("could not set lock") || lower.contains("conflicting lock")
+}
+
+/// Run `open`, retrying with backoff while the file is locked, and turn a
+/// lock that outlasts the retries into `DbError::Locked`
+fn open_with_retry<T, E, F>(path: &Path, backoff: Duration, mut open: F) -> Result<T>
+where
+    E: std::error::Error + Send + Sync + 'static,
+    F: FnMut() -> std::result::Result<T, E>,
+{
+    let mut delay = backoff;
+    let mut attempt = 1;
+    loop {
+        match open() {
+            Ok(conn) => return Ok(conn),
+            Err(e) if !is_lock_error(&e.to_string()) => return Err(e.into()),
+            Err(e) if attempt == LOCK_ATTEMPTS => {
+                return Err(DbError::Locked {
+                    path: path.to_path_buf(),
+                    detail: e.to_string(),
+                }
+                .into());
+            }
+            Err(_) => {
+                std::thread::sleep(delay);
+                delay *= 2;
+                attempt += 1;
+            }
+        }
+    }
+}
 
 /// Database connection wrapper for WiFi network persistence
 pub struct Database {
@@ -15,6 +139,8 @@ pub struct Location {
     pub name: String,
     pub created_at: DateTime<Utc>,
     pub last_used_at: DateTime<Utc>,
+    /// Scoring profile applied when scanning here (balanced if unset)
+    pub profile: Option<ScoringProfile>,
 }
 
 /// A network record from the database
@@ -37,14 +163,26 @@ pub struct ScanResultRecord {
 }
 
 impl Database {
-    /// Open or create a database at the given path
+    /// Open or create a database at the given path. Fails with
+    /// `DbError::Locked` if another process keeps the file locked.
     pub fn open(path: impl AsRef<Path>) -> Result<Self> {
-        let conn = Connection::open(path.as_ref())?;
+        let path = path.as_ref();
+        let conn = open_with_retry(path, LOCK_BACKOFF, || Connection::open(path))?;
         let db = Self { conn };
         db.initialize_schema()?;
         Ok(db)
     }
 
+    /// Open an existing database without creating or migrating its schema.
+    /// Used by `doctor`, which must not change the file it inspects.
+    pub fn open_read_only(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let conn = open_with_retry(path, LOCK_BACKOFF, || {
+            Connection::open_with_flags(path, Config::default().access_mode(AccessMode::ReadOnly)?)
+        })?;
+        Ok(Self { conn })
+    }
+
     /// Create an in-memory database (for testing)
     #[allow(dead_code)]
     pub fn open_in_memory() -> Result<Self> {
@@ -68,6 +206,8 @@ impl Database {
             CREATE SEQUENCE IF NOT EXISTS seq_devices_id START 1;
             CREATE SEQUENCE IF NOT EXISTS seq_device_services_id START 1;
             CREATE SEQUENCE IF NOT EXISTS seq_device_scans_id START 1;
+            CREATE SEQUENCE IF NOT EXISTS seq_roam_events_id START 1;
+            CREATE SEQUENCE IF NOT EXISTS seq_connection_attempts_id START 1;
             "#,
         )?;
 
@@ -80,7 +220,8 @@ impl Database {
                 id INTEGER PRIMARY KEY DEFAULT nextval('seq_locations_id'),
                 name TEXT NOT NULL UNIQUE,
                 created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-                last_used_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+                last_used_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                scoring_profile TEXT
             );
 
             -- Networks: unique by BSSID (MAC address)
@@ -89,7 +230,8 @@ impl Database {
                 bssid TEXT NOT NULL UNIQUE,
                 ssid TEXT NOT NULL,
                 first_seen_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-                last_seen_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+                last_seen_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                notes TEXT
             );
 
             -- Scans: individual scan events
@@ -126,11 +268,25 @@ impl Database {
                 local_ip TEXT,
                 public_ip TEXT,
                 download_mbps REAL,
-                upload_mbps REAL
+                upload_mbps REAL,
+                captive_status TEXT,
+                local_ipv6 TEXT,
+                public_ipv6 TEXT,
+                latency_ms REAL
             );
             CREATE INDEX IF NOT EXISTS idx_connections_network ON connections(network_id);
             CREATE INDEX IF NOT EXISTS idx_connections_time ON connections(connected_at DESC);
 
+            -- Connection attempts: every connect, including the ones that failed
+            CREATE TABLE IF NOT EXISTS connection_attempts (
+                id INTEGER PRIMARY KEY DEFAULT nextval('seq_connection_attempts_id'),
+                network_id INTEGER NOT NULL,
+                attempted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                succeeded BOOLEAN NOT NULL,
+                reason TEXT
+            );
+            CREATE INDEX IF NOT EXISTS idx_connection_attempts_network ON connection_attempts(network_id);
+
             -- Known networks: imported from macOS plist
             CREATE TABLE IF NOT EXISTS known_networks (
                 id INTEGER PRIMARY KEY DEFAULT nextval('seq_known_networks_id'),
@@ -151,7 +307,9 @@ impl Database {
                 custom_name TEXT,
                 first_seen TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
                 last_seen TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-                network_bssid TEXT
+                network_bssid TEXT,
+                notes TEXT,
+                tags TEXT
             );
             CREATE INDEX IF NOT EXISTS idx_devices_mac ON devices(mac_address);
 
@@ -178,6 +336,30 @@ impl Database {
                 devices_found INTEGER,
                 scan_type TEXT
             );
+
+            -- Roam events: connected BSSID changed while the SSID stayed the same
+            CREATE TABLE IF NOT EXISTS roam_events (
+                id INTEGER PRIMARY KEY DEFAULT nextval('seq_roam_events_id'),
+                ssid TEXT NOT NULL,
+                from_bssid TEXT NOT NULL,
+                to_bssid TEXT NOT NULL,
+                roamed_at TIMESTAMP NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_roam_events_time ON roam_events(roamed_at);
+            "#,
+        )?;
+
+        // Columns added after the initial schema
+        self.conn.execute_batch(
+            r#"
+            ALTER TABLE connections ADD COLUMN IF NOT EXISTS captive_status TEXT;
+            ALTER TABLE connections ADD COLUMN IF NOT EXISTS local_ipv6 TEXT;
+            ALTER TABLE connections ADD COLUMN IF NOT EXISTS public_ipv6 TEXT;
+            ALTER TABLE connections ADD COLUMN IF NOT EXISTS latency_ms REAL;
+            ALTER TABLE networks ADD COLUMN IF NOT EXISTS notes TEXT;
+            ALTER TABLE locations ADD COLUMN IF NOT EXISTS scoring_profile TEXT;
+            ALTER TABLE devices ADD COLUMN IF NOT EXISTS notes TEXT;
+            ALTER TABLE devices ADD COLUMN IF NOT EXISTS tags TEXT;
             "#,
         )?;
         Ok(())
@@ -221,7 +403,7 @@ impl Database {
     /// List all known locations
     pub fn list_locations(&self) -> Result<Vec<Location>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, name, CAST(created_at AS VARCHAR), CAST(last_used_at AS VARCHAR) FROM locations ORDER BY last_used_at DESC",
+            "SELECT id, name, CAST(created_at AS VARCHAR), CAST(last_used_at AS VARCHAR), scoring_profile FROM locations ORDER BY last_used_at DESC",
         )?;
         let mut rows = stmt.query([])?;
         let mut locations = Vec::new();
@@ -231,18 +413,40 @@ impl Database {
             let name: String = row.get(1)?;
             let created_at: String = row.get(2)?;
             let last_used_at: String = row.get(3)?;
+            let profile: Option<String> = row.get(4)?;
 
             locations.push(Location {
                 id,
                 name,
                 created_at: parse_timestamp(&created_at),
                 last_used_at: parse_timestamp(&last_used_at),
+                profile: profile.as_deref().and_then(ScoringProfile::from_name),
             });
         }
 
         Ok(locations)
     }
 
+    /// Remember which scoring profile to use at a location
+    pub fn set_location_profile(&self, location_id: i64, profile: ScoringProfile) -> Result<()> {
+        self.conn.execute(
+            "UPDATE locations SET scoring_profile = ? WHERE id = ?",
+            params![profile.name(), location_id],
+        )?;
+        Ok(())
+    }
+
+    /// The scoring profile saved for a location, if any (unknown names are ignored)
+    pub fn get_location_profile(&self, location_id: i64) -> Result<Option<ScoringProfile>> {
+        let mut stmt = self.conn.prepare("SELECT scoring_profile FROM locations WHERE id = ?")?;
+        let mut rows = stmt.query(params![location_id])?;
+        let name: Option<String> = match rows.next()? {
+            Some(row) => row.get(0)?,
+            None => None,
+        };
+        Ok(name.as_deref().and_then(ScoringProfile::from_name))
+    }
+
     /// Insert or update a network by BSSID
     fn upsert_network(&self, bssid: &str, ssid: &str) -> Result<i64> {
         let bssid_upper = bssid.to_uppercase();
@@ -326,7 +530,6 @@ impl Database {
     }
 
     /// Get signal history for a network (by BSSID)
-    #[allow(dead_code)]
     pub fn get_signal_history(&self, bssid: &str, limit: usize) -> Result<Vec<i32>> {
         let bssid_upper = bssid.to_uppercase();
         let mut stmt = self.conn.prepare(
@@ -336,7 +539,7 @@ impl Database {
             JOIN networks n ON sr.network_id = n.id
             JOIN scans s ON sr.scan_id = s.id
             WHERE n.bssid = ?
-            ORDER BY s.scanned_at DESC
+            ORDER BY s.scanned_at DESC, s.id DESC
             LIMIT ?
             "#,
         )?;
@@ -390,24 +593,88 @@ impl Database {
         let mut networks = Vec::new();
 
         while let Some(row) = rows.next()? {
-            let scanned_at_str: String = row.get(7)?;
-            networks.push(LoadedNetwork {
-                bssid: row.get(0)?,
-                ssid: row.get(1)?,
-                channel: row.get::<_, i32>(2)? as u8,
-                signal_dbm: row.get(3)?,
-                security: row.get(4)?,
-                frequency_band: row.get(5)?,
-                score: row.get::<_, i32>(6)? as u8,
-                last_seen: parse_timestamp(&scanned_at_str),
+            networks.push(loaded_network(row)?);
+        }
+
+        Ok(networks)
+    }
+
+    /// The most recent observation of every BSSID across all locations, with
+    /// the location whose latest reading of it was strongest
+    pub fn load_all_networks_latest(&self) -> Result<Vec<GlobalNetwork>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            WITH per_location AS (
+                SELECT
+                    sr.network_id,
+                    s.location_id,
+                    sr.channel,
+                    sr.signal_dbm,
+                    sr.security,
+                    sr.frequency_band,
+                    sr.score,
+                    s.scanned_at,
+                    s.id as scan_id,
+                    ROW_NUMBER() OVER (
+                        PARTITION BY sr.network_id, s.location_id ORDER BY s.scanned_at DESC, s.id DESC
+                    ) as rn
+                FROM scan_results sr
+                JOIN scans s ON sr.scan_id = s.id
+            ),
+            ranked AS (
+                SELECT
+                    *,
+                    ROW_NUMBER() OVER (PARTITION BY network_id ORDER BY scanned_at DESC, scan_id DESC) as newest,
+                    ROW_NUMBER() OVER (PARTITION BY network_id ORDER BY signal_dbm DESC, scanned_at DESC) as strongest,
+                    COUNT(*) OVER (PARTITION BY network_id) as locations_seen
+                FROM per_location
+                WHERE rn = 1
+            )
+            SELECT
+                n.bssid,
+                n.ssid,
+                latest.channel,
+                latest.signal_dbm,
+                latest.security,
+                latest.frequency_band,
+                latest.score,
+                CAST(latest.scanned_at AS VARCHAR),
+                l.name,
+                best.signal_dbm,
+                latest.locations_seen
+            FROM networks n
+            JOIN ranked latest ON latest.network_id = n.id AND latest.newest = 1
+            JOIN ranked best ON best.network_id = n.id AND best.strongest = 1
+            JOIN locations l ON l.id = best.location_id
+            ORDER BY best.signal_dbm DESC, latest.score DESC
+            "#,
+        )?;
+        let mut rows = stmt.query([])?;
+        let mut networks = Vec::new();
+
+        while let Some(row) = rows.next()? {
+            networks.push(GlobalNetwork {
+                network: loaded_network(row)?,
+                best_location: row.get(8)?,
+                best_signal_dbm: row.get(9)?,
+                locations_seen: row.get(10)?,
             });
         }
 
         Ok(networks)
     }
 
-    /// Get networks seen at a location with their most recent stats
-    #[allow(dead_code)]
+    /// Strongest signal ever recorded for a network, at any location
+    pub fn get_peak_signal(&self, network_id: i64) -> Result<Option<i32>> {
+        let peak = self.conn.query_row(
+            "SELECT MAX(signal_dbm) FROM scan_results WHERE network_id = ?",
+            params![network_id],
+            |row| row.get(0),
+        )?;
+        Ok(peak)
+    }
+
+    /// Get networks seen at a location with their averages and most recent stats
     pub fn get_networks_at_location(&self, location_id: i64) -> Result<Vec<NetworkSummary>> {
         let mut stmt = self.conn.prepare(
             r#"
@@ -421,17 +688,17 @@ impl Database {
                  FROM scan_results sr2
                  JOIN scans s2 ON sr2.scan_id = s2.id
                  WHERE sr2.network_id = n.id AND s2.location_id = ?
-                 ORDER BY s2.scanned_at DESC LIMIT 1) as last_signal,
+                 ORDER BY s2.scanned_at DESC, s2.id DESC LIMIT 1) as last_signal,
                 (SELECT sr2.score
                  FROM scan_results sr2
                  JOIN scans s2 ON sr2.scan_id = s2.id
                  WHERE sr2.network_id = n.id AND s2.location_id = ?
-                 ORDER BY s2.scanned_at DESC LIMIT 1) as last_score
+                 ORDER BY s2.scanned_at DESC, s2.id DESC LIMIT 1) as last_score
             FROM networks n
             JOIN scan_results sr ON sr.network_id = n.id
             JOIN scans s ON sr.scan_id = s.id
             WHERE s.location_id = ?
-            GROUP BY n.id
+            GROUP BY n.id, n.bssid, n.ssid
             ORDER BY avg_score DESC
             "#,
         )?;
@@ -453,6 +720,43 @@ impl Database {
         Ok(summaries)
     }
 
+    /// Per-location reception of an SSID (all of its BSSIDs), best average score first
+    pub fn compare_network_across_locations(&self, ssid: &str) -> Result<Vec<LocationComparison>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT
+                l.name,
+                AVG(sr.signal_dbm) as avg_signal,
+                AVG(sr.score) as avg_score,
+                COUNT(*) as times_seen,
+                arg_max(sr.signal_dbm, sr.id) as last_signal,
+                arg_max(sr.score, sr.id) as last_score
+            FROM networks n
+            JOIN scan_results sr ON sr.network_id = n.id
+            JOIN scans s ON sr.scan_id = s.id
+            JOIN locations l ON s.location_id = l.id
+            WHERE n.ssid = ?
+            GROUP BY l.id, l.name
+            ORDER BY avg_score DESC, avg_signal DESC
+            "#,
+        )?;
+        let mut rows = stmt.query(params![ssid])?;
+        let mut comparisons = Vec::new();
+
+        while let Some(row) = rows.next()? {
+            comparisons.push(LocationComparison {
+                location: row.get(0)?,
+                avg_signal_dbm: row.get(1)?,
+                avg_score: row.get(2)?,
+                times_seen: row.get(3)?,
+                last_signal_dbm: row.get(4)?,
+                last_score: row.get(5)?,
+            });
+        }
+
+        Ok(comparisons)
+    }
+
     // ========== Connection Management ==========
 
     /// Get network ID by BSSID (public method)
@@ -470,21 +774,50 @@ impl Database {
         }
     }
 
+    /// Get the IDs of every BSSID broadcasting an SSID, most recently seen first
+    pub fn get_network_ids_by_ssid(&self, ssid: &str) -> Result<Vec<i64>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id FROM networks WHERE ssid = ? ORDER BY last_seen_at DESC",
+        )?;
+        let mut rows = stmt.query(params![ssid])?;
+        let mut ids = Vec::new();
+
+        while let Some(row) = rows.next()? {
+            ids.push(row.get(0)?);
+        }
+
+        Ok(ids)
+    }
+
     /// Insert a new connection record
     pub fn insert_connection(
         &self,
         network_id: i64,
-        local_ip: Option<&str>,
-        public_ip: Option<&str>,
+        ips: &IpAddresses,
         download_mbps: Option<f64>,
         upload_mbps: Option<f64>,
+        latency_ms: Option<f64>,
+        captive_status: Option<&str>,
     ) -> Result<i64> {
         self.conn.execute(
             r#"
-            INSERT INTO connections (network_id, local_ip, public_ip, download_mbps, upload_mbps)
-            VALUES (?, ?, ?, ?, ?)
+            INSERT INTO connections (
+                network_id, local_ip, public_ip, local_ipv6, public_ipv6,
+                download_mbps, upload_mbps, latency_ms, captive_status
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
-            params![network_id, local_ip, public_ip, download_mbps, upload_mbps],
+            params![
+                network_id,
+                ips.local_v4,
+                ips.public_v4,
+                ips.local_v6,
+                ips.public_v6,
+                download_mbps,
+                upload_mbps,
+                latency_ms,
+                captive_status
+            ],
         )?;
 
         // Get the inserted ID
@@ -519,7 +852,11 @@ impl Database {
                 local_ip,
                 public_ip,
                 download_mbps,
-                upload_mbps
+                upload_mbps,
+                captive_status,
+                local_ipv6,
+                public_ipv6,
+                latency_ms
             FROM connections
             WHERE network_id = ?
             ORDER BY connected_at DESC
@@ -530,24 +867,73 @@ impl Database {
         let mut records = Vec::new();
 
         while let Some(row) = rows.next()? {
-            let connected_at_str: String = row.get(2)?;
-            let disconnected_at_str: Option<String> = row.get(3)?;
+            records.push(connection_record(row)?);
+        }
 
-            records.push(ConnectionRecord {
-                id: row.get(0)?,
-                network_id: row.get(1)?,
-                connected_at: parse_timestamp(&connected_at_str),
-                disconnected_at: disconnected_at_str.map(|s| parse_timestamp(&s)),
-                local_ip: row.get(4)?,
-                public_ip: row.get(5)?,
-                download_mbps: row.get(6)?,
-                upload_mbps: row.get(7)?,
-            });
+        Ok(records)
+    }
+
+    /// Get every connection to an SSID, across all of its BSSIDs, newest first
+    pub fn get_ssid_connection_history(&self, ssid: &str) -> Result<Vec<ConnectionRecord>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT
+                c.id,
+                c.network_id,
+                CAST(c.connected_at AS VARCHAR),
+                CAST(c.disconnected_at AS VARCHAR),
+                c.local_ip,
+                c.public_ip,
+                c.download_mbps,
+                c.upload_mbps,
+                c.captive_status,
+                c.local_ipv6,
+                c.public_ipv6,
+                c.latency_ms
+            FROM connections c
+            JOIN networks n ON n.id = c.network_id
+            WHERE n.ssid = ?
+            ORDER BY c.connected_at DESC, c.id DESC
+            "#,
+        )?;
+        let mut rows = stmt.query(params![ssid])?;
+        let mut records = Vec::new();
+
+        while let Some(row) = rows.next()? {
+            records.push(connection_record(row)?);
         }
 
         Ok(records)
     }
 
+    /// Download/upload speeds from the most recent speed tests, oldest first
+    pub fn get_speed_history(&self, network_id: i64, limit: usize) -> Result<Vec<(DateTime<Utc>, f64, f64)>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT epoch_us(connected_at), download_mbps, upload_mbps
+            FROM connections
+            WHERE network_id = ? AND download_mbps IS NOT NULL AND upload_mbps IS NOT NULL
+            ORDER BY connected_at DESC, id DESC
+            LIMIT ?
+            "#,
+        )?;
+        let mut rows = stmt.query(params![network_id, limit as i64])?;
+        let mut history = Vec::new();
+
+        while let Some(row) = rows.next()? {
+            let micros: i64 = row.get(0)?;
+            history.push((
+                DateTime::from_timestamp_micros(micros).unwrap_or_else(Utc::now),
+                row.get(1)?,
+                row.get(2)?,
+            ));
+        }
+
+        // Reverse to get oldest-to-newest order
+        history.reverse();
+        Ok(history)
+    }
+
     /// Get recent distinct IPs for a network
     pub fn get_recent_ips(&self, network_id: i64, limit: usize) -> Result<Vec<String>> {
         let mut stmt = self.conn.prepare(
@@ -588,6 +974,137 @@ impl Database {
         Ok(records.into_iter().next())
     }
 
+    /// Get the latest captive portal check result for every network that has one
+    pub fn get_captive_statuses(&self) -> Result<Vec<(String, String)>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            WITH latest AS (
+                SELECT
+                    network_id,
+                    captive_status,
+                    ROW_NUMBER() OVER (PARTITION BY network_id ORDER BY connected_at DESC, id DESC) as rn
+                FROM connections
+                WHERE captive_status IS NOT NULL
+            )
+            SELECT n.bssid, l.captive_status
+            FROM latest l
+            JOIN networks n ON n.id = l.network_id
+            WHERE l.rn = 1
+            "#,
+        )?;
+        let mut rows = stmt.query([])?;
+        let mut statuses = Vec::new();
+
+        while let Some(row) = rows.next()? {
+            statuses.push((row.get(0)?, row.get(1)?));
+        }
+
+        Ok(statuses)
+    }
+
+    /// Record a connect attempt; `reason` says why a failed one failed
+    pub fn record_connection_attempt(&self, network_id: i64, succeeded: bool, reason: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO connection_attempts (network_id, succeeded, reason) VALUES (?, ?, ?)",
+            params![network_id, succeeded, reason],
+        )?;
+        Ok(())
+    }
+
+    /// How many connect attempts to a network succeeded, or None if it was never tried
+    pub fn get_success_rate(&self, network_id: i64) -> Result<Option<SuccessRate>> {
+        let (succeeded, attempts): (Option<i64>, i64) = self.conn.query_row(
+            "SELECT SUM(CASE WHEN succeeded THEN 1 ELSE 0 END), COUNT(*) FROM connection_attempts WHERE network_id = ?",
+            params![network_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        Ok((attempts > 0).then(|| SuccessRate {
+            succeeded: succeeded.unwrap_or(0),
+            attempts,
+        }))
+    }
+
+    // ========== Notes ==========
+
+    /// Attach a free-form note to a network; an empty note removes it.
+    /// Returns false if the BSSID has never been saved by a scan.
+    pub fn set_network_note(&self, bssid: &str, note: &str) -> Result<bool> {
+        let note = Some(note.trim()).filter(|n| !n.is_empty());
+        let updated = self.conn.execute(
+            "UPDATE networks SET notes = ? WHERE bssid = ?",
+            params![note, bssid.to_uppercase()],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// The note attached to a network, if any
+    pub fn get_network_note(&self, bssid: &str) -> Result<Option<String>> {
+        let mut stmt = self.conn.prepare("SELECT notes FROM networks WHERE bssid = ?")?;
+        let mut rows = stmt.query(params![bssid.to_uppercase()])?;
+        match rows.next()? {
+            Some(row) => Ok(row.get(0)?),
+            None => Ok(None),
+        }
+    }
+
+    /// (BSSID, note) for every network that has a note
+    pub fn get_network_notes(&self) -> Result<Vec<(String, String)>> {
+        let mut stmt = self.conn.prepare("SELECT bssid, notes FROM networks WHERE notes IS NOT NULL")?;
+        let mut rows = stmt.query([])?;
+        let mut notes = Vec::new();
+
+        while let Some(row) = rows.next()? {
+            notes.push((row.get(0)?, row.get(1)?));
+        }
+
+        Ok(notes)
+    }
+
+    // ========== Roaming ==========
+
+    /// Record a roam between two BSSIDs of the same SSID
+    pub fn insert_roam_event(&self, event: &RoamEvent) -> Result<()> {
+        self.conn.execute(
+            r#"
+            INSERT INTO roam_events (ssid, from_bssid, to_bssid, roamed_at)
+            VALUES (?, ?, ?, make_timestamp(CAST(? AS BIGINT)))
+            "#,
+            params![
+                event.ssid,
+                event.from_bssid.to_uppercase(),
+                event.to_bssid.to_uppercase(),
+                event.roamed_at.timestamp_micros()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Roam events at or after `since`, oldest first
+    pub fn get_roam_events_since(&self, since: DateTime<Utc>) -> Result<Vec<RoamEvent>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT ssid, from_bssid, to_bssid, epoch_us(roamed_at)
+            FROM roam_events
+            WHERE roamed_at >= make_timestamp(CAST(? AS BIGINT))
+            ORDER BY roamed_at, id
+            "#,
+        )?;
+        let mut rows = stmt.query(params![since.timestamp_micros()])?;
+        let mut events = Vec::new();
+
+        while let Some(row) = rows.next()? {
+            let micros: i64 = row.get(3)?;
+            events.push(RoamEvent {
+                ssid: row.get(0)?,
+                from_bssid: row.get(1)?,
+                to_bssid: row.get(2)?,
+                roamed_at: DateTime::from_timestamp_micros(micros).unwrap_or_else(Utc::now),
+            });
+        }
+
+        Ok(events)
+    }
+
     // ========== Known Networks Management ==========
 
     /// Import a known network from plist
@@ -657,6 +1174,14 @@ impl Database {
         Ok(networks)
     }
 
+    /// Delete a known network, returning whether a row was removed
+    pub fn delete_known_network(&self, ssid: &str) -> Result<bool> {
+        let deleted = self
+            .conn
+            .execute("DELETE FROM known_networks WHERE ssid = ?", params![ssid])?;
+        Ok(deleted > 0)
+    }
+
     /// Get known network count (to check if import is needed)
     pub fn get_known_networks_count(&self) -> Result<i64> {
         let mut stmt = self.conn.prepare("SELECT COUNT(*) FROM known_networks")?;
@@ -669,6 +1194,13 @@ impl Database {
 
     // ========== Device Management ==========
 
+    /// Whether a device with this MAC has been recorded before
+    pub fn device_exists(&self, mac_address: &str) -> Result<bool> {
+        let mut stmt = self.conn.prepare("SELECT 1 FROM devices WHERE mac_address = ? LIMIT 1")?;
+        let mut rows = stmt.query(params![mac_address.to_uppercase()])?;
+        Ok(rows.next()?.is_some())
+    }
+
     /// Insert or update a device
     pub fn upsert_device(
         &self,
@@ -715,6 +1247,26 @@ impl Database {
         Ok(())
     }
 
+    /// Attach a free-form note to a device; an empty note removes it
+    pub fn update_device_notes(&self, mac_address: &str, notes: &str) -> Result<()> {
+        let notes = Some(notes.trim()).filter(|n| !n.is_empty());
+        self.conn.execute(
+            "UPDATE devices SET notes = ? WHERE mac_address = ?",
+            params![notes, mac_address.to_uppercase()],
+        )?;
+        Ok(())
+    }
+
+    /// Replace a device's tags, stored space-separated without the '#'
+    pub fn update_device_tags(&self, mac_address: &str, tags: &[String]) -> Result<()> {
+        let tags = Some(tags.join(" ")).filter(|t| !t.is_empty());
+        self.conn.execute(
+            "UPDATE devices SET tags = ? WHERE mac_address = ?",
+            params![tags, mac_address.to_uppercase()],
+        )?;
+        Ok(())
+    }
+
     /// Insert or update a service for a device
     pub fn upsert_device_service(
         &self,
@@ -742,11 +1294,11 @@ impl Database {
     pub fn get_devices_for_network(&self, network_bssid: Option<&str>) -> Result<Vec<DeviceRecord>> {
         let query = if network_bssid.is_some() {
             r#"SELECT id, mac_address, ip_address, hostname, vendor, device_type, custom_name,
-               CAST(first_seen AS VARCHAR), CAST(last_seen AS VARCHAR), network_bssid
+               CAST(first_seen AS VARCHAR), CAST(last_seen AS VARCHAR), network_bssid, notes, tags
                FROM devices WHERE network_bssid = ? ORDER BY last_seen DESC"#
         } else {
             r#"SELECT id, mac_address, ip_address, hostname, vendor, device_type, custom_name,
-               CAST(first_seen AS VARCHAR), CAST(last_seen AS VARCHAR), network_bssid
+               CAST(first_seen AS VARCHAR), CAST(last_seen AS VARCHAR), network_bssid, notes, tags
                FROM devices ORDER BY last_seen DESC"#
         };
 
@@ -772,6 +1324,8 @@ impl Database {
                 first_seen: parse_timestamp(&first_seen_str),
                 last_seen: parse_timestamp(&last_seen_str),
                 network_bssid: row.get(9)?,
+                notes: row.get(10)?,
+                tags: row.get(11)?,
             });
         }
         Ok(devices)
@@ -814,11 +1368,229 @@ impl Database {
         )?;
         Ok(())
     }
+
+    // ========== Maintenance ==========
+
+    /// Delete scans older than the given number of days, along with their
+    /// scan results. Returns the number of scans removed.
+    pub fn prune_scans_older_than(&self, days: i64) -> Result<usize> {
+        let cutoff = "CAST(CURRENT_TIMESTAMP AS TIMESTAMP) - to_days(CAST(? AS INTEGER))";
+
+        self.conn.execute(
+            &format!(
+                "DELETE FROM scan_results WHERE scan_id IN (SELECT id FROM scans WHERE scanned_at < {})",
+                cutoff
+            ),
+            params![days],
+        )?;
+        let removed = self.conn.execute(
+            &format!("DELETE FROM scans WHERE scanned_at < {}", cutoff),
+            params![days],
+        )?;
+        Ok(removed)
+    }
+
+    /// Reclaim space and refresh statistics after large deletes
+    pub fn vacuum(&self) -> Result<()> {
+        self.conn.execute_batch("VACUUM; CHECKPOINT;")?;
+        Ok(())
+    }
+
+    /// Flush the write-ahead log into the database file before exiting
+    pub fn checkpoint(&self) -> Result<()> {
+        self.conn.execute_batch("CHECKPOINT;")?;
+        Ok(())
+    }
+
+    // ========== Snapshots ==========
+
+    /// Copy every table into memory, ready to be written with `Snapshot::write`
+    pub fn snapshot(&self) -> Result<Snapshot> {
+        let mut tables = Vec::new();
+        for &table in SNAPSHOT_TABLES {
+            tables.push(self.dump_table(table)?);
+        }
+        Ok(Snapshot {
+            version: SNAPSHOT_VERSION,
+            exported_at: Utc::now(),
+            tables,
+        })
+    }
+
+    /// Write every table to a JSON file that can be shared and loaded with
+    /// `import_snapshot`. Returns the number of rows written per table.
+    pub fn export_snapshot(&self, path: impl AsRef<Path>) -> Result<Vec<(String, usize)>> {
+        let snapshot = self.snapshot()?;
+        snapshot.write(path)?;
+        Ok(snapshot.row_counts())
+    }
+
+    /// Load a snapshot written by `export_snapshot`. Ids are kept as they
+    /// were, so this only works on an empty database; the id sequences are
+    /// advanced past the imported rows. All or nothing: on error no rows
+    /// are kept. Returns the number of rows loaded per table.
+    pub fn import_snapshot(&self, path: impl AsRef<Path>) -> Result<Vec<(String, usize)>> {
+        let snapshot: Snapshot = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        if snapshot.version > SNAPSHOT_VERSION {
+            return Err(eyre!(
+                "snapshot version {} is newer than this version of wifi-analyzer supports ({})",
+                snapshot.version,
+                SNAPSHOT_VERSION
+            ));
+        }
+        for table in &snapshot.tables {
+            if !SNAPSHOT_TABLES.contains(&table.name.as_str()) {
+                return Err(eyre!("snapshot contains unknown table \"{}\"", table.name));
+            }
+        }
+        for &table in SNAPSHOT_TABLES {
+            if self.count_rows(table)? > 0 {
+                return Err(eyre!("database is not empty ({} has rows); restore into a new file", table));
+            }
+        }
+
+        self.conn.execute_batch("BEGIN TRANSACTION;")?;
+        let result = snapshot
+            .tables
+            .iter()
+            .map(|table| self.restore_table(table).map(|rows| (table.name.clone(), rows)))
+            .collect::<Result<Vec<_>>>();
+        match result {
+            Ok(counts) => {
+                self.conn.execute_batch("COMMIT;")?;
+                Ok(counts)
+            }
+            Err(e) => {
+                self.conn.execute_batch("ROLLBACK;")?;
+                Err(e)
+            }
+        }
+    }
+
+    fn count_rows(&self, table: &str) -> Result<i64> {
+        Ok(self.conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))?)
+    }
+
+    /// Column names and types of a table, in declaration order
+    fn table_columns(&self, table: &str) -> Result<Vec<(String, String)>> {
+        let mut stmt = self.conn.prepare(
+            r#"SELECT column_name, data_type FROM information_schema.columns
+               WHERE table_schema = 'main' AND table_name = ? ORDER BY ordinal_position"#,
+        )?;
+        let mut rows = stmt.query(params![table])?;
+        let mut columns = Vec::new();
+        while let Some(row) = rows.next()? {
+            columns.push((row.get(0)?, row.get(1)?));
+        }
+        Ok(columns)
+    }
+
+    fn dump_table(&self, table: &str) -> Result<TableSnapshot> {
+        let columns = self.table_columns(table)?;
+        let kinds: Vec<ColumnKind> = columns
+            .iter()
+            .map(|(_, data_type)| match data_type.as_str() {
+                "TINYINT" | "SMALLINT" | "INTEGER" | "BIGINT" => ColumnKind::Integer,
+                "FLOAT" | "REAL" | "DOUBLE" => ColumnKind::Float,
+                "BOOLEAN" => ColumnKind::Boolean,
+                _ => ColumnKind::Text,
+            })
+            .collect();
+        // Everything else (text, timestamps) is exported as text
+        let select: Vec<String> = columns
+            .iter()
+            .zip(&kinds)
+            .map(|((name, _), kind)| match kind {
+                ColumnKind::Text => format!("CAST({} AS VARCHAR)", name),
+                _ => name.clone(),
+            })
+            .collect();
+
+        let mut stmt = self
+            .conn
+            .prepare(&format!("SELECT {} FROM {} ORDER BY id", select.join(", "), table))?;
+        let mut rows = stmt.query([])?;
+        let mut dumped = Vec::new();
+        while let Some(row) = rows.next()? {
+            let mut values = Vec::with_capacity(columns.len());
+            for (i, kind) in kinds.iter().enumerate() {
+                let value = match kind {
+                    ColumnKind::Integer => row.get::<_, Option<i64>>(i)?.map(serde_json::Value::from),
+                    ColumnKind::Float => row.get::<_, Option<f64>>(i)?.map(serde_json::Value::from),
+                    ColumnKind::Boolean => row.get::<_, Option<bool>>(i)?.map(serde_json::Value::from),
+                    ColumnKind::Text => row.get::<_, Option<String>>(i)?.map(serde_json::Value::from),
+                };
+                values.push(value.unwrap_or(serde_json::Value::Null));
+            }
+            dumped.push(values);
+        }
+
+        Ok(TableSnapshot {
+            name: table.to_string(),
+            columns: columns.into_iter().map(|(name, _)| name).collect(),
+            rows: dumped,
+        })
+    }
+
+    /// Insert a table's snapshot rows with their original ids, then move the
+    /// table's id sequence past the largest one
+    fn restore_table(&self, table: &TableSnapshot) -> Result<usize> {
+        let known: Vec<String> = self.table_columns(&table.name)?.into_iter().map(|(name, _)| name).collect();
+        if let Some(unknown) = table.columns.iter().find(|c| !known.contains(c)) {
+            return Err(eyre!("snapshot column {}.{} doesn't exist in this database", table.name, unknown));
+        }
+
+        let placeholders = vec!["?"; table.columns.len()].join(", ");
+        let mut stmt = self.conn.prepare(&format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            table.name,
+            table.columns.join(", "),
+            placeholders
+        ))?;
+        for row in &table.rows {
+            if row.len() != table.columns.len() {
+                return Err(eyre!("snapshot row in {} has {} values for {} columns", table.name, row.len(), table.columns.len()));
+            }
+            let values = row.iter().map(|value| match value {
+                serde_json::Value::Null => Ok(Value::Null),
+                serde_json::Value::Bool(b) => Ok(Value::Boolean(*b)),
+                serde_json::Value::Number(n) => n
+                    .as_i64()
+                    .map(Value::BigInt)
+                    .or_else(|| n.as_f64().map(Value::Double))
+                    .ok_or_else(|| eyre!("unsupported number {} in {}", n, table.name)),
+                serde_json::Value::String(s) => Ok(Value::Text(s.clone())),
+                other => Err(eyre!("unsupported value {} in {}", other, table.name)),
+            });
+            stmt.execute(params_from_iter(values.collect::<Result<Vec<_>>>()?))?;
+        }
+
+        // Sequences can't be restarted, so draw them up to the largest id
+        let max_id: Option<i64> =
+            self.conn.query_row(&format!("SELECT MAX(id) FROM {}", table.name), [], |row| row.get(0))?;
+        if let Some(max_id) = max_id {
+            let sql = format!("SELECT COUNT(nextval('seq_{}_id')) FROM range({})", table.name, max_id);
+            self.conn.query_row(&sql, [], |row| row.get::<_, i64>(0))?;
+        }
+        Ok(table.rows.len())
+    }
+}
+
+/// Connect attempts to one network and how many of them worked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SuccessRate {
+    pub succeeded: i64,
+    pub attempts: i64,
+}
+
+impl SuccessRate {
+    pub fn percent(&self) -> i64 {
+        if self.attempts == 0 { 0 } else { self.succeeded * 100 / self.attempts }
+    }
 }
 
 /// Summary of a network's historical data
 #[derive(Debug, Clone)]
-#[allow(dead_code)]
 pub struct NetworkSummary {
     pub bssid: String,
     pub ssid: String,
@@ -829,6 +1601,17 @@ pub struct NetworkSummary {
     pub last_score: i32,
 }
 
+/// Reception of one SSID at one location
+#[derive(Debug, Clone)]
+pub struct LocationComparison {
+    pub location: String,
+    pub avg_signal_dbm: f64,
+    pub avg_score: f64,
+    pub times_seen: i64,
+    pub last_signal_dbm: i32,
+    pub last_score: i32,
+}
+
 /// Network data loaded from database for display
 #[derive(Debug, Clone)]
 pub struct LoadedNetwork {
@@ -842,6 +1625,16 @@ pub struct LoadedNetwork {
     pub last_seen: DateTime<Utc>,
 }
 
+/// A network's most recent observation across every location
+#[derive(Debug, Clone)]
+pub struct GlobalNetwork {
+    pub network: LoadedNetwork,
+    /// Location whose latest reading of the network was strongest
+    pub best_location: String,
+    pub best_signal_dbm: i32,
+    pub locations_seen: i64,
+}
+
 /// Connection record from the database
 #[derive(Debug, Clone)]
 pub struct ConnectionRecord {
@@ -853,6 +1646,21 @@ pub struct ConnectionRecord {
     pub public_ip: Option<String>,
     pub download_mbps: Option<f64>,
     pub upload_mbps: Option<f64>,
+    /// Captive portal check result (see `CaptiveStatus`)
+    pub captive_status: Option<String>,
+    pub local_ipv6: Option<String>,
+    pub public_ipv6: Option<String>,
+    /// Round-trip latency measured alongside the speed test
+    pub latency_ms: Option<f64>,
+}
+
+/// The connected BSSID changed while the SSID stayed the same (mesh/multi-AP roaming)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoamEvent {
+    pub ssid: String,
+    pub from_bssid: String,
+    pub to_bssid: String,
+    pub roamed_at: DateTime<Utc>,
 }
 
 /// Known network record from the database
@@ -878,6 +1686,9 @@ pub struct DeviceRecord {
     pub first_seen: DateTime<Utc>,
     pub last_seen: DateTime<Utc>,
     pub network_bssid: Option<String>,
+    pub notes: Option<String>,
+    /// Space-separated, see `network_map::parse_tags`
+    pub tags: Option<String>,
 }
 
 /// Service record from the database
@@ -891,6 +1702,42 @@ pub struct ServiceRecord {
     pub detected_agent: Option<String>,
 }
 
+/// A `ConnectionRecord` from the columns selected by `get_connection_history`
+fn connection_record(row: &duckdb::Row) -> duckdb::Result<ConnectionRecord> {
+    let connected_at_str: String = row.get(2)?;
+    let disconnected_at_str: Option<String> = row.get(3)?;
+    Ok(ConnectionRecord {
+        id: row.get(0)?,
+        network_id: row.get(1)?,
+        connected_at: parse_timestamp(&connected_at_str),
+        disconnected_at: disconnected_at_str.map(|s| parse_timestamp(&s)),
+        local_ip: row.get(4)?,
+        public_ip: row.get(5)?,
+        download_mbps: row.get(6)?,
+        upload_mbps: row.get(7)?,
+        captive_status: row.get(8)?,
+        local_ipv6: row.get(9)?,
+        public_ipv6: row.get(10)?,
+        latency_ms: row.get(11)?,
+    })
+}
+
+/// A `LoadedNetwork` from the first eight columns: bssid, ssid, channel,
+/// signal, security, band, score and scan time
+fn loaded_network(row: &duckdb::Row) -> duckdb::Result<LoadedNetwork> {
+    let scanned_at_str: String = row.get(7)?;
+    Ok(LoadedNetwork {
+        bssid: row.get(0)?,
+        ssid: row.get(1)?,
+        channel: row.get::<_, i32>(2)? as u8,
+        signal_dbm: row.get(3)?,
+        security: row.get(4)?,
+        frequency_band: row.get(5)?,
+        score: row.get::<_, i32>(6)? as u8,
+        last_seen: parse_timestamp(&scanned_at_str),
+    })
+}
+
 /// Parse a timestamp string from DuckDB
 fn parse_timestamp(s: &str) -> DateTime<Utc> {
     // DuckDB returns timestamps in ISO 8601 format
@@ -899,3 +1746,514 @@ fn parse_timestamp(s: &str) -> DateTime<Utc> {
         .or_else(|_| s.parse::<DateTime<Utc>>())
         .unwrap_or_else(|_| Utc::now())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn record(bssid: &str) -> ScanResultRecord {
+        ScanResultRecord {
+            bssid: bssid.to_string(),
+            ssid: "TestNet".to_string(),
+            channel: 6,
+            signal_dbm: -60,
+            security: "WPA2".to_string(),
+            frequency_band: "Band2_4GHz".to_string(),
+            score: 70,
+        }
+    }
+
+    fn lock_error() -> std::io::Error {
+        std::io::Error::other(
+            "IO Error: Could not set lock on file \"wifi.duckdb\": Conflicting lock is held in /usr/bin/wifi-analyzer (PID 4242)",
+        )
+    }
+
+    #[test]
+    fn test_open_retries_while_locked() {
+        let mut attempts = 0;
+        let result = open_with_retry(Path::new("wifi.duckdb"), Duration::ZERO, || {
+            attempts += 1;
+            if attempts < 3 { Err(lock_error()) } else { Ok("connection") }
+        });
+        assert_eq!(result.unwrap(), "connection");
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn test_open_reports_persistent_lock() {
+        let mut attempts = 0;
+        let err = open_with_retry(Path::new("wifi.duckdb"), Duration::ZERO, || -> std::io::Result<()> {
+            attempts += 1;
+            Err(lock_error())
+        })
+        .unwrap_err();
+        assert_eq!(attempts, LOCK_ATTEMPTS);
+        assert!(matches!(err.downcast_ref::<DbError>(), Some(DbError::Locked { detail, .. }) if detail.contains("PID 4242")));
+    }
+
+    #[test]
+    fn test_open_other_errors_fail_immediately() {
+        let mut attempts = 0;
+        let err = open_with_retry(Path::new("wifi.duckdb"), Duration::ZERO, || -> std::io::Result<()> {
+            attempts += 1;
+            Err(std::io::Error::other("IO Error: Permission denied"))
+        })
+        .unwrap_err();
+        assert_eq!(attempts, 1);
+        assert!(err.downcast_ref::<DbError>().is_none());
+    }
+
+    fn count(db: &Database, table: &str) -> i64 {
+        let mut stmt = db.conn.prepare(&format!("SELECT COUNT(*) FROM {}", table)).unwrap();
+        let mut rows = stmt.query([]).unwrap();
+        rows.next().unwrap().unwrap().get(0).unwrap()
+    }
+
+    #[test]
+    fn test_delete_known_network() {
+        let db = Database::open_in_memory().unwrap();
+        db.import_known_network("HomeNet", None, None).unwrap();
+        db.import_known_network("Office", None, None).unwrap();
+
+        assert!(db.delete_known_network("HomeNet").unwrap());
+        assert!(!db.delete_known_network("HomeNet").unwrap());
+        assert!(!db.is_known_network("HomeNet").unwrap());
+        assert!(db.is_known_network("Office").unwrap());
+    }
+
+    #[test]
+    fn test_network_note_round_trip() {
+        let db = Database::open_in_memory().unwrap();
+        assert!(!db.set_network_note("aa:bb:cc:dd:ee:ff", "unsaved").unwrap());
+
+        db.upsert_network("aa:bb:cc:dd:ee:ff", "Cafe").unwrap();
+        assert!(db.set_network_note("aa:bb:cc:dd:ee:ff", "  requires portal login ").unwrap());
+        // Later scans of the same BSSID keep the note
+        db.upsert_network("AA:BB:CC:DD:EE:FF", "Cafe").unwrap();
+        assert_eq!(
+            db.get_network_note("AA:BB:CC:DD:EE:FF").unwrap().as_deref(),
+            Some("requires portal login")
+        );
+        assert_eq!(
+            db.get_network_notes().unwrap(),
+            vec![("AA:BB:CC:DD:EE:FF".to_string(), "requires portal login".to_string())]
+        );
+
+        db.set_network_note("aa:bb:cc:dd:ee:ff", "").unwrap();
+        assert_eq!(db.get_network_note("aa:bb:cc:dd:ee:ff").unwrap(), None);
+        assert!(db.get_network_notes().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let db = Database::open_in_memory().unwrap();
+        // Burn some ids so the restored ids aren't just 1, 2, 3...
+        db.create_or_get_location("scratch").unwrap();
+        let home = db.create_or_get_location("home").unwrap();
+        let scan_id = db.create_scan(home).unwrap();
+        db.record_scan_results(scan_id, &[record("AA:BB:CC:00:00:01"), record("AA:BB:CC:00:00:02")])
+            .unwrap();
+        db.set_network_note("AA:BB:CC:00:00:01", "by the window").unwrap();
+        let network_id = db.get_network_id_by_bssid("AA:BB:CC:00:00:01").unwrap().unwrap();
+        let ips = IpAddresses {
+            local_v4: Some("192.168.1.20".to_string()),
+            ..IpAddresses::default()
+        };
+        db.insert_connection(network_id, &ips, Some(48.5), Some(12.25), Some(18.0), None).unwrap();
+        db.record_connection_attempt(network_id, false, Some("timed out")).unwrap();
+        let device_id = db
+            .upsert_device("aa:bb:cc:dd:ee:01", "192.168.1.5", Some("nas"), None, "Server", None, None)
+            .unwrap();
+        db.upsert_device_service(device_id, 445, "tcp", Some("microsoft-ds"), None, None).unwrap();
+        db.import_known_network("HomeNet", None, None).unwrap();
+
+        let path = std::env::temp_dir().join(format!("wifi_analyzer_snapshot_{}.json", std::process::id()));
+        let exported = db.export_snapshot(&path).unwrap();
+        let restored = Database::open_in_memory().unwrap();
+        let imported = restored.import_snapshot(&path).unwrap();
+        // Restoring twice would duplicate ids
+        let again = restored.import_snapshot(&path);
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(exported, imported);
+        assert!(again.unwrap_err().to_string().contains("not empty"));
+        for &table in SNAPSHOT_TABLES {
+            assert_eq!(count(&restored, table), count(&db, table), "{}", table);
+        }
+
+        // Relationships survive: the connection still belongs to the same network
+        let restored_network = restored.get_network_id_by_bssid("AA:BB:CC:00:00:01").unwrap();
+        assert_eq!(restored_network, Some(network_id));
+        let history = restored.get_connection_history(network_id, 10).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].download_mbps, Some(48.5));
+        assert_eq!(history[0].local_ip.as_deref(), Some("192.168.1.20"));
+        assert_eq!(
+            restored.get_network_note("AA:BB:CC:00:00:01").unwrap().as_deref(),
+            Some("by the window")
+        );
+        assert_eq!(
+            restored.get_success_rate(network_id).unwrap(),
+            Some(SuccessRate { succeeded: 0, attempts: 1 })
+        );
+        assert_eq!(restored.get_device_services(device_id).unwrap().len(), 1);
+        let locations = restored.list_locations().unwrap();
+        assert!(locations.iter().any(|l| l.id == home && l.name == "home"));
+
+        // New rows get ids after the restored ones
+        let office = restored.create_or_get_location("office").unwrap();
+        assert!(office > home);
+    }
+
+    #[test]
+    fn test_ssid_history_spans_every_bssid() {
+        let db = Database::open_in_memory().unwrap();
+        let upstairs = db.upsert_network("AA:BB:CC:00:00:01", "HomeMesh").unwrap();
+        let downstairs = db.upsert_network("AA:BB:CC:00:00:02", "HomeMesh").unwrap();
+        let neighbor = db.upsert_network("AA:BB:CC:00:00:03", "Neighbor").unwrap();
+        let ips = IpAddresses::default();
+        db.insert_connection(upstairs, &ips, Some(10.0), Some(1.0), None, None).unwrap();
+        db.insert_connection(downstairs, &ips, Some(20.0), Some(2.0), None, None).unwrap();
+        db.insert_connection(neighbor, &ips, Some(30.0), Some(3.0), None, None).unwrap();
+
+        let mut ids = db.get_network_ids_by_ssid("HomeMesh").unwrap();
+        ids.sort();
+        assert_eq!(ids, [upstairs, downstairs]);
+        assert!(db.get_network_ids_by_ssid("Nowhere").unwrap().is_empty());
+
+        let history = db.get_ssid_connection_history("HomeMesh").unwrap();
+        let mut networks: Vec<i64> = history.iter().map(|c| c.network_id).collect();
+        networks.sort();
+        assert_eq!(networks, [upstairs, downstairs]);
+        assert!(db.get_ssid_connection_history("Nowhere").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_connection_success_rate() {
+        let db = Database::open_in_memory().unwrap();
+        assert_eq!(db.get_success_rate(1).unwrap(), None);
+
+        for _ in 0..8 {
+            db.record_connection_attempt(1, true, None).unwrap();
+        }
+        db.record_connection_attempt(1, false, Some("Connection error: timed out")).unwrap();
+        db.record_connection_attempt(1, false, None).unwrap();
+        db.record_connection_attempt(2, false, None).unwrap();
+
+        let rate = db.get_success_rate(1).unwrap().unwrap();
+        assert_eq!(rate, SuccessRate { succeeded: 8, attempts: 10 });
+        assert_eq!(rate.percent(), 80);
+        assert_eq!(db.get_success_rate(2).unwrap().unwrap().percent(), 0);
+    }
+
+    #[test]
+    fn test_location_profiles_change_scores() {
+        use crate::scanner::{Network, SecurityType};
+        use crate::scoring::calculate_score_weighted;
+
+        let db = Database::open_in_memory().unwrap();
+        let cafe = db.create_or_get_location("cafe").unwrap();
+        let office = db.create_or_get_location("office").unwrap();
+        let home = db.create_or_get_location("home").unwrap();
+        db.set_location_profile(cafe, ScoringProfile::Cafe).unwrap();
+        db.set_location_profile(office, ScoringProfile::Office).unwrap();
+
+        assert_eq!(db.get_location_profile(cafe).unwrap(), Some(ScoringProfile::Cafe));
+        assert_eq!(db.get_location_profile(office).unwrap(), Some(ScoringProfile::Office));
+        assert_eq!(db.get_location_profile(home).unwrap(), None);
+        let listed = db.list_locations().unwrap();
+        assert!(listed.iter().any(|l| l.id == office && l.profile == Some(ScoringProfile::Office)));
+
+        let network = Network {
+            mac: "AA:BB:CC:00:00:01".to_string(),
+            security: SecurityType::Open,
+            ..Network::test("FreeWiFi", 6, -55)
+        };
+        let all = std::slice::from_ref(&network);
+        let score_at = |location_id| {
+            let weights = db.get_location_profile(location_id).unwrap().unwrap_or_default().weights();
+            calculate_score_weighted(&network, all, false, &weights)
+        };
+
+        // An open network suits the café but is the worst option at the office
+        assert!(score_at(cafe) > score_at(office));
+        assert_ne!(score_at(home), score_at(office));
+    }
+
+    #[test]
+    fn test_speed_history_oldest_first() {
+        let db = Database::open_in_memory().unwrap();
+        let ips = IpAddresses::default();
+        db.insert_connection(1, &ips, Some(10.0), Some(1.0), None, None).unwrap();
+        db.insert_connection(1, &ips, None, None, None, Some("CaptivePortal")).unwrap();
+        db.insert_connection(1, &ips, Some(20.0), Some(2.0), None, None).unwrap();
+        db.insert_connection(1, &ips, Some(30.0), Some(3.0), None, None).unwrap();
+        db.insert_connection(2, &ips, Some(99.0), Some(9.0), None, None).unwrap();
+
+        let speeds: Vec<(f64, f64)> = db
+            .get_speed_history(1, 2)
+            .unwrap()
+            .into_iter()
+            .map(|(_, down, up)| (down, up))
+            .collect();
+        assert_eq!(speeds, vec![(20.0, 2.0), (30.0, 3.0)]);
+
+        let history = db.get_speed_history(1, 10).unwrap();
+        assert_eq!(history.len(), 3);
+        assert!(history.windows(2).all(|w| w[0].0 <= w[1].0));
+    }
+
+    #[test]
+    fn test_device_exists() {
+        let db = Database::open_in_memory().unwrap();
+        assert!(!db.device_exists("aa:bb:cc:dd:ee:ff").unwrap());
+
+        db.upsert_device("aa:bb:cc:dd:ee:ff", "192.168.1.10", None, None, "Unknown", None, None).unwrap();
+        assert!(db.device_exists("aa:bb:cc:dd:ee:ff").unwrap());
+        assert!(db.device_exists("AA:BB:CC:DD:EE:FF").unwrap());
+    }
+
+    #[test]
+    fn test_device_notes_and_tags_round_trip() {
+        let db = Database::open_in_memory().unwrap();
+        db.upsert_device("aa:bb:cc:dd:ee:ff", "192.168.1.10", None, None, "Tablet", None, None).unwrap();
+        db.update_device_notes("aa:bb:cc:dd:ee:ff", "  kid's tablet ").unwrap();
+        db.update_device_tags("AA:BB:CC:DD:EE:FF", &["trusted".to_string(), "kids".to_string()]).unwrap();
+
+        // A later scan updating the device keeps what the user entered
+        db.upsert_device("aa:bb:cc:dd:ee:ff", "192.168.1.11", Some("ipad"), None, "Tablet", None, None).unwrap();
+        let devices = db.get_devices_for_network(None).unwrap();
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].ip_address.as_deref(), Some("192.168.1.11"));
+        assert_eq!(devices[0].notes.as_deref(), Some("kid's tablet"));
+        assert_eq!(devices[0].tags.as_deref(), Some("trusted kids"));
+
+        // Empty input clears them
+        db.update_device_notes("aa:bb:cc:dd:ee:ff", "").unwrap();
+        db.update_device_tags("aa:bb:cc:dd:ee:ff", &[]).unwrap();
+        let devices = db.get_devices_for_network(None).unwrap();
+        assert_eq!(devices[0].notes, None);
+        assert_eq!(devices[0].tags, None);
+    }
+
+    #[test]
+    fn test_open_read_only_leaves_schema_alone() {
+        let dir = std::env::temp_dir().join(format!("wifi_analyzer_read_only_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("test.duckdb");
+        Connection::open(&path).unwrap().execute_batch("CREATE TABLE devices (id INTEGER)").unwrap();
+
+        let db = Database::open_read_only(&path).unwrap();
+        let write = db.conn.execute_batch("ALTER TABLE devices ADD COLUMN notes VARCHAR");
+        drop(db);
+        let conn = Connection::open(&path).unwrap();
+        let columns: i64 = conn
+            .query_row("SELECT COUNT(*) FROM information_schema.columns WHERE table_name = 'devices'", [], |r| r.get(0))
+            .unwrap();
+        let tables: i64 = conn
+            .query_row("SELECT COUNT(*) FROM information_schema.tables", [], |r| r.get(0))
+            .unwrap();
+        drop(conn);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(write.is_err());
+        assert_eq!(columns, 1);
+        assert_eq!(tables, 1);
+    }
+
+    #[test]
+    fn test_checkpoint_flushes_wal() {
+        let dir = std::env::temp_dir().join(format!("wifi_analyzer_checkpoint_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("test.duckdb");
+
+        let db = Database::open(&path).unwrap();
+        db.create_or_get_location("home").unwrap();
+        db.checkpoint().unwrap();
+        let wal_len = std::fs::metadata(dir.join("test.duckdb.wal")).map(|m| m.len()).unwrap_or(0);
+        drop(db);
+
+        let reopened = Database::open(&path).unwrap();
+        let locations = count(&reopened, "locations");
+        drop(reopened);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(wal_len, 0);
+        assert_eq!(locations, 1);
+    }
+
+    #[test]
+    fn test_roam_events_since() {
+        let db = Database::open_in_memory().unwrap();
+        let now = Utc::now();
+        let event = |from: &str, to: &str, minutes_ago: i64| RoamEvent {
+            ssid: "Mesh".to_string(),
+            from_bssid: from.to_string(),
+            to_bssid: to.to_string(),
+            roamed_at: now - chrono::Duration::minutes(minutes_ago),
+        };
+
+        db.insert_roam_event(&event("aa:aa:aa:aa:aa:01", "aa:aa:aa:aa:aa:02", 90)).unwrap();
+        db.insert_roam_event(&event("aa:aa:aa:aa:aa:02", "aa:aa:aa:aa:aa:03", 30)).unwrap();
+        db.insert_roam_event(&event("aa:aa:aa:aa:aa:03", "aa:aa:aa:aa:aa:01", 5)).unwrap();
+
+        let recent = db.get_roam_events_since(now - chrono::Duration::hours(1)).unwrap();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].from_bssid, "AA:AA:AA:AA:AA:02");
+        assert_eq!(recent[1].to_bssid, "AA:AA:AA:AA:AA:01");
+        assert_eq!(recent[1].roamed_at.timestamp(), (now - chrono::Duration::minutes(5)).timestamp());
+    }
+
+    #[test]
+    fn test_networks_at_location_averages() {
+        let db = Database::open_in_memory().unwrap();
+        let location_id = db.create_or_get_location("home").unwrap();
+        let other_location = db.create_or_get_location("office").unwrap();
+
+        for (signal, score) in [(-50, 60), (-60, 70), (-70, 80)] {
+            let scan_id = db.create_scan(location_id).unwrap();
+            let r = ScanResultRecord { signal_dbm: signal, score, ..record("AA:AA:AA:AA:AA:01") };
+            db.record_scan_results(scan_id, &[r]).unwrap();
+        }
+        // Observations elsewhere don't count towards this location
+        let scan_id = db.create_scan(other_location).unwrap();
+        let r = ScanResultRecord { signal_dbm: -90, score: 10, ..record("AA:AA:AA:AA:AA:01") };
+        db.record_scan_results(scan_id, &[r]).unwrap();
+
+        let summaries = db.get_networks_at_location(location_id).unwrap();
+        assert_eq!(summaries.len(), 1);
+        let summary = &summaries[0];
+        assert_eq!(summary.bssid, "AA:AA:AA:AA:AA:01");
+        assert_eq!(summary.times_seen, 3);
+        assert!((summary.avg_signal_dbm - -60.0).abs() < 1e-9);
+        assert!((summary.avg_score - 70.0).abs() < 1e-9);
+        assert_eq!(summary.last_signal_dbm, -70);
+        assert_eq!(summary.last_score, 80);
+    }
+
+    #[test]
+    fn test_compare_network_across_locations() {
+        let db = Database::open_in_memory().unwrap();
+        let desk = db.create_or_get_location("desk").unwrap();
+        let conference = db.create_or_get_location("conference-room").unwrap();
+
+        // Two BSSIDs of the same SSID count together
+        let office = |bssid: &str, signal: i32, score: u8| ScanResultRecord {
+            ssid: "Office".to_string(),
+            signal_dbm: signal,
+            score,
+            ..record(bssid)
+        };
+        let scan_id = db.create_scan(desk).unwrap();
+        db.record_scan_results(scan_id, &[office("AA:AA:AA:AA:AA:01", -70, 50)]).unwrap();
+        let scan_id = db.create_scan(desk).unwrap();
+        db.record_scan_results(scan_id, &[office("AA:AA:AA:AA:AA:02", -60, 60)]).unwrap();
+        let scan_id = db.create_scan(conference).unwrap();
+        db.record_scan_results(scan_id, &[office("AA:AA:AA:AA:AA:01", -45, 90), record("AA:AA:AA:AA:AA:09")])
+            .unwrap();
+
+        let comparison = db.compare_network_across_locations("Office").unwrap();
+        assert_eq!(comparison.len(), 2);
+        assert_eq!(comparison[0].location, "conference-room");
+        assert_eq!(comparison[0].times_seen, 1);
+        assert_eq!(comparison[1].location, "desk");
+        assert_eq!(comparison[1].times_seen, 2);
+        assert!((comparison[1].avg_signal_dbm - -65.0).abs() < 1e-9);
+        assert_eq!(comparison[1].last_signal_dbm, -60);
+        assert_eq!(comparison[1].last_score, 60);
+
+        assert!(db.compare_network_across_locations("Nowhere").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_all_networks_latest_merges_locations() {
+        let db = Database::open_in_memory().unwrap();
+        let kitchen = db.create_or_get_location("kitchen").unwrap();
+        let office = db.create_or_get_location("office").unwrap();
+
+        let seen = |bssid: &str, signal: i32| ScanResultRecord {
+            signal_dbm: signal,
+            ..record(bssid)
+        };
+        let scan_id = db.create_scan(kitchen).unwrap();
+        db.record_scan_results(scan_id, &[seen("AA:AA:AA:AA:AA:01", -50), seen("AA:AA:AA:AA:AA:02", -80)])
+            .unwrap();
+        let scan_id = db.create_scan(office).unwrap();
+        db.record_scan_results(scan_id, &[seen("AA:AA:AA:AA:AA:01", -75), seen("AA:AA:AA:AA:AA:03", -60)])
+            .unwrap();
+
+        let networks = db.load_all_networks_latest().unwrap();
+        let by_bssid: HashMap<&str, &GlobalNetwork> =
+            networks.iter().map(|g| (g.network.bssid.as_str(), g)).collect();
+        assert_eq!(networks.len(), 3);
+        assert_eq!(by_bssid.len(), 3);
+
+        // Seen in both: the latest reading is the office's, the best is the kitchen's
+        let shared = by_bssid["AA:AA:AA:AA:AA:01"];
+        assert_eq!(shared.network.signal_dbm, -75);
+        assert_eq!(shared.best_location, "kitchen");
+        assert_eq!(shared.best_signal_dbm, -50);
+        assert_eq!(shared.locations_seen, 2);
+
+        assert_eq!(by_bssid["AA:AA:AA:AA:AA:02"].best_location, "kitchen");
+        assert_eq!(by_bssid["AA:AA:AA:AA:AA:03"].best_location, "office");
+        assert_eq!(by_bssid["AA:AA:AA:AA:AA:03"].locations_seen, 1);
+        // Strongest first
+        assert_eq!(networks[0].network.bssid, "AA:AA:AA:AA:AA:01");
+    }
+
+    #[test]
+    fn test_peak_signal() {
+        let db = Database::open_in_memory().unwrap();
+        let kitchen = db.create_or_get_location("kitchen").unwrap();
+        let garden = db.create_or_get_location("garden").unwrap();
+
+        for (location_id, signal) in [(kitchen, -70), (garden, -41), (kitchen, -55), (garden, -88)] {
+            let scan_id = db.create_scan(location_id).unwrap();
+            let result = ScanResultRecord {
+                signal_dbm: signal,
+                ..record("AA:AA:AA:AA:AA:01")
+            };
+            db.record_scan_results(scan_id, &[result, record("AA:AA:AA:AA:AA:02")]).unwrap();
+        }
+
+        let network_id = db.get_network_id_by_bssid("AA:AA:AA:AA:AA:01").unwrap().unwrap();
+        assert_eq!(db.get_peak_signal(network_id).unwrap(), Some(-41));
+        let other_id = db.get_network_id_by_bssid("AA:AA:AA:AA:AA:02").unwrap().unwrap();
+        assert_eq!(db.get_peak_signal(other_id).unwrap(), Some(-60));
+        assert_eq!(db.get_peak_signal(9999).unwrap(), None);
+    }
+
+    #[test]
+    fn test_prune_scans_older_than() {
+        let db = Database::open_in_memory().unwrap();
+        let location_id = db.create_or_get_location("test").unwrap();
+
+        // Two scans backdated well past the retention window
+        for days_ago in [40, 90] {
+            let scan_id = db.create_scan(location_id).unwrap();
+            db.conn
+                .execute(
+                    "UPDATE scans SET scanned_at = CAST(CURRENT_TIMESTAMP AS TIMESTAMP) - to_days(CAST(? AS INTEGER)) WHERE id = ?",
+                    params![days_ago, scan_id],
+                )
+                .unwrap();
+            db.record_scan_results(scan_id, &[record("AA:AA:AA:AA:AA:01")]).unwrap();
+        }
+
+        // One recent scan
+        let recent_id = db.create_scan(location_id).unwrap();
+        db.record_scan_results(recent_id, &[record("AA:AA:AA:AA:AA:01"), record("AA:AA:AA:AA:AA:02")])
+            .unwrap();
+
+        let removed = db.prune_scans_older_than(30).unwrap();
+        db.vacuum().unwrap();
+
+        assert_eq!(removed, 2);
+        assert_eq!(count(&db, "scans"), 1);
+        assert_eq!(count(&db, "scan_results"), 2);
+        assert_eq!(db.get_signal_history("AA:AA:AA:AA:AA:01", 10).unwrap(), vec![-60]);
+    }
+}
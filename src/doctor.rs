@@ -0,0 +1,258 @@
+//! `doctor` subcommand: check the tools, permissions and files the analyzer
+//! depends on, with a hint for everything that fails
+
+use crate::connection::list_wireless_interfaces;
+use crate::db::Database;
+use crate::scanner::{is_demo_mode, scan_networks};
+use crate::scripts::swift_available;
+use crate::speedtest::check_reachable;
+use std::path::Path;
+
+/// Outcome of one check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: &'static str,
+    pub passed: bool,
+    /// A failed critical check keeps the analyzer from working at all
+    pub critical: bool,
+    /// What was found, e.g. a version, a path or an error
+    pub detail: String,
+    /// How to fix a failure
+    pub hint: &'static str,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Check {
+            name,
+            passed: true,
+            critical: false,
+            detail: detail.into(),
+            hint: "",
+        }
+    }
+
+    fn fail(name: &'static str, critical: bool, detail: impl Into<String>, hint: &'static str) -> Self {
+        Check {
+            name,
+            passed: false,
+            critical,
+            detail: detail.into(),
+            hint,
+        }
+    }
+
+    /// "✓ name  detail", plus an indented hint line for failures
+    pub fn render(&self) -> String {
+        let mark = if self.passed { "\u{2713}" } else { "\u{2717}" };
+        let mut line = format!("  {} {:<18} {}", mark, self.name, self.detail);
+        if !self.passed && !self.hint.is_empty() {
+            line.push_str(&format!("\n      \u{2192} {}", self.hint));
+        }
+        line
+    }
+}
+
+/// Failed checks that keep the analyzer from working
+pub fn critical_failures(checks: &[Check]) -> usize {
+    checks.iter().filter(|c| !c.passed && c.critical).count()
+}
+
+/// Run every check. `interface` is the --interface override, if any.
+pub async fn run_checks(db_path: &Path, interface: Option<&str>) -> Vec<Check> {
+    let mut checks = tool_checks();
+    if cfg!(target_os = "macos") {
+        checks.push(interface_check(interface));
+    }
+    checks.push(scan_check().await);
+    checks.push(database_check(db_path));
+    checks.push(reachability_check().await);
+    checks
+}
+
+/// External programs the scanner, connector and device discovery call
+fn tool_checks() -> Vec<Check> {
+    let mut checks = Vec::new();
+    if cfg!(target_os = "macos") {
+        checks.push(if swift_available() {
+            Check::pass("swift", "CoreWLAN helpers can run")
+        } else {
+            Check::fail(
+                "swift",
+                false,
+                "not found (scans fall back to the legacy scanner, without BSSIDs)",
+                "Install the Xcode Command Line Tools: xcode-select --install",
+            )
+        });
+        checks.push(program_check(
+            "networksetup",
+            true,
+            "Needed to list interfaces and connect; it ships with macOS in /usr/sbin, so check your PATH",
+        ));
+    } else if cfg!(target_os = "linux") {
+        checks.push(program_check(
+            "nmcli",
+            true,
+            "Install NetworkManager (e.g. apt install network-manager)",
+        ));
+        checks.push(program_check(
+            "iw",
+            false,
+            "Scanning without a connection uses iw; install it (e.g. apt install iw)",
+        ));
+    } else if cfg!(target_os = "windows") {
+        checks.push(program_check(
+            "netsh",
+            true,
+            "netsh ships with Windows in C:\\Windows\\System32; check your PATH",
+        ));
+    }
+    checks.push(program_check(
+        "arp",
+        false,
+        "Device discovery reads the ARP cache; install net-tools",
+    ));
+    checks.push(program_check(
+        "ping",
+        false,
+        "The device ping sweep needs ping; install iputils-ping",
+    ));
+    checks
+}
+
+fn program_check(program: &'static str, critical: bool, hint: &'static str) -> Check {
+    match find_on_path(program) {
+        Some(path) => Check::pass(program, path.display().to_string()),
+        None => Check::fail(program, critical, "not found on PATH", hint),
+    }
+}
+
+/// First PATH entry containing `program`
+fn find_on_path(program: &str) -> Option<std::path::PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).find_map(|dir| {
+        let candidate = dir.join(program);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        let exe = dir.join(format!("{}.exe", program));
+        exe.is_file().then_some(exe)
+    })
+}
+
+fn interface_check(interface: Option<&str>) -> Check {
+    let interfaces = list_wireless_interfaces();
+    let names: Vec<&str> = interfaces.iter().map(|i| i.device.as_str()).collect();
+    match (interface, names.first()) {
+        (Some(wanted), _) if names.contains(&wanted) => Check::pass("WiFi interface", wanted),
+        (Some(wanted), _) => Check::fail(
+            "WiFi interface",
+            true,
+            format!("{} is not a wireless port (found: {})", wanted, names.join(", ")),
+            "Pass --interface with one of the devices listed by networksetup -listallhardwareports",
+        ),
+        (None, Some(first)) => Check::pass("WiFi interface", format!("{} (of {})", first, names.join(", "))),
+        (None, None) => Check::fail(
+            "WiFi interface",
+            true,
+            "no wireless hardware port found",
+            "Turn WiFi on, or pass --interface with your adapter's name (e.g. en0)",
+        ),
+    }
+}
+
+/// One real scan, which is where a missing Location permission shows up
+async fn scan_check() -> Check {
+    if is_demo_mode() {
+        return Check::pass("WiFi scan", "skipped in demo mode");
+    }
+    match scan_networks().await {
+        Ok(networks) if networks.is_empty() => Check::fail(
+            "WiFi scan",
+            false,
+            "no networks seen",
+            "Check that WiFi is enabled and that networks are in range",
+        ),
+        Ok(networks) => Check::pass("WiFi scan", format!("{} networks seen", networks.len())),
+        Err(e) => Check::fail("WiFi scan", true, e.to_string(), e.advice()),
+    }
+}
+
+/// Open an existing database read-only, or make sure one can be created.
+/// Neither case migrates or creates anything, so running doctor leaves the
+/// database as it found it.
+fn database_check(db_path: &Path) -> Check {
+    const HINT: &str = "Pass --db-path with a writable location, or close the other wifi-analyzer using it";
+    if db_path.exists() {
+        return match Database::open_read_only(db_path) {
+            Ok(_) => Check::pass("Database", db_path.display().to_string()),
+            Err(e) => Check::fail("Database", true, format!("{}: {}", db_path.display(), e), HINT),
+        };
+    }
+    let dir = match db_path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let probe = dir.join(format!(".wifi-analyzer-doctor-{}", std::process::id()));
+    match std::fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = std::fs::remove_file(&probe);
+            Check::pass("Database", format!("{} (will be created)", db_path.display()))
+        }
+        Err(e) => Check::fail("Database", true, format!("can't write to {}: {}", dir.display(), e), HINT),
+    }
+}
+
+async fn reachability_check() -> Check {
+    match tokio::task::spawn_blocking(check_reachable).await {
+        Ok(Ok(ms)) => Check::pass("Internet", format!("speed test server answered in {:.0} ms", ms)),
+        Ok(Err(e)) => Check::fail(
+            "Internet",
+            false,
+            e.to_string(),
+            "Speed tests need https://speed.cloudflare.com; check your connection or captive portal",
+        ),
+        Err(e) => Check::fail("Internet", false, e.to_string(), ""),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_critical_failures_count() {
+        let checks = vec![
+            Check::pass("swift", "ok"),
+            Check::fail("arp", false, "not found on PATH", "install net-tools"),
+            Check::fail("Database", true, "read-only", "use --db-path"),
+        ];
+        assert_eq!(critical_failures(&checks), 1);
+        assert_eq!(critical_failures(&checks[..2]), 0);
+    }
+
+    #[test]
+    fn test_render_shows_hint_for_failures() {
+        let failed = Check::fail("arp", false, "not found on PATH", "install net-tools").render();
+        assert!(failed.contains("\u{2717} arp"));
+        assert!(failed.contains("\u{2192} install net-tools"));
+        let passed = Check::pass("ping", "/sbin/ping").render();
+        assert!(passed.starts_with("  \u{2713} ping"));
+        assert!(!passed.contains('\u{2192}'));
+    }
+
+    #[test]
+    fn test_database_check_leaves_no_file() {
+        let dir = std::env::temp_dir().join(format!("wifi_analyzer_doctor_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("new.duckdb");
+        let check = database_check(&path);
+        assert!(check.passed, "{:?}", check);
+        assert!(!path.exists());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let unwritable = database_check(&dir.join("missing").join("db.duckdb"));
+        assert!(!unwritable.passed && unwritable.critical);
+    }
+}
@@ -1,5 +1,6 @@
+use crate::app::{App, AppAction};
 use color_eyre::Result;
-use crossterm::event::{self, Event as CrosstermEvent, KeyEvent};
+use crossterm::event::{self, Event as CrosstermEvent, KeyCode, KeyEvent, KeyModifiers};
 use std::time::Duration;
 use tokio::sync::mpsc;
 
@@ -7,6 +8,8 @@ use tokio::sync::mpsc;
 pub enum Event {
     Key(KeyEvent),
     Tick,
+    /// The process received SIGINT, SIGTERM or SIGHUP
+    Quit,
     #[allow(dead_code)]
     Resize(u16, u16),
 }
@@ -43,6 +46,14 @@ impl EventHandler {
             }
         });
 
+        // Turn termination signals into a normal quit so the terminal gets restored
+        let signal_tx = tx.clone();
+        tokio::spawn(async move {
+            if wait_for_signal().await.is_ok() {
+                let _ = signal_tx.send(Event::Quit);
+            }
+        });
+
         Self { rx, _tx: tx }
     }
 
@@ -53,3 +64,49 @@ impl EventHandler {
             .ok_or_else(|| color_eyre::eyre::eyre!("Event channel closed"))
     }
 }
+
+/// Apply events to the app through the same dispatch as the main loop, with
+/// no terminal. Actions (scans, connect, speed test) aren't run; they are
+/// returned in order so callers can check which were requested.
+pub fn drive(app: &mut App, events: &[Event]) -> Vec<AppAction> {
+    let mut actions = Vec::new();
+    for event in events {
+        match event {
+            Event::Key(key) => actions.extend(app.handle_key(*key)),
+            Event::Tick => app.handle_tick(),
+            Event::Quit => app.quit(),
+            Event::Resize(_, _) => {}
+        }
+        app.sync_selection_cache();
+        if app.should_quit {
+            break;
+        }
+    }
+    actions
+}
+
+/// A key press without modifiers, for scripting `drive`
+pub fn key(code: KeyCode) -> Event {
+    Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+}
+
+/// Resolve on the first SIGINT, SIGTERM or SIGHUP
+#[cfg(unix)]
+pub async fn wait_for_signal() -> Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = signal(SignalKind::terminate())?;
+    let mut hangup = signal(SignalKind::hangup())?;
+    tokio::select! {
+        result = tokio::signal::ctrl_c() => result?,
+        _ = terminate.recv() => {}
+        _ = hangup.recv() => {}
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub async fn wait_for_signal() -> Result<()> {
+    tokio::signal::ctrl_c().await?;
+    Ok(())
+}
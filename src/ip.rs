@@ -1,8 +1,21 @@
 //! IP address retrieval module
 //!
-//! Provides functions to get local and public IP addresses.
+//! Provides functions to get local and public IP addresses (IPv4 and IPv6).
 
+use color_eyre::eyre::eyre;
 use color_eyre::Result;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Local and public addresses for both IP families (None when unavailable)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IpAddresses {
+    pub local_v4: Option<String>,
+    pub local_v6: Option<String>,
+    pub public_v4: Option<String>,
+    pub public_v6: Option<String>,
+}
 
 /// Get the local IP address assigned by the router
 pub fn get_local_ip() -> Result<String> {
@@ -10,41 +23,182 @@ pub fn get_local_ip() -> Result<String> {
     Ok(ip.to_string())
 }
 
-/// Get the public IP address visible to the internet (blocking version)
+/// Get the IPv4 address of a specific interface, falling back to the
+/// default local IP if the interface has none
+pub fn get_interface_ip(interface: &str) -> Result<String> {
+    if let Ok(interfaces) = local_ip_address::list_afinet_netifas()
+        && let Some((_, ip)) = interfaces
+            .iter()
+            .find(|(name, ip)| name == interface && ip.is_ipv4())
+    {
+        return Ok(ip.to_string());
+    }
+    get_local_ip()
+}
+
+/// Get the local IPv6 address
+pub fn get_local_ipv6() -> Result<String> {
+    let ip = local_ip_address::local_ipv6()?;
+    Ok(ip.to_string())
+}
+
+/// Get a routable IPv6 address of a specific interface (skipping link-local),
+/// falling back to the default local IPv6
+pub fn get_interface_ipv6(interface: &str) -> Result<String> {
+    if let Ok(interfaces) = local_ip_address::list_afinet_netifas()
+        && let Some((_, ip)) = interfaces.iter().find(|(name, ip)| {
+            name == interface && matches!(ip, IpAddr::V6(v6) if is_routable_v6(v6))
+        })
+    {
+        return Ok(ip.to_string());
+    }
+    get_local_ipv6()
+}
+
+/// Whether an IPv6 address is usable beyond the local link
+fn is_routable_v6(ip: &Ipv6Addr) -> bool {
+    let link_local = (ip.segments()[0] & 0xffc0) == 0xfe80;
+    !ip.is_loopback() && !ip.is_unspecified() && !link_local
+}
+
+/// Public IPv4 lookup services, tried in order. icanhazip.com and
+/// ifconfig.me answer over IPv6 too, so the IPv4-only host is used where
+/// there is one.
+pub const DEFAULT_PUBLIC_IP_ENDPOINTS: &[&str] = &[
+    "https://api.ipify.org",
+    "https://ipv4.icanhazip.com",
+    "https://ifconfig.me/ip",
+];
+
+/// Per-request timeout of a public IP lookup
+const DEFAULT_PUBLIC_IP_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Attempts per endpoint before moving on to the next one
+const PUBLIC_IP_ATTEMPTS: usize = 2;
+
+/// User-configured lookup endpoints and timeout (set before the first lookup)
+static PUBLIC_IP_OPTIONS: OnceLock<PublicIpOptions> = OnceLock::new();
+
+/// Where the public IPv4 address is looked up and how long each request may take
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicIpOptions {
+    /// Services returning the caller's address as plain text, tried in order
+    pub endpoints: Vec<String>,
+    /// Timeout of each request
+    pub timeout: Duration,
+}
+
+impl Default for PublicIpOptions {
+    fn default() -> Self {
+        Self {
+            endpoints: DEFAULT_PUBLIC_IP_ENDPOINTS.iter().map(|e| e.to_string()).collect(),
+            timeout: DEFAULT_PUBLIC_IP_TIMEOUT,
+        }
+    }
+}
+
+/// Use these endpoints and timeout instead of the defaults.
+/// Must be called before the first public IP lookup.
+pub fn set_public_ip_options(options: PublicIpOptions) {
+    let _ = PUBLIC_IP_OPTIONS.set(options);
+}
+
+fn public_ip_options() -> &'static PublicIpOptions {
+    PUBLIC_IP_OPTIONS.get_or_init(PublicIpOptions::default)
+}
+
+/// Try each endpoint in order, `PUBLIC_IP_ATTEMPTS` times each, and return
+/// the first response that is an IPv4 address along with the endpoint that
+/// gave it. Anything else (errors, captive portal pages, an IPv6 address
+/// from a dual-stack service) moves on.
+fn first_public_ip<F>(endpoints: &[String], mut fetch: F) -> Result<(String, String)>
+where
+    F: FnMut(&str) -> Result<String>,
+{
+    let mut failures = Vec::new();
+    for endpoint in endpoints {
+        for _ in 0..PUBLIC_IP_ATTEMPTS {
+            match fetch(endpoint) {
+                Ok(body) if body.trim().parse::<Ipv4Addr>().is_ok() => {
+                    return Ok((body.trim().to_string(), endpoint.clone()));
+                }
+                Ok(_) => failures.push(format!("{}: response is not an IPv4 address", endpoint)),
+                Err(e) => failures.push(format!("{}: {}", endpoint, e)),
+            }
+        }
+    }
+    if failures.is_empty() {
+        return Err(eyre!("no public IP endpoints configured"));
+    }
+    Err(eyre!("public IP lookup failed ({})", failures.join("; ")))
+}
+
+/// Get the public IP address visible to the internet and the endpoint that
+/// reported it (blocking version)
 /// Note: This must NOT be called from within an async context.
 /// Use get_public_ip_blocking() wrapped in spawn_blocking instead.
-fn get_public_ip_blocking() -> Result<String> {
+fn get_public_ip_blocking() -> Result<(String, String)> {
+    let options = public_ip_options();
     let client = reqwest::blocking::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
+        .timeout(options.timeout)
         .build()?;
 
-    // Use ipify.org - simple API that returns just the IP
-    let response = client.get("https://api.ipify.org").send()?;
-
-    if response.status().is_success() {
-        let ip = response.text()?;
-        Ok(ip.trim().to_string())
-    } else {
-        // Fallback to alternative service
-        let response = client.get("https://icanhazip.com").send()?;
-        let ip = response.text()?;
-        Ok(ip.trim().to_string())
-    }
+    first_public_ip(&options.endpoints, |endpoint| {
+        Ok(client.get(endpoint).send()?.error_for_status()?.text()?)
+    })
+}
+
+/// Get the public IPv6 address (blocking version).
+/// api6.ipify.org only answers over IPv6, so this fails on v4-only hosts.
+fn get_public_ipv6_blocking() -> Result<String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()?;
+
+    let ip = client.get("https://api6.ipify.org").send()?.error_for_status()?.text()?;
+    Ok(ip.trim().to_string())
 }
 
 /// Get the public IP address (safe to call from async context)
 pub fn get_public_ip() -> Option<String> {
     // Use std::thread for blocking HTTP call to avoid Tokio runtime conflicts
     let handle = std::thread::spawn(get_public_ip_blocking);
+    handle.join().ok().and_then(|r| r.ok()).map(|(ip, _)| ip)
+}
+
+/// Get the public IPv6 address (safe to call from async context)
+pub fn get_public_ipv6() -> Option<String> {
+    let handle = std::thread::spawn(get_public_ipv6_blocking);
     handle.join().ok().and_then(|r| r.ok())
 }
 
-/// Get both local and public IPs (safe to call from async context)
-pub fn get_all_ips() -> (Option<String>, Option<String>) {
-    let local = get_local_ip().ok();
-    // Spawn public IP fetch in a separate thread to avoid blocking Tokio
-    let public = get_public_ip();
-    (local, public)
+/// Get both public addresses, fetched in parallel. The IPv4 lookup comes
+/// with the endpoint that answered, or the reason every endpoint failed.
+pub fn get_public_ips() -> (Result<(String, String)>, Option<String>) {
+    let v4 = std::thread::spawn(get_public_ip_blocking);
+    let v6 = std::thread::spawn(get_public_ipv6_blocking);
+    (
+        v4.join().unwrap_or_else(|_| Err(eyre!("public IP lookup panicked"))),
+        v6.join().ok().and_then(|r| r.ok()),
+    )
+}
+
+/// Get local and public IPs for both families (safe to call from async
+/// context), along with the endpoint the public IPv4 address came from
+pub fn get_all_ips(interface: &str) -> (IpAddresses, Result<String>) {
+    // Public IPs are fetched in separate threads to avoid blocking Tokio
+    let (public_v4, public_v6) = get_public_ips();
+    let (public_v4, source) = match public_v4 {
+        Ok((ip, endpoint)) => (Some(ip), Ok(endpoint)),
+        Err(e) => (None, Err(e)),
+    };
+    let ips = IpAddresses {
+        local_v4: get_interface_ip(interface).ok(),
+        local_v6: get_interface_ipv6(interface).ok(),
+        public_v4,
+        public_v6,
+    };
+    (ips, source)
 }
 
 #[cfg(test)]
@@ -62,4 +216,74 @@ mod tests {
     }
 
     // Note: Public IP test skipped as it requires network access
+
+    fn endpoints(urls: &[&str]) -> Vec<String> {
+        urls.iter().map(|u| u.to_string()).collect()
+    }
+
+    #[test]
+    fn test_public_ip_falls_back_past_failing_endpoint() {
+        let mut calls = Vec::new();
+        let result = first_public_ip(&endpoints(&["https://down.example", "https://up.example"]), |endpoint| {
+            calls.push(endpoint.to_string());
+            if endpoint == "https://down.example" {
+                Err(eyre!("timed out"))
+            } else {
+                Ok("203.0.113.7\n".to_string())
+            }
+        })
+        .unwrap();
+
+        assert_eq!(result, ("203.0.113.7".to_string(), "https://up.example".to_string()));
+        // The failing endpoint gets one retry before falling back
+        assert_eq!(calls, ["https://down.example", "https://down.example", "https://up.example"]);
+    }
+
+    #[test]
+    fn test_public_ip_retries_then_succeeds() {
+        let mut attempts = 0;
+        let (ip, endpoint) = first_public_ip(&endpoints(&["https://flaky.example", "https://unused.example"]), |_| {
+            attempts += 1;
+            if attempts == 1 {
+                Err(eyre!("connection reset"))
+            } else {
+                Ok("198.51.100.4".to_string())
+            }
+        })
+        .unwrap();
+        assert_eq!((ip.as_str(), endpoint.as_str()), ("198.51.100.4", "https://flaky.example"));
+    }
+
+    #[test]
+    fn test_public_ip_rejects_non_ip_responses() {
+        // A captive portal answering with its login page must not count as an address
+        let err = first_public_ip(&endpoints(&["https://portal.example"]), |_| {
+            Ok("<html>Sign in</html>".to_string())
+        })
+        .unwrap_err();
+        assert!(err.to_string().contains("not an IPv4 address"));
+        assert!(first_public_ip(&[], |_| Ok("203.0.113.7".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_public_ip_skips_ipv6_responses() {
+        // A dual-stack service reached over IPv6 answers with the IPv6 address
+        let result = first_public_ip(&endpoints(&["https://dual.example", "https://v4.example"]), |endpoint| {
+            if endpoint == "https://dual.example" {
+                Ok("2001:db8::1\n".to_string())
+            } else {
+                Ok("203.0.113.7\n".to_string())
+            }
+        })
+        .unwrap();
+        assert_eq!(result, ("203.0.113.7".to_string(), "https://v4.example".to_string()));
+    }
+
+    #[test]
+    fn test_routable_v6() {
+        assert!(is_routable_v6(&"2001:db8::1".parse().unwrap()));
+        assert!(!is_routable_v6(&"fe80::1".parse().unwrap()));
+        assert!(!is_routable_v6(&Ipv6Addr::LOCALHOST));
+        assert!(!is_routable_v6(&Ipv6Addr::UNSPECIFIED));
+    }
 }
@@ -0,0 +1,547 @@
+//! Remappable key bindings, configured in the `[keys]` section of the config file:
+//!
+//! ```toml
+//! [keys]
+//! scan = "F5"
+//! sort = ["o", "ctrl+s"]
+//! ```
+//!
+//! Each entry replaces all default keys of that action; actions not listed
+//! keep their defaults. A key only has to be unique within each view it
+//! works in, so `s` can sort networks and sweep for devices.
+
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Something a remappable key does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyAction {
+    Quit,
+    SwitchView,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    First,
+    Last,
+    Connect,
+    Scan,
+    Demo,
+    AutoScan,
+    IntervalUp,
+    IntervalDown,
+    Sort,
+    ChannelChart,
+    History,
+    Copy,
+    Filter,
+    Band,
+    Gateway,
+    Nearby,
+    PinConnected,
+    TopN,
+    Meter,
+    Search,
+    Note,
+    MacFormat,
+    SpeedUnits,
+    DeviceDetail,
+    DeviceScan,
+    DeepScan,
+    Rename,
+    DeviceNotes,
+    DeviceTags,
+    DeviceSort,
+    AgentFilter,
+    Forget,
+    Reimport,
+    Log,
+    Help,
+}
+
+/// The tabs key bindings apply in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyView {
+    Networks,
+    Devices,
+    Known,
+}
+
+impl KeyView {
+    pub const ALL: [KeyView; 3] = [KeyView::Networks, KeyView::Devices, KeyView::Known];
+}
+
+impl KeyAction {
+    pub const ALL: [KeyAction; 41] = [
+        KeyAction::Quit,
+        KeyAction::SwitchView,
+        KeyAction::Up,
+        KeyAction::Down,
+        KeyAction::PageUp,
+        KeyAction::PageDown,
+        KeyAction::First,
+        KeyAction::Last,
+        KeyAction::Connect,
+        KeyAction::Scan,
+        KeyAction::Demo,
+        KeyAction::AutoScan,
+        KeyAction::IntervalUp,
+        KeyAction::IntervalDown,
+        KeyAction::Sort,
+        KeyAction::ChannelChart,
+        KeyAction::History,
+        KeyAction::Copy,
+        KeyAction::Filter,
+        KeyAction::Band,
+        KeyAction::Gateway,
+        KeyAction::Nearby,
+        KeyAction::PinConnected,
+        KeyAction::TopN,
+        KeyAction::Meter,
+        KeyAction::Search,
+        KeyAction::Note,
+        KeyAction::MacFormat,
+        KeyAction::SpeedUnits,
+        KeyAction::DeviceDetail,
+        KeyAction::DeviceScan,
+        KeyAction::DeepScan,
+        KeyAction::Rename,
+        KeyAction::DeviceNotes,
+        KeyAction::DeviceTags,
+        KeyAction::DeviceSort,
+        KeyAction::AgentFilter,
+        KeyAction::Forget,
+        KeyAction::Reimport,
+        KeyAction::Log,
+        KeyAction::Help,
+    ];
+
+    /// Name used in the config file
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeyAction::Quit => "quit",
+            KeyAction::SwitchView => "switch_view",
+            KeyAction::Up => "up",
+            KeyAction::Down => "down",
+            KeyAction::PageUp => "page_up",
+            KeyAction::PageDown => "page_down",
+            KeyAction::First => "first",
+            KeyAction::Last => "last",
+            KeyAction::Connect => "connect",
+            KeyAction::Scan => "scan",
+            KeyAction::Demo => "demo",
+            KeyAction::AutoScan => "auto_scan",
+            KeyAction::IntervalUp => "interval_up",
+            KeyAction::IntervalDown => "interval_down",
+            KeyAction::Sort => "sort",
+            KeyAction::ChannelChart => "channel_chart",
+            KeyAction::History => "history",
+            KeyAction::Copy => "copy",
+            KeyAction::Filter => "filter",
+            KeyAction::Band => "band",
+            KeyAction::Gateway => "gateway",
+            KeyAction::Nearby => "nearby",
+            KeyAction::PinConnected => "pin_connected",
+            KeyAction::TopN => "top_n",
+            KeyAction::Meter => "meter",
+            KeyAction::Search => "search",
+            KeyAction::Note => "note",
+            KeyAction::MacFormat => "mac_format",
+            KeyAction::SpeedUnits => "speed_units",
+            KeyAction::DeviceDetail => "device_detail",
+            KeyAction::DeviceScan => "device_scan",
+            KeyAction::DeepScan => "deep_scan",
+            KeyAction::Rename => "rename",
+            KeyAction::DeviceNotes => "device_notes",
+            KeyAction::DeviceTags => "device_tags",
+            KeyAction::DeviceSort => "device_sort",
+            KeyAction::AgentFilter => "agent_filter",
+            KeyAction::Forget => "forget",
+            KeyAction::Reimport => "reimport",
+            KeyAction::Log => "log",
+            KeyAction::Help => "help",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+
+    /// Views the action works in; its keys can do something else elsewhere
+    pub fn views(&self) -> &'static [KeyView] {
+        match self {
+            KeyAction::Quit
+            | KeyAction::SwitchView
+            | KeyAction::Up
+            | KeyAction::Down
+            | KeyAction::PageUp
+            | KeyAction::PageDown
+            | KeyAction::First
+            | KeyAction::Last
+            | KeyAction::Log
+            | KeyAction::Help => &KeyView::ALL,
+            KeyAction::AutoScan | KeyAction::Copy | KeyAction::MacFormat => &[KeyView::Networks, KeyView::Devices],
+            KeyAction::DeviceDetail
+            | KeyAction::DeviceScan
+            | KeyAction::DeepScan
+            | KeyAction::Rename
+            | KeyAction::DeviceNotes
+            | KeyAction::DeviceTags
+            | KeyAction::DeviceSort
+            | KeyAction::AgentFilter => &[KeyView::Devices],
+            KeyAction::Forget | KeyAction::Reimport => &[KeyView::Known],
+            // The rest are WiFi Networks keys
+            _ => &[KeyView::Networks],
+        }
+    }
+
+    /// Today's bindings, used for actions the config doesn't mention
+    fn default_keys(&self) -> &'static [&'static str] {
+        match self {
+            KeyAction::Quit => &["q", "esc"],
+            KeyAction::SwitchView => &["tab"],
+            KeyAction::Up => &["up", "k"],
+            KeyAction::Down => &["down", "j"],
+            KeyAction::PageUp => &["pageup"],
+            KeyAction::PageDown => &["pagedown"],
+            KeyAction::First => &["home"],
+            KeyAction::Last => &["end"],
+            KeyAction::Connect => &["enter"],
+            KeyAction::Scan => &["r"],
+            KeyAction::Demo => &["d"],
+            KeyAction::AutoScan => &["a"],
+            KeyAction::IntervalUp => &["+", "="],
+            KeyAction::IntervalDown => &["-"],
+            KeyAction::Sort => &["s"],
+            KeyAction::ChannelChart => &["c"],
+            KeyAction::History => &["h"],
+            KeyAction::Copy => &["y"],
+            KeyAction::Filter => &["f"],
+            KeyAction::Band => &["b"],
+            KeyAction::Gateway => &["g"],
+            KeyAction::Nearby => &["n"],
+            KeyAction::PinConnected => &["p"],
+            KeyAction::TopN => &["t"],
+            KeyAction::Meter => &["w"],
+            KeyAction::Search => &["/"],
+            KeyAction::Note => &["e"],
+            KeyAction::MacFormat => &["m"],
+            KeyAction::SpeedUnits => &["u"],
+            KeyAction::DeviceDetail => &["enter"],
+            KeyAction::DeviceScan => &["s"],
+            KeyAction::DeepScan => &["S"],
+            KeyAction::Rename => &["r", "R"],
+            KeyAction::DeviceNotes => &["e"],
+            KeyAction::DeviceTags => &["t"],
+            KeyAction::DeviceSort => &["o"],
+            KeyAction::AgentFilter => &["i"],
+            KeyAction::Forget => &["x", "delete"],
+            KeyAction::Reimport => &["r"],
+            KeyAction::Log => &["l"],
+            KeyAction::Help => &["?"],
+        }
+    }
+}
+
+/// A key plus Ctrl/Alt, parsed from strings like "r", "F5", "ctrl+r" or "esc".
+/// Shift isn't tracked separately: "R" is shift+r.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn parse(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(eyre!("empty key"));
+        }
+        // Split off the key after the last '+', allowing "+" and "ctrl++" themselves
+        let last_char = s.char_indices().last().map_or(0, |(idx, _)| idx);
+        let (prefix, key) = match s[..last_char].rfind('+') {
+            Some(idx) => (&s[..idx], &s[idx + 1..]),
+            None => ("", s),
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        let mut shift = false;
+        for part in prefix.split('+').filter(|p| !p.is_empty()) {
+            match part.to_lowercase().as_str() {
+                "ctrl" | "control" => modifiers |= KeyModifiers::CONTROL,
+                "alt" => modifiers |= KeyModifiers::ALT,
+                "shift" => shift = true,
+                _ => return Err(eyre!("unknown modifier \"{}\" in \"{}\"", part, s)),
+            }
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if shift => KeyCode::Char(c.to_ascii_uppercase()),
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_lowercase().as_str() {
+                "esc" | "escape" => KeyCode::Esc,
+                "enter" | "return" => KeyCode::Enter,
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "space" => KeyCode::Char(' '),
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(eyre!("unknown key \"{}\"", s)),
+                },
+            },
+        };
+        Ok(Self { code, modifiers })
+    }
+
+    /// The chord a key event matches; Shift is already part of the character
+    pub fn from_event(key: KeyEvent) -> Self {
+        Self {
+            code: key.code,
+            modifiers: key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT),
+        }
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{}", format!("{:?}", code).to_lowercase()),
+        }
+    }
+}
+
+/// One key or a list of keys for an action in the `[keys]` config section
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeyBinding {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyBinding {
+    fn keys(&self) -> Vec<&str> {
+        match self {
+            KeyBinding::One(key) => vec![key.as_str()],
+            KeyBinding::Many(keys) => keys.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+/// Which action each key triggers in each view
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: HashMap<(KeyView, KeyChord), KeyAction>,
+    /// Each action's keys in the order they were configured, for the help screen
+    keys: HashMap<KeyAction, Vec<KeyChord>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_config(&BTreeMap::new()).expect("default key bindings conflict")
+    }
+}
+
+impl Keymap {
+    /// Build the keymap from the `[keys]` config section on top of the
+    /// defaults. Unknown actions, unparseable keys and keys bound to two
+    /// actions in the same view are all reported together in one error.
+    pub fn from_config(keys: &BTreeMap<String, KeyBinding>) -> Result<Self> {
+        let mut problems = Vec::new();
+        for name in keys.keys() {
+            if KeyAction::from_name(name).is_none() {
+                problems.push(format!("unknown action \"{}\"", name));
+            }
+        }
+
+        let mut bindings: HashMap<(KeyView, KeyChord), KeyAction> = HashMap::new();
+        let mut action_keys: HashMap<KeyAction, Vec<KeyChord>> = HashMap::new();
+        for action in KeyAction::ALL {
+            let names = match keys.get(action.name()) {
+                Some(binding) => binding.keys(),
+                None => action.default_keys().to_vec(),
+            };
+            for name in names {
+                let chord = match KeyChord::parse(name) {
+                    Ok(chord) => chord,
+                    Err(e) => {
+                        problems.push(format!("{}: {}", action.name(), e));
+                        continue;
+                    }
+                };
+                let chords = action_keys.entry(action).or_default();
+                if !chords.contains(&chord) {
+                    chords.push(chord);
+                }
+                for &view in action.views() {
+                    if let Some(other) = bindings.insert((view, chord), action)
+                        && other != action
+                    {
+                        let problem = format!("\"{}\" is bound to both {} and {}", chord, other.name(), action.name());
+                        // Global actions clash in every view; say so once
+                        if !problems.contains(&problem) {
+                            problems.push(problem);
+                        }
+                    }
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(Self { bindings, keys: action_keys })
+        } else {
+            Err(eyre!("invalid key bindings: {}", problems.join("; ")))
+        }
+    }
+
+    /// The action a key press triggers in `view`, if any
+    pub fn action_for(&self, view: KeyView, key: KeyEvent) -> Option<KeyAction> {
+        self.bindings.get(&(view, KeyChord::from_event(key))).copied()
+    }
+
+    /// The keys bound to an action, in the order they were configured
+    pub fn keys_for(&self, action: KeyAction) -> &[KeyChord] {
+        self.keys.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// An action's keys as shown to the user, e.g. "x/delete"
+    pub fn describe(&self, action: KeyAction) -> String {
+        let keys: Vec<String> = self.keys_for(action).iter().map(|chord| chord.to_string()).collect();
+        keys.join("/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bindings(entries: &[(&str, &str)]) -> BTreeMap<String, KeyBinding> {
+        entries
+            .iter()
+            .map(|(action, key)| (action.to_string(), KeyBinding::One(key.to_string())))
+            .collect()
+    }
+
+    fn press(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_parse_chords() {
+        let chord = |s| KeyChord::parse(s).unwrap();
+        assert_eq!(chord("r").code, KeyCode::Char('r'));
+        assert_eq!(chord("R").code, KeyCode::Char('R'));
+        assert_eq!(chord("shift+r").code, KeyCode::Char('R'));
+        assert_eq!(chord("+").code, KeyCode::Char('+'));
+        assert_eq!(chord("F5").code, KeyCode::F(5));
+        assert_eq!(chord("Esc").code, KeyCode::Esc);
+        let ctrl = chord("ctrl+r");
+        assert_eq!((ctrl.code, ctrl.modifiers), (KeyCode::Char('r'), KeyModifiers::CONTROL));
+        let ctrl_plus = chord("ctrl++");
+        assert_eq!((ctrl_plus.code, ctrl_plus.modifiers), (KeyCode::Char('+'), KeyModifiers::CONTROL));
+        assert!(KeyChord::parse("hyper+r").is_err());
+        assert!(KeyChord::parse("F13").is_err());
+        assert!(KeyChord::parse("").is_err());
+    }
+
+    #[test]
+    fn test_defaults_match_existing_keys() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.action_for(KeyView::Networks, press(KeyCode::Char('r'))), Some(KeyAction::Scan));
+        assert_eq!(keymap.action_for(KeyView::Networks, press(KeyCode::Char('s'))), Some(KeyAction::Sort));
+        assert_eq!(keymap.action_for(KeyView::Networks, press(KeyCode::Enter)), Some(KeyAction::Connect));
+        assert_eq!(keymap.action_for(KeyView::Networks, press(KeyCode::Tab)), Some(KeyAction::SwitchView));
+        assert_eq!(keymap.action_for(KeyView::Networks, press(KeyCode::Esc)), Some(KeyAction::Quit));
+        assert_eq!(keymap.action_for(KeyView::Networks, press(KeyCode::PageDown)), Some(KeyAction::PageDown));
+        assert_eq!(keymap.action_for(KeyView::Networks, press(KeyCode::Home)), Some(KeyAction::First));
+        // Ctrl+r isn't r
+        let ctrl_r = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL);
+        assert_eq!(keymap.action_for(KeyView::Networks, ctrl_r), None);
+    }
+
+    #[test]
+    fn test_keys_are_per_view() {
+        let keymap = Keymap::default();
+        let s = press(KeyCode::Char('s'));
+        assert_eq!(keymap.action_for(KeyView::Networks, s), Some(KeyAction::Sort));
+        assert_eq!(keymap.action_for(KeyView::Devices, s), Some(KeyAction::DeviceScan));
+        assert_eq!(keymap.action_for(KeyView::Known, s), None);
+        assert_eq!(keymap.action_for(KeyView::Known, press(KeyCode::Delete)), Some(KeyAction::Forget));
+        assert_eq!(keymap.action_for(KeyView::Known, press(KeyCode::Char('r'))), Some(KeyAction::Reimport));
+        // Shared actions and global ones work in several views
+        assert_eq!(keymap.action_for(KeyView::Devices, press(KeyCode::Char('y'))), Some(KeyAction::Copy));
+        assert_eq!(keymap.action_for(KeyView::Known, press(KeyCode::Tab)), Some(KeyAction::SwitchView));
+
+        // A device key clashing with another device key is caught...
+        let err = Keymap::from_config(&bindings(&[("rename", "o")])).unwrap_err().to_string();
+        assert!(err.contains("\"o\" is bound to both rename and device_sort"), "{}", err);
+        // ...as is one clashing with a global key, once
+        let err = Keymap::from_config(&bindings(&[("forget", "tab")])).unwrap_err().to_string();
+        assert_eq!(err.matches("is bound to both").count(), 1, "{}", err);
+        // but reusing a WiFi Networks key is fine
+        assert!(Keymap::from_config(&bindings(&[("forget", "c")])).is_ok());
+    }
+
+    #[test]
+    fn test_remap_replaces_defaults() {
+        let keymap = Keymap::from_config(&bindings(&[("scan", "F5")])).unwrap();
+        assert_eq!(keymap.action_for(KeyView::Networks, press(KeyCode::F(5))), Some(KeyAction::Scan));
+        assert_eq!(keymap.action_for(KeyView::Networks, press(KeyCode::Char('r'))), None);
+    }
+
+    #[test]
+    fn test_keys_for_follows_remaps() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.describe(KeyAction::Forget), "x/delete");
+        assert_eq!(keymap.describe(KeyAction::Quit), "q/esc");
+
+        let keys = BTreeMap::from([
+            ("scan".to_string(), KeyBinding::One("F5".to_string())),
+            ("sort".to_string(), KeyBinding::Many(vec!["o".to_string(), "ctrl+s".to_string()])),
+        ]);
+        let keymap = Keymap::from_config(&keys).unwrap();
+        assert_eq!(keymap.keys_for(KeyAction::Scan), [KeyChord::parse("F5").unwrap()]);
+        assert_eq!(keymap.describe(KeyAction::Sort), "o/ctrl+s");
+    }
+
+    #[test]
+    fn test_conflicts_are_reported() {
+        // s is still the default sort key
+        let err = Keymap::from_config(&bindings(&[("scan", "s")])).unwrap_err().to_string();
+        assert!(err.contains("\"s\" is bound to both"), "{}", err);
+
+        // Swapping two keys is fine as long as both are remapped
+        assert!(Keymap::from_config(&bindings(&[("scan", "s"), ("sort", "r")])).is_ok());
+    }
+
+    #[test]
+    fn test_all_problems_reported_together() {
+        let err = Keymap::from_config(&bindings(&[("rescan", "r"), ("sort", "hyper+s")]))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("unknown action \"rescan\""), "{}", err);
+        assert!(err.contains("sort: unknown modifier"), "{}", err);
+    }
+}
@@ -1,11 +1,21 @@
+pub mod adaptive;
 pub mod app;
+pub mod bench;
+pub mod captive;
 pub mod components;
+pub mod config;
 pub mod connection;
 pub mod db;
+pub mod doctor;
 pub mod event;
 pub mod ip;
+pub mod keymap;
+pub mod logger;
+pub mod mac;
 pub mod network_map;
+pub mod redact;
 pub mod scanner;
+pub mod scripts;
 pub mod scoring;
 pub mod speedtest;
 pub mod theme;
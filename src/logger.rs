@@ -0,0 +1,101 @@
+use crate::scanner::Network;
+use chrono::{DateTime, Utc};
+use color_eyre::Result;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const CSV_HEADER: &str = "timestamp,ssid,bssid,channel,signal_dbm,security,band,score";
+
+/// Appends every scan to a CSV file for analysis outside the database
+pub struct ScanLogger {
+    path: PathBuf,
+}
+
+impl ScanLogger {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append one row per network, writing the header first if the file is new
+    pub fn log_scan(&self, networks: &[Network], timestamp: DateTime<Utc>) -> Result<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+
+        let mut out = String::new();
+        if file.metadata()?.len() == 0 {
+            out.push_str(CSV_HEADER);
+            out.push('\n');
+        }
+
+        let timestamp = timestamp.to_rfc3339();
+        for n in networks {
+            out.push_str(&format!(
+                "{},{},{},{},{},{},{},{}\n",
+                timestamp,
+                csv_field(&n.ssid),
+                csv_field(&n.mac),
+                n.channel,
+                n.signal_dbm,
+                n.security,
+                n.frequency_band,
+                n.score
+            ));
+        }
+
+        file.write_all(out.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Quote a field if it contains a delimiter, quote or newline
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn network(ssid: &str) -> Network {
+        Network { score: 80, ..Network::test(ssid, 6, -55) }
+    }
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("Cafe"), "Cafe");
+        assert_eq!(csv_field("Bob's, Cafe"), "\"Bob's, Cafe\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn test_header_written_once() {
+        let path = std::env::temp_dir().join(format!("wifi_analyzer_log_{}.csv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let logger = ScanLogger::new(&path);
+        logger.log_scan(&[network("One")], Utc::now()).unwrap();
+        logger.log_scan(&[network("Two, Inc")], Utc::now()).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let lines: Vec<&str> = contents.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], CSV_HEADER);
+        assert!(lines[1].contains(",One,AA:BB:CC:DD:EE:FF,6,-55,WPA2,2.4 GHz,80"));
+        assert!(lines[2].contains(",\"Two, Inc\","));
+    }
+}
@@ -0,0 +1,160 @@
+//! MAC address display formats
+//!
+//! Addresses are stored canonically (uppercase, colon-separated) in the
+//! database and scan results; these helpers only change how they're shown.
+
+/// Separator between octets when displaying a MAC address
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MacSeparator {
+    /// AA:BB:CC:DD:EE:FF
+    #[default]
+    Colon,
+    /// AA-BB-CC-DD-EE-FF
+    Hyphen,
+    /// AABBCCDDEEFF
+    Bare,
+}
+
+/// How MAC addresses are displayed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MacFormat {
+    pub separator: MacSeparator,
+    pub uppercase: bool,
+}
+
+impl Default for MacFormat {
+    /// The canonical form used for storage
+    fn default() -> Self {
+        Self {
+            separator: MacSeparator::Colon,
+            uppercase: true,
+        }
+    }
+}
+
+impl MacFormat {
+    /// Next format in the display cycle: each separator in upper, then lower case
+    pub fn next(self) -> Self {
+        if self.uppercase {
+            return Self {
+                uppercase: false,
+                ..self
+            };
+        }
+        let separator = match self.separator {
+            MacSeparator::Colon => MacSeparator::Hyphen,
+            MacSeparator::Hyphen => MacSeparator::Bare,
+            MacSeparator::Bare => MacSeparator::Colon,
+        };
+        Self {
+            separator,
+            uppercase: true,
+        }
+    }
+
+    /// Example of the format for status messages, e.g. "aa-bb-cc-dd-ee-ff"
+    pub fn label(&self) -> String {
+        format_mac("AA:BB:CC:DD:EE:FF", *self)
+    }
+}
+
+/// Split a MAC address into its octets, accepting colon, hyphen or dot
+/// separators, single-digit octets ("a:b:c:d:e:f" from arp) or bare hex
+fn octets(mac: &str) -> Option<Vec<String>> {
+    let mac = mac.trim();
+    let parts: Vec<String> = if mac.contains([':', '-']) {
+        mac.split([':', '-'])
+            .map(|octet| format!("{:0>2}", octet))
+            .collect()
+    } else {
+        let hex: Vec<char> = mac.chars().filter(|c| *c != '.').collect();
+        if hex.len() != 12 {
+            return None;
+        }
+        hex.chunks(2).map(|pair| pair.iter().collect()).collect()
+    };
+
+    let valid = parts.len() == 6
+        && parts
+            .iter()
+            .all(|octet| octet.len() == 2 && octet.chars().all(|c| c.is_ascii_hexdigit()));
+    valid.then_some(parts)
+}
+
+/// Format a MAC address for display. Anything that isn't a MAC address
+/// is returned unchanged.
+pub fn format_mac(mac: &str, format: MacFormat) -> String {
+    let Some(octets) = octets(mac) else {
+        return mac.to_string();
+    };
+    let separator = match format.separator {
+        MacSeparator::Colon => ":",
+        MacSeparator::Hyphen => "-",
+        MacSeparator::Bare => "",
+    };
+    let joined = octets.join(separator);
+    if format.uppercase {
+        joined.to_uppercase()
+    } else {
+        joined.to_lowercase()
+    }
+}
+
+/// Canonical storage form: uppercase and colon-separated
+pub fn canonical_mac(mac: &str) -> String {
+    format_mac(mac, MacFormat::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAC: &str = "a4:C3:61:0b:2e:ff";
+
+    fn fmt(separator: MacSeparator, uppercase: bool) -> MacFormat {
+        MacFormat { separator, uppercase }
+    }
+
+    #[test]
+    fn test_colon() {
+        assert_eq!(format_mac(MAC, fmt(MacSeparator::Colon, true)), "A4:C3:61:0B:2E:FF");
+        assert_eq!(format_mac(MAC, fmt(MacSeparator::Colon, false)), "a4:c3:61:0b:2e:ff");
+    }
+
+    #[test]
+    fn test_hyphen() {
+        assert_eq!(format_mac(MAC, fmt(MacSeparator::Hyphen, true)), "A4-C3-61-0B-2E-FF");
+        assert_eq!(format_mac(MAC, fmt(MacSeparator::Hyphen, false)), "a4-c3-61-0b-2e-ff");
+    }
+
+    #[test]
+    fn test_bare() {
+        assert_eq!(format_mac(MAC, fmt(MacSeparator::Bare, true)), "A4C3610B2EFF");
+        assert_eq!(format_mac(MAC, fmt(MacSeparator::Bare, false)), "a4c3610b2eff");
+    }
+
+    #[test]
+    fn test_input_forms() {
+        assert_eq!(canonical_mac("a:b:c:d:e:f"), "0A:0B:0C:0D:0E:0F");
+        assert_eq!(canonical_mac("A4-C3-61-0B-2E-FF"), "A4:C3:61:0B:2E:FF");
+        assert_eq!(canonical_mac("a4c3.610b.2eff"), "A4:C3:61:0B:2E:FF");
+        assert_eq!(canonical_mac("a4c3610b2eff"), "A4:C3:61:0B:2E:FF");
+        // Not a MAC address: left alone
+        assert_eq!(canonical_mac("unknown"), "unknown");
+        assert_eq!(canonical_mac("A4:C3:61"), "A4:C3:61");
+    }
+
+    #[test]
+    fn test_cycle_visits_every_format() {
+        let mut format = MacFormat::default();
+        let mut seen = Vec::new();
+        for _ in 0..6 {
+            seen.push(format);
+            format = format.next();
+        }
+        assert_eq!(format, MacFormat::default());
+        for (i, a) in seen.iter().enumerate() {
+            assert!(seen[i + 1..].iter().all(|b| b != a));
+        }
+    }
+}
@@ -1,24 +1,52 @@
 use clap::{Parser, Subcommand};
 use color_eyre::Result;
-use crossterm::event::{KeyCode, KeyModifiers};
+use ipnetwork::IpNetwork;
 use std::io::{self, Write};
 use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
 use std::time::Duration;
-use wifi_analyzer::app::App;
-use wifi_analyzer::db::Database;
-use wifi_analyzer::event::{Event, EventHandler};
-use wifi_analyzer::scanner::enable_demo_mode;
+use wifi_analyzer::app::{clamp_interval, App, AppAction, SignalUnits};
+use wifi_analyzer::config::Config;
+use wifi_analyzer::connection::default_wireless_interface;
+use wifi_analyzer::db::{Database, DbError};
+use wifi_analyzer::doctor::{critical_failures, run_checks};
+use wifi_analyzer::event::{wait_for_signal, Event, EventHandler};
+use wifi_analyzer::ip::{set_public_ip_options, PublicIpOptions};
+use wifi_analyzer::keymap::Keymap;
+use wifi_analyzer::logger::ScanLogger;
+use wifi_analyzer::network_map::{load_port_file, parse_port_list, parse_target_subnet, Device, COMMON_PORTS};
+use wifi_analyzer::redact::{redact_device, redact_snapshot, DEFAULT_SALT};
+use wifi_analyzer::scanner::{enable_demo_mode, is_demo_mode, set_scan_interface, set_scan_options, ScanOptions};
+use wifi_analyzer::scoring::ScoringProfile;
+use wifi_analyzer::scripts::{swift_available, SWIFT_MISSING_WARNING};
+use wifi_analyzer::speedtest::{format_speed, SpeedUnits};
+use wifi_analyzer::theme::{no_color_requested, set_colors_enabled};
 use wifi_analyzer::tui;
 
+/// Auto-refresh interval when neither --interval nor the config file sets one
+const DEFAULT_INTERVAL_SECS: u64 = 15;
+
 #[derive(Parser, Debug)]
 #[command(name = "wifi-analyzer")]
 #[command(author = "Aviv E")]
 #[command(version = "0.1.0")]
 #[command(about = "A terminal-based WiFi analyzer for finding the best public WiFi")]
 struct Args {
-    /// Auto-refresh interval in seconds
-    #[arg(short, long, default_value = "15")]
-    interval: u64,
+    /// Auto-refresh interval in seconds, 5-300 [default: last used, else 15]
+    #[arg(short, long)]
+    interval: Option<u64>,
+
+    /// Shorten the auto-refresh interval while networks change and lengthen it while they don't
+    #[arg(long)]
+    adaptive: bool,
+
+    /// Shortest adaptive auto-refresh interval in seconds
+    #[arg(long, value_name = "SECONDS", default_value = "5", requires = "adaptive")]
+    adaptive_min: u64,
+
+    /// Longest adaptive auto-refresh interval in seconds
+    #[arg(long, value_name = "SECONDS", default_value = "120", requires = "adaptive")]
+    adaptive_max: u64,
 
     /// Start in manual mode (no auto-refresh)
     #[arg(short = 'm', long)]
@@ -32,6 +60,10 @@ struct Args {
     #[arg(short, long)]
     location: Option<String>,
 
+    /// Scoring profile (balanced, cafe, office), saved for --location and used there from then on
+    #[arg(long, value_parser = parse_profile, global = true)]
+    profile: Option<ScoringProfile>,
+
     /// Database file path for persistence
     #[arg(long, default_value = "wifi_analyzer.duckdb")]
     db_path: PathBuf,
@@ -40,6 +72,95 @@ struct Args {
     #[arg(long)]
     no_persist: bool,
 
+    /// Delete scans older than this many days on startup
+    #[arg(long, value_name = "DAYS")]
+    retention_days: Option<i64>,
+
+    /// IEEE OUI CSV for vendor lookup [default: $XDG_DATA_HOME/wifi-analyzer/oui.csv]
+    #[arg(long, value_name = "PATH")]
+    oui_file: Option<PathBuf>,
+
+    /// IANA service-names CSV for naming open ports [default: $XDG_DATA_HOME/wifi-analyzer/service-names-port-numbers.csv]
+    #[arg(long, value_name = "PATH")]
+    services_file: Option<PathBuf>,
+
+    /// Wireless interface to use (defaults to the first WiFi device)
+    #[arg(long, value_name = "NAME")]
+    interface: Option<String>,
+
+    /// Append every scan to this CSV file
+    #[arg(long, value_name = "PATH")]
+    csv_log: Option<PathBuf>,
+
+    /// Only report what connecting to a network would do, without invoking networksetup
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Scan once, print networks ranked by score and exit (no TUI)
+    #[arg(long)]
+    once: bool,
+
+    /// Moving past the last table row selects the first, and up from the first selects the last
+    #[arg(long)]
+    wrap_navigation: bool,
+
+    /// Seconds between automatic device scans when enabled with `a` in the devices view
+    #[arg(long, value_name = "SECONDS", default_value = "300")]
+    device_interval: u64,
+
+    /// Discover devices in this subnet instead of the /24 around the local IP (e.g. 10.0.0.0/22)
+    #[arg(long, value_name = "CIDR", global = true)]
+    subnet: Option<String>,
+
+    /// Allow --subnet ranges larger than 4094 hosts
+    #[arg(long, global = true)]
+    force: bool,
+
+    /// TCP ports probed on each device instead of the built-in list, e.g. 22,80,443,8000-8100
+    #[arg(long, value_name = "PORTS", global = true, conflicts_with = "ports_file")]
+    ports: Option<String>,
+
+    /// File listing the TCP ports to probe (comma- or line-separated, ranges allowed, # comments)
+    #[arg(long, value_name = "PATH", global = true)]
+    ports_file: Option<PathBuf>,
+
+    /// Hide networks weaker than this many dBm from the table (toggle with `n`)
+    #[arg(long, value_name = "DBM", allow_hyphen_values = true)]
+    min_signal: Option<i32>,
+
+    /// Show only the top N networks in the table by current sort (toggle with `t`)
+    #[arg(long, value_name = "N")]
+    max_display: Option<usize>,
+
+    /// WiFi scan passes merged per refresh; more catch more APs on flaky adapters
+    #[arg(long, value_name = "N", default_value = "2")]
+    scan_passes: usize,
+
+    /// Milliseconds to wait between scan passes
+    #[arg(long, value_name = "MS", default_value = "500")]
+    scan_delay_ms: u64,
+
+    /// Public IP lookup services tried in order, comma-separated
+    /// [default: api.ipify.org, ipv4.icanhazip.com, ifconfig.me/ip]
+    #[arg(long, value_name = "URLS", value_delimiter = ',', global = true)]
+    ip_endpoints: Vec<String>,
+
+    /// Timeout of each public IP lookup request
+    #[arg(long, value_name = "MS", default_value = "3000", global = true)]
+    ip_timeout_ms: u64,
+
+    /// Disable colors in the TUI and error output (also set by a non-empty NO_COLOR)
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// How signal strength is shown in the network table and detail panel
+    #[arg(long, value_parser = parse_signal_units, default_value = "both")]
+    signal_units: SignalUnits,
+
+    /// Units for speed test results: Mbps or MBps (MB/s = Mbps / 8)
+    #[arg(long, value_parser = parse_speed_units, global = true, default_value = "Mbps")]
+    speed_units: SpeedUnits,
+
     #[command(subcommand)]
     command: Option<Command>,
 }
@@ -54,48 +175,243 @@ enum Command {
         /// Do full ping sweep to discover all devices (slower but more thorough)
         #[arg(short, long)]
         full: bool,
+        /// Number of hosts pinged in parallel during a full sweep
+        #[arg(long, default_value = "50")]
+        ping_concurrency: usize,
+        /// TCP connect timeout per port in milliseconds
+        #[arg(long, default_value = "500")]
+        port_timeout: u64,
+        /// Number of ports probed in parallel per device
+        #[arg(long, default_value = "50")]
+        concurrency: usize,
+        /// Also probe common UDP services (DNS, NTP, SNMP, SSDP, mDNS)
+        #[arg(long)]
+        udp: bool,
+        /// Print one JSON object per device (NDJSON) instead of the table
+        #[arg(long)]
+        json: bool,
+        /// With --json, hash hostnames and mask MACs (vendor OUI kept)
+        #[arg(long, requires = "json")]
+        redact: bool,
+        /// Salt for --redact hashes; use your own so names can't be guessed
+        #[arg(long, requires = "redact", default_value = DEFAULT_SALT)]
+        salt: String,
     },
     /// Discover devices on the network (ARP only, no port scan)
     Discover {
         /// Do full ping sweep to discover all devices
         #[arg(short, long)]
         full: bool,
+        /// Number of hosts pinged in parallel during a full sweep
+        #[arg(long, default_value = "50")]
+        ping_concurrency: usize,
     },
     /// Test port scanning on a specific IP
     ScanPorts {
         /// IP address to scan
         ip: String,
+        /// Also probe common UDP services (DNS, NTP, SNMP, SSDP, mDNS)
+        #[arg(long)]
+        udp: bool,
+    },
+    /// Show the connection timeline for a network
+    History {
+        /// Network name (SSID)
+        ssid: String,
+    },
+    /// Compare a network's reception across scanning locations
+    Compare {
+        /// Network name (SSID)
+        ssid: String,
+    },
+    /// List every network ever scanned, best signal first, with the location it was strongest at
+    AllLocations,
+    /// Scan and suggest the least congested channels for your own access point
+    Recommend,
+    /// Scan and save to the database forever without the TUI, one summary line per scan
+    Watch {
+        /// Seconds between scans (at least 5)
+        #[arg(short, long, default_value = "30")]
+        interval: u64,
+        /// Location name to record scans under
+        #[arg(short, long)]
+        location: String,
+    },
+    /// Export the whole database to a portable JSON file
+    Snapshot {
+        /// File to write
+        #[arg(long, value_name = "FILE")]
+        out: PathBuf,
+        /// Hash SSIDs and hostnames and mask MACs (vendor OUI kept) before writing
+        #[arg(long)]
+        redact: bool,
+        /// Salt for --redact hashes; use your own so names can't be guessed
+        #[arg(long, requires = "redact", default_value = DEFAULT_SALT)]
+        salt: String,
     },
+    /// Check that swift, the WiFi interface, the database and the internet are usable
+    Doctor,
+    /// Load a JSON snapshot into a new, empty database (see --db-path)
+    Restore {
+        /// Snapshot file written by the snapshot subcommand
+        #[arg(long = "in", value_name = "FILE")]
+        input: PathBuf,
+    },
+    /// Time scan parsing, scoring and sorting on demo data (no WiFi or network needed)
+    Bench {
+        /// Number of parse/score/sort rounds (at least 1)
+        #[arg(short, long, default_value = "100")]
+        iterations: usize,
+    },
+}
+
+/// Parse --profile by the names the database stores
+fn parse_profile(name: &str) -> std::result::Result<ScoringProfile, String> {
+    ScoringProfile::from_name(name).ok_or_else(|| {
+        let names: Vec<&str> = ScoringProfile::ALL.iter().map(|p| p.name()).collect();
+        format!("expected one of: {}", names.join(", "))
+    })
+}
+
+/// Parse --signal-units by name
+fn parse_signal_units(name: &str) -> std::result::Result<SignalUnits, String> {
+    SignalUnits::from_name(name).ok_or_else(|| {
+        let names: Vec<&str> = SignalUnits::ALL.iter().map(|u| u.name()).collect();
+        format!("expected one of: {}", names.join(", "))
+    })
+}
+
+/// Parse --speed-units by name
+fn parse_speed_units(name: &str) -> std::result::Result<SpeedUnits, String> {
+    SpeedUnits::from_name(name).ok_or_else(|| {
+        let names: Vec<&str> = SpeedUnits::ALL.iter().map(|u| u.name()).collect();
+        format!("expected one of: {}", names.join(", "))
+    })
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    color_eyre::install()?;
+    let mut args = Args::parse();
 
-    let args = Args::parse();
+    if args.no_color || no_color_requested() {
+        set_colors_enabled(false);
+        color_eyre::config::HookBuilder::default()
+            .theme(color_eyre::config::Theme::new())
+            .install()?;
+    } else {
+        color_eyre::install()?;
+    }
 
-    // Handle subcommands (CLI mode)
-    if let Some(cmd) = args.command {
-        return run_cli_command(cmd).await;
+    if let Some(ref path) = args.oui_file {
+        wifi_analyzer::network_map::set_oui_file(path.clone());
+    }
+    if let Some(ref path) = args.services_file {
+        wifi_analyzer::network_map::set_services_file(path.clone());
     }
 
-    let interval = Duration::from_secs(args.interval);
+    let target_subnet = args
+        .subnet
+        .as_deref()
+        .map(|cidr| parse_target_subnet(cidr, args.force))
+        .transpose()?;
+    let scan_ports = match (&args.ports, &args.ports_file) {
+        (Some(spec), _) => parse_port_list(spec).map_err(|e| color_eyre::eyre::eyre!("--ports: {}", e))?,
+        (None, Some(path)) => load_port_file(path)?,
+        (None, None) => COMMON_PORTS.to_vec(),
+    };
 
     // Enable demo mode if requested
     if args.demo {
         enable_demo_mode();
     }
+    set_scan_options(ScanOptions {
+        passes: args.scan_passes.max(1),
+        delay: Duration::from_millis(args.scan_delay_ms),
+    });
+    if let Some(interface) = &args.interface {
+        set_scan_interface(interface.clone());
+    }
+    let mut ip_options = PublicIpOptions {
+        timeout: Duration::from_millis(args.ip_timeout_ms.max(100)),
+        ..PublicIpOptions::default()
+    };
+    if !args.ip_endpoints.is_empty() {
+        ip_options.endpoints = args.ip_endpoints.clone();
+    }
+    set_public_ip_options(ip_options);
+
+    // Handle subcommands (CLI mode)
+    if let Some(cmd) = args.command.take() {
+        return run_cli_command(cmd, &args, target_subnet.as_ref(), &scan_ports).await;
+    }
+
+    // An explicit --interval wins over the interval remembered in the config file
+    let config_path = Config::default_path();
+    let config = match &config_path {
+        Some(path) => Config::load(path).unwrap_or_else(|e| {
+            eprintln!("Warning: ignoring config file {}: {}", path.display(), e);
+            Config::default()
+        }),
+        None => Config::default(),
+    };
+    let interval_secs = args.interval.or(config.interval_secs).unwrap_or(DEFAULT_INTERVAL_SECS);
+    let interval = Duration::from_secs(clamp_interval(interval_secs));
 
     // Initialize database and get location (before TUI starts)
-    let mut app = App::new(interval, !args.manual);
+    let interface = args.interface.clone().unwrap_or_else(default_wireless_interface);
+    let mut app = App::new(interval, !args.manual)
+        .with_interface(interface)
+        .with_dry_run(args.dry_run)
+        .with_wrap_navigation(args.wrap_navigation || config.wrap_navigation)
+        .with_signal_units(args.signal_units)
+        .with_speed_units(args.speed_units)
+        .with_device_scan_interval(Duration::from_secs(args.device_interval.max(30)));
+    if let Some(path) = config_path {
+        app = app.with_config_path(path);
+    }
+    match Keymap::from_config(&config.keys) {
+        Ok(keymap) => app = app.with_keymap(keymap),
+        Err(e) => app.set_error(format!("{}; using default keys", e)),
+    }
+    if let Some(subnet) = target_subnet {
+        app = app.with_target_subnet(subnet);
+    }
+    app = app.with_scan_ports(scan_ports);
+    if let Some(dbm) = args.min_signal {
+        app = app.with_min_signal(dbm);
+    }
+    if args.adaptive {
+        app = app.with_adaptive_interval(
+            Duration::from_secs(clamp_interval(args.adaptive_min)),
+            Duration::from_secs(clamp_interval(args.adaptive_max)),
+        );
+    }
+    if let Some(count) = args.max_display {
+        app = app.with_max_display(count);
+    }
+    if let Some(profile) = args.profile {
+        app = app.with_scoring_profile(profile);
+    }
+    if let Some(path) = &args.csv_log {
+        app = app.with_csv_log(ScanLogger::new(path));
+    }
+
+    if args.once {
+        return run_once(&args, app).await;
+    }
 
     // Initialize persistence (location prompt happens here, before TUI)
     let db_info = if !args.no_persist {
-        match initialize_persistence(&args) {
+        match initialize_persistence(&args, None) {
             Ok(info) => Some(info),
             Err(e) => {
-                eprintln!("Warning: Failed to initialize database: {}", e);
-                eprintln!("Running in memory-only mode.\n");
+                if let Some(DbError::Locked { .. }) = e.downcast_ref::<DbError>() {
+                    eprintln!("Database in use by another instance; running memory-only.");
+                    eprintln!("({})\n", e);
+                } else {
+                    eprintln!("Warning: Failed to initialize database: {}", e);
+                    eprintln!("Running in memory-only mode.\n");
+                }
                 None
             }
         }
@@ -116,6 +432,7 @@ async fn main() -> Result<()> {
         app = app.with_database(db, location_id, location_name);
         // Load existing networks for this location from DB
         if let Err(e) = app.load_networks_from_db() {
+            app.log(format!("DB load warning: {}", e));
             app.status_message = Some(format!("DB load warning: {}", e));
         }
         terminal.draw(|frame| app.render(frame))?;
@@ -127,20 +444,33 @@ async fn main() -> Result<()> {
 
     app.trigger_scan();
     if let Err(e) = app.perform_scan().await {
-        app.set_error(format!("{}", e));
+        app.set_scan_error(&e);
     }
 
     // Initialize connection state (fast - no network calls now)
     if let Err(e) = app.init_connection_state() {
+        app.log(format!("Warning: {}", e));
         app.status_message = Some(format!("Warning: {}", e));
     } else {
         app.status_message = None;
     }
 
-    // Track previous selection to update cache when it changes
-    let mut prev_selected_idx = app.selected_index;
-    let mut prev_selected_mac = app.networks.get(app.selected_index).map(|n| n.mac.clone());
+    // The Swift helpers are skipped without swift; say so once rather than every scan
+    if cfg!(target_os = "macos") && !is_demo_mode() && !swift_available() {
+        app.log(SWIFT_MISSING_WARNING);
+        app.status_message = Some(SWIFT_MISSING_WARNING.to_string());
+    }
+
+    // Restore the terminal and flush the database however the loop ends
+    let result = run_event_loop(&mut terminal, &mut app, &mut events).await;
+    let restored = tui::restore();
+    app.shutdown()?;
+    restored?;
+    result
+}
 
+/// Render and dispatch events until the user quits or a termination signal arrives
+async fn run_event_loop(terminal: &mut tui::Tui, app: &mut App, events: &mut EventHandler) -> Result<()> {
     loop {
         // Render
         terminal.draw(|frame| app.render(frame))?;
@@ -148,170 +478,183 @@ async fn main() -> Result<()> {
         // Handle events
         match events.next().await? {
             Event::Key(key) => {
-                // Handle Ctrl+C
-                if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c')
+                if let Some(action) = app.handle_key(key)
+                    && !app.run_action(action)
                 {
-                    app.quit();
-                }
-
-                // Handle popup keys first
-                if app.show_connect_popup {
-                    match key.code {
-                        KeyCode::Char('y') | KeyCode::Char('Y') => {
-                            // Dismiss dialog immediately and render
-                            app.show_connect_popup = false;
-                            app.status_message = Some("Connecting...".to_string());
-                            terminal.draw(|frame| app.render(frame))?;
-
-                            // Now attempt connection
+                    // Show the dismissed dialog and status message before blocking
+                    terminal.draw(|frame| app.render(frame))?;
+                    match action {
+                        AppAction::Connect => {
                             if let Err(e) = app.do_connect() {
                                 app.set_error(format!("Connection failed: {}", e));
                             }
                         }
-                        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
-                            app.cancel_connect_dialog();
-                        }
-                        _ => {}
-                    }
-                } else if app.show_speedtest_popup {
-                    match key.code {
-                        KeyCode::Char('y') | KeyCode::Char('Y') => {
-                            // Render immediately to show "Running speed test..." message
-                            terminal.draw(|frame| app.render(frame))?;
+                        AppAction::SpeedTest => {
                             if let Err(e) = app.confirm_speedtest() {
                                 app.set_error(format!("Speed test failed: {}", e));
                             }
                         }
-                        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
-                            app.cancel_speedtest_dialog();
-                        }
-                        _ => {}
-                    }
-                } else {
-                    // Normal key handling based on current view
-                    match app.current_view {
-                        wifi_analyzer::app::AppView::WifiNetworks => {
-                            // WiFi Networks view keys
-                            match key.code {
-                                KeyCode::Char('q') | KeyCode::Esc => app.quit(),
-                                KeyCode::Tab => app.switch_view(),
-                                KeyCode::Up | KeyCode::Char('k') => app.navigate_up(),
-                                KeyCode::Down | KeyCode::Char('j') => app.navigate_down(),
-                                KeyCode::Enter => {
-                                    app.show_connect_dialog();
-                                }
-                                KeyCode::Char('r') => {
-                                    app.trigger_scan();
-                                    match app.perform_scan().await {
-                                        Ok(()) => {
-                                            app.clear_error();
-                                            let _ = app.refresh_current_connection();
-                                        }
-                                        Err(e) => app.set_error(format!("{}", e)),
-                                    }
-                                }
-                                KeyCode::Char('d') => {
-                                    enable_demo_mode();
-                                    app.clear_error();
-                                    let _ = app.perform_scan().await;
-                                }
-                                KeyCode::Char('a') => app.toggle_scan_mode(),
-                                KeyCode::Char('s') => app.cycle_sort(),
-                                KeyCode::Char('?') => app.toggle_help(),
-                                _ => {}
-                            }
-                        }
-                        wifi_analyzer::app::AppView::NetworkDevices => {
-                            // Network Devices view keys
-                            if app.show_rename_dialog {
-                                match key.code {
-                                    KeyCode::Enter => app.confirm_rename(),
-                                    KeyCode::Esc => app.cancel_rename(),
-                                    KeyCode::Backspace => app.rename_input_backspace(),
-                                    KeyCode::Char(c) => app.rename_input_char(c),
-                                    _ => {}
-                                }
-                            } else if app.device_scan_progress.is_some() {
-                                match key.code {
-                                    KeyCode::Esc => app.cancel_device_scan(),
-                                    _ => {}
-                                }
-                            } else {
-                                match key.code {
-                                    KeyCode::Char('q') | KeyCode::Esc => app.quit(),
-                                    KeyCode::Tab => app.switch_view(),
-                                    KeyCode::Up | KeyCode::Char('k') => app.device_navigate_up(),
-                                    KeyCode::Down | KeyCode::Char('j') => app.device_navigate_down(),
-                                    KeyCode::Enter => app.toggle_device_detail(),
-                                    KeyCode::Char('s') | KeyCode::Char('S') => app.start_device_scan(),
-                                    KeyCode::Char('r') | KeyCode::Char('R') => app.start_rename_device(),
-                                    KeyCode::Char('?') => app.toggle_help(),
-                                    _ => {}
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-            Event::Tick => {
-                // Check for background speed test completion
-                app.check_speedtest_result();
-
-                // Check for device scan progress
-                app.check_device_scan_progress();
-
-                // Check for auto-scan
-                if app.should_scan() {
-                    match app.perform_scan().await {
-                        Ok(()) => {
-                            app.clear_error();
-                            let _ = app.refresh_current_connection();
-                        }
-                        Err(e) => app.set_error(format!("{}", e)),
+                        // Already started by run_action
+                        AppAction::Scan | AppAction::EnableDemo => {}
                     }
                 }
             }
+            Event::Tick => app.handle_tick(),
+            Event::Quit => app.quit(),
             Event::Resize(_, _) => {
                 // Terminal will handle resize on next draw
             }
         }
 
         // Update connection data cache if selection changed
-        let current_mac = app.networks.get(app.selected_index).map(|n| n.mac.clone());
-        if app.selected_index != prev_selected_idx || current_mac != prev_selected_mac {
-            app.clear_connection_cache();
-
-            // Load connection data for newly selected network
-            if let Some(ref mac) = current_mac {
-                let _ = app.get_connection_history(mac);
-                let _ = app.get_recent_ips(mac);
-            }
-
-            prev_selected_idx = app.selected_index;
-            prev_selected_mac = current_mac;
-        }
+        app.sync_selection_cache();
 
         if app.should_quit {
             break;
         }
     }
 
-    tui::restore()?;
     Ok(())
 }
 
-/// Initialize database persistence and get location
-fn initialize_persistence(args: &Args) -> Result<(Database, i64, String)> {
+/// Scan once and print the networks ranked by score, for cron and scripts
+async fn run_once(args: &Args, mut app: App) -> Result<()> {
+    // Never prompt for a location: there may be no one to answer
+    if !args.no_persist {
+        if args.location.is_some() {
+            match initialize_persistence(args, None) {
+                Ok((db, location_id, location_name)) => {
+                    app = app.with_database(db, location_id, location_name);
+                }
+                Err(e) => eprintln!("Warning: Failed to initialize database: {}", e),
+            }
+        } else {
+            eprintln!("Note: scan not saved; pass --location to record it");
+        }
+    }
+
+    app.perform_scan().await?;
+
+    println!(
+        "{:>4}  {:<32}  {:<17}  {:>7}  {:>7}  {:<10}  {:>5}",
+        "Rank", "SSID", "BSSID", "Channel", "dBm", "Security", "Score"
+    );
+    // perform_scan leaves the networks sorted by score, best first
+    for (idx, network) in app.networks.iter().enumerate() {
+        println!(
+            "{:>4}  {:<32}  {:<17}  {:>7}  {:>7}  {:<10}  {:>5}",
+            idx + 1,
+            network.ssid,
+            network.mac,
+            network.channel,
+            network.signal_dbm,
+            network.security.to_string(),
+            network.score
+        );
+    }
+
+    match app.networks.first() {
+        Some(best) => println!("Best: {} (score {})", best.ssid, best.score),
+        None => println!("Best: none"),
+    }
+
+    app.shutdown()
+}
+
+/// Scan on an interval and persist every result, without a TUI, until
+/// Ctrl+C (or SIGTERM/SIGHUP); for unattended logging on a headless box
+async fn run_watch(args: &Args, location: &str, interval: Duration) -> Result<()> {
+    let (db, location_id, location_name) = initialize_persistence(args, Some(location))?;
+    let interface = args.interface.clone().unwrap_or_else(default_wireless_interface);
+    let mut app = App::new(interval, true)
+        .with_interface(interface)
+        .with_database(db, location_id, location_name.clone());
+    if let Some(path) = &args.csv_log {
+        app = app.with_csv_log(ScanLogger::new(path));
+    }
+
+    println!(
+        "Watching every {}s at \"{}\" ({} profile), saving to {} (Ctrl+C to stop)",
+        interval.as_secs(),
+        location_name,
+        app.scoring_profile.name(),
+        args.db_path.display()
+    );
+
+    let signal = wait_for_signal();
+    tokio::pin!(signal);
+    loop {
+        tokio::select! {
+            _ = &mut signal => break,
+            result = app.perform_scan() => {
+                let now = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
+                match result {
+                    Ok(()) => {
+                        let best = match app.networks.first() {
+                            Some(best) => format!("best {} ({}, {} dBm)", best.ssid, best.score, best.signal_dbm),
+                            None => "best none".to_string(),
+                        };
+                        println!("{}  {:>3} networks  {}", now, app.networks.len(), best);
+                    }
+                    // Keep watching: the adapter may come back
+                    Err(e) => eprintln!("{}  scan failed: {}", now, e),
+                }
+            }
+        }
+
+        tokio::select! {
+            _ = &mut signal => break,
+            _ = tokio::time::sleep(interval) => {}
+        }
+    }
+
+    println!("Stopping");
+    app.shutdown()
+}
+
+/// Print the doctor checklist; fails if anything critical is missing
+async fn run_doctor(args: &Args) -> Result<()> {
+    println!("=== WiFi Analyzer Doctor ===\n");
+    let checks = run_checks(&args.db_path, args.interface.as_deref()).await;
+    for check in &checks {
+        println!("{}", check.render());
+    }
+    println!();
+    match critical_failures(&checks) {
+        0 => {
+            println!("Everything needed is in place.");
+            Ok(())
+        }
+        n => Err(color_eyre::eyre::eyre!("{} critical check(s) failed", n)),
+    }
+}
+
+/// Initialize database persistence and get location. `location` overrides
+/// --location; without either, the user is prompted.
+fn initialize_persistence(args: &Args, location: Option<&str>) -> Result<(Database, i64, String)> {
     let db = Database::open(&args.db_path)?;
 
+    // Prune old scans to bound database growth
+    if let Some(days) = args.retention_days {
+        let removed = db.prune_scans_older_than(days)?;
+        if removed > 0 {
+            db.vacuum()?;
+            // stderr, so --once output stays parseable
+            eprintln!("Pruned {} scans older than {} days", removed, days);
+        }
+    }
+
     // Get location name from CLI arg or prompt user
-    let location_name = if let Some(ref name) = args.location {
-        name.clone()
+    let location_name = if let Some(name) = location.or(args.location.as_deref()) {
+        name.to_string()
     } else {
         prompt_for_location(&db)?
     };
 
     let location_id = db.create_or_get_location(&location_name)?;
+    if let Some(profile) = args.profile {
+        db.set_location_profile(location_id, profile)?;
+    }
 
     Ok((db, location_id, location_name))
 }
@@ -325,7 +668,10 @@ fn prompt_for_location(db: &Database) -> Result<String> {
     if !locations.is_empty() {
         println!("Existing locations:");
         for (i, loc) in locations.iter().enumerate() {
-            println!("  {}. {}", i + 1, loc.name);
+            match loc.profile {
+                Some(profile) => println!("  {}. {} ({} profile)", i + 1, loc.name, profile.name()),
+                None => println!("  {}. {}", i + 1, loc.name),
+            }
         }
         println!();
     }
@@ -348,18 +694,41 @@ fn prompt_for_location(db: &Database) -> Result<String> {
 }
 
 /// Run CLI commands (non-TUI mode)
-async fn run_cli_command(cmd: Command) -> Result<()> {
+async fn run_cli_command(cmd: Command, args: &Args, target_subnet: Option<&IpNetwork>, ports: &[u16]) -> Result<()> {
     use wifi_analyzer::network_map::{
-        discover_devices_with_options, identify_device, scan_devices_ports,
-        Device, ScanPhase, ScanProgress, COMMON_PORTS,
+        discover_and_scan_devices, discover_devices_with_options, identify_device, scan_devices_ports,
+        PingSweepConfig, PortScanConfig, ScanPhase, ScanProgress, UDP_PORTS,
     };
+    let db_path = args.db_path.as_path();
+    let speed_units = args.speed_units;
 
     match cmd {
-        Command::ScanDevices { verbose, full } => {
-            println!("=== Network Device Scanner{} ===\n", if full { " (Full)" } else { "" });
+        Command::ScanDevices { verbose, full, ping_concurrency, port_timeout, concurrency, udp, json, redact, salt } => {
+            // With --json, stdout carries only device objects; progress goes to stderr
+            macro_rules! status {
+                ($($arg:tt)*) => {
+                    if json { eprintln!($($arg)*) } else { println!($($arg)*) }
+                };
+            }
+
+            let sweep_config = PingSweepConfig {
+                concurrency: ping_concurrency,
+                ..PingSweepConfig::default()
+            };
+            let scan_config = PortScanConfig {
+                connect_timeout: Duration::from_millis(port_timeout),
+                max_concurrent_ports: concurrency,
+                include_udp: udp,
+                ..PortScanConfig::default()
+            };
+
+            status!("=== Network Device Scanner{} ===\n", if full { " (Full)" } else { "" });
 
             // Phase 1: Discovery
-            println!("[1/3] Discovering devices{}...", if full { " (with ping sweep)" } else { "" });
+            status!(
+                "[1/3] Discovering devices{}...",
+                if full { " (with ping sweep, port scanning hosts as they reply)" } else { "" }
+            );
             let (progress_tx, mut progress_rx) = tokio::sync::mpsc::channel::<ScanProgress>(10);
 
             // Spawn progress printer
@@ -368,51 +737,76 @@ async fn run_cli_command(cmd: Command) -> Result<()> {
                 while let Some(progress) = progress_rx.recv().await {
                     if verbose_clone {
                         match progress.phase {
+                            ScanPhase::Discovery if progress.total_ports > 0 => {
+                                status!(
+                                    "  Ping sweep: {}/{} addresses, {} replied",
+                                    progress.ports_scanned, progress.total_ports, progress.devices_found
+                                );
+                            }
                             ScanPhase::Discovery => {
-                                println!("  Discovery: {} devices found", progress.devices_found);
+                                status!("  Discovery: {} devices found", progress.devices_found);
                             }
                             ScanPhase::PortScan => {
                                 if let Some(ref dev) = progress.current_device {
-                                    println!(
-                                        "  Port scan: {} ({}/{})",
-                                        dev, progress.ports_scanned, progress.total_ports
+                                    let overall = progress.percent.map(|p| format!(", {}% overall", p)).unwrap_or_default();
+                                    status!(
+                                        "  Port scan: {} ({}/{}{})",
+                                        dev, progress.ports_scanned, progress.total_ports, overall
                                     );
                                 }
                             }
                             ScanPhase::Identification => {
-                                println!("  Identifying {} devices...", progress.devices_found);
+                                status!("  Identifying {} devices...", progress.devices_found);
                             }
                             ScanPhase::Complete => {
-                                println!("  Complete!");
+                                status!("  Complete!");
                             }
                         }
                     }
                 }
             });
 
-            let mut devices = match discover_devices_with_options(Some(progress_tx.clone()), full).await {
+            let discovered = if full {
+                let cancel = AtomicBool::new(false);
+                discover_and_scan_devices(
+                    Some(progress_tx.clone()),
+                    &sweep_config,
+                    target_subnet,
+                    ports,
+                    &scan_config,
+                    &cancel,
+                )
+                .await
+            } else {
+                discover_devices_with_options(Some(progress_tx.clone()), None, target_subnet).await
+            };
+            let mut devices = match discovered {
                 Ok(d) => d,
                 Err(e) => {
                     eprintln!("Discovery error: {}", e);
                     return Ok(());
                 }
             };
-            println!("  Found {} devices\n", devices.len());
+            status!("  Found {} devices\n", devices.len());
 
             if devices.is_empty() {
-                println!("No devices found. Make sure you're connected to a network.");
+                status!("No devices found. Make sure you're connected to a network.");
                 return Ok(());
             }
 
-            // Phase 2: Port scanning
-            println!("[2/3] Scanning ports on {} devices...", devices.len());
-            if let Err(e) = scan_devices_ports(&mut devices, Some(progress_tx.clone())).await {
-                eprintln!("Port scan error: {}", e);
+            // Phase 2: Port scanning (already done during a full discovery)
+            if full {
+                status!("[2/3] Ports scanned during discovery\n");
+            } else {
+                status!("[2/3] Scanning ports on {} devices...", devices.len());
+                if let Err(e) = scan_devices_ports(&mut devices, ports, Some(progress_tx.clone()), &scan_config).await {
+                    eprintln!("Port scan error: {}", e);
+                }
+                status!("  Port scan complete\n");
             }
-            println!("  Port scan complete\n");
 
             // Phase 3: Identification
-            println!("[3/3] Identifying devices...");
+            status!("[3/3] Identifying devices...");
             let _ = progress_tx
                 .send(ScanProgress {
                     phase: ScanPhase::Identification,
@@ -420,22 +814,33 @@ async fn run_cli_command(cmd: Command) -> Result<()> {
                     current_device: None,
                     ports_scanned: 0,
                     total_ports: 0,
+                    percent: None,
                 })
                 .await;
 
             let device_count = devices.len();
             for (i, device) in devices.iter_mut().enumerate() {
                 if verbose {
-                    println!("  Identifying device {}/{}: {}", i + 1, device_count, device.ip_address);
+                    status!("  Identifying device {}/{}: {}", i + 1, device_count, device.ip_address);
                 }
                 identify_device(device);
+                if json && redact {
+                    println!("{}", serde_json::to_string(&redact_device(device, &salt))?);
+                } else if json {
+                    println!("{}", serde_json::to_string(device)?);
+                }
             }
-            println!("  Identification complete\n");
+            status!("  Identification complete\n");
 
             // Close progress channel
             drop(progress_tx);
             let _ = progress_handle.await;
 
+            if json {
+                eprintln!("Total: {} devices", devices.len());
+                return Ok(());
+            }
+
             // Print results
             println!("=== Results ===\n");
             for device in &devices {
@@ -466,7 +871,7 @@ async fn run_cli_command(cmd: Command) -> Result<()> {
                         println!(
                             "  └─ :{:<5} {}{}",
                             svc.port,
-                            svc.service_name.as_deref().unwrap_or("unknown"),
+                            svc.display_name().unwrap_or("unknown"),
                             agent_str
                         );
                     }
@@ -485,14 +890,19 @@ async fn run_cli_command(cmd: Command) -> Result<()> {
                     );
                 }
             }
+            print_ip_conflicts(&devices);
 
             println!("\nTotal: {} devices", devices.len());
         }
 
-        Command::Discover { full } => {
+        Command::Discover { full, ping_concurrency } => {
             println!("=== Device Discovery{} ===\n", if full { " (Full Sweep)" } else { " (ARP only)" });
 
-            let devices = match discover_devices_with_options(None, full).await {
+            let sweep_config = PingSweepConfig {
+                concurrency: ping_concurrency,
+                ..PingSweepConfig::default()
+            };
+            let devices = match discover_devices_with_options(None, full.then_some(&sweep_config), target_subnet).await {
                 Ok(d) => d,
                 Err(e) => {
                     eprintln!("Discovery error: {}", e);
@@ -510,18 +920,27 @@ async fn run_cli_command(cmd: Command) -> Result<()> {
                     name
                 );
             }
+            print_ip_conflicts(&devices);
         }
 
-        Command::ScanPorts { ip } => {
+        Command::ScanPorts { ip, udp } => {
             println!("=== Port Scan: {} ===\n", ip);
 
             let mut device = Device::new("00:00:00:00:00:00".to_string(), ip.clone());
+            let scan_config = PortScanConfig {
+                include_udp: udp,
+                ..PortScanConfig::default()
+            };
 
-            println!("Scanning {} common ports...", COMMON_PORTS.len());
+            if udp {
+                println!("Scanning {} TCP ports and {} UDP ports...", ports.len(), UDP_PORTS.len());
+            } else {
+                println!("Scanning {} TCP ports...", ports.len());
+            }
 
             // Create a single-device vec for scanning
             let mut devices = vec![device];
-            if let Err(e) = scan_devices_ports(&mut devices, None).await {
+            if let Err(e) = scan_devices_ports(&mut devices, ports, None, &scan_config).await {
                 eprintln!("Port scan error: {}", e);
                 return Ok(());
             }
@@ -554,7 +973,7 @@ async fn run_cli_command(cmd: Command) -> Result<()> {
                         "  :{:<5} {} {}{}{}",
                         svc.port,
                         svc.protocol,
-                        svc.service_name.as_deref().unwrap_or("unknown"),
+                        svc.display_name().unwrap_or("unknown"),
                         agent_str,
                         banner_str
                     );
@@ -565,7 +984,243 @@ async fn run_cli_command(cmd: Command) -> Result<()> {
                 println!("\nAI Agents detected: {:?}", device.detected_agents);
             }
         }
+
+        Command::History { ssid } => {
+            println!("=== Connection History: {} ===\n", ssid);
+
+            let db = Database::open(db_path)?;
+            if db.get_network_ids_by_ssid(&ssid)?.is_empty() {
+                println!("Network \"{}\" not found in {}", ssid, db_path.display());
+                return Ok(());
+            }
+
+            // Every BSSID of the SSID, so roaming between access points keeps the history whole
+            let history = db.get_ssid_connection_history(&ssid)?;
+            let total = history.len();
+            if history.is_empty() {
+                println!("No connections recorded.");
+                return Ok(());
+            }
+
+            for conn in &history {
+                let speed = match (conn.download_mbps, conn.upload_mbps, conn.latency_ms) {
+                    (Some(down), Some(up), Some(ms)) => format!(
+                        "↓{} ↑{}  {:.0} ms",
+                        format_speed(down, speed_units),
+                        format_speed(up, speed_units),
+                        ms
+                    ),
+                    (Some(down), Some(up), None) => {
+                        format!("↓{} ↑{}", format_speed(down, speed_units), format_speed(up, speed_units))
+                    }
+                    _ => "-".to_string(),
+                };
+                println!(
+                    "  {}  local {:<15}  public {:<15}  {}",
+                    conn.connected_at.format("%Y-%m-%d %H:%M"),
+                    conn.local_ip.as_deref().unwrap_or("-"),
+                    conn.public_ip.as_deref().unwrap_or("-"),
+                    speed
+                );
+                if conn.local_ipv6.is_some() || conn.public_ipv6.is_some() {
+                    println!(
+                        "                    local v6 {}  public v6 {}",
+                        conn.local_ipv6.as_deref().unwrap_or("-"),
+                        conn.public_ipv6.as_deref().unwrap_or("-")
+                    );
+                }
+            }
+
+            println!("\nTotal connections: {}", total);
+
+            let tested: Vec<_> = history
+                .iter()
+                .filter_map(|c| Some((c, c.download_mbps?, c.upload_mbps?)))
+                .collect();
+            if tested.is_empty() {
+                println!("No speed tests recorded.");
+            } else {
+                let n = tested.len() as f64;
+                let avg_down = tested.iter().map(|(_, d, _)| d).sum::<f64>() / n;
+                let avg_up = tested.iter().map(|(_, _, u)| u).sum::<f64>() / n;
+                println!(
+                    "Average speed:     ↓{} ↑{} ({} tests)",
+                    format_speed(avg_down, speed_units),
+                    format_speed(avg_up, speed_units),
+                    tested.len()
+                );
+
+                let by_download = |a: &&(_, f64, f64), b: &&(_, f64, f64)| a.1.total_cmp(&b.1);
+                if let (Some(best), Some(worst)) = (
+                    tested.iter().max_by(by_download),
+                    tested.iter().min_by(by_download),
+                ) {
+                    println!(
+                        "Best:              ↓{} ↑{} on {}",
+                        format_speed(best.1, speed_units),
+                        format_speed(best.2, speed_units),
+                        best.0.connected_at.format("%Y-%m-%d %H:%M")
+                    );
+                    println!(
+                        "Worst:             ↓{} ↑{} on {}",
+                        format_speed(worst.1, speed_units),
+                        format_speed(worst.2, speed_units),
+                        worst.0.connected_at.format("%Y-%m-%d %H:%M")
+                    );
+                }
+            }
+        }
+
+        Command::Compare { ssid } => {
+            println!("=== Reception by Location: {} ===\n", ssid);
+
+            let db = Database::open(db_path)?;
+            let comparison = db.compare_network_across_locations(&ssid)?;
+            if comparison.is_empty() {
+                println!("Network \"{}\" has not been scanned at any location", ssid);
+                return Ok(());
+            }
+
+            println!(
+                "  {:<20} {:>10} {:>11} {:>9} {:>10} {:>6}",
+                "Location", "Avg dBm", "Last dBm", "Avg score", "Last score", "Scans"
+            );
+            // Sorted best average score first
+            for (idx, row) in comparison.iter().enumerate() {
+                println!(
+                    "  {:<20} {:>10.1} {:>11} {:>9.1} {:>10} {:>6}{}",
+                    row.location,
+                    row.avg_signal_dbm,
+                    row.last_signal_dbm,
+                    row.avg_score,
+                    row.last_score,
+                    row.times_seen,
+                    if idx == 0 && comparison.len() > 1 { "  ★ best" } else { "" }
+                );
+            }
+
+            if let [best, .., worst] = comparison.as_slice() {
+                println!(
+                    "\nBest reception at \"{}\" ({:+.1} dBm, {:+.1} score vs \"{}\")",
+                    best.location,
+                    best.avg_signal_dbm - worst.avg_signal_dbm,
+                    best.avg_score - worst.avg_score,
+                    worst.location
+                );
+            }
+        }
+        Command::AllLocations => {
+            let db = Database::open(db_path)?;
+            let networks = db.load_all_networks_latest()?;
+            if networks.is_empty() {
+                println!("No scans recorded yet");
+                return Ok(());
+            }
+            println!("=== Networks Across All Locations ({}) ===\n", networks.len());
+
+            println!(
+                "  {:<24} {:<17} {:>4} {:>9} {:>9}  {:<20} {:>9}",
+                "SSID", "BSSID", "Ch", "Last dBm", "Best dBm", "Best location", "Locations"
+            );
+            for global in &networks {
+                let network = &global.network;
+                println!(
+                    "  {:<24} {:<17} {:>4} {:>9} {:>9}  {:<20} {:>9}",
+                    network.ssid,
+                    network.bssid,
+                    network.channel,
+                    network.signal_dbm,
+                    global.best_signal_dbm,
+                    global.best_location,
+                    global.locations_seen
+                );
+            }
+        }
+        Command::Recommend => {
+            use wifi_analyzer::scanner::{scan_networks, FrequencyBand};
+            use wifi_analyzer::scoring::{channel_interference, recommend_channel, recommendation_summary};
+
+            let networks = scan_networks().await?;
+            println!("=== Channel Recommendation ({} networks nearby) ===\n", networks.len());
+
+            for band in [FrequencyBand::Band2_4GHz, FrequencyBand::Band5GHz] {
+                let channels = recommend_channel(&networks, band);
+                let Some(&best) = channels.first() else {
+                    continue;
+                };
+                let list: Vec<String> = channels.iter().map(|c| c.to_string()).collect();
+                println!(
+                    "  {:<8} ch {:<20} (interference {:.1})",
+                    band.to_string(),
+                    list.join(", "),
+                    channel_interference(best, &networks)
+                );
+            }
+
+            println!("\n{}", recommendation_summary(&networks));
+        }
+
+        Command::Watch { interval, location } => {
+            run_watch(args, &location, Duration::from_secs(interval.max(5))).await?;
+        }
+
+        Command::Doctor => run_doctor(args).await?,
+
+        Command::Snapshot { out, redact, salt } => {
+            let db = Database::open(db_path)?;
+            let mut snapshot = db.snapshot()?;
+            if redact {
+                redact_snapshot(&mut snapshot, &salt);
+            }
+            snapshot.write(&out)?;
+            print_table_counts(&snapshot.row_counts());
+            let note = if redact { " (redacted)" } else { "" };
+            println!("\nWrote {} to {}{}", db_path.display(), out.display(), note);
+        }
+
+        Command::Bench { iterations } => {
+            let iterations = iterations.max(1);
+            let timings = wifi_analyzer::bench::run(iterations);
+            println!("=== Benchmark ({} iterations on demo data) ===\n", iterations);
+            println!("  {:<6} {:>10} {:>10} {:>10} {:>10}", "Stage", "min ms", "median ms", "p95 ms", "max ms");
+            for (stage, samples) in timings.stages() {
+                if let Some(p) = wifi_analyzer::bench::Percentiles::of(samples) {
+                    println!(
+                        "  {:<6} {:>10.3} {:>10.3} {:>10.3} {:>10.3}",
+                        stage, p.min, p.median, p.p95, p.max
+                    );
+                }
+            }
+        }
+        Command::Restore { input } => {
+            let db = Database::open(db_path)?;
+            let counts = db.import_snapshot(&input)?;
+            db.checkpoint()?;
+            print_table_counts(&counts);
+            println!("\nRestored {} into {}", input.display(), db_path.display());
+        }
     }
 
     Ok(())
 }
+
+/// List devices the ARP cache gave contradictory addresses, if any
+fn print_ip_conflicts(devices: &[Device]) {
+    let conflicted: Vec<_> = devices.iter().filter_map(|d| d.ip_conflict.as_ref().map(|c| (d, c))).collect();
+    if conflicted.is_empty() {
+        return;
+    }
+    println!("\n=== \u{26a0} IP Conflicts ===");
+    for (device, conflict) in conflicted {
+        for explanation in conflict.explanations(&device.ip_address) {
+            println!("  {} ({}): {}", device.ip_address, device.mac_address, explanation);
+        }
+    }
+}
+
+/// Rows per table, for the snapshot and restore subcommands
+fn print_table_counts(counts: &[(String, usize)]) {
+    for (table, rows) in counts {
+        println!("  {:<16} {:>8} rows", table, rows);
+    }
+}
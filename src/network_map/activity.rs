@@ -0,0 +1,123 @@
+//! "Last active" times from repeated ARP cache snapshots.
+//!
+//! The kernel only resolves an address again after its cache entry has
+//! expired, so an entry that appears between two snapshots (or a MAC that
+//! shows up at a new IP) means the device exchanged traffic in between.
+//! That's a different signal from being found by a device scan, which
+//! only proves the device answered when asked - and the scan's own pings
+//! refresh entries too, so snapshots overlapping one only reset the baseline.
+
+use crate::mac::canonical_mac;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+
+/// Activity seen across ARP snapshots, keyed by canonical MAC
+#[derive(Debug, Clone, Default)]
+pub struct ArpActivity {
+    /// (MAC, IP) pairs in the latest snapshot
+    entries: HashSet<(String, String)>,
+    last_active: HashMap<String, DateTime<Utc>>,
+    /// The first snapshot only sets the baseline: an entry already cached
+    /// says nothing about when it was last refreshed
+    primed: bool,
+}
+
+impl ArpActivity {
+    /// Record a snapshot of (IP, MAC) pairs taken at `at`
+    pub fn record_snapshot(&mut self, entries: &[(String, String)], at: DateTime<Utc>) {
+        let current: HashSet<(String, String)> = entries
+            .iter()
+            .map(|(ip, mac)| (canonical_mac(mac), ip.clone()))
+            .collect();
+        if self.primed {
+            for (mac, _) in current.difference(&self.entries) {
+                self.last_active.insert(mac.clone(), at);
+            }
+        }
+        self.entries = current;
+        self.primed = true;
+    }
+
+    /// Record a snapshot taken while our own pings or scans may have
+    /// refreshed entries: it becomes the new baseline without marking
+    /// anything active
+    pub fn record_baseline(&mut self, entries: &[(String, String)]) {
+        self.entries = entries.iter().map(|(ip, mac)| (canonical_mac(mac), ip.clone())).collect();
+        self.primed = true;
+    }
+
+    /// When the device's ARP entry last refreshed, if it has since polling began
+    pub fn last_active(&self, mac: &str) -> Option<DateTime<Utc>> {
+        self.last_active.get(&canonical_mac(mac)).copied()
+    }
+
+    /// Whether the MAC is in the latest snapshot
+    pub fn in_cache(&self, mac: &str) -> bool {
+        let mac = canonical_mac(mac);
+        self.entries.iter().any(|(cached, _)| *cached == mac)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn snapshot(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(ip, mac)| (ip.to_string(), mac.to_string())).collect()
+    }
+
+    #[test]
+    fn test_refreshed_entries_mark_activity() {
+        let start = Utc::now();
+        let mut activity = ArpActivity::default();
+
+        // Already cached when polling starts: present, but no activity time yet
+        activity.record_snapshot(
+            &snapshot(&[("192.168.1.1", "aa:bb:cc:00:00:01"), ("192.168.1.20", "aa:bb:cc:00:00:02")]),
+            start,
+        );
+        assert!(activity.in_cache("AA:BB:CC:00:00:01"));
+        assert_eq!(activity.last_active("AA:BB:CC:00:00:01"), None);
+
+        // The phone's entry expires...
+        activity.record_snapshot(&snapshot(&[("192.168.1.1", "aa:bb:cc:00:00:01")]), start + Duration::minutes(1));
+        assert!(!activity.in_cache("AA:BB:CC:00:00:02"));
+        assert_eq!(activity.last_active("AA:BB:CC:00:00:02"), None);
+
+        // ...and comes back once it talks again; the router's entry never changed
+        let refreshed = start + Duration::minutes(2);
+        activity.record_snapshot(
+            &snapshot(&[("192.168.1.1", "aa:bb:cc:00:00:01"), ("192.168.1.20", "aa:bb:cc:0:0:2")]),
+            refreshed,
+        );
+        assert_eq!(activity.last_active("AA:BB:CC:00:00:02"), Some(refreshed));
+        assert_eq!(activity.last_active("AA:BB:CC:00:00:01"), None);
+
+        // A new IP for a known MAC counts too
+        let moved = start + Duration::minutes(3);
+        activity.record_snapshot(
+            &snapshot(&[("192.168.1.2", "aa:bb:cc:00:00:01"), ("192.168.1.20", "aa:bb:cc:00:00:02")]),
+            moved,
+        );
+        assert_eq!(activity.last_active("aa:bb:cc:00:00:01"), Some(moved));
+        assert_eq!(activity.last_active("AA:BB:CC:00:00:02"), Some(refreshed));
+    }
+
+    #[test]
+    fn test_own_probes_only_reset_the_baseline() {
+        let start = Utc::now();
+        let mut activity = ArpActivity::default();
+        activity.record_snapshot(&snapshot(&[("192.168.1.1", "aa:bb:cc:00:00:01")]), start);
+
+        // A liveness ping re-resolved the idle TV's expired entry
+        let pinged = snapshot(&[("192.168.1.1", "aa:bb:cc:00:00:01"), ("192.168.1.30", "aa:bb:cc:00:00:03")]);
+        activity.record_baseline(&pinged);
+        assert!(activity.in_cache("AA:BB:CC:00:00:03"));
+        assert_eq!(activity.last_active("AA:BB:CC:00:00:03"), None);
+
+        // Unchanged afterwards: still nothing to report
+        activity.record_snapshot(&pinged, start + Duration::minutes(1));
+        assert_eq!(activity.last_active("AA:BB:CC:00:00:03"), None);
+    }
+}
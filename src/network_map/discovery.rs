@@ -1,21 +1,65 @@
-use crate::network_map::{Device, ScanPhase, ScanProgress};
+use crate::mac::canonical_mac;
+use crate::network_map::{Device, IpConflict, ScanPhase, ScanProgress};
 use color_eyre::Result;
 use ipnetwork::IpNetwork;
+use std::collections::{BTreeSet, HashMap};
 use std::net::IpAddr;
 use std::process::Command;
+use std::time::Duration;
 use tokio::sync::mpsc;
 
+const PING_CONCURRENCY: usize = 50;
+/// Longer than ping's own one-second `-W 1` wait, so a probe is only cut
+/// short when ping itself hangs
+const PING_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Largest subnet (in sweepable hosts) scanned without `--force`; a /20
+pub const MAX_SWEEP_HOSTS: u128 = 4094;
+
+/// Tuning knobs for the ping sweep
+#[derive(Debug, Clone, Copy)]
+pub struct PingSweepConfig {
+    /// Hosts pinged in parallel
+    pub concurrency: usize,
+    /// How long to wait for each reply
+    pub timeout: Duration,
+}
+
+impl Default for PingSweepConfig {
+    fn default() -> Self {
+        Self {
+            concurrency: PING_CONCURRENCY,
+            timeout: PING_TIMEOUT,
+        }
+    }
+}
+
 /// Discover devices on the local network using ARP cache
 pub async fn discover_devices(
     progress_tx: Option<mpsc::Sender<ScanProgress>>,
 ) -> Result<Vec<Device>> {
-    discover_devices_with_options(progress_tx, false).await
+    discover_devices_with_options(progress_tx, None, None).await
 }
 
-/// Discover devices with optional ping sweep
+/// Discover devices, optionally ping sweeping the subnet first so hosts
+/// missing from the ARP cache are found too. `target_subnet` replaces the
+/// /24 around the local IP and limits results to addresses inside it.
 pub async fn discover_devices_with_options(
     progress_tx: Option<mpsc::Sender<ScanProgress>>,
-    do_ping_sweep: bool,
+    sweep: Option<&PingSweepConfig>,
+    target_subnet: Option<&IpNetwork>,
+) -> Result<Vec<Device>> {
+    discover_devices_streaming(progress_tx, sweep, target_subnet, None).await
+}
+
+/// `discover_devices_with_options`, also sending the IP of every host that
+/// answers the ping sweep through `responders` as soon as it replies, so
+/// it can be port scanned before discovery finishes
+pub async fn discover_devices_streaming(
+    progress_tx: Option<mpsc::Sender<ScanProgress>>,
+    sweep: Option<&PingSweepConfig>,
+    target_subnet: Option<&IpNetwork>,
+    responders: Option<mpsc::UnboundedSender<String>>,
 ) -> Result<Vec<Device>> {
     if let Some(ref tx) = progress_tx {
         let _ = tx.send(ScanProgress {
@@ -24,31 +68,52 @@ pub async fn discover_devices_with_options(
             current_device: None,
             ports_scanned: 0,
             total_ports: 0,
+            percent: None,
         }).await;
     }
 
-    let (local_ip, subnet) = get_local_network_info()?;
+    let (local_ip, local_subnet) = get_local_network_info()?;
+    let subnet = target_subnet.copied().unwrap_or(local_subnet);
 
     // Optional ping sweep to populate ARP cache with all active devices
-    if do_ping_sweep {
-        eprintln!("  Ping sweep on {} (this may take a moment)...", subnet);
-        ping_sweep(&subnet).await?;
-    }
+    let ttls = match sweep {
+        Some(config) => ping_sweep_streaming(&subnet, config, progress_tx.as_ref(), responders.as_ref()).await?,
+        None => HashMap::new(),
+    };
+
+    // Read the ARP cache after the sweep so newly-responsive hosts are included
     let mut devices = parse_arp_cache()?;
+    if target_subnet.is_some() {
+        devices.retain(|d| d.ip_address.parse().is_ok_and(|ip| subnet.contains(ip)));
+    }
 
-    if let Some(gateway) = get_default_gateway()? {
-        if !devices.iter().any(|d| d.ip_address == gateway) {
-            let gateway_mac = get_mac_for_ip(&gateway).unwrap_or_else(|| "00:00:00:00:00:00".to_string());
-            let mut gw_device = Device::new(gateway_mac, gateway);
-            gw_device.device_type = crate::network_map::DeviceType::Router;
-            devices.push(gw_device);
+    // A responder can still be missing from the listing (e.g. its entry
+    // landed after `arp -a` ran), so look those up individually
+    let mut responders: Vec<&String> = ttls.keys().collect();
+    responders.sort();
+    for ip in responders {
+        if devices.iter().any(|d| &d.ip_address == ip) {
+            continue;
         }
+        if let Some(mac) = get_mac_for_ip(ip)
+            && mac != "(incomplete)"
+            && !devices.iter().any(|d| d.mac_address.eq_ignore_ascii_case(&mac))
+        {
+            devices.push(Device::new(mac.to_uppercase(), ip.clone()));
+        }
+    }
+
+    if let Some(gateway) = gateway_device()?
+        && !devices.iter().any(|d| d.ip_address == gateway.ip_address)
+    {
+        devices.push(gateway);
     }
 
     for device in &mut devices {
         if device.ip_address == local_ip {
             device.hostname = Some("This device".to_string());
         }
+        device.ttl = ttls.get(&device.ip_address).copied();
     }
 
     if let Some(ref tx) = progress_tx {
@@ -58,12 +123,44 @@ pub async fn discover_devices_with_options(
             current_device: None,
             ports_scanned: 0,
             total_ports: 0,
+            percent: None,
         }).await;
     }
 
     Ok(devices)
 }
 
+/// Parse a user-supplied CIDR for discovery, refusing ranges with more than
+/// `MAX_SWEEP_HOSTS` addresses unless `force` is set
+pub fn parse_target_subnet(cidr: &str, force: bool) -> Result<IpNetwork> {
+    let subnet: IpNetwork = cidr
+        .trim()
+        .parse()
+        .map_err(|e| color_eyre::eyre::eyre!("Invalid subnet '{}': {}", cidr, e))?;
+    let hosts = sweep_host_count(&subnet);
+    if hosts > MAX_SWEEP_HOSTS && !force {
+        return Err(color_eyre::eyre::eyre!(
+            "Subnet {} has {} hosts, more than the {} allowed (pass --force to sweep it anyway)",
+            subnet,
+            hosts,
+            MAX_SWEEP_HOSTS
+        ));
+    }
+    Ok(subnet)
+}
+
+/// Number of addresses a ping sweep of the subnet covers (excluding the
+/// IPv4 network and broadcast addresses), without listing them
+pub fn sweep_host_count(subnet: &IpNetwork) -> u128 {
+    match subnet {
+        IpNetwork::V4(net) => {
+            let size = 1u128 << (32 - net.prefix());
+            if net.prefix() < 31 { size - 2 } else { size }
+        }
+        IpNetwork::V6(net) => 1u128.checked_shl(128 - u32::from(net.prefix())).unwrap_or(u128::MAX),
+    }
+}
+
 fn get_local_network_info() -> Result<(String, IpNetwork)> {
     let local_ip = local_ip_address::local_ip()
         .map_err(|e| color_eyre::eyre::eyre!("Failed to get local IP: {}", e))?;
@@ -79,20 +176,39 @@ fn parse_arp_cache() -> Result<Vec<Device>> {
         .output()
         .map_err(|e| color_eyre::eyre::eyre!("Failed to run arp: {}", e))?;
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut devices = Vec::new();
-    let mut seen_macs = std::collections::HashSet::new();
+    Ok(parse_arp_output(&String::from_utf8_lossy(&output.stdout)))
+}
 
-    for line in stdout.lines() {
+/// Every (IP, MAC) pair in the ARP cache, for activity polling
+pub fn arp_entries() -> Result<Vec<(String, String)>> {
+    let output = Command::new("arp")
+        .arg("-a")
+        .output()
+        .map_err(|e| color_eyre::eyre::eyre!("Failed to run arp: {}", e))?;
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_arp_line)
+        .filter(|(_, _, mac)| mac != "(incomplete)" && !mac.eq_ignore_ascii_case("ff:ff:ff:ff:ff:ff"))
+        .map(|(_, ip, mac)| (ip, mac))
+        .collect())
+}
+
+/// One device per MAC from `arp -a` output, flagged where entries conflict
+fn parse_arp_output(output: &str) -> Vec<Device> {
+    let mut devices: Vec<Device> = Vec::new();
+    let mut entries = Vec::new();
+
+    for line in output.lines() {
         if let Some((hostname, ip, mac)) = parse_arp_line(line) {
-            if mac == "(incomplete)" || mac == "ff:ff:ff:ff:ff:ff" {
+            if mac == "(incomplete)" || mac.eq_ignore_ascii_case("ff:ff:ff:ff:ff:ff") {
                 continue;
             }
             let mac_upper = mac.to_uppercase();
-            if seen_macs.contains(&mac_upper) {
+            entries.push((ip.clone(), mac_upper.clone()));
+            if devices.iter().any(|d| d.mac_address == mac_upper) {
                 continue;
             }
-            seen_macs.insert(mac_upper.clone());
             let mut device = Device::new(mac_upper, ip);
             // Set hostname if it's not just "?"
             if !hostname.is_empty() && hostname != "?" {
@@ -101,7 +217,36 @@ fn parse_arp_cache() -> Result<Vec<Device>> {
             devices.push(device);
         }
     }
-    Ok(devices)
+
+    flag_ip_conflicts(&mut devices, &entries);
+    devices
+}
+
+/// Mark devices whose IP is claimed by several MACs, or whose MAC answers
+/// for several IPs. `entries` are all (IP, MAC) pairs, duplicates included:
+/// the same pair listed once per interface is not a conflict.
+pub fn flag_ip_conflicts(devices: &mut [Device], entries: &[(String, String)]) {
+    let pairs: BTreeSet<(&str, &str)> = entries.iter().map(|(ip, mac)| (ip.as_str(), mac.as_str())).collect();
+
+    for device in devices.iter_mut() {
+        let mac = device.mac_address.as_str();
+        let other_macs: BTreeSet<&str> = pairs
+            .iter()
+            .filter(|(ip, other)| *ip == device.ip_address && !other.eq_ignore_ascii_case(mac))
+            .map(|(_, other)| *other)
+            .collect();
+        let other_ips: BTreeSet<&str> = pairs
+            .iter()
+            .filter(|(ip, other)| other.eq_ignore_ascii_case(mac) && *ip != device.ip_address)
+            .map(|(ip, _)| *ip)
+            .collect();
+
+        let conflict = IpConflict {
+            other_macs: other_macs.into_iter().map(str::to_string).collect(),
+            other_ips: other_ips.into_iter().map(str::to_string).collect(),
+        };
+        device.ip_conflict = (!conflict.is_empty()).then_some(conflict);
+    }
 }
 
 /// Parse ARP line format: "hostname (IP) at MAC on interface ..."
@@ -127,6 +272,18 @@ fn parse_arp_line(line: &str) -> Option<(String, String, String)> {
     Some((hostname, ip, mac))
 }
 
+/// The default gateway as a router device, with its MAC from the ARP cache
+/// (all zeros until the router has been talked to)
+pub fn gateway_device() -> Result<Option<Device>> {
+    let Some(gateway) = get_default_gateway()? else {
+        return Ok(None);
+    };
+    let gateway_mac = get_mac_for_ip(&gateway).unwrap_or_else(|| "00:00:00:00:00:00".to_string());
+    let mut device = Device::new(canonical_mac(&gateway_mac), gateway);
+    device.device_type = crate::network_map::DeviceType::Router;
+    Ok(Some(device))
+}
+
 fn get_default_gateway() -> Result<Option<String>> {
     let output = Command::new("netstat")
         .args(["-nr"])
@@ -156,28 +313,266 @@ fn get_mac_for_ip(ip: &str) -> Option<String> {
     parse_arp_line(&stdout).map(|(_, _, mac)| mac)
 }
 
-/// Ping all IPs in subnet to populate ARP cache
-pub async fn ping_sweep(subnet: &IpNetwork) -> Result<()> {
+/// Addresses worth pinging in a subnet: everything except loopback and,
+/// for IPv4, the network and broadcast addresses
+pub fn sweep_targets(subnet: &IpNetwork) -> Vec<IpAddr> {
+    let reserved: Vec<IpAddr> = match subnet {
+        // /31 and /32 have no network or broadcast address
+        IpNetwork::V4(net) if net.prefix() < 31 => vec![net.network().into(), net.broadcast().into()],
+        _ => Vec::new(),
+    };
+    subnet
+        .iter()
+        .filter(|ip| !ip.is_loopback() && !reserved.contains(ip))
+        .collect()
+}
+
+/// Ping all IPs in subnet to populate ARP cache, keeping at most
+/// `config.concurrency` pings in flight. Progress reports the number of
+/// addresses probed in `ports_scanned`/`total_ports`.
+/// Returns the reply TTL for every host that answered.
+pub async fn ping_sweep(
+    subnet: &IpNetwork,
+    config: &PingSweepConfig,
+    progress_tx: Option<&mpsc::Sender<ScanProgress>>,
+) -> Result<HashMap<String, u8>> {
+    ping_sweep_streaming(subnet, config, progress_tx, None).await
+}
+
+/// `ping_sweep`, sending each responding IP through `responders` as it replies
+async fn ping_sweep_streaming(
+    subnet: &IpNetwork,
+    config: &PingSweepConfig,
+    progress_tx: Option<&mpsc::Sender<ScanProgress>>,
+    responders: Option<&mpsc::UnboundedSender<String>>,
+) -> Result<HashMap<String, u8>> {
+    use tokio::process::Command as TokioCommand;
+    use tokio::task::JoinSet;
+    use tokio::time::timeout;
+
+    let targets = sweep_targets(subnet);
+    let total = targets.len();
+    let mut probed = 0;
+    let mut ttls = HashMap::new();
+    let mut in_flight = JoinSet::new();
+    let mut targets = targets.into_iter();
+
+    loop {
+        // Top up to the concurrency limit
+        while in_flight.len() < config.concurrency.max(1) {
+            let Some(ip) = targets.next() else { break };
+            let ip_str = ip.to_string();
+            let wait = config.timeout;
+            in_flight.spawn(async move {
+                // kill_on_drop stops the ping if the timeout gives up on it,
+                // so it can't outlive its slot under the concurrency cap
+                let output = timeout(
+                    wait,
+                    TokioCommand::new("ping")
+                        .args(["-c", "1", "-W", "1", &ip_str])
+                        .kill_on_drop(true)
+                        .output()
+                ).await;
+                let ttl = match output {
+                    Ok(Ok(out)) => parse_ping_ttl(&String::from_utf8_lossy(&out.stdout)),
+                    _ => None,
+                };
+                (ip_str, ttl)
+            });
+        }
+
+        let Some(result) = in_flight.join_next().await else { break };
+        if let Ok((ip, Some(ttl))) = result {
+            if let Some(responders) = responders {
+                let _ = responders.send(ip.clone());
+            }
+            ttls.insert(ip, ttl);
+        }
+        probed += 1;
+
+        // Report every batch's worth of probes (and the last one)
+        if let Some(tx) = progress_tx
+            && (probed % config.concurrency.max(1) == 0 || probed == total)
+        {
+            let _ = tx.send(ScanProgress {
+                phase: ScanPhase::Discovery,
+                devices_found: ttls.len(),
+                current_device: None,
+                ports_scanned: probed,
+                total_ports: total,
+                percent: None,
+            }).await;
+        }
+    }
+    Ok(ttls)
+}
+
+/// Ports tried when a device ignores ping (a refused connection still
+/// proves the host is up)
+const LIVENESS_PORTS: &[u16] = &[80, 443, 22, 445, 62078];
+
+/// Re-check whether previously seen devices are still reachable and update
+/// `is_online` (and `last_seen` for devices that answer). At most
+/// `PING_CONCURRENCY` devices are probed at once, like the sweep.
+pub async fn refresh_device_liveness(devices: &mut [Device]) {
+    use tokio::task::JoinSet;
+
+    let mut targets = devices
+        .iter()
+        .enumerate()
+        .filter(|(_, device)| !device.ip_address.is_empty())
+        .map(|(idx, device)| (idx, device.ip_address.clone()))
+        .collect::<Vec<_>>()
+        .into_iter();
+
+    for device in devices.iter_mut() {
+        device.is_online = false;
+    }
+    let now = chrono::Utc::now();
+    let mut in_flight = JoinSet::new();
+    loop {
+        while in_flight.len() < PING_CONCURRENCY {
+            let Some((idx, ip)) = targets.next() else { break };
+            in_flight.spawn(async move { (idx, is_host_alive(&ip).await) });
+        }
+
+        let Some(result) = in_flight.join_next().await else { break };
+        if let Ok((idx, true)) = result {
+            devices[idx].is_online = true;
+            devices[idx].last_seen = now;
+        }
+    }
+}
+
+/// Ping the host, falling back to a quick TCP probe since many phones drop ICMP
+async fn is_host_alive(ip: &str) -> bool {
     use tokio::process::Command as TokioCommand;
     use tokio::time::{timeout, Duration};
 
-    let mut handles = Vec::new();
-    for ip in subnet.iter() {
-        if ip.is_loopback() { continue; }
-        let ip_str = ip.to_string();
-        let handle = tokio::spawn(async move {
-            let _ = timeout(
-                Duration::from_millis(500),
-                TokioCommand::new("ping")
-                    .args(["-c", "1", "-W", "1", &ip_str])
-                    .output()
-            ).await;
-        });
-        handles.push(handle);
-        if handles.len() >= 50 {
-            for h in handles.drain(..) { let _ = h.await; }
+    let ping = timeout(
+        Duration::from_millis(1500),
+        TokioCommand::new("ping")
+            .args(["-c", "1", "-W", "1", ip])
+            .kill_on_drop(true)
+            .output()
+    ).await;
+    if let Ok(Ok(out)) = ping
+        && out.status.success()
+    {
+        return true;
+    }
+
+    let Ok(addr) = ip.parse::<IpAddr>() else {
+        return false;
+    };
+    for &port in LIVENESS_PORTS {
+        match timeout(Duration::from_millis(300), tokio::net::TcpStream::connect((addr, port))).await {
+            Ok(Ok(_)) => return true,
+            Ok(Err(e)) if e.kind() == std::io::ErrorKind::ConnectionRefused => return true,
+            _ => {}
         }
     }
-    for h in handles { let _ = h.await; }
-    Ok(())
+    false
+}
+
+/// Extract the TTL from ping output ("... icmp_seq=0 ttl=64 time=1.2 ms")
+fn parse_ping_ttl(output: &str) -> Option<u8> {
+    let lower = output.to_lowercase();
+    let start = lower.find("ttl=")? + 4;
+    lower[start..]
+        .split(|c: char| !c.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sweep_targets_skip_reserved() {
+        let subnet: IpNetwork = "192.168.1.0/24".parse().unwrap();
+        let targets = sweep_targets(&subnet);
+        assert_eq!(targets.len(), 254);
+        assert_eq!(targets.first().unwrap().to_string(), "192.168.1.1");
+        assert_eq!(targets.last().unwrap().to_string(), "192.168.1.254");
+
+        let loopback: IpNetwork = "127.0.0.0/30".parse().unwrap();
+        assert!(sweep_targets(&loopback).is_empty());
+
+        let point_to_point: IpNetwork = "10.0.0.0/31".parse().unwrap();
+        assert_eq!(sweep_targets(&point_to_point).len(), 2);
+    }
+
+    #[test]
+    fn test_parse_target_subnet() {
+        let subnet = parse_target_subnet("10.0.0.0/22", false).unwrap();
+        assert_eq!(subnet.to_string(), "10.0.0.0/22");
+        assert_eq!(sweep_host_count(&subnet), 1022);
+        assert_eq!(sweep_host_count(&subnet), sweep_targets(&subnet).len() as u128);
+
+        assert!(parse_target_subnet("10.0.0.0/33", false).is_err());
+        assert!(parse_target_subnet("not-a-subnet", false).is_err());
+
+        // A /20 is the largest range allowed without --force
+        assert_eq!(sweep_host_count(&parse_target_subnet("172.16.0.0/20", false).unwrap()), MAX_SWEEP_HOSTS);
+        assert!(parse_target_subnet("172.16.0.0/19", false).is_err());
+        assert!(parse_target_subnet("172.16.0.0/16", true).is_ok());
+        assert!(parse_target_subnet("fd00::/64", false).is_err());
+    }
+
+    #[test]
+    fn test_sweep_host_count_bounds() {
+        let count = |cidr: &str| sweep_host_count(&cidr.parse().unwrap());
+        assert_eq!(count("192.168.1.7/32"), 1);
+        assert_eq!(count("192.168.1.6/31"), 2);
+        assert_eq!(count("192.168.1.0/30"), 2);
+        assert_eq!(count("192.168.1.0/24"), 254);
+        assert_eq!(count("0.0.0.0/0"), (1u128 << 32) - 2);
+        assert_eq!(count("fd00::/120"), 256);
+        assert_eq!(count("::/0"), u128::MAX);
+    }
+
+    #[test]
+    fn test_arp_conflicts_flagged() {
+        let output = "\
+router (192.168.1.1) at aa:bb:cc:00:00:01 on en0 ifscope [ethernet]
+? (192.168.1.20) at aa:bb:cc:00:00:02 on en0 ifscope [ethernet]
+? (192.168.1.20) at aa:bb:cc:00:00:03 on en0 ifscope [ethernet]
+? (192.168.1.30) at aa:bb:cc:00:00:04 on en0 ifscope [ethernet]
+? (192.168.1.31) at aa:bb:cc:00:00:04 on en0 ifscope [ethernet]
+? (192.168.1.40) at aa:bb:cc:00:00:05 on en0 ifscope [ethernet]
+? (192.168.1.40) at aa:bb:cc:00:00:05 on bridge100 ifscope [ethernet]
+? (192.168.1.50) at (incomplete) on en0 ifscope [ethernet]
+? (192.168.1.255) at ff:ff:ff:ff:ff:ff on en0 ifscope [ethernet]";
+        let devices = parse_arp_output(output);
+        let conflict = |mac: &str| {
+            devices
+                .iter()
+                .find(|d| d.mac_address == mac)
+                .unwrap_or_else(|| panic!("{} missing", mac))
+                .ip_conflict
+                .clone()
+        };
+
+        assert_eq!(devices.len(), 5);
+        assert_eq!(conflict("AA:BB:CC:00:00:01"), None);
+        // Two MACs on one IP: both are flagged, each naming the other
+        assert_eq!(conflict("AA:BB:CC:00:00:02").unwrap().other_macs, vec!["AA:BB:CC:00:00:03"]);
+        assert_eq!(conflict("AA:BB:CC:00:00:03").unwrap().other_macs, vec!["AA:BB:CC:00:00:02"]);
+        // One MAC on two IPs: the device keeps the first IP and lists the second
+        let multi = conflict("AA:BB:CC:00:00:04").unwrap();
+        assert_eq!(multi.other_ips, vec!["192.168.1.31"]);
+        assert!(multi.other_macs.is_empty());
+        // The same pair on two interfaces is not a conflict
+        assert_eq!(conflict("AA:BB:CC:00:00:05"), None);
+    }
+
+    #[test]
+    fn test_parse_ping_ttl() {
+        let output = "64 bytes from 192.168.1.1: icmp_seq=0 ttl=64 time=1.234 ms";
+        assert_eq!(parse_ping_ttl(output), Some(64));
+        assert_eq!(parse_ping_ttl("Request timeout for icmp_seq 0"), None);
+    }
 }
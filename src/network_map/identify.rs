@@ -1,4 +1,10 @@
-use crate::network_map::{lookup_vendor, Device, DeviceType};
+use crate::network_map::{is_randomized_mac, lookup_vendor, Device, DeviceType, PortState};
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+
+/// Largest gap between one randomized MAC's last sighting and the next one's
+/// first sighting for the two to count as the same device
+const MAC_MERGE_WINDOW_HOURS: i64 = 24;
 
 /// Identify device type and vendor information
 pub fn identify_device(device: &mut Device) {
@@ -15,6 +21,72 @@ pub fn identify_device(device: &mut Device) {
         .iter()
         .filter_map(|s| s.detected_agent.clone())
         .collect();
+
+    device.os_guess = guess_os(device).map(String::from);
+}
+
+/// Guess the OS family from banners, open ports and ping TTL.
+/// Conservative: only returns a guess when at least two signals agree.
+pub fn guess_os(device: &Device) -> Option<&'static str> {
+    let banner_hint = os_from_banners(device);
+    let port_hint = os_from_ports(device);
+    let ttl_hints = device.ttl.map(os_from_ttl).unwrap_or(&[]);
+
+    for candidate in [banner_hint, port_hint].into_iter().flatten() {
+        let votes = [banner_hint, port_hint]
+            .iter()
+            .filter(|hint| **hint == Some(candidate))
+            .count()
+            + usize::from(ttl_hints.contains(&candidate));
+        if votes >= 2 {
+            return Some(candidate);
+        }
+    }
+    None
+}
+
+fn os_from_banners(device: &Device) -> Option<&'static str> {
+    for banner in device.services.iter().filter_map(|s| s.banner.as_deref()) {
+        let banner = banner.to_lowercase();
+        if banner.contains("windows") || banner.contains("microsoft") {
+            return Some("Windows");
+        }
+        if banner.contains("darwin") || banner.contains("macos") || banner.contains("mac os") {
+            return Some("macOS");
+        }
+        if banner.contains("ubuntu") || banner.contains("debian") || banner.contains("raspbian")
+            || banner.contains("fedora") || banner.contains("centos") || banner.contains("linux")
+        {
+            return Some("Linux");
+        }
+    }
+    None
+}
+
+fn os_from_ports(device: &Device) -> Option<&'static str> {
+    let ports: Vec<u16> = device.services.iter().map(|s| s.port).collect();
+    if ports.contains(&62078) {
+        return Some("iOS");
+    }
+    if ports.contains(&548) && ports.contains(&22) {
+        return Some("macOS");
+    }
+    if (ports.contains(&445) && ports.contains(&139)) || ports.contains(&3389) {
+        return Some("Windows");
+    }
+    if ports.contains(&9100) {
+        return Some("Printer firmware");
+    }
+    None
+}
+
+/// OS families consistent with an observed TTL (initial TTL 64, 128 or 255)
+fn os_from_ttl(ttl: u8) -> &'static [&'static str] {
+    match ttl {
+        0..=64 => &["Linux", "macOS", "iOS"],
+        65..=128 => &["Windows"],
+        _ => &["Network OS", "Printer firmware"],
+    }
 }
 
 /// Infer device type from open ports, vendor, and hostname
@@ -197,3 +269,187 @@ pub fn identify_all_devices(devices: &mut [Device]) {
         identify_device(device);
     }
 }
+
+/// Merge devices that are one physical device behind rotating randomized MACs.
+/// Conservative: both MACs must be randomized, seen one after the other (the
+/// older one gone before the newer one appeared) within `MAC_MERGE_WINDOW_HOURS`,
+/// and share a hostname (or, lacking hostnames, the same IP and the same
+/// non-empty set of open ports). The newest MAC stays primary.
+pub fn merge_randomized_macs(mut devices: Vec<Device>) -> Vec<Device> {
+    devices.sort_by_key(|d| Reverse(d.last_seen));
+
+    let mut merged: Vec<Device> = Vec::with_capacity(devices.len());
+    for device in devices {
+        match merged.iter_mut().find(|primary| same_randomized_device(primary, &device)) {
+            Some(primary) => absorb_alias(primary, device),
+            None => merged.push(device),
+        }
+    }
+    merged
+}
+
+fn same_randomized_device(primary: &Device, older: &Device) -> bool {
+    if !is_randomized_mac(&primary.mac_address)
+        || !is_randomized_mac(&older.mac_address)
+        || primary.mac_address.eq_ignore_ascii_case(&older.mac_address)
+    {
+        return false;
+    }
+
+    // Sightings that overlap are two devices online at once, whatever their
+    // names say (two phones can share a stock hostname)
+    let gap = primary.first_seen.signed_duration_since(older.last_seen);
+    if gap < chrono::Duration::zero() || gap > chrono::Duration::hours(MAC_MERGE_WINDOW_HOURS) {
+        return false;
+    }
+
+    match (&primary.hostname, &older.hostname) {
+        (Some(a), Some(b)) => !a.is_empty() && a.eq_ignore_ascii_case(b),
+        (None, None) => {
+            let ports = open_ports(primary);
+            primary.ip_address == older.ip_address && !ports.is_empty() && ports == open_ports(older)
+        }
+        _ => false,
+    }
+}
+
+fn open_ports(device: &Device) -> BTreeSet<u16> {
+    device
+        .services
+        .iter()
+        .filter(|s| s.state == PortState::Open)
+        .map(|s| s.port)
+        .collect()
+}
+
+fn absorb_alias(primary: &mut Device, older: Device) {
+    primary.mac_aliases.push(older.mac_address);
+    primary.mac_aliases.extend(older.mac_aliases);
+    // A rotated-away MAC still cached at the same IP isn't a conflict
+    if let Some(conflict) = &mut primary.ip_conflict {
+        conflict.other_macs.retain(|mac| !primary.mac_aliases.iter().any(|alias| alias.eq_ignore_ascii_case(mac)));
+        if conflict.is_empty() {
+            primary.ip_conflict = None;
+        }
+    }
+    primary.first_seen = primary.first_seen.min(older.first_seen);
+    primary.is_online |= older.is_online;
+    if primary.custom_name.is_none() {
+        primary.custom_name = older.custom_name;
+    }
+    if primary.notes.is_none() {
+        primary.notes = older.notes;
+    }
+    if primary.tags.is_empty() {
+        primary.tags = older.tags;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network_map::{PortState, Protocol, Service};
+
+    fn device_with(ports: &[u16], banner: Option<&str>, ttl: Option<u8>) -> Device {
+        let mut device = Device::new("00:11:22:33:44:55".to_string(), "192.168.1.10".to_string());
+        device.services = ports
+            .iter()
+            .map(|&port| Service {
+                port,
+                protocol: Protocol::Tcp,
+                state: PortState::Open,
+                service_name: None,
+                banner: banner.map(String::from),
+                detected_agent: None,
+                certificate: None,
+            })
+            .collect();
+        device.ttl = ttl;
+        device
+    }
+
+    #[test]
+    fn test_os_guess_requires_two_signals() {
+        // Ports alone aren't enough
+        assert_eq!(guess_os(&device_with(&[139, 445], None, None)), None);
+        // Ports + TTL agree
+        assert_eq!(guess_os(&device_with(&[139, 445], None, Some(128))), Some("Windows"));
+        // Banner + TTL agree
+        assert_eq!(
+            guess_os(&device_with(&[22], Some("SSH-2.0-OpenSSH_8.9p1 Ubuntu-3"), Some(64))),
+            Some("Linux")
+        );
+    }
+
+    #[test]
+    fn test_os_guess_conflicting_signals() {
+        assert_eq!(guess_os(&device_with(&[139, 445], None, Some(64))), None);
+    }
+
+    fn randomized(mac: &str, hostname: Option<&str>, hours_ago: i64) -> Device {
+        let mut device = Device::new(mac.to_string(), "192.168.1.20".to_string());
+        device.hostname = hostname.map(String::from);
+        device.last_seen = chrono::Utc::now() - chrono::Duration::hours(hours_ago);
+        device.first_seen = device.last_seen;
+        device
+    }
+
+    #[test]
+    fn test_merge_randomized_macs_by_hostname() {
+        let devices = vec![
+            randomized("AA:00:00:00:00:01", Some("pixel-7"), 10),
+            randomized("DA:00:00:00:00:02", Some("Pixel-7"), 5),
+            randomized("2E:00:00:00:00:03", Some("pixel-7"), 0),
+        ];
+        let merged = merge_randomized_macs(devices);
+
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].mac_address, "2E:00:00:00:00:03");
+        assert_eq!(merged[0].mac_aliases, vec!["DA:00:00:00:00:02", "AA:00:00:00:00:01"]);
+    }
+
+    #[test]
+    fn test_merge_is_conservative() {
+        // Globally unique MAC, different hostnames, no hostname or ports, and a stale sighting
+        let mut global = randomized("00:11:22:33:44:55", Some("laptop"), 1);
+        global.ip_address = "192.168.1.30".to_string();
+        let devices = vec![
+            global,
+            randomized("02:00:00:00:00:01", Some("laptop"), 0),
+            randomized("06:00:00:00:00:02", Some("phone-a"), 0),
+            randomized("0A:00:00:00:00:03", Some("phone-b"), 1),
+            randomized("0E:00:00:00:00:04", None, 0),
+            randomized("12:00:00:00:00:05", None, 1),
+            randomized("16:00:00:00:00:06", Some("phone-a"), 72),
+        ];
+        assert_eq!(merge_randomized_macs(devices).len(), 7);
+    }
+
+    #[test]
+    fn test_overlapping_sightings_stay_separate() {
+        // Two "iPhone"s online at the same time, at different addresses
+        let mut newer = randomized("6A:00:00:00:00:01", Some("iPhone"), 0);
+        newer.first_seen = newer.last_seen - chrono::Duration::hours(2);
+        let mut older = randomized("7E:00:00:00:00:02", Some("iPhone"), 1);
+        older.first_seen = older.last_seen - chrono::Duration::hours(3);
+        older.ip_address = "192.168.1.21".to_string();
+        assert_eq!(merge_randomized_macs(vec![newer.clone(), older.clone()]).len(), 2);
+
+        // Once the older one was gone before the newer one appeared, they merge
+        older.last_seen = newer.first_seen;
+        assert_eq!(merge_randomized_macs(vec![newer, older]).len(), 1);
+    }
+
+    #[test]
+    fn test_merge_by_ip_and_ports() {
+        let mut newer = device_with(&[62078], None, None);
+        newer.mac_address = "3A:00:00:00:00:01".to_string();
+        let mut older = device_with(&[62078], None, None);
+        older.mac_address = "5E:00:00:00:00:02".to_string();
+        older.last_seen = newer.last_seen - chrono::Duration::hours(1);
+
+        let merged = merge_randomized_macs(vec![older, newer]);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].mac_aliases, vec!["5E:00:00:00:00:02"]);
+    }
+}
@@ -1,11 +1,21 @@
+mod activity;
 mod discovery;
 mod identify;
 mod oui;
+mod pipeline;
+mod port_list;
 mod port_scan;
+mod risk;
+mod services;
 mod types;
 
+pub use activity::ArpActivity;
 pub use discovery::*;
 pub use identify::*;
-pub use oui::lookup_vendor;
+pub use oui::{is_randomized_mac, lookup_vendor, parse_oui_csv, set_oui_file};
+pub use pipeline::discover_and_scan_devices;
+pub use port_list::{load_port_file, parse_port_list};
 pub use port_scan::*;
+pub use risk::{port_risk, PortRisk};
+pub use services::{lookup_service_name, parse_service_names_csv, set_services_file};
 pub use types::*;
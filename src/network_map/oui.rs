@@ -1,14 +1,56 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::OnceLock;
 
+/// User-configured path to the IEEE OUI CSV (set before the first lookup)
+static OUI_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Use an IEEE OUI CSV at this path instead of the default location.
+/// Must be called before the first `lookup_vendor`.
+pub fn set_oui_file(path: PathBuf) {
+    let _ = OUI_FILE.set(path);
+}
+
 /// Lookup vendor name from MAC address prefix (OUI)
 pub fn lookup_vendor(mac: &str) -> Option<&'static str> {
     let oui = get_oui_database();
+    let normalized = normalize_oui(mac);
+
+    if normalized.len() < 6 {
+        return None;
+    }
 
+    // First check the OUI database (before checking for randomized).
+    // Built-in names are shorter, so they win over the IEEE file.
+    if let Some(vendor) = oui.get(normalized.as_str()).copied() {
+        return Some(vendor);
+    }
+    if let Some(vendor) = get_external_oui_database().get(&normalized) {
+        return Some(vendor.as_str());
+    }
+
+    if is_randomized_mac(mac) {
+        return Some("Private/Randomized");
+    }
+
+    None
+}
+
+/// Whether a MAC is locally administered (second nibble is 2, 6, A, or E).
+/// These are randomized MACs used by phones/laptops for privacy.
+pub fn is_randomized_mac(mac: &str) -> bool {
+    normalize_oui(mac)
+        .chars()
+        .nth(1)
+        .is_some_and(|c| matches!(c, '2' | '6' | 'A' | 'E'))
+}
+
+/// First three octets as six uppercase hex digits
+fn normalize_oui(mac: &str) -> String {
     // Handle MAC addresses that may have single-digit octets (e.g., "0:E0:4C" instead of "00:E0:4C")
     // Split by common separators and pad each octet
     let parts: Vec<&str> = mac.split(|c| c == ':' || c == '-' || c == '.').collect();
-    let normalized = if parts.len() >= 3 {
+    if parts.len() >= 3 {
         // MAC with separators - pad each octet to 2 chars
         parts.iter()
             .take(3)
@@ -21,26 +63,7 @@ pub fn lookup_vendor(mac: &str) -> Option<&'static str> {
             .take(6)
             .collect::<String>()
             .to_uppercase()
-    };
-
-    if normalized.len() < 6 {
-        return None;
     }
-
-    // First check the OUI database (before checking for randomized)
-    if let Some(vendor) = oui.get(normalized.as_str()).copied() {
-        return Some(vendor);
-    }
-
-    // Check if locally administered address (second nibble is 2, 6, A, or E)
-    // These are randomized MACs used by phones/laptops for privacy
-    if let Some(second_char) = normalized.chars().nth(1) {
-        if matches!(second_char, '2' | '6' | 'A' | 'E') {
-            return Some("Private/Randomized");
-        }
-    }
-
-    None
 }
 
 fn get_oui_database() -> &'static HashMap<&'static str, &'static str> {
@@ -212,6 +235,70 @@ fn get_oui_database() -> &'static HashMap<&'static str, &'static str> {
     })
 }
 
+/// Vendors from the IEEE OUI CSV, loaded once from `--oui-file` or
+/// `$XDG_DATA_HOME/wifi-analyzer/oui.csv` (empty if no file is present)
+fn get_external_oui_database() -> &'static HashMap<String, String> {
+    static EXTERNAL_DB: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+    EXTERNAL_DB.get_or_init(|| {
+        let path = OUI_FILE.get().cloned().or_else(|| default_data_file("oui.csv"));
+        path.and_then(|p| std::fs::read_to_string(p).ok())
+            .map(|contents| parse_oui_csv(&contents))
+            .unwrap_or_default()
+    })
+}
+
+/// `$XDG_DATA_HOME/wifi-analyzer/<name>`, defaulting to `~/.local/share`
+pub(super) fn default_data_file(name: &str) -> Option<PathBuf> {
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local/share")))?;
+    Some(data_home.join("wifi-analyzer").join(name))
+}
+
+/// Parse the IEEE MA-L CSV ("Registry,Assignment,Organization Name,Organization Address")
+/// into a map of uppercase 6-hex-digit prefix to organization name
+pub fn parse_oui_csv(contents: &str) -> HashMap<String, String> {
+    let mut map = HashMap::new();
+
+    for line in contents.lines() {
+        let fields = split_csv_line(line);
+        let (Some(assignment), Some(name)) = (fields.get(1), fields.get(2)) else {
+            continue;
+        };
+        let assignment = assignment.trim().to_uppercase();
+        let name = name.trim();
+        // Skips the header row and anything that isn't a 24-bit OUI
+        if assignment.len() == 6 && assignment.chars().all(|c| c.is_ascii_hexdigit()) && !name.is_empty() {
+            map.insert(assignment, name.to_string());
+        }
+    }
+
+    map
+}
+
+/// Split a CSV line, honoring double-quoted fields with embedded commas
+pub(super) fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -231,4 +318,20 @@ mod tests {
     fn test_lookup_espressif() {
         assert_eq!(lookup_vendor("5C:CF:7F:12:34:56"), Some("Espressif"));
     }
+
+    #[test]
+    fn test_parse_oui_csv() {
+        let csv = "Registry,Assignment,Organization Name,Organization Address\n\
+                   MA-L,002272,American Micro-Fuel Device Corp.,2181 Buchanan Loop Ferndale WA US 98248\n\
+                   MA-L,F0EE10,\"Samsung Electronics Co.,Ltd\",\"#94-1, Imsoo-Dong Gumi  Gyeongbuk KR 730-350\"\n\
+                   MA-L,ac3c0b,\"Apple, Inc.\",1 Infinite Loop Cupertino CA US 95014\n";
+
+        let map = parse_oui_csv(csv);
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get("002272").map(String::as_str), Some("American Micro-Fuel Device Corp."));
+        assert_eq!(map.get("F0EE10").map(String::as_str), Some("Samsung Electronics Co.,Ltd"));
+        // Assignments are normalized to uppercase
+        assert_eq!(map.get("AC3C0B").map(String::as_str), Some("Apple, Inc."));
+        assert!(!map.contains_key("ASSIGNMENT"));
+    }
 }
@@ -0,0 +1,297 @@
+//! Full network scan with discovery and port scanning overlapped: every
+//! host that answers the ping sweep is port scanned while the sweep carries
+//! on, and hosts that only show up in the ARP cache are scanned last.
+
+use crate::network_map::port_scan::{ports_per_device, scan_device_ports};
+use crate::network_map::{
+    discover_devices_streaming, Device, PingSweepConfig, PortScanConfig, ScanPhase, ScanProgress, Service,
+};
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+use ipnetwork::IpNetwork;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+
+/// How often the cancel flag is checked while waiting on slow probes
+const CANCEL_POLL: Duration = Duration::from_millis(100);
+
+/// Share (in percent) of the overall progress given to discovery
+const DISCOVERY_SHARE: usize = 40;
+
+/// Discover devices with a ping sweep and scan their `ports` in the same
+/// pass. Results match discovery followed by `scan_devices_ports`.
+/// Setting `cancel` stops the scan, aborting probes in flight.
+pub async fn discover_and_scan_devices(
+    progress_tx: Option<mpsc::Sender<ScanProgress>>,
+    sweep: &PingSweepConfig,
+    target_subnet: Option<&IpNetwork>,
+    ports: &[u16],
+    config: &PortScanConfig,
+    cancel: &AtomicBool,
+) -> Result<Vec<Device>> {
+    let config = *config;
+    let per_device = ports_per_device(ports, &config);
+    let ports: Arc<[u16]> = ports.into();
+    run_pipeline(
+        |discovery_tx, responders| {
+            discover_devices_streaming(Some(discovery_tx), Some(sweep), target_subnet, Some(responders))
+        },
+        move |ip| {
+            let ports = ports.clone();
+            async move { scan_device_ports(&ip, &ports, &config).await.unwrap_or_default() }
+        },
+        config.max_concurrent_devices,
+        per_device,
+        progress_tx,
+        cancel,
+    )
+    .await
+}
+
+/// Where a pipelined scan has got to, turned into `ScanProgress` updates
+#[derive(Debug, Default)]
+struct PipelineProgress {
+    discovery_done: bool,
+    devices_found: usize,
+    /// Addresses pinged so far and in total during the sweep
+    pinged: usize,
+    to_ping: usize,
+    hosts_scanned: usize,
+    hosts_queued: usize,
+    last_host: Option<String>,
+    /// Highest percentage reported, so newly queued hosts don't move it back
+    reported: u8,
+}
+
+impl PipelineProgress {
+    fn percent(&mut self) -> u8 {
+        let discovery = if self.discovery_done {
+            100
+        } else {
+            (self.pinged * 100).checked_div(self.to_ping).unwrap_or(0)
+        };
+        let scanning = (self.hosts_scanned * 100)
+            .checked_div(self.hosts_queued)
+            .unwrap_or(if self.discovery_done { 100 } else { 0 });
+        let overall = (discovery * DISCOVERY_SHARE + scanning * (100 - DISCOVERY_SHARE)) / 100;
+        // Only a finished scan reads 100%
+        let cap = if self.discovery_done && self.hosts_scanned == self.hosts_queued { 100 } else { 99 };
+        self.reported = self.reported.max(overall.min(cap) as u8);
+        self.reported
+    }
+
+    fn snapshot(&mut self, ports_per_device: usize) -> ScanProgress {
+        let percent = Some(self.percent());
+        if self.discovery_done {
+            ScanProgress {
+                phase: ScanPhase::PortScan,
+                devices_found: self.devices_found,
+                current_device: self.last_host.clone(),
+                ports_scanned: self.hosts_scanned * ports_per_device,
+                total_ports: self.hosts_queued * ports_per_device,
+                percent,
+            }
+        } else {
+            ScanProgress {
+                phase: ScanPhase::Discovery,
+                devices_found: self.devices_found.max(self.hosts_queued),
+                current_device: self.last_host.clone(),
+                ports_scanned: self.pinged,
+                total_ports: self.to_ping,
+                percent,
+            }
+        }
+    }
+}
+
+/// Run `discover`, port scanning each IP it reports through its second
+/// argument (and every device it returns) with `scan`, at most
+/// `max_concurrent` hosts at a time. Services are attached by IP.
+async fn run_pipeline<D, DF, S, SF>(
+    discover: D,
+    scan: S,
+    max_concurrent: usize,
+    ports_per_device: usize,
+    progress_tx: Option<mpsc::Sender<ScanProgress>>,
+    cancel: &AtomicBool,
+) -> Result<Vec<Device>>
+where
+    D: FnOnce(mpsc::Sender<ScanProgress>, mpsc::UnboundedSender<String>) -> DF,
+    DF: Future<Output = Result<Vec<Device>>>,
+    S: Fn(String) -> SF,
+    SF: Future<Output = Vec<Service>> + Send + 'static,
+{
+    let (discovery_tx, mut discovery_rx) = mpsc::channel(10);
+    let (responders_tx, mut responders_rx) = mpsc::unbounded_channel();
+    let discovery = discover(discovery_tx, responders_tx);
+    tokio::pin!(discovery);
+
+    let mut devices: Option<Vec<Device>> = None;
+    let mut queued: HashSet<String> = HashSet::new();
+    let mut pending: VecDeque<String> = VecDeque::new();
+    let mut services: HashMap<String, Vec<Service>> = HashMap::new();
+    let mut in_flight = JoinSet::new();
+    let mut progress = PipelineProgress::default();
+
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            // Dropping the JoinSet aborts the probes still running
+            return Err(eyre!("device scan cancelled"));
+        }
+
+        while in_flight.len() < max_concurrent.max(1) {
+            let Some(ip) = pending.pop_front() else { break };
+            let probe = scan(ip.clone());
+            in_flight.spawn(async move { (ip, probe.await) });
+        }
+        if devices.is_some() && in_flight.is_empty() {
+            break;
+        }
+
+        let mut enqueue = |ip: String| {
+            if !ip.is_empty() && queued.insert(ip.clone()) {
+                pending.push_back(ip);
+            }
+        };
+        tokio::select! {
+            result = &mut discovery, if devices.is_none() => {
+                let found = result?;
+                while let Ok(ip) = responders_rx.try_recv() {
+                    enqueue(ip);
+                }
+                for device in &found {
+                    enqueue(device.ip_address.clone());
+                }
+                progress.discovery_done = true;
+                progress.devices_found = found.len();
+                devices = Some(found);
+            }
+            Some(ip) = responders_rx.recv(), if devices.is_none() => enqueue(ip),
+            Some(update) = discovery_rx.recv(), if devices.is_none() => {
+                if update.total_ports > 0 {
+                    progress.pinged = update.ports_scanned;
+                    progress.to_ping = update.total_ports;
+                }
+                progress.devices_found = update.devices_found;
+            }
+            Some(joined) = in_flight.join_next(), if !in_flight.is_empty() => {
+                if let Ok((ip, found)) = joined {
+                    progress.last_host = Some(ip.clone());
+                    services.insert(ip, found);
+                }
+                progress.hosts_scanned += 1;
+            }
+            _ = tokio::time::sleep(CANCEL_POLL) => continue,
+        }
+        progress.hosts_queued = queued.len();
+
+        if let Some(ref tx) = progress_tx {
+            let _ = tx.send(progress.snapshot(ports_per_device)).await;
+        }
+    }
+
+    let mut devices = devices.unwrap_or_default();
+    for device in &mut devices {
+        if let Some(found) = services.remove(&device.ip_address) {
+            device.services = found;
+        }
+    }
+    Ok(devices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network_map::{PortState, Protocol};
+
+    /// Two hosts answer the sweep (one of them never resolves to a MAC),
+    /// then the ARP cache adds a host that ignored ping
+    async fn fake_discovery(
+        progress: mpsc::Sender<ScanProgress>,
+        responders: mpsc::UnboundedSender<String>,
+    ) -> Result<Vec<Device>> {
+        for (i, ip) in ["192.168.1.10", "192.168.1.99"].into_iter().enumerate() {
+            let _ = responders.send(ip.to_string());
+            let _ = progress
+                .send(ScanProgress {
+                    phase: ScanPhase::Discovery,
+                    devices_found: i + 1,
+                    current_device: None,
+                    ports_scanned: i + 1,
+                    total_ports: 2,
+                    percent: None,
+                })
+                .await;
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        Ok(vec![
+            Device::new("AA:BB:CC:00:00:10".to_string(), "192.168.1.10".to_string()),
+            Device::new("AA:BB:CC:00:00:11".to_string(), "192.168.1.11".to_string()),
+            Device::new("AA:BB:CC:00:00:12".to_string(), "192.168.1.12".to_string()),
+        ])
+    }
+
+    /// One open port per host, numbered after the last octet
+    async fn fake_scan(ip: String) -> Vec<Service> {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let octet: u16 = ip.rsplit('.').next().unwrap().parse().unwrap();
+        vec![Service {
+            port: 8000 + octet,
+            protocol: Protocol::Tcp,
+            state: PortState::Open,
+            service_name: None,
+            banner: None,
+            detected_agent: None,
+            certificate: None,
+        }]
+    }
+
+    fn summary(devices: &[Device]) -> Vec<(String, String, Vec<u16>)> {
+        let mut summary: Vec<_> = devices
+            .iter()
+            .map(|d| (d.mac_address.clone(), d.ip_address.clone(), d.services.iter().map(|s| s.port).collect()))
+            .collect();
+        summary.sort();
+        summary
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_matches_sequential_scan() {
+        // Sequential: discover everything, then scan each device
+        let (progress, _progress_rx) = mpsc::channel(10);
+        let (responders, _responders_rx) = mpsc::unbounded_channel();
+        let mut sequential = fake_discovery(progress, responders).await.unwrap();
+        for device in &mut sequential {
+            device.services = fake_scan(device.ip_address.clone()).await;
+        }
+
+        let (progress_tx, mut progress_rx) = mpsc::channel(100);
+        let cancel = AtomicBool::new(false);
+        let pipelined = run_pipeline(fake_discovery, fake_scan, 2, 1, Some(progress_tx), &cancel)
+            .await
+            .unwrap();
+
+        assert_eq!(summary(&pipelined), summary(&sequential));
+        let mut updates = Vec::new();
+        while let Ok(update) = progress_rx.try_recv() {
+            updates.push(update);
+        }
+        let percents: Vec<u8> = updates.iter().filter_map(|u| u.percent).collect();
+        assert!(percents.windows(2).all(|w| w[0] <= w[1]), "{:?}", percents);
+        assert_eq!(percents.last(), Some(&100));
+        // Port scanning started before discovery finished
+        assert!(updates.iter().any(|u| u.phase == ScanPhase::Discovery && u.current_device.is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_cancel() {
+        let cancel = AtomicBool::new(true);
+        let result = run_pipeline(fake_discovery, fake_scan, 2, 1, None, &cancel).await;
+        assert!(result.unwrap_err().to_string().contains("cancelled"));
+    }
+}
@@ -0,0 +1,85 @@
+//! Custom TCP port lists given with --ports or --ports-file
+
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+use std::path::Path;
+
+/// Parse a list like "22,80,443,8000-8100" into sorted ports without
+/// duplicates. Whitespace around entries is ignored; port 0, reversed
+/// ranges and anything that isn't a number are rejected.
+pub fn parse_port_list(spec: &str) -> Result<Vec<u16>> {
+    let mut ports = Vec::new();
+    for entry in spec.split([',', '\n']).map(str::trim).filter(|e| !e.is_empty()) {
+        match entry.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (parse_port(start)?, parse_port(end)?);
+                if start > end {
+                    return Err(eyre!("port range {} runs backwards", entry));
+                }
+                ports.extend(start..=end);
+            }
+            None => ports.push(parse_port(entry)?),
+        }
+    }
+    if ports.is_empty() {
+        return Err(eyre!("no ports given"));
+    }
+    ports.sort_unstable();
+    ports.dedup();
+    Ok(ports)
+}
+
+fn parse_port(s: &str) -> Result<u16> {
+    match s.trim().parse::<u16>() {
+        Ok(0) => Err(eyre!("port 0 can't be scanned")),
+        Ok(port) => Ok(port),
+        Err(_) => Err(eyre!("\"{}\" is not a port (1-65535)", s.trim())),
+    }
+}
+
+/// Read a port list file: entries separated by commas or newlines, `#`
+/// starting a comment that runs to the end of the line
+pub fn load_port_file(path: &Path) -> Result<Vec<u16>> {
+    let contents = std::fs::read_to_string(path).map_err(|e| eyre!("{}: {}", path.display(), e))?;
+    let spec: Vec<&str> = contents
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .collect();
+    parse_port_list(&spec.join("\n")).map_err(|e| eyre!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ranges_expand() {
+        assert_eq!(parse_port_list("22, 8000-8003").unwrap(), vec![22, 8000, 8001, 8002, 8003]);
+        assert_eq!(parse_port_list("443-443").unwrap(), vec![443]);
+        assert_eq!(parse_port_list("65534-65535").unwrap(), vec![65534, 65535]);
+    }
+
+    #[test]
+    fn test_duplicates_removed_and_sorted() {
+        assert_eq!(
+            parse_port_list("1883,80,22,80,8080-8082,8081").unwrap(),
+            vec![22, 80, 1883, 8080, 8081, 8082]
+        );
+    }
+
+    #[test]
+    fn test_invalid_entries_rejected() {
+        for spec in ["", ",", "0", "http", "70000", "100-50", "22,-5", "8000-"] {
+            assert!(parse_port_list(spec).is_err(), "{:?} should be rejected", spec);
+        }
+    }
+
+    #[test]
+    fn test_port_file_with_comments() {
+        let path = std::env::temp_dir().join(format!("wifi_analyzer_ports_{}.txt", std::process::id()));
+        std::fs::write(&path, "# home lab\n22\n1883  # MQTT\n5353,32400\n\n8000-8001\n").unwrap();
+        let ports = load_port_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(ports.unwrap(), vec![22, 1883, 5353, 8000, 8001, 32400]);
+    }
+}
@@ -1,30 +1,78 @@
-use crate::network_map::{Device, PortState, Protocol, ScanPhase, ScanProgress, Service, COMMON_PORTS};
+use crate::network_map::{CertificateInfo, Device, PortState, Protocol, ScanPhase, ScanProgress, Service, UDP_PORTS};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use color_eyre::eyre::eyre;
 use color_eyre::Result;
-use std::net::SocketAddr;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
 use std::time::Duration;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::TcpStream;
+use tokio::net::{TcpStream, UdpSocket};
 use tokio::sync::mpsc;
 use tokio::time::timeout;
+use tokio_rustls::rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use tokio_rustls::rustls::crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use tokio_rustls::rustls::{self, ClientConfig, DigitallySignedStruct, SignatureScheme};
+use tokio_rustls::TlsConnector;
 
 const CONNECT_TIMEOUT: Duration = Duration::from_millis(500);
 const BANNER_TIMEOUT: Duration = Duration::from_millis(1000);
 const MAX_CONCURRENT_PORTS: usize = 50;
 const MAX_CONCURRENT_DEVICES: usize = 10;
 
+/// Tuning knobs for port scanning
+#[derive(Debug, Clone, Copy)]
+pub struct PortScanConfig {
+    /// How long to wait for a TCP connect before treating the port as closed
+    pub connect_timeout: Duration,
+    /// How long to wait for a banner after connecting
+    pub banner_timeout: Duration,
+    /// Ports probed in parallel per device
+    pub max_concurrent_ports: usize,
+    /// Devices scanned in parallel
+    pub max_concurrent_devices: usize,
+    /// Also probe `UDP_PORTS` (replies are awaited for `banner_timeout`)
+    pub include_udp: bool,
+}
+
+impl Default for PortScanConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: CONNECT_TIMEOUT,
+            banner_timeout: BANNER_TIMEOUT,
+            max_concurrent_ports: MAX_CONCURRENT_PORTS,
+            max_concurrent_devices: MAX_CONCURRENT_DEVICES,
+            include_udp: false,
+        }
+    }
+}
+
+/// Ports probed on each device by `scan_devices_ports` given its TCP `ports`
+pub(super) fn ports_per_device(ports: &[u16], config: &PortScanConfig) -> usize {
+    ports.len() + if config.include_udp { UDP_PORTS.len() } else { 0 }
+}
+
+/// Scan `ports` (usually `COMMON_PORTS`) on every device
 pub async fn scan_devices_ports(
     devices: &mut [Device],
+    ports: &[u16],
     progress_tx: Option<mpsc::Sender<ScanProgress>>,
+    config: &PortScanConfig,
 ) -> Result<()> {
     let devices_count = devices.len();
-    let total_ports = COMMON_PORTS.len() * devices_count;
+    let ports_per_device = ports_per_device(ports, config);
+    let ports: Arc<[u16]> = ports.into();
+    let total_ports = ports_per_device * devices_count;
     let mut scanned = 0;
 
-    for chunk in devices.chunks_mut(MAX_CONCURRENT_DEVICES) {
+    for chunk in devices.chunks_mut(config.max_concurrent_devices.max(1)) {
         let mut handles = Vec::new();
         for device in chunk.iter() {
             let ip = device.ip_address.clone();
-            let handle = tokio::spawn(async move { scan_device_ports(&ip, COMMON_PORTS).await });
+            let config = *config;
+            let ports = ports.clone();
+            let handle = tokio::spawn(async move { scan_device_ports(&ip, &ports, &config).await });
             handles.push((device.mac_address.clone(), handle));
         }
 
@@ -34,7 +82,7 @@ pub async fn scan_devices_ports(
                     device.services = services;
                 }
             }
-            scanned += COMMON_PORTS.len();
+            scanned += ports_per_device;
             if let Some(ref tx) = progress_tx {
                 let _ = tx.send(ScanProgress {
                     phase: ScanPhase::PortScan,
@@ -42,6 +90,7 @@ pub async fn scan_devices_ports(
                     current_device: Some(mac),
                     ports_scanned: scanned,
                     total_ports,
+                    percent: None,
                 }).await;
             }
         }
@@ -49,13 +98,14 @@ pub async fn scan_devices_ports(
     Ok(())
 }
 
-async fn scan_device_ports(ip: &str, ports: &[u16]) -> Result<Vec<Service>> {
+pub(super) async fn scan_device_ports(ip: &str, ports: &[u16], config: &PortScanConfig) -> Result<Vec<Service>> {
     let mut services = Vec::new();
-    for chunk in ports.chunks(MAX_CONCURRENT_PORTS) {
+    for chunk in ports.chunks(config.max_concurrent_ports.max(1)) {
         let mut handles = Vec::new();
         for &port in chunk {
             let ip = ip.to_string();
-            let handle = tokio::spawn(async move { scan_port(&ip, port).await });
+            let config = *config;
+            let handle = tokio::spawn(async move { scan_port(&ip, port, &config).await });
             handles.push((port, handle));
         }
         for (_port, handle) in handles {
@@ -64,18 +114,135 @@ async fn scan_device_ports(ip: &str, ports: &[u16]) -> Result<Vec<Service>> {
             }
         }
     }
+    if config.include_udp {
+        services.extend(scan_device_udp_ports(ip, UDP_PORTS, config).await);
+    }
     Ok(services)
 }
 
-async fn scan_port(ip: &str, port: u16) -> Result<Option<Service>> {
+async fn scan_device_udp_ports(ip: &str, ports: &[u16], config: &PortScanConfig) -> Vec<Service> {
+    let mut handles = Vec::new();
+    for &port in ports {
+        let ip = ip.to_string();
+        let config = *config;
+        handles.push(tokio::spawn(async move { scan_udp_port(&ip, port, &config).await }));
+    }
+    let mut services = Vec::new();
+    for handle in handles {
+        if let Ok(Ok(Some(service))) = handle.await {
+            services.push(service);
+        }
+    }
+    services
+}
+
+/// Probe a UDP port with a protocol-appropriate datagram.
+/// A reply means open; silence is open|filtered and isn't reported,
+/// and an ICMP port-unreachable surfaces as a recv error (closed).
+pub async fn scan_udp_port(ip: &str, port: u16, config: &PortScanConfig) -> Result<Option<Service>> {
     let addr: SocketAddr = format!("{}:{}", ip, port).parse()?;
-    let connect_result = timeout(CONNECT_TIMEOUT, TcpStream::connect(addr)).await;
+    let bind_addr = if addr.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+    let socket = UdpSocket::bind(bind_addr).await?;
+    socket.connect(addr).await?;
+    socket.send(&udp_probe(port)).await?;
+
+    let mut buf = [0u8; 512];
+    match timeout(config.banner_timeout, socket.recv(&mut buf)).await {
+        Ok(Ok(n)) if n > 0 => {
+            // Only text protocols give a useful banner
+            let banner = if port == 1900 { printable_banner(&buf[..n]) } else { None };
+            Ok(Some(Service {
+                port,
+                protocol: Protocol::Udp,
+                state: PortState::Open,
+                service_name: Service::well_known_name(port).map(str::to_string),
+                banner,
+                detected_agent: None,
+                certificate: None,
+            }))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Build a probe datagram that elicits a reply from the service on `port`
+fn udp_probe(port: u16) -> Vec<u8> {
+    match port {
+        // DNS: standard query for the root NS records
+        53 => vec![
+            0x12, 0x34, 0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x02, 0x00, 0x01,
+        ],
+        // NTP: version 3 client request
+        123 => {
+            let mut packet = vec![0u8; 48];
+            packet[0] = 0x1B;
+            packet
+        }
+        // SNMP: v1 GetRequest for sysDescr.0 with community "public"
+        161 => vec![
+            0x30, 0x26, 0x02, 0x01, 0x00, 0x04, 0x06, b'p', b'u', b'b', b'l', b'i', b'c',
+            0xA0, 0x19, 0x02, 0x01, 0x01, 0x02, 0x01, 0x00, 0x02, 0x01, 0x00, 0x30, 0x0E,
+            0x30, 0x0C, 0x06, 0x08, 0x2B, 0x06, 0x01, 0x02, 0x01, 0x01, 0x01, 0x00, 0x05,
+            0x00,
+        ],
+        // SSDP: unicast M-SEARCH
+        1900 => b"M-SEARCH * HTTP/1.1\r\nHOST: 239.255.255.250:1900\r\nMAN: \"ssdp:discover\"\r\nMX: 1\r\nST: ssdp:all\r\n\r\n".to_vec(),
+        // mDNS: legacy unicast PTR query for _services._dns-sd._udp.local
+        5353 => {
+            let mut packet = vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+            for label in ["_services", "_dns-sd", "_udp", "local"] {
+                packet.push(label.len() as u8);
+                packet.extend_from_slice(label.as_bytes());
+            }
+            packet.extend_from_slice(&[0x00, 0x00, 0x0C, 0x00, 0x01]);
+            packet
+        }
+        _ => vec![0x00],
+    }
+}
+
+/// Keep the printable text of a raw response, collapsing whitespace runs.
+/// Mostly-binary responses (TLS records, RPC framing) yield nothing rather than noise.
+fn printable_banner(bytes: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(bytes);
+    let total = text.chars().count();
+    let printable = text
+        .chars()
+        .filter(|c| (!c.is_control() && *c != char::REPLACEMENT_CHARACTER) || c.is_ascii_whitespace())
+        .count();
+    // Text protocols are almost entirely printable; allow a little framing
+    if total == 0 || printable * 10 < total * 9 {
+        return None;
+    }
+    let banner = text
+        .split(|c: char| c.is_whitespace() || c.is_control() || c == char::REPLACEMENT_CHARACTER)
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(200)
+        .collect::<String>();
+    if banner.is_empty() { None } else { Some(banner) }
+}
+
+async fn scan_port(ip: &str, port: u16, config: &PortScanConfig) -> Result<Option<Service>> {
+    let addr: SocketAddr = format!("{}:{}", ip, port).parse()?;
+    let connect_result = timeout(config.connect_timeout, TcpStream::connect(addr)).await;
 
     match connect_result {
         Ok(Ok(mut stream)) => {
-            let banner = grab_banner(&mut stream, port).await.ok().flatten();
-            let service_name = identify_service(port, banner.as_deref());
-            let detected_agent = detect_agent(port, banner.as_deref());
+            let probe = BannerProbe::for_port(port);
+            let (banner, certificate) = if probe == BannerProbe::Tls {
+                let certificate = grab_certificate(stream, ip, config.banner_timeout).await;
+                (certificate.as_ref().and_then(|c| c.common_name.clone()), certificate)
+            } else {
+                (grab_banner(&mut stream, probe, config.banner_timeout).await.ok().flatten(), None)
+            };
+            // A certificate name says nothing about the protocol behind it
+            let text_banner = if probe == BannerProbe::Tls { None } else { banner.as_deref() };
+            let service_name = identify_service(port, text_banner);
+            let detected_agent = detect_agent(port, text_banner);
             Ok(Some(Service {
                 port,
                 protocol: Protocol::Tcp,
@@ -83,6 +250,7 @@ async fn scan_port(ip: &str, port: u16) -> Result<Option<Service>> {
                 service_name,
                 banner,
                 detected_agent,
+                certificate,
             }))
         }
         Ok(Err(_)) => Ok(None),
@@ -90,30 +258,257 @@ async fn scan_port(ip: &str, port: u16) -> Result<Option<Service>> {
     }
 }
 
-async fn grab_banner(stream: &mut TcpStream, port: u16) -> Result<Option<String>> {
+/// How to coax an identifying banner out of a freshly connected TCP port
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BannerProbe {
+    /// Send an HTTP request and read the response headers
+    Http,
+    /// Announce our SSH version; the server sends its own on connect
+    Ssh,
+    /// Complete a TLS handshake and report the certificate's common name
+    Tls,
+    /// Just listen; FTP, SMTP, Telnet and friends greet first
+    Passive,
+}
+
+impl BannerProbe {
+    fn for_port(port: u16) -> Self {
+        match port {
+            80 | 8080 | 8000 | 8001 | 3000 | 3001 | 8008 | 11434 | 18789 | 18793 => Self::Http,
+            22 | 2222 => Self::Ssh,
+            443 | 8443 | 5001 | 8009 | 465 | 636 | 993 | 995 => Self::Tls,
+            _ => Self::Passive,
+        }
+    }
+}
+
+const SSH_CLIENT_VERSION: &str = "SSH-2.0-wifi_analyzer\r\n";
+
+async fn grab_banner(stream: &mut TcpStream, probe: BannerProbe, banner_timeout: Duration) -> Result<Option<String>> {
+    let request: Option<&[u8]> = match probe {
+        BannerProbe::Http => Some(b"GET / HTTP/1.0\r\nHost: localhost\r\n\r\n"),
+        BannerProbe::Ssh => Some(SSH_CLIENT_VERSION.as_bytes()),
+        // scan_port reads certificates with grab_certificate instead
+        BannerProbe::Tls | BannerProbe::Passive => None,
+    };
+    if let Some(request) = request {
+        let _ = stream.write_all(request).await;
+    }
     let mut buf = [0u8; 256];
-    let probe = match port {
-        80 | 8080 | 8000 | 8001 | 3000 | 3001 | 8008 | 11434 | 18789 | 18793 => {
-            Some("GET / HTTP/1.0\r\nHost: localhost\r\n\r\n")
+    match timeout(banner_timeout, stream.read(&mut buf)).await {
+        Ok(Ok(n)) if n > 0 => Ok(printable_banner(&buf[..n])),
+        _ => Ok(None),
+    }
+}
+
+/// Rustls settings that finish a handshake whatever the certificate is
+fn tls_client_config() -> Arc<ClientConfig> {
+    static CONFIG: OnceLock<Arc<ClientConfig>> = OnceLock::new();
+    CONFIG
+        .get_or_init(|| {
+            let provider = Arc::new(rustls::crypto::ring::default_provider());
+            let config = ClientConfig::builder_with_provider(provider.clone())
+                .with_safe_default_protocol_versions()
+                .expect("ring supports the default protocol versions")
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(AcceptAnyCertificate(provider)))
+                .with_no_client_auth();
+            Arc::new(config)
+        })
+        .clone()
+}
+
+/// Trusts every certificate: the scan only reads it, and the self-signed or
+/// expired ones on home devices are exactly the ones worth reporting.
+/// Handshake signatures are still checked, so the peer must hold the key.
+#[derive(Debug)]
+struct AcceptAnyCertificate(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for AcceptAnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+/// Complete a TLS handshake (1.2 or 1.3) and parse the server's leaf
+/// certificate. Nothing is verified, so self-signed and expired
+/// certificates come back too.
+async fn grab_certificate(stream: TcpStream, ip: &str, banner_timeout: Duration) -> Option<CertificateInfo> {
+    // An IP address as the server name means no SNI is sent
+    let server_name = ServerName::try_from(ip.to_string()).ok()?;
+    let connector = TlsConnector::from(tls_client_config());
+    let tls = timeout(banner_timeout, connector.connect(server_name, stream)).await.ok()?.ok()?;
+    let (_, connection) = tls.get_ref();
+    parse_certificate(connection.peer_certificates()?.first()?.as_ref())
+}
+
+/// Split one DER element into (tag, contents, remainder)
+fn der_element(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, rest) = input.split_first()?;
+    let (&first, rest) = rest.split_first()?;
+    let (length, rest) = if first < 0x80 {
+        (first as usize, rest)
+    } else {
+        let count = (first & 0x7F) as usize;
+        if count == 0 || count > 4 || rest.len() < count {
+            return None;
         }
-        _ => None,
+        let length = rest[..count].iter().fold(0usize, |acc, &b| (acc << 8) | b as usize);
+        (length, &rest[count..])
     };
-    if let Some(probe) = probe {
-        let _ = stream.write_all(probe.as_bytes()).await;
+    if rest.len() < length {
+        return None;
     }
-    match timeout(BANNER_TIMEOUT, stream.read(&mut buf)).await {
-        Ok(Ok(n)) if n > 0 => {
-            let banner = String::from_utf8_lossy(&buf[..n])
-                .chars()
-                .filter(|c| c.is_ascii_graphic() || c.is_ascii_whitespace())
-                .take(200)
-                .collect::<String>()
-                .trim()
-                .to_string();
-            if banner.is_empty() { Ok(None) } else { Ok(Some(banner)) }
+    Some((tag, &rest[..length], &rest[length..]))
+}
+
+const DER_SEQUENCE: u8 = 0x30;
+const DER_OID: u8 = 0x06;
+const COMMON_NAME_OID: [u8; 3] = [0x55, 0x04, 0x03];
+const ORGANIZATION_OID: [u8; 3] = [0x55, 0x04, 0x0A];
+const SUBJECT_ALT_NAME_OID: [u8; 3] = [0x55, 0x1D, 0x11];
+
+/// Subject, alternative names, issuer and expiry of a DER-encoded X.509 certificate
+pub fn parse_certificate(der: &[u8]) -> Option<CertificateInfo> {
+    let (_, certificate, _) = der_element(der).filter(|(tag, _, _)| *tag == DER_SEQUENCE)?;
+    let (_, tbs, _) = der_element(certificate).filter(|(tag, _, _)| *tag == DER_SEQUENCE)?;
+
+    // version [0] (optional), serial, signature, issuer, validity, subject,
+    // subjectPublicKeyInfo, then the optional [1], [2] and [3] extensions
+    let mut fields = tbs;
+    let (tag, _, rest) = der_element(fields)?;
+    if tag == 0xA0 {
+        fields = rest;
+    }
+    for _ in 0..2 {
+        fields = der_element(fields)?.2;
+    }
+    let (_, issuer, fields) = der_element(fields)?;
+    let (_, validity, fields) = der_element(fields)?;
+    let (_, subject, fields) = der_element(fields).filter(|(tag, _, _)| *tag == DER_SEQUENCE)?;
+
+    let mut subject_alt_names = Vec::new();
+    let mut optional = der_element(fields).map_or(&[][..], |(_, _, rest)| rest);
+    while let Some((tag, contents, rest)) = der_element(optional) {
+        if tag == 0xA3 {
+            subject_alt_names = alt_names(contents);
         }
-        _ => Ok(None),
+        optional = rest;
+    }
+
+    // Validity ::= SEQUENCE { notBefore Time, notAfter Time }
+    let not_after = der_element(validity)
+        .and_then(|(_, _, rest)| der_element(rest))
+        .and_then(|(tag, time, _)| der_time(tag, time));
+
+    Some(CertificateInfo {
+        common_name: name_attribute(subject, &COMMON_NAME_OID),
+        subject_alt_names,
+        issuer: name_attribute(issuer, &COMMON_NAME_OID).or_else(|| name_attribute(issuer, &ORGANIZATION_OID)),
+        not_after,
+    })
+}
+
+/// First attribute of type `oid` in a Name
+fn name_attribute(mut name: &[u8], oid: &[u8]) -> Option<String> {
+    // Name ::= SEQUENCE OF SET OF SEQUENCE { type OID, value string }
+    while let Some((_, set, rest)) = der_element(name) {
+        let mut attributes = set;
+        while let Some((_, attribute, next)) = der_element(attributes) {
+            if let Some((DER_OID, attribute_oid, value)) = der_element(attribute)
+                && attribute_oid == oid
+                && let Some((_, value, _)) = der_element(value)
+            {
+                return printable_banner(value);
+            }
+            attributes = next;
+        }
+        name = rest;
+    }
+    None
+}
+
+/// DNS names and IP addresses from the subjectAltName extension, given the
+/// contents of the [3] extensions field
+fn alt_names(extensions: &[u8]) -> Vec<String> {
+    let mut names = Vec::new();
+    let Some((_, mut extensions, _)) = der_element(extensions) else {
+        return names;
+    };
+    // Extension ::= SEQUENCE { extnID OID, critical BOOLEAN (optional), extnValue OCTET STRING }
+    while let Some((_, extension, rest)) = der_element(extensions) {
+        if let Some((DER_OID, oid, mut value)) = der_element(extension)
+            && oid == SUBJECT_ALT_NAME_OID
+        {
+            if let Some((0x01, _, after_critical)) = der_element(value) {
+                value = after_critical;
+            }
+            if let Some((0x04, octets, _)) = der_element(value)
+                && let Some((_, mut general_names, _)) = der_element(octets)
+            {
+                while let Some((tag, general_name, next)) = der_element(general_names) {
+                    match tag {
+                        // dNSName [2]
+                        0x82 => names.extend(printable_banner(general_name)),
+                        // iPAddress [7]
+                        0x87 => match general_name.len() {
+                            4 => names.push(Ipv4Addr::from(<[u8; 4]>::try_from(general_name).unwrap()).to_string()),
+                            16 => names.push(Ipv6Addr::from(<[u8; 16]>::try_from(general_name).unwrap()).to_string()),
+                            _ => {}
+                        },
+                        _ => {}
+                    }
+                    general_names = next;
+                }
+            }
+        }
+        extensions = rest;
     }
+    names
+}
+
+/// A UTCTime (YYMMDDHHMMSSZ) or GeneralizedTime (YYYYMMDDHHMMSSZ)
+fn der_time(tag: u8, value: &[u8]) -> Option<DateTime<Utc>> {
+    let text = std::str::from_utf8(value).ok()?;
+    let full = match tag {
+        // RFC 5280: two-digit years from 50 are 19xx, below are 20xx
+        0x17 => {
+            let year: u32 = text.get(..2)?.parse().ok()?;
+            format!("{}{}", if year >= 50 { "19" } else { "20" }, text)
+        }
+        0x18 => text.to_string(),
+        _ => return None,
+    };
+    NaiveDateTime::parse_from_str(&full, "%Y%m%d%H%M%SZ").ok().map(|time| time.and_utc())
 }
 
 fn identify_service(port: u16, banner: Option<&str>) -> Option<String> {
@@ -127,22 +522,7 @@ fn identify_service(port: u16, banner: Option<&str>) -> Option<String> {
         if banner_lower.contains("openclaw") { return Some("OpenClaw".to_string()); }
     }
     match port {
-        21 => Some("FTP".to_string()),
-        22 => Some("SSH".to_string()),
-        23 => Some("Telnet".to_string()),
-        25 => Some("SMTP".to_string()),
-        53 => Some("DNS".to_string()),
-        80 => Some("HTTP".to_string()),
-        443 => Some("HTTPS".to_string()),
-        139 | 445 => Some("SMB".to_string()),
-        548 => Some("AFP".to_string()),
-        554 => Some("RTSP".to_string()),
-        3389 => Some("RDP".to_string()),
-        5000 | 5001 => Some("Synology".to_string()),
-        8080 | 8443 => Some("HTTP Alt".to_string()),
-        9100 => Some("Printer".to_string()),
-        62078 => Some("Apple Device".to_string()),
-        8008 | 8009 => Some("Chromecast".to_string()),
+        // AI agent and dev server ports, which IANA assigns to unrelated services
         11434 => Some("Ollama".to_string()),
         9229 => Some("Node Debug".to_string()),
         8501 => Some("Streamlit".to_string()),
@@ -150,7 +530,7 @@ fn identify_service(port: u16, banner: Option<&str>) -> Option<String> {
         8000 | 8001 => Some("Python Server".to_string()),
         18789 => Some("OpenClaw Gateway".to_string()),
         18793 => Some("OpenClaw Canvas".to_string()),
-        _ => None,
+        _ => Service::well_known_name(port).map(str::to_string),
     }
 }
 
@@ -194,18 +574,26 @@ fn detect_agent(port: u16, banner: Option<&str>) -> Option<String> {
     }
 }
 
-#[allow(dead_code)]
+/// Scan every TCP port (1-65535) on a single device, reporting progress per chunk.
+/// Setting `cancel` stops the scan between chunks and leaves `device` untouched.
 pub async fn deep_scan_device(
     device: &mut Device,
     progress_tx: Option<mpsc::Sender<ScanProgress>>,
+    config: &PortScanConfig,
+    cancel: &AtomicBool,
 ) -> Result<()> {
     let all_ports: Vec<u16> = (1..=65535).collect();
     let total_ports = all_ports.len();
     let mut scanned = 0;
     let mut services = Vec::new();
+    // UDP is probed once at the end rather than once per chunk
+    let tcp_config = PortScanConfig { include_udp: false, ..*config };
 
     for chunk in all_ports.chunks(2000) {
-        let chunk_services = scan_device_ports(&device.ip_address, chunk).await?;
+        if cancel.load(Ordering::Relaxed) {
+            return Err(eyre!("deep scan cancelled"));
+        }
+        let chunk_services = scan_device_ports(&device.ip_address, chunk, &tcp_config).await?;
         services.extend(chunk_services);
         scanned += chunk.len();
         if let Some(ref tx) = progress_tx {
@@ -215,9 +603,160 @@ pub async fn deep_scan_device(
                 current_device: Some(device.ip_address.clone()),
                 ports_scanned: scanned,
                 total_ports,
+                percent: None,
             }).await;
         }
     }
+    if config.include_udp {
+        services.extend(scan_device_udp_ports(&device.ip_address, UDP_PORTS, config).await);
+    }
+    if cancel.load(Ordering::Relaxed) {
+        return Err(eyre!("deep scan cancelled"));
+    }
     device.services = services;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn test_short_timeout_returns_quickly() {
+        let config = PortScanConfig {
+            connect_timeout: Duration::from_millis(20),
+            banner_timeout: Duration::from_millis(20),
+            ..PortScanConfig::default()
+        };
+
+        // TEST-NET-1 (RFC 5737) is never routable, so every port is unreachable
+        let start = Instant::now();
+        let services = scan_device_ports("192.0.2.1", &[22, 80, 443, 8080], &config)
+            .await
+            .unwrap();
+
+        assert!(services.is_empty());
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_identify_service() {
+        // Banners win over the port table
+        assert_eq!(identify_service(8080, Some("SSH-2.0-OpenSSH_9.6")).as_deref(), Some("SSH"));
+        // AI agent ports keep their own names
+        assert_eq!(identify_service(11434, None).as_deref(), Some("Ollama"));
+        assert_eq!(identify_service(631, None).as_deref(), Some("IPP/CUPS"));
+    }
+
+    #[test]
+    fn test_banner_probe_per_port() {
+        assert_eq!(BannerProbe::for_port(80), BannerProbe::Http);
+        assert_eq!(BannerProbe::for_port(11434), BannerProbe::Http);
+        assert_eq!(BannerProbe::for_port(22), BannerProbe::Ssh);
+        assert_eq!(BannerProbe::for_port(443), BannerProbe::Tls);
+        assert_eq!(BannerProbe::for_port(8443), BannerProbe::Tls);
+        assert_eq!(BannerProbe::for_port(21), BannerProbe::Passive);
+        assert_eq!(BannerProbe::for_port(25), BannerProbe::Passive);
+    }
+
+    #[test]
+    fn test_printable_banner_rejects_binary() {
+        assert_eq!(
+            printable_banner(b"220 mail.example.com ESMTP Postfix\r\n").as_deref(),
+            Some("220 mail.example.com ESMTP Postfix")
+        );
+        // A TLS alert record is not a banner
+        assert_eq!(printable_banner(&[0x15, 0x03, 0x03, 0x00, 0x02, 0x02, 0x28]), None);
+        assert_eq!(printable_banner(b"  \r\n"), None);
+    }
+
+    /// DER for a minimal certificate with the given subject CN
+    fn fake_certificate(common_name: &str) -> Vec<u8> {
+        fn element(tag: u8, contents: &[u8]) -> Vec<u8> {
+            let mut out = vec![tag];
+            if contents.len() < 0x80 {
+                out.push(contents.len() as u8);
+            } else {
+                out.extend_from_slice(&[0x82, (contents.len() >> 8) as u8, contents.len() as u8]);
+            }
+            out.extend_from_slice(contents);
+            out
+        }
+        let name = |cn: &str| {
+            let mut attribute = element(0x06, &[0x55, 0x04, 0x03]);
+            attribute.extend(element(0x0C, cn.as_bytes()));
+            element(0x30, &element(0x31, &element(0x30, &attribute)))
+        };
+        let mut tbs = element(0xA0, &element(0x02, &[0x02]));
+        tbs.extend(element(0x02, &[0x01]));
+        tbs.extend(element(0x30, &element(0x06, &[0x2A, 0x86, 0x48])));
+        tbs.extend(name("Example Issuing CA"));
+        tbs.extend(element(0x30, &[]));
+        tbs.extend(name(common_name));
+        element(0x30, &element(0x30, &tbs))
+    }
+
+    #[test]
+    fn test_fake_certificate_parses() {
+        let der = fake_certificate("router.local");
+        assert_eq!(parse_certificate(&der).unwrap().common_name.as_deref(), Some("router.local"));
+    }
+
+    #[tokio::test]
+    async fn test_grab_certificate_from_plain_text_server() {
+        // A service that greets in plain text on a TLS port gives no certificate, promptly
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let _ = socket.write_all(b"220 mail.example.com ESMTP\r\n").await;
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+
+        let stream = TcpStream::connect(addr).await.unwrap();
+        let start = Instant::now();
+        assert_eq!(grab_certificate(stream, "127.0.0.1", Duration::from_secs(2)).await, None);
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_parse_certificate_fixture() {
+        // CA-signed leaf: subject O=Example Printers, CN=printer.local; issuer C=US, O=Example Home Lab
+        let der = include_bytes!("../../tests/fixtures/printer_cert.der");
+        let info = parse_certificate(der).unwrap();
+        assert_eq!(info.common_name.as_deref(), Some("printer.local"));
+        assert_eq!(info.subject_alt_names, vec!["printer.local", "printer", "192.168.1.50"]);
+        // No issuer CN, so the organization stands in
+        assert_eq!(info.issuer.as_deref(), Some("Example Home Lab"));
+        let not_after = DateTime::parse_from_rfc3339("2027-03-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(info.not_after, Some(not_after));
+        assert!(!info.is_expired(not_after - chrono::Duration::days(1)));
+        assert!(info.is_expired(not_after + chrono::Duration::days(1)));
+
+        assert!(parse_certificate(&der[..100]).is_none());
+    }
+
+    #[test]
+    fn test_der_time_formats() {
+        let utc = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        assert_eq!(der_time(0x17, b"491231235959Z"), Some(utc("2049-12-31T23:59:59Z")));
+        assert_eq!(der_time(0x17, b"500101000000Z"), Some(utc("1950-01-01T00:00:00Z")));
+        assert_eq!(der_time(0x18, b"20991231235959Z"), Some(utc("2099-12-31T23:59:59Z")));
+        assert_eq!(der_time(0x17, b"garbage"), None);
+    }
+
+    #[test]
+    fn test_udp_probes_are_well_formed() {
+        let snmp = udp_probe(161);
+        assert_eq!(snmp[1] as usize + 2, snmp.len());
+
+        let dns = udp_probe(53);
+        assert_eq!(&dns[4..6], &[0x00, 0x01]); // one question
+
+        let mdns = udp_probe(5353);
+        assert!(mdns.ends_with(&[0x05, b'l', b'o', b'c', b'a', b'l', 0x00, 0x00, 0x0C, 0x00, 0x01]));
+
+        assert_eq!(udp_probe(123).len(), 48);
+    }
+}
@@ -0,0 +1,78 @@
+/// How much an open port is worth a second look
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PortRisk {
+    Neutral,
+    /// Remote administration: fine when intended, worth knowing about
+    Management,
+    /// Plaintext logins or commonly exploited file/desktop sharing
+    Risky,
+}
+
+impl PortRisk {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PortRisk::Neutral => "",
+            PortRisk::Management => "management",
+            PortRisk::Risky => "risky",
+        }
+    }
+}
+
+/// Plaintext logins (FTP, Telnet, TFTP) and services often exploited when
+/// exposed (NetBIOS, SMB, RDP)
+const RISKY_PORTS: &[u16] = &[20, 21, 23, 69, 139, 445, 3389];
+
+/// Remote administration: SSH, SNMP, IPMI, VNC, WinRM, MikroTik Winbox,
+/// Cockpit, Webmin and alternate admin HTTPS
+const MANAGEMENT_PORTS: &[u16] = &[22, 161, 623, 2222, 5900, 5985, 5986, 8291, 8443, 9090, 10000];
+
+/// Classify an open port. The banner catches Telnet and FTP servers moved
+/// to a non-standard port, which are as risky as on their usual one.
+pub fn port_risk(port: u16, banner: Option<&str>) -> PortRisk {
+    if RISKY_PORTS.contains(&port) {
+        return PortRisk::Risky;
+    }
+    if let Some(banner) = banner {
+        let lower = banner.to_lowercase();
+        if (lower.starts_with("220") && lower.contains("ftp")) || lower.contains("telnet") {
+            return PortRisk::Risky;
+        }
+        if lower.starts_with("ssh-") || lower.starts_with("rfb ") {
+            return PortRisk::Management;
+        }
+    }
+    if MANAGEMENT_PORTS.contains(&port) {
+        PortRisk::Management
+    } else {
+        PortRisk::Neutral
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_well_known_ports() {
+        for port in [21, 23, 445, 3389] {
+            assert_eq!(port_risk(port, None), PortRisk::Risky, "port {}", port);
+        }
+        for port in [22, 161, 5900, 8443] {
+            assert_eq!(port_risk(port, None), PortRisk::Management, "port {}", port);
+        }
+        for port in [53, 80, 443, 631, 11434] {
+            assert_eq!(port_risk(port, None), PortRisk::Neutral, "port {}", port);
+        }
+    }
+
+    #[test]
+    fn test_banner_overrides_port() {
+        assert_eq!(port_risk(2121, Some("220 ProFTPD Server ready")), PortRisk::Risky);
+        assert_eq!(port_risk(2323, Some("BusyBox telnetd")), PortRisk::Risky);
+        assert_eq!(port_risk(2200, Some("SSH-2.0-OpenSSH_9.6")), PortRisk::Management);
+        assert_eq!(port_risk(5901, Some("RFB 003.008")), PortRisk::Management);
+        // A banner never lowers the risk of a risky port
+        assert_eq!(port_risk(21, Some("SSH-2.0-odd")), PortRisk::Risky);
+        assert_eq!(port_risk(80, Some("HTTP/1.1 200 OK")), PortRisk::Neutral);
+    }
+}
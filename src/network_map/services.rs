@@ -0,0 +1,187 @@
+use crate::network_map::oui::{default_data_file, split_csv_line};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// User-configured path to the IANA service names CSV (set before the first lookup)
+static SERVICES_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Use an IANA service-names CSV at this path instead of the default location.
+/// Must be called before the first `lookup_service_name`.
+pub fn set_services_file(path: PathBuf) {
+    let _ = SERVICES_FILE.set(path);
+}
+
+/// Best-effort name for the service usually found on a port.
+/// Built-in names are friendlier, so they win over the IANA file.
+pub fn lookup_service_name(port: u16) -> Option<&'static str> {
+    if let Some(name) = get_services_database().get(&port).copied() {
+        return Some(name);
+    }
+    get_external_services_database().get(&port).map(String::as_str)
+}
+
+fn get_services_database() -> &'static HashMap<u16, &'static str> {
+    static SERVICES_DB: OnceLock<HashMap<u16, &'static str>> = OnceLock::new();
+
+    SERVICES_DB.get_or_init(|| {
+        HashMap::from([
+            // Core internet services
+            (20, "FTP Data"),
+            (21, "FTP"),
+            (22, "SSH"),
+            (23, "Telnet"),
+            (25, "SMTP"),
+            (53, "DNS"),
+            (67, "DHCP"),
+            (69, "TFTP"),
+            (80, "HTTP"),
+            (88, "Kerberos"),
+            (110, "POP3"),
+            (123, "NTP"),
+            (143, "IMAP"),
+            (161, "SNMP"),
+            (389, "LDAP"),
+            (443, "HTTPS"),
+            (465, "SMTPS"),
+            (514, "Syslog"),
+            (587, "SMTP Submission"),
+            (636, "LDAPS"),
+            (853, "DNS over TLS"),
+            (993, "IMAPS"),
+            (995, "POP3S"),
+            (1194, "OpenVPN"),
+            (1723, "PPTP"),
+            (1883, "MQTT"),
+            (8883, "MQTT over TLS"),
+            (51820, "WireGuard"),
+            // File sharing and remote access
+            (111, "RPC"),
+            (135, "MS RPC"),
+            (137, "NetBIOS Name"),
+            (139, "SMB"),
+            (445, "SMB"),
+            (548, "AFP"),
+            (2049, "NFS"),
+            (3389, "RDP"),
+            (5900, "VNC"),
+            (5985, "WinRM"),
+            (5986, "WinRM over TLS"),
+            // Printing and scanning
+            (515, "LPD"),
+            (631, "IPP/CUPS"),
+            (9100, "Printer"),
+            // Discovery
+            (1900, "SSDP/UPnP"),
+            (3702, "WS-Discovery"),
+            (5353, "mDNS"),
+            (5355, "LLMNR"),
+            // Media and smart home
+            (554, "RTSP"),
+            (1400, "Sonos"),
+            (3689, "DAAP/iTunes"),
+            (5000, "Synology"),
+            (5001, "Synology"),
+            (7000, "AirPlay"),
+            (8008, "Chromecast"),
+            (8009, "Chromecast"),
+            (8123, "Home Assistant"),
+            (8200, "DLNA"),
+            (32400, "Plex"),
+            (49152, "UPnP"),
+            (62078, "Apple Device"),
+            // Databases and web apps
+            (1433, "MS SQL"),
+            (3306, "MySQL"),
+            (5432, "PostgreSQL"),
+            (6379, "Redis"),
+            (8080, "HTTP Alt"),
+            (8443, "HTTP Alt"),
+            (9000, "Portainer"),
+            (9090, "Prometheus"),
+            (27017, "MongoDB"),
+        ])
+    })
+}
+
+/// Service names from the IANA CSV, loaded once from `--services-file` or
+/// `$XDG_DATA_HOME/wifi-analyzer/service-names-port-numbers.csv` (empty if no file is present)
+fn get_external_services_database() -> &'static HashMap<u16, String> {
+    static EXTERNAL_DB: OnceLock<HashMap<u16, String>> = OnceLock::new();
+
+    EXTERNAL_DB.get_or_init(|| {
+        let path = SERVICES_FILE
+            .get()
+            .cloned()
+            .or_else(|| default_data_file("service-names-port-numbers.csv"));
+        path.and_then(|p| std::fs::read_to_string(p).ok())
+            .map(|contents| parse_service_names_csv(&contents))
+            .unwrap_or_default()
+    })
+}
+
+/// Parse the IANA service names CSV ("Service Name,Port Number,Transport Protocol,Description,...")
+/// into a map of port to uppercased service name. TCP entries win over other
+/// protocols; port ranges and unnamed (reserved) rows are skipped.
+pub fn parse_service_names_csv(contents: &str) -> HashMap<u16, String> {
+    let mut map: HashMap<u16, String> = HashMap::new();
+    let mut from_tcp = HashSet::new();
+
+    for line in contents.lines() {
+        let fields = split_csv_line(line);
+        let (Some(name), Some(port), Some(protocol)) = (fields.first(), fields.get(1), fields.get(2)) else {
+            continue;
+        };
+        let name = name.trim();
+        // Skips the header row and ranges like "6000-6063"
+        let Ok(port) = port.trim().parse::<u16>() else {
+            continue;
+        };
+        if name.is_empty() {
+            continue;
+        }
+
+        let is_tcp = protocol.trim().eq_ignore_ascii_case("tcp");
+        if from_tcp.contains(&port) || (map.contains_key(&port) && !is_tcp) {
+            continue;
+        }
+        map.insert(port, name.to_uppercase());
+        if is_tcp {
+            from_tcp.insert(port);
+        }
+    }
+
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_from_table() {
+        assert_eq!(lookup_service_name(631), Some("IPP/CUPS"));
+        assert_eq!(lookup_service_name(22), Some("SSH"));
+    }
+
+    #[test]
+    fn test_parse_service_names_csv() {
+        let csv = "Service Name,Port Number,Transport Protocol,Description,Assignee,Contact\n\
+                   ,0,tcp,Reserved,,\n\
+                   x11,6000-6063,tcp,X Window System,,\n\
+                   ipp,631,tcp,IPP (Internet Printing Protocol),,\n\
+                   ipp,631,udp,IPP (Internet Printing Protocol),,\n\
+                   syslog,514,udp,,,\n\
+                   shell,514,tcp,\"cmd, like exec\",,\n\
+                   sctp-only,9902,sctp,,,\n";
+
+        let map = parse_service_names_csv(csv);
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get(&631).map(String::as_str), Some("IPP"));
+        // TCP entries replace names from other protocols
+        assert_eq!(map.get(&514).map(String::as_str), Some("SHELL"));
+        assert_eq!(map.get(&9902).map(String::as_str), Some("SCTP-ONLY"));
+        assert!(!map.contains_key(&0));
+        assert!(!map.contains_key(&6000));
+    }
+}
@@ -1,7 +1,8 @@
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
 /// A device discovered on the network
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Device {
     pub mac_address: String,
     pub ip_address: String,
@@ -9,11 +10,23 @@ pub struct Device {
     pub vendor: Option<String>,
     pub device_type: DeviceType,
     pub custom_name: Option<String>,
+    /// Free-form note, e.g. "kid's tablet"
+    pub notes: Option<String>,
+    /// Tags without the leading '#', e.g. "trusted"
+    pub tags: Vec<String>,
     pub first_seen: DateTime<Utc>,
     pub last_seen: DateTime<Utc>,
     pub is_online: bool,
     pub services: Vec<Service>,
     pub detected_agents: Vec<String>,
+    /// TTL from an ICMP echo reply (only set after a ping sweep)
+    pub ttl: Option<u8>,
+    /// Coarse OS guess from banners, port fingerprint and TTL
+    pub os_guess: Option<String>,
+    /// Earlier randomized MACs merged into this device (see `merge_randomized_macs`)
+    pub mac_aliases: Vec<String>,
+    /// Set when the ARP cache disagrees about who owns this device's IP
+    pub ip_conflict: Option<IpConflict>,
 }
 
 impl Device {
@@ -26,14 +39,25 @@ impl Device {
             vendor: None,
             device_type: DeviceType::Unknown,
             custom_name: None,
+            notes: None,
+            tags: Vec::new(),
             first_seen: now,
             last_seen: now,
             is_online: true,
             services: Vec::new(),
             detected_agents: Vec::new(),
+            ttl: None,
+            os_guess: None,
+            mac_aliases: Vec::new(),
+            ip_conflict: None,
         }
     }
 
+    /// Tags as typed, e.g. "#trusted #kids"
+    pub fn tags_text(&self) -> String {
+        self.tags.iter().map(|tag| format!("#{}", tag)).collect::<Vec<_>>().join(" ")
+    }
+
     /// Get display name (custom name > hostname > vendor + type > MAC)
     pub fn display_name(&self) -> String {
         if let Some(ref name) = self.custom_name {
@@ -49,8 +73,43 @@ impl Device {
     }
 }
 
-/// Device type inferred from ports and vendor
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// ARP cache entries that contradict each other: a misconfigured static
+/// IP, a DHCP clash, or someone spoofing ARP replies
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IpConflict {
+    /// Other MACs claiming this device's IP
+    pub other_macs: Vec<String>,
+    /// Other IPs this device's MAC also answers for
+    pub other_ips: Vec<String>,
+}
+
+impl IpConflict {
+    pub fn is_empty(&self) -> bool {
+        self.other_macs.is_empty() && self.other_ips.is_empty()
+    }
+
+    /// One sentence per kind of conflict, for the device detail view
+    pub fn explanations(&self, ip: &str) -> Vec<String> {
+        let mut lines = Vec::new();
+        if !self.other_macs.is_empty() {
+            lines.push(format!(
+                "{} is also claimed by {}: a duplicate static IP, or ARP spoofing",
+                ip,
+                self.other_macs.join(", ")
+            ));
+        }
+        if !self.other_ips.is_empty() {
+            lines.push(format!(
+                "This MAC also answers for {}: a router or bridge doing proxy ARP, or a spoofer",
+                self.other_ips.join(", ")
+            ));
+        }
+        lines
+    }
+}
+
+/// Device type inferred from ports and vendor, serialized by variant name (e.g. "SmartTV")
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum DeviceType {
     Router,
     Phone,
@@ -85,7 +144,7 @@ impl std::fmt::Display for DeviceType {
 }
 
 /// A service/port discovered on a device
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Service {
     pub port: u16,
     pub protocol: Protocol,
@@ -93,9 +152,43 @@ pub struct Service {
     pub service_name: Option<String>,
     pub banner: Option<String>,
     pub detected_agent: Option<String>,
+    /// Server certificate, for TLS services
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub certificate: Option<CertificateInfo>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl Service {
+    /// Best-effort name for whatever usually listens on `port`
+    pub fn well_known_name(port: u16) -> Option<&'static str> {
+        crate::network_map::lookup_service_name(port)
+    }
+
+    /// Name detected during the scan, else the port's well-known name
+    pub fn display_name(&self) -> Option<&str> {
+        self.service_name.as_deref().or_else(|| Self::well_known_name(self.port))
+    }
+}
+
+/// The parts of a TLS server certificate worth showing
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CertificateInfo {
+    /// Subject common name
+    pub common_name: Option<String>,
+    /// DNS names and IP addresses from the subjectAltName extension
+    pub subject_alt_names: Vec<String>,
+    /// Issuer common name, or its organization when it has none
+    pub issuer: Option<String>,
+    pub not_after: Option<DateTime<Utc>>,
+}
+
+impl CertificateInfo {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.not_after.is_some_and(|not_after| not_after < now)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Protocol {
     Tcp,
     Udp,
@@ -110,7 +203,8 @@ impl std::fmt::Display for Protocol {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum PortState {
     Open,
     Closed,
@@ -123,8 +217,12 @@ pub struct ScanProgress {
     pub phase: ScanPhase,
     pub devices_found: usize,
     pub current_device: Option<String>,
+    /// Ports scanned so far (addresses pinged during a discovery ping sweep)
     pub ports_scanned: usize,
+    /// Ports to scan (addresses to ping during a discovery ping sweep)
     pub total_ports: usize,
+    /// Progress across all phases, when discovery and port scanning overlap
+    pub percent: Option<u8>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -179,3 +277,90 @@ pub const COMMON_PORTS: &[u16] = &[
     18789, // OpenClaw Gateway WebSocket
     18793, // OpenClaw Canvas HTTP
 ];
+
+/// Common UDP services probed when UDP scanning is enabled
+pub const UDP_PORTS: &[u16] = &[
+    53,   // DNS
+    123,  // NTP
+    161,  // SNMP
+    1900, // SSDP/UPnP
+    5353, // mDNS
+];
+
+/// Split tag input like "#trusted, kids #Trusted" into distinct tags
+/// without the '#' ("trusted", "kids"); the first spelling of a tag wins
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.split(|c: char| c == ',' || c.is_whitespace()) {
+        let tag = tag.trim_start_matches('#');
+        if !tag.is_empty() && !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::DeserializeOwned;
+    use std::fmt::Debug;
+
+    fn assert_round_trip<T: Serialize + DeserializeOwned + PartialEq + Debug>(value: T, json: &str) {
+        assert_eq!(serde_json::to_string(&value).unwrap(), json);
+        assert_eq!(serde_json::from_str::<T>(json).unwrap(), value);
+    }
+
+    #[test]
+    fn test_device_type_serde() {
+        assert_round_trip(DeviceType::Router, "\"Router\"");
+        assert_round_trip(DeviceType::SmartTV, "\"SmartTV\"");
+        assert_round_trip(DeviceType::NAS, "\"NAS\"");
+        assert_round_trip(DeviceType::IoT, "\"IoT\"");
+        assert_round_trip(DeviceType::GameConsole, "\"GameConsole\"");
+        assert_round_trip(DeviceType::Unknown, "\"Unknown\"");
+    }
+
+    #[test]
+    fn test_protocol_and_port_state_serde() {
+        assert_round_trip(Protocol::Tcp, "\"tcp\"");
+        assert_round_trip(Protocol::Udp, "\"udp\"");
+        assert_round_trip(PortState::Open, "\"open\"");
+        assert_round_trip(PortState::Closed, "\"closed\"");
+        assert_round_trip(PortState::Filtered, "\"filtered\"");
+    }
+
+    #[test]
+    fn test_device_serde_round_trip() {
+        let mut device = Device::new("AA:BB:CC:DD:EE:FF".to_string(), "192.168.1.20".to_string());
+        device.device_type = DeviceType::Printer;
+        device.services.push(Service {
+            port: 631,
+            protocol: Protocol::Tcp,
+            state: PortState::Open,
+            service_name: Some("IPP/CUPS".to_string()),
+            banner: None,
+            detected_agent: None,
+            certificate: None,
+        });
+
+        let json = serde_json::to_string(&device).unwrap();
+        let parsed: Device = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.mac_address, device.mac_address);
+        assert_eq!(parsed.device_type, DeviceType::Printer);
+        assert_eq!(parsed.first_seen, device.first_seen);
+        assert_eq!(parsed.services.len(), 1);
+        assert_eq!(parsed.services[0].protocol, Protocol::Tcp);
+        assert_eq!(parsed.services[0].state, PortState::Open);
+    }
+
+    #[test]
+    fn test_parse_tags() {
+        assert_eq!(parse_tags("#trusted, kids  #Trusted ##iot"), ["trusted", "kids", "iot"]);
+        assert!(parse_tags(" , # ").is_empty());
+
+        let mut device = Device::new("AA:BB:CC:DD:EE:FF".to_string(), "192.168.1.5".to_string());
+        device.tags = parse_tags("trusted kids");
+        assert_eq!(device.tags_text(), "#trusted #kids");
+    }
+}
@@ -0,0 +1,360 @@
+//! Anonymize exports before sharing them
+//!
+//! SSIDs, hostnames and location names become short salted hashes
+//! ("net_3fa91c") and the device-specific half of every MAC address is
+//! replaced, keeping the vendor OUI. Service banners, which can carry a
+//! certificate's common name, are dropped, and so are the names in TLS
+//! certificates. Signal, channel, scores, speeds
+//! and timestamps are left alone. The same input and salt always give the same output, so rows
+//! that refer to the same network still line up after redaction.
+
+use crate::db::Snapshot;
+use crate::mac::canonical_mac;
+use crate::network_map::{CertificateInfo, Device};
+use crate::scanner::{Network, HIDDEN_SSID};
+
+/// Salt used when --salt isn't given. Anyone can hash a guessed SSID with
+/// it, so pass your own salt when the names themselves are sensitive.
+pub const DEFAULT_SALT: &str = "wifi-analyzer";
+
+/// How a snapshot column is anonymized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Redaction {
+    Ssid,
+    Mac,
+    Hostname,
+    Location,
+    /// Free text or addresses that identify the owner: replaced with null
+    Drop,
+}
+
+/// Snapshot columns that identify people or places, by (table, column)
+const SNAPSHOT_REDACTIONS: &[(&str, &str, Redaction)] = &[
+    ("locations", "name", Redaction::Location),
+    ("networks", "bssid", Redaction::Mac),
+    ("networks", "ssid", Redaction::Ssid),
+    ("networks", "notes", Redaction::Drop),
+    ("connections", "public_ip", Redaction::Drop),
+    ("connections", "public_ipv6", Redaction::Drop),
+    // Only globally routable IPv6 addresses are recorded, so this carries the same prefix
+    ("connections", "local_ipv6", Redaction::Drop),
+    ("known_networks", "ssid", Redaction::Ssid),
+    ("devices", "mac_address", Redaction::Mac),
+    ("devices", "hostname", Redaction::Hostname),
+    ("devices", "custom_name", Redaction::Drop),
+    ("devices", "notes", Redaction::Drop),
+    ("devices", "tags", Redaction::Drop),
+    ("devices", "network_bssid", Redaction::Mac),
+    ("device_services", "banner", Redaction::Drop),
+    ("device_scans", "network_bssid", Redaction::Mac),
+    ("roam_events", "ssid", Redaction::Ssid),
+    ("roam_events", "from_bssid", Redaction::Mac),
+    ("roam_events", "to_bssid", Redaction::Mac),
+];
+
+/// 64-bit FNV-1a of salt and value. Written out rather than using std's
+/// hasher, whose output may change between Rust releases.
+fn salted_hash(salt: &str, value: &str) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let bytes = salt.bytes().chain(std::iter::once(0)).chain(value.bytes());
+    for byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    // Fold the high half in; the low bits of FNV alone mix poorly
+    hash ^ (hash >> 32)
+}
+
+fn short_hash(prefix: &str, salt: &str, value: &str) -> String {
+    format!("{}_{:06x}", prefix, salted_hash(salt, value) & 0xff_ffff)
+}
+
+/// "net_" plus a salted hash. Hidden and empty SSIDs carry no name and are kept.
+pub fn redact_ssid(ssid: &str, salt: &str) -> String {
+    if ssid == HIDDEN_SSID || ssid.is_empty() {
+        return ssid.to_string();
+    }
+    short_hash("net", salt, ssid)
+}
+
+pub fn redact_hostname(hostname: &str, salt: &str) -> String {
+    short_hash("host", salt, hostname)
+}
+
+/// "loc_" plus a salted hash; distinct names stay distinct, so a redacted
+/// snapshot still restores into the unique locations table
+pub fn redact_location(name: &str, salt: &str) -> String {
+    short_hash("loc", salt, name)
+}
+
+/// Keep the vendor OUI (first three octets) and replace the rest with a
+/// salted hash of the whole address. Hashing rather than blanking keeps
+/// BSSIDs unique, so a redacted snapshot can still be restored and roam
+/// events still point at the right access point. Anything that isn't a
+/// MAC address is returned unchanged.
+pub fn redact_mac(mac: &str, salt: &str) -> String {
+    let canonical = canonical_mac(mac);
+    let octets: Vec<&str> = canonical.split(':').collect();
+    if octets.len() != 6 || octets.iter().any(|o| o.len() != 2) {
+        return mac.to_string();
+    }
+    let hash = salted_hash(salt, &canonical);
+    format!(
+        "{}:{:02X}:{:02X}:{:02X}",
+        octets[..3].join(":"),
+        (hash >> 16) as u8,
+        (hash >> 8) as u8,
+        hash as u8
+    )
+}
+
+pub fn redact_network(network: &Network, salt: &str) -> Network {
+    Network {
+        ssid: redact_ssid(&network.ssid, salt),
+        mac: redact_mac(&network.mac, salt),
+        ..network.clone()
+    }
+}
+
+/// Redact a device's addresses, names, notes and tags, and drop its
+/// service banners and certificate names; its vendor, type, open ports and
+/// certificate expiry are kept
+pub fn redact_device(device: &Device, salt: &str) -> Device {
+    let mut redacted = device.clone();
+    redacted.mac_address = redact_mac(&device.mac_address, salt);
+    redacted.hostname = device.hostname.as_deref().map(|h| redact_hostname(h, salt));
+    redacted.custom_name = None;
+    redacted.notes = None;
+    redacted.tags = Vec::new();
+    redacted.mac_aliases = device.mac_aliases.iter().map(|m| redact_mac(m, salt)).collect();
+    for service in &mut redacted.services {
+        service.banner = None;
+        if let Some(certificate) = &mut service.certificate {
+            *certificate = CertificateInfo { not_after: certificate.not_after, ..CertificateInfo::default() };
+        }
+    }
+    if let Some(conflict) = &mut redacted.ip_conflict {
+        for mac in &mut conflict.other_macs {
+            *mac = redact_mac(mac, salt);
+        }
+    }
+    redacted
+}
+
+/// Apply the same redactions to every table in a database snapshot
+pub fn redact_snapshot(snapshot: &mut Snapshot, salt: &str) {
+    for table in &mut snapshot.tables {
+        let rules: Vec<(usize, Redaction)> = SNAPSHOT_REDACTIONS
+            .iter()
+            .filter(|(name, _, _)| *name == table.name)
+            .filter_map(|(_, column, rule)| {
+                table.columns.iter().position(|c| c == column).map(|i| (i, *rule))
+            })
+            .collect();
+        for row in &mut table.rows {
+            for &(i, rule) in &rules {
+                let Some(value) = row.get_mut(i) else { continue };
+                let serde_json::Value::String(text) = value else { continue };
+                *value = match rule {
+                    Redaction::Ssid => redact_ssid(text, salt).into(),
+                    Redaction::Mac => redact_mac(text, salt).into(),
+                    Redaction::Hostname => redact_hostname(text, salt).into(),
+                    Redaction::Location => redact_location(text, salt).into(),
+                    Redaction::Drop => serde_json::Value::Null,
+                };
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::TableSnapshot;
+    use crate::network_map::{IpConflict, PortState, Protocol, Service};
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn network() -> Network {
+        Network {
+            mac: "a4:c3:61:0b:2e:ff".to_string(),
+            score: 81,
+            ..Network::test("Smith Family WiFi", 36, -52)
+        }
+    }
+
+    #[test]
+    fn test_redaction_is_deterministic() {
+        let network = network();
+        let (a, b) = (redact_network(&network, DEFAULT_SALT), redact_network(&network, DEFAULT_SALT));
+        assert_eq!((a.ssid, a.mac), (b.ssid, b.mac));
+        assert_eq!(redact_ssid("Cafe", "s1"), redact_ssid("Cafe", "s1"));
+        // Same MAC in another spelling redacts the same way
+        assert_eq!(redact_mac("A4-C3-61-0B-2E-FF", "s1"), redact_mac("a4:c3:61:0b:2e:ff", "s1"));
+        // The salt changes the result
+        assert_ne!(redact_ssid("Cafe", "s1"), redact_ssid("Cafe", "s2"));
+        assert_ne!(redact_ssid("Cafe", "s1"), redact_ssid("Cafe2", "s1"));
+    }
+
+    #[test]
+    fn test_network_keeps_oui_and_metrics() {
+        let original = network();
+        let redacted = redact_network(&original, DEFAULT_SALT);
+
+        assert!(!redacted.ssid.contains("Smith"));
+        assert!(redacted.ssid.starts_with("net_"));
+        assert_eq!(redacted.ssid.len(), "net_".len() + 6);
+        assert!(redacted.mac.starts_with("A4:C3:61:"));
+        assert_ne!(redacted.mac, canonical_mac(&original.mac));
+        assert_eq!(redacted.channel, original.channel);
+        assert_eq!(redacted.signal_dbm, original.signal_dbm);
+        assert_eq!(redacted.security, original.security);
+        assert_eq!(redacted.score, original.score);
+
+        assert_eq!(redact_ssid(HIDDEN_SSID, DEFAULT_SALT), HIDDEN_SSID);
+        assert_eq!(redact_mac("unknown", DEFAULT_SALT), "unknown");
+    }
+
+    #[test]
+    fn test_device_redaction() {
+        let mut device = Device::new("a4:c3:61:0b:2e:01".to_string(), "192.168.1.5".to_string());
+        device.hostname = Some("Johns-iPhone".to_string());
+        device.custom_name = Some("John's phone".to_string());
+        device.notes = Some("lives in John's room".to_string());
+        device.tags = vec!["johns-room".to_string(), "kids".to_string()];
+        device.vendor = Some("Apple".to_string());
+        device.mac_aliases = vec!["DA:11:22:33:44:55".to_string()];
+        device.ip_conflict = Some(IpConflict {
+            other_macs: vec!["00:11:22:33:44:55".to_string()],
+            other_ips: Vec::new(),
+        });
+
+        let redacted = redact_device(&device, DEFAULT_SALT);
+        assert!(redacted.mac_address.starts_with("A4:C3:61:"));
+        assert_ne!(redacted.mac_address, "A4:C3:61:0B:2E:01");
+        assert!(!redacted.hostname.as_deref().unwrap().contains("John"));
+        assert_eq!(redacted.custom_name, None);
+        assert_eq!(redacted.notes, None);
+        assert!(redacted.tags.is_empty());
+        assert_eq!(redacted.vendor.as_deref(), Some("Apple"));
+        assert_eq!(redacted.ip_address, "192.168.1.5");
+        assert!(redacted.mac_aliases[0].starts_with("DA:11:22:"));
+        let conflict = redacted.ip_conflict.unwrap();
+        assert_eq!(conflict.other_macs[0], redact_mac("00:11:22:33:44:55", DEFAULT_SALT));
+    }
+
+    #[test]
+    fn test_banners_and_locations_are_redacted() {
+        let mut device = Device::new("a4:c3:61:0b:2e:01".to_string(), "192.168.1.5".to_string());
+        device.services.push(Service {
+            port: 443,
+            protocol: Protocol::Tcp,
+            state: PortState::Open,
+            service_name: Some("https".to_string()),
+            banner: Some("TLS CN=johns-nas.smith.home".to_string()),
+            detected_agent: None,
+            certificate: Some(CertificateInfo {
+                common_name: Some("johns-nas.smith.home".to_string()),
+                subject_alt_names: vec!["johns-nas.local".to_string(), "192.168.1.5".to_string()],
+                issuer: Some("johns-nas.smith.home".to_string()),
+                not_after: Some(Utc::now()),
+            }),
+        });
+        let redacted = redact_device(&device, DEFAULT_SALT);
+        assert_eq!(redacted.services[0].port, 443);
+        assert_eq!(redacted.services[0].banner, None);
+        let certificate = redacted.services[0].certificate.as_ref().unwrap();
+        assert_eq!(certificate.common_name, None);
+        assert!(certificate.subject_alt_names.is_empty());
+        assert_eq!(certificate.issuer, None);
+        assert_eq!(certificate.not_after, device.services[0].certificate.as_ref().unwrap().not_after);
+
+        let mut snapshot = Snapshot {
+            version: 1,
+            exported_at: Utc::now(),
+            tables: vec![
+                TableSnapshot {
+                    name: "locations".to_string(),
+                    columns: vec!["id".into(), "name".into()],
+                    rows: vec![vec![json!(1), json!("Smith House")], vec![json!(2), json!("Office")]],
+                },
+                TableSnapshot {
+                    name: "device_services".to_string(),
+                    columns: vec!["port".into(), "banner".into()],
+                    rows: vec![vec![json!(443), json!("TLS CN=johns-nas.smith.home")]],
+                },
+            ],
+        };
+        redact_snapshot(&mut snapshot, DEFAULT_SALT);
+
+        let locations = &snapshot.tables[0].rows;
+        assert_eq!(locations[0][1], json!(redact_location("Smith House", DEFAULT_SALT)));
+        assert!(locations[0][1].as_str().unwrap().starts_with("loc_"));
+        assert_ne!(locations[0][1], locations[1][1]);
+        assert_eq!(snapshot.tables[1].rows[0], vec![json!(443), serde_json::Value::Null]);
+    }
+
+    #[test]
+    fn test_snapshot_redaction() {
+        let mut snapshot = Snapshot {
+            version: 1,
+            exported_at: Utc::now(),
+            tables: vec![
+                TableSnapshot {
+                    name: "networks".to_string(),
+                    columns: vec!["id".into(), "bssid".into(), "ssid".into(), "notes".into()],
+                    rows: vec![vec![json!(1), json!("A4:C3:61:0B:2E:FF"), json!("HomeNet"), json!("upstairs")]],
+                },
+                TableSnapshot {
+                    name: "roam_events".to_string(),
+                    columns: vec!["ssid".into(), "from_bssid".into(), "to_bssid".into()],
+                    rows: vec![vec![json!("HomeNet"), json!("A4:C3:61:0B:2E:FF"), json!("A4:C3:61:0B:2E:00")]],
+                },
+                TableSnapshot {
+                    name: "scan_results".to_string(),
+                    columns: vec!["network_id".into(), "signal_dbm".into()],
+                    rows: vec![vec![json!(1), json!(-60)]],
+                },
+            ],
+        };
+        redact_snapshot(&mut snapshot, DEFAULT_SALT);
+
+        let networks = &snapshot.tables[0].rows[0];
+        let roams = &snapshot.tables[1].rows[0];
+        assert_eq!(networks[0], json!(1));
+        assert_eq!(networks[2], json!(redact_ssid("HomeNet", DEFAULT_SALT)));
+        assert_eq!(networks[3], serde_json::Value::Null);
+        assert!(networks[1].as_str().unwrap().starts_with("A4:C3:61:"));
+        // The same SSID and BSSID redact identically across tables
+        assert_eq!(roams[0], networks[2]);
+        assert_eq!(roams[1], networks[1]);
+        assert_ne!(roams[1], roams[2]);
+        assert_eq!(snapshot.tables[2].rows[0], vec![json!(1), json!(-60)]);
+    }
+
+    #[test]
+    fn test_connection_addresses_are_dropped() {
+        let mut snapshot = Snapshot {
+            version: 1,
+            exported_at: Utc::now(),
+            tables: vec![TableSnapshot {
+                name: "connections".to_string(),
+                columns: vec!["local_ip".into(), "public_ip".into(), "local_ipv6".into(), "public_ipv6".into()],
+                rows: vec![vec![
+                    json!("192.168.1.20"),
+                    json!("203.0.113.7"),
+                    json!("2001:db8:1:2::20"),
+                    json!("2001:db8:1:2::20"),
+                ]],
+            }],
+        };
+        redact_snapshot(&mut snapshot, DEFAULT_SALT);
+
+        let connection = &snapshot.tables[0].rows[0];
+        // Private IPv4 addresses don't identify anyone and are kept
+        assert_eq!(connection[0], json!("192.168.1.20"));
+        assert_eq!(connection[1], serde_json::Value::Null);
+        assert_eq!(connection[2], serde_json::Value::Null);
+        assert_eq!(connection[3], serde_json::Value::Null);
+    }
+}
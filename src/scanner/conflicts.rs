@@ -0,0 +1,140 @@
+use crate::network_map::lookup_vendor;
+use crate::scanner::{Network, SecurityType, HIDDEN_SSID};
+use std::collections::BTreeMap;
+
+/// Access points advertising the same SSID that disagree on security or
+/// hardware vendor, which a single operator's network rarely does and an
+/// evil twin usually does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SsidConflict {
+    pub ssid: String,
+    /// Uppercase BSSIDs advertising the SSID, sorted
+    pub bssids: Vec<String>,
+    /// Distinct security types seen, when more than one
+    pub securities: Vec<SecurityType>,
+    /// Distinct AP vendors seen, when more than one
+    pub vendors: Vec<&'static str>,
+}
+
+impl SsidConflict {
+    pub fn contains(&self, bssid: &str) -> bool {
+        self.bssids.iter().any(|b| b.eq_ignore_ascii_case(bssid))
+    }
+
+    /// Short reason for the warning, e.g. "WPA2 vs Open security"
+    pub fn explanation(&self) -> String {
+        let mut reasons = Vec::new();
+        if !self.securities.is_empty() {
+            let names: Vec<String> = self.securities.iter().map(|s| s.to_string()).collect();
+            reasons.push(format!("{} security", names.join(" vs ")));
+        }
+        if !self.vendors.is_empty() {
+            reasons.push(format!("{} hardware", self.vendors.join(" vs ")));
+        }
+        reasons.join(", ")
+    }
+}
+
+/// Group networks by SSID and report groups whose APs use different
+/// security types or come from different vendors. Only vendors known from
+/// the OUI are compared: randomized or unlisted BSSIDs are common on
+/// legitimate multi-SSID APs.
+pub fn find_ssid_conflicts(networks: &[Network]) -> Vec<SsidConflict> {
+    let mut groups: BTreeMap<&str, Vec<&Network>> = BTreeMap::new();
+    for network in networks.iter().filter(|n| n.ssid != HIDDEN_SSID && !n.ssid.is_empty()) {
+        groups.entry(network.ssid.as_str()).or_default().push(network);
+    }
+
+    let mut conflicts = Vec::new();
+    for (ssid, group) in groups {
+        if group.len() < 2 {
+            continue;
+        }
+
+        let mut securities: Vec<SecurityType> = Vec::new();
+        for network in &group {
+            if network.security != SecurityType::Unknown && !securities.contains(&network.security) {
+                securities.push(network.security.clone());
+            }
+        }
+        let mut vendors: Vec<&'static str> = group
+            .iter()
+            .filter_map(|n| lookup_vendor(&n.mac))
+            .filter(|v| *v != "Private/Randomized")
+            .collect();
+        vendors.sort_unstable();
+        vendors.dedup();
+
+        if securities.len() < 2 {
+            securities.clear();
+        }
+        if vendors.len() < 2 {
+            vendors.clear();
+        }
+        if securities.is_empty() && vendors.is_empty() {
+            continue;
+        }
+
+        let mut bssids: Vec<String> = group.iter().map(|n| n.mac.to_uppercase()).collect();
+        bssids.sort();
+        bssids.dedup();
+        conflicts.push(SsidConflict {
+            ssid: ssid.to_string(),
+            bssids,
+            securities,
+            vendors,
+        });
+    }
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn network(ssid: &str, mac: &str, security: SecurityType) -> Network {
+        Network { mac: mac.to_string(), security, ..Network::test(ssid, 6, -60) }
+    }
+
+    #[test]
+    fn test_single_conflict() {
+        let networks = vec![
+            // Mesh network: same vendor and security on every AP
+            network("HomeMesh", "00:26:BB:00:00:01", SecurityType::WPA2),
+            network("HomeMesh", "A4:C3:61:00:00:02", SecurityType::WPA2),
+            // Randomized secondary BSSID of the same AP isn't a vendor mismatch
+            network("HomeMesh", "02:26:BB:00:00:03", SecurityType::WPA2),
+            // Evil twin: an open AP copying a secured cafe network
+            network("Cafe", "14:CC:20:00:00:01", SecurityType::WPA2),
+            network("Cafe", "aa:bb:cc:00:00:02", SecurityType::Open),
+            // Unique SSIDs and hidden networks are never grouped
+            network("Solo", "00:11:22:00:00:01", SecurityType::Open),
+            network(HIDDEN_SSID, "00:26:BB:00:00:09", SecurityType::WPA2),
+            network(HIDDEN_SSID, "14:CC:20:00:00:09", SecurityType::Open),
+        ];
+
+        let conflicts = find_ssid_conflicts(&networks);
+        assert_eq!(conflicts.len(), 1);
+        let conflict = &conflicts[0];
+        assert_eq!(conflict.ssid, "Cafe");
+        assert_eq!(conflict.bssids, vec!["14:CC:20:00:00:01", "AA:BB:CC:00:00:02"]);
+        assert_eq!(conflict.securities, vec![SecurityType::WPA2, SecurityType::Open]);
+        assert!(conflict.vendors.is_empty());
+        assert!(conflict.contains("aa:bb:cc:00:00:02"));
+        assert_eq!(conflict.explanation(), "WPA2 vs Open security");
+    }
+
+    #[test]
+    fn test_vendor_mismatch() {
+        let networks = vec![
+            network("Office", "00:26:BB:00:00:01", SecurityType::WPA2),
+            network("Office", "14:CC:20:00:00:02", SecurityType::WPA2),
+        ];
+
+        let conflicts = find_ssid_conflicts(&networks);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].vendors, vec!["Apple", "TP-Link"]);
+        assert!(conflicts[0].securities.is_empty());
+        assert_eq!(conflicts[0].explanation(), "Apple vs TP-Link hardware");
+    }
+}
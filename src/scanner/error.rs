@@ -0,0 +1,112 @@
+use std::fmt;
+
+/// Why a WiFi scan failed, so callers can tell a missing adapter from a
+/// permissions problem or a scan that merely took too long
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanError {
+    /// No wireless interface to scan with
+    NoInterface,
+    /// The OS refused the scan (e.g. Location permission not granted)
+    PermissionDenied,
+    /// The scanner didn't finish in time; usually transient
+    Timeout,
+    /// The adapter is busy with another scan or a connection attempt
+    Busy,
+    /// The interface exists but can't scan (down, or no scan support)
+    Unsupported(String),
+    /// Any other scanner failure
+    Backend(String),
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScanError::NoInterface => write!(f, "No WiFi interface found"),
+            ScanError::PermissionDenied => write!(f, "Permission denied while scanning for networks"),
+            ScanError::Timeout => write!(f, "WiFi scan timed out"),
+            ScanError::Busy => write!(f, "WiFi interface is busy"),
+            ScanError::Unsupported(msg) => write!(f, "Can't scan: {}", msg),
+            ScanError::Backend(msg) => write!(f, "WiFi scan failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ScanError {}
+
+impl ScanError {
+    /// Classify scanner stderr output
+    pub fn from_stderr(stderr: &str) -> Self {
+        let lower = stderr.to_lowercase();
+        if lower.contains("no wifi interface") || lower.contains("no wi-fi interface") || lower.contains("no such device")
+        {
+            ScanError::NoInterface
+        } else if lower.contains("not permitted") || lower.contains("permission") || lower.contains("denied") {
+            ScanError::PermissionDenied
+        } else if lower.contains("timed out") || lower.contains("timeout") {
+            ScanError::Timeout
+        } else if lower.contains("resource busy") {
+            ScanError::Busy
+        } else if lower.contains("network is down") {
+            ScanError::Unsupported("the interface is down".to_string())
+        } else if lower.contains("not supported") {
+            ScanError::Unsupported("the interface doesn't support scanning".to_string())
+        } else {
+            ScanError::Backend(stderr.trim().to_string())
+        }
+    }
+
+    /// What the user can do about it
+    pub fn advice(&self) -> &'static str {
+        match self {
+            ScanError::NoInterface => "Turn WiFi on, or pass --interface with your adapter's name (e.g. en0)",
+            ScanError::PermissionDenied if cfg!(target_os = "linux") => {
+                "Scanning needs root on Linux: run with sudo, or grant the binary CAP_NET_ADMIN with setcap"
+            }
+            ScanError::PermissionDenied => {
+                "Grant Location permission to your terminal in System Settings > Privacy & Security > Location Services"
+            }
+            ScanError::Timeout | ScanError::Busy => "The scan is retried automatically; press 'r' to try again",
+            ScanError::Unsupported(_) => {
+                "Bring the interface up (ip link set <iface> up), or pass --interface with an adapter that can scan"
+            }
+            ScanError::Backend(_) => "Check that WiFi is enabled, then press 'r' to scan again",
+        }
+    }
+
+    /// Whether trying again might succeed
+    pub fn is_transient(&self) -> bool {
+        matches!(self, ScanError::Timeout | ScanError::Busy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_stderr() {
+        assert_eq!(ScanError::from_stderr("Error: No WiFi interface found\n"), ScanError::NoInterface);
+        assert_eq!(
+            ScanError::from_stderr("Error scanning: The operation couldn’t be completed. Operation not permitted"),
+            ScanError::PermissionDenied
+        );
+        assert_eq!(ScanError::from_stderr("Error scanning: timed out"), ScanError::Timeout);
+        // iw's messages on Linux
+        assert_eq!(ScanError::from_stderr("command failed: Device or resource busy (-16)"), ScanError::Busy);
+        assert!(ScanError::Busy.is_transient());
+        assert_eq!(ScanError::from_stderr("command failed: No such device (-19)"), ScanError::NoInterface);
+        assert_eq!(ScanError::from_stderr("command failed: Operation not permitted (-1)"), ScanError::PermissionDenied);
+        assert_eq!(
+            ScanError::from_stderr("command failed: Network is down (-100)"),
+            ScanError::Unsupported("the interface is down".to_string())
+        );
+        assert_eq!(
+            ScanError::from_stderr("command failed: Operation not supported (-95)"),
+            ScanError::Unsupported("the interface doesn't support scanning".to_string())
+        );
+        assert_eq!(
+            ScanError::from_stderr("  something else \n"),
+            ScanError::Backend("something else".to_string())
+        );
+    }
+}
@@ -1,11 +1,21 @@
+mod conflicts;
+mod error;
 mod platform;
 
-pub use platform::{enable_demo_mode, get_scan_detected_connection, is_demo_mode, scan_networks, CurrentConnectionInfo};
+pub use conflicts::{find_ssid_conflicts, SsidConflict};
+pub use error::ScanError;
+pub use platform::{
+    demo_networks, enable_demo_mode, get_scan_detected_connection, is_demo_mode, parse_current_channel,
+    parse_iw_scan_output, parse_swift_scanner_output, scan_networks, set_scan_interface, set_scan_options,
+    strongest_on_channel, CurrentConnectionInfo, ScanOptions, HIDDEN_SSID,
+};
 
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Serialized as its `as_str` form, e.g. "WPA2WPA3"
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[allow(clippy::upper_case_acronyms)]
 pub enum SecurityType {
     Open,
@@ -13,6 +23,12 @@ pub enum SecurityType {
     WPA,
     WPA2,
     WPA3,
+    /// WPA2/WPA3 transition mode (accepts clients of either)
+    WPA2WPA3,
+    /// Opportunistic Wireless Encryption ("Enhanced Open"): no password, but encrypted
+    OWE,
+    /// 802.1X/EAP: needs per-user credentials rather than a shared password
+    Enterprise,
     Unknown,
 }
 
@@ -24,26 +40,57 @@ impl fmt::Display for SecurityType {
             SecurityType::WPA => write!(f, "WPA"),
             SecurityType::WPA2 => write!(f, "WPA2"),
             SecurityType::WPA3 => write!(f, "WPA3"),
+            SecurityType::WPA2WPA3 => write!(f, "WPA2/WPA3"),
+            SecurityType::OWE => write!(f, "OWE"),
+            SecurityType::Enterprise => write!(f, "Enterprise"),
             SecurityType::Unknown => write!(f, "Unknown"),
         }
     }
 }
 
 impl SecurityType {
-    /// Parse from debug/display string (stored in DB)
+    /// Canonical form stored in the database (matches the Debug form, so
+    /// rows written by older versions parse the same way)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecurityType::Open => "Open",
+            SecurityType::WEP => "WEP",
+            SecurityType::WPA => "WPA",
+            SecurityType::WPA2 => "WPA2",
+            SecurityType::WPA3 => "WPA3",
+            SecurityType::WPA2WPA3 => "WPA2WPA3",
+            SecurityType::OWE => "OWE",
+            SecurityType::Enterprise => "Enterprise",
+            SecurityType::Unknown => "Unknown",
+        }
+    }
+
+    /// Parse the stored (`as_str`/Debug) or Display form, ignoring case and separators
     pub fn from_str(s: &str) -> Self {
-        match s {
-            "Open" => SecurityType::Open,
-            "WEP" => SecurityType::WEP,
-            "WPA" => SecurityType::WPA,
-            "WPA2" => SecurityType::WPA2,
-            "WPA3" => SecurityType::WPA3,
+        match normalize(s).as_str() {
+            "open" => SecurityType::Open,
+            "wep" => SecurityType::WEP,
+            "wpa" => SecurityType::WPA,
+            "wpa2" => SecurityType::WPA2,
+            "wpa3" => SecurityType::WPA3,
+            "wpa2wpa3" => SecurityType::WPA2WPA3,
+            "owe" => SecurityType::OWE,
+            "enterprise" => SecurityType::Enterprise,
             _ => SecurityType::Unknown,
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Lowercase and drop the separators that differ between Debug and Display forms
+fn normalize(s: &str) -> String {
+    s.chars()
+        .filter(|c| !matches!(c, ' ' | '_' | '/' | '-'))
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// Serialized as its `as_str` form, e.g. "Band2_4GHz"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum FrequencyBand {
     Band2_4GHz,
     Band5GHz,
@@ -72,18 +119,28 @@ impl FrequencyBand {
         }
     }
 
-    /// Parse from debug string (stored in DB)
+    /// Canonical form stored in the database (matches the Debug form)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FrequencyBand::Band2_4GHz => "Band2_4GHz",
+            FrequencyBand::Band5GHz => "Band5GHz",
+            FrequencyBand::Band6GHz => "Band6GHz",
+            FrequencyBand::Unknown => "Unknown",
+        }
+    }
+
+    /// Parse the stored (`as_str`/Debug) or Display form, e.g. "Band2_4GHz" or "2.4 GHz"
     pub fn from_str(s: &str) -> Self {
-        match s {
-            "Band2_4GHz" => FrequencyBand::Band2_4GHz,
-            "Band5GHz" => FrequencyBand::Band5GHz,
-            "Band6GHz" => FrequencyBand::Band6GHz,
+        match normalize(s).trim_start_matches("band") {
+            "24ghz" | "2.4ghz" => FrequencyBand::Band2_4GHz,
+            "5ghz" => FrequencyBand::Band5GHz,
+            "6ghz" => FrequencyBand::Band6GHz,
             _ => FrequencyBand::Unknown,
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Network {
     pub ssid: String,
     pub mac: String,
@@ -95,7 +152,18 @@ pub struct Network {
     pub last_seen: DateTime<Utc>,
 }
 
+/// Map signal strength to 0-100%: -30 dBm or stronger is 100%, -90 dBm or weaker is 0%
+pub fn dbm_to_percent(dbm: i32) -> u8 {
+    let clamped = dbm.clamp(-90, -30);
+    ((clamped + 90) * 100 / 60) as u8
+}
+
 impl Network {
+    /// Signal strength as a percentage, for readers unfamiliar with dBm
+    pub fn signal_percent(&self) -> u8 {
+        dbm_to_percent(self.signal_dbm)
+    }
+
     pub fn signal_bars(&self) -> String {
         let bars = match self.signal_dbm {
             s if s >= -50 => 5,
@@ -108,4 +176,109 @@ impl Network {
         let empty = "\u{2591}".repeat(5 - bars);
         format!("{}{}", filled, empty)
     }
+
+    /// A WPA2 network with the band taken from its channel, shared by the
+    /// unit tests and the test binaries
+    #[doc(hidden)]
+    pub fn test(ssid: &str, channel: u8, signal_dbm: i32) -> Self {
+        Network {
+            ssid: ssid.to_string(),
+            mac: "AA:BB:CC:DD:EE:FF".to_string(),
+            channel,
+            signal_dbm,
+            security: SecurityType::WPA2,
+            frequency_band: FrequencyBand::from_channel(channel),
+            score: 0,
+            last_seen: Utc::now(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn network(signal_dbm: i32) -> Network {
+        Network::test("TestNet", 6, signal_dbm)
+    }
+
+    const SECURITY_TYPES: [SecurityType; 9] = [
+        SecurityType::Open,
+        SecurityType::WEP,
+        SecurityType::WPA,
+        SecurityType::WPA2,
+        SecurityType::WPA3,
+        SecurityType::WPA2WPA3,
+        SecurityType::OWE,
+        SecurityType::Enterprise,
+        SecurityType::Unknown,
+    ];
+
+    const BANDS: [FrequencyBand; 4] = [
+        FrequencyBand::Band2_4GHz,
+        FrequencyBand::Band5GHz,
+        FrequencyBand::Band6GHz,
+        FrequencyBand::Unknown,
+    ];
+
+    #[test]
+    fn test_security_round_trip() {
+        for security in SECURITY_TYPES {
+            assert_eq!(SecurityType::from_str(&security.to_string()), security);
+            assert_eq!(SecurityType::from_str(&format!("{:?}", security)), security);
+            assert_eq!(SecurityType::from_str(security.as_str()), security);
+        }
+        assert_eq!(SecurityType::from_str("wpa2/wpa3"), SecurityType::WPA2WPA3);
+        assert_eq!(SecurityType::from_str("bogus"), SecurityType::Unknown);
+    }
+
+    #[test]
+    fn test_band_round_trip() {
+        for band in BANDS {
+            assert_eq!(FrequencyBand::from_str(&band.to_string()), band);
+            assert_eq!(FrequencyBand::from_str(&format!("{:?}", band)), band);
+            assert_eq!(FrequencyBand::from_str(band.as_str()), band);
+        }
+        assert_eq!(FrequencyBand::from_str("2.4GHz"), FrequencyBand::Band2_4GHz);
+        assert_eq!(FrequencyBand::from_str("bogus"), FrequencyBand::Unknown);
+    }
+
+    #[test]
+    fn test_security_serde_uses_stored_form() {
+        for security in SECURITY_TYPES {
+            let json = serde_json::to_string(&security).unwrap();
+            assert_eq!(json, format!("\"{}\"", security.as_str()));
+            assert_eq!(serde_json::from_str::<SecurityType>(&json).unwrap(), security);
+        }
+    }
+
+    #[test]
+    fn test_band_serde_uses_stored_form() {
+        for band in BANDS {
+            let json = serde_json::to_string(&band).unwrap();
+            assert_eq!(json, format!("\"{}\"", band.as_str()));
+            assert_eq!(serde_json::from_str::<FrequencyBand>(&json).unwrap(), band);
+        }
+    }
+
+    #[test]
+    fn test_network_serde_round_trip() {
+        let original = network(-61);
+        let json = serde_json::to_string(&original).unwrap();
+        let parsed: Network = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.mac, original.mac);
+        assert_eq!(parsed.security, original.security);
+        assert_eq!(parsed.frequency_band, original.frequency_band);
+        assert_eq!(parsed.last_seen, original.last_seen);
+    }
+
+    #[test]
+    fn test_signal_percent_boundaries() {
+        assert_eq!(network(-30).signal_percent(), 100);
+        assert_eq!(network(-60).signal_percent(), 50);
+        assert_eq!(network(-90).signal_percent(), 0);
+        // Values outside the range are clamped
+        assert_eq!(network(-20).signal_percent(), 100);
+        assert_eq!(network(-100).signal_percent(), 0);
+    }
 }
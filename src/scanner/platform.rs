@@ -1,20 +1,72 @@
-use crate::scanner::{FrequencyBand, Network, SecurityType};
+use crate::mac::canonical_mac;
+use crate::scanner::{FrequencyBand, Network, ScanError, SecurityType};
 use chrono::Utc;
-use color_eyre::Result;
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
-use std::process::Command;
+use std::future::Future;
+use std::ops::ControlFlow;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
 use std::time::Duration;
 
 static DEMO_MODE: AtomicBool = AtomicBool::new(false);
 
+/// User-configured scan passes and delay (set before the first scan)
+static SCAN_OPTIONS: OnceLock<ScanOptions> = OnceLock::new();
+
+/// Interface passed with --interface; Linux scans it instead of the first
+/// wireless interface in sysfs
+static SCAN_INTERFACE: OnceLock<String> = OnceLock::new();
+
 /// Number of scan passes to perform for thorough network discovery
 const SCAN_PASSES: usize = 2;
 
 /// Delay between scan passes in milliseconds
 const SCAN_DELAY_MS: u64 = 500;
 
+/// How many passes each scan makes and how long to wait between them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanOptions {
+    /// Scan passes merged into one result (at least 1)
+    pub passes: usize,
+    /// Pause between passes
+    pub delay: Duration,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            passes: SCAN_PASSES,
+            delay: Duration::from_millis(SCAN_DELAY_MS),
+        }
+    }
+}
+
+/// Use these scan passes and delay instead of the defaults.
+/// Must be called before the first `scan_networks`.
+pub fn set_scan_options(options: ScanOptions) {
+    let _ = SCAN_OPTIONS.set(options);
+}
+
+/// Scan this interface where the backend lets us pick one (Linux).
+/// Must be called before the first `scan_networks`.
+pub fn set_scan_interface(interface: String) {
+    let _ = SCAN_INTERFACE.set(interface);
+}
+
+/// How long a single scan pass may take before it counts as timed out
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+const SCAN_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Retries of a timed-out scan pass, waiting `SCAN_RETRY_BACKOFF_MS` and then twice as long
+const SCAN_RETRIES: u32 = 2;
+
+/// Delay before the first retry in milliseconds; doubles on each further retry
+const SCAN_RETRY_BACKOFF_MS: u64 = 1000;
+
+/// Placeholder SSID for networks that don't broadcast their name
+pub const HIDDEN_SSID: &str = "<Hidden>";
+
 /// Enable demo mode with simulated networks
 pub fn enable_demo_mode() {
     DEMO_MODE.store(true, Ordering::SeqCst);
@@ -27,37 +79,182 @@ pub fn is_demo_mode() -> bool {
 
 /// Scan WiFi networks using Swift CoreWLAN helper (works on modern macOS)
 #[cfg(target_os = "macos")]
-async fn scan_macos_swift() -> Result<Vec<Network>> {
-    // Find the Swift script relative to the executable or current directory
-    let script_paths = [
-        std::env::current_exe()
-            .ok()
-            .and_then(|p| p.parent().map(|p| p.join("../scripts/wifi_scan.swift")))
-            .unwrap_or_default(),
-        std::path::PathBuf::from("scripts/wifi_scan.swift"),
-        std::path::PathBuf::from("./scripts/wifi_scan.swift"),
-    ];
-
-    let script_path = script_paths
-        .iter()
-        .find(|p| p.exists())
-        .ok_or_else(|| color_eyre::eyre::eyre!("Swift scanner script not found"))?
-        .clone();
+async fn scan_macos_swift() -> Result<Vec<Network>, ScanError> {
+    if !crate::scripts::swift_available() {
+        return Err(ScanError::Backend("swift is not installed".to_string()));
+    }
 
-    let output = tokio::task::spawn_blocking(move || {
-        Command::new("swift")
-            .arg(&script_path)
-            .output()
-    })
-    .await??;
+    // The script is embedded in the binary and extracted to a temp file
+    let script_path = crate::scripts::WIFI_SCAN
+        .path()
+        .map_err(|e| ScanError::Backend(e.to_string()))?;
+
+    // kill_on_drop stops a hung scanner once the timeout gives up on it
+    let command = tokio::process::Command::new("swift")
+        .arg(&script_path)
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(SCAN_TIMEOUT, command)
+        .await
+        .map_err(|_| ScanError::Timeout)?
+        .map_err(|e| ScanError::Backend(e.to_string()))?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(color_eyre::eyre::eyre!("Swift scanner failed: {}", stderr));
+        return Err(ScanError::from_stderr(&String::from_utf8_lossy(&output.stderr)));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
-    parse_swift_scanner_output(&stdout)
+    Ok(parse_swift_scanner_output(&stdout))
+}
+
+/// Scan with `iw dev <iface> scan`, which needs no association, so a
+/// disconnected laptop can still survey. None when iw isn't installed.
+#[cfg(target_os = "linux")]
+async fn scan_linux_iw() -> Option<Result<Vec<Network>, ScanError>> {
+    let Some(interface) = SCAN_INTERFACE.get().cloned().or_else(linux_wireless_interface) else {
+        return Some(Err(ScanError::NoInterface));
+    };
+
+    let command = tokio::process::Command::new("iw")
+        .args(["dev", &interface, "scan"])
+        .kill_on_drop(true)
+        .output();
+    let output = match tokio::time::timeout(SCAN_TIMEOUT, command).await {
+        Err(_) => return Some(Err(ScanError::Timeout)),
+        Ok(Err(e)) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Ok(Err(e)) => return Some(Err(ScanError::Backend(e.to_string()))),
+        Ok(Ok(output)) => output,
+    };
+
+    if !output.status.success() {
+        return Some(Err(match ScanError::from_stderr(&String::from_utf8_lossy(&output.stderr)) {
+            ScanError::Unsupported(reason) => ScanError::Unsupported(format!("{}: {}", interface, reason)),
+            e => e,
+        }));
+    }
+    Some(Ok(parse_iw_scan_output(&String::from_utf8_lossy(&output.stdout))))
+}
+
+/// First wireless interface by name; wireless ones have a `wireless` directory in sysfs
+#[cfg(target_os = "linux")]
+fn linux_wireless_interface() -> Option<String> {
+    let mut names: Vec<String> = std::fs::read_dir("/sys/class/net")
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join("wireless").exists())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    names.sort();
+    names.into_iter().next()
+}
+
+/// Parse `iw dev <iface> scan` output: a "BSS <bssid>(on <iface>)" line
+/// starts each access point, followed by indented "key: value" lines
+pub fn parse_iw_scan_output(output: &str) -> Vec<Network> {
+    let mut networks = Vec::new();
+    let mut current: Option<IwBss> = None;
+
+    for line in output.lines() {
+        if let Some(rest) = line.strip_prefix("BSS ") {
+            networks.extend(current.take().map(IwBss::into_network));
+            let bssid = rest.split(['(', ' ']).next().unwrap_or_default();
+            current = Some(IwBss { bssid: bssid.to_string(), ..IwBss::default() });
+            continue;
+        }
+        let Some(bss) = current.as_mut() else {
+            continue;
+        };
+
+        let trimmed = line.trim().trim_start_matches("* ");
+        if let Some(freq) = trimmed.strip_prefix("freq:") {
+            bss.freq_mhz = freq.trim().parse::<f32>().map(|mhz| mhz as u32).unwrap_or(0);
+        } else if let Some(signal) = trimmed.strip_prefix("signal:") {
+            // "-52.00 dBm"
+            bss.signal_dbm = signal.trim().trim_end_matches("dBm").trim().parse::<f32>().ok().map(|dbm| dbm.round() as i32);
+        } else if let Some(ssid) = trimmed.strip_prefix("SSID:") {
+            bss.ssid = ssid.trim().to_string();
+        } else if let Some(capability) = trimmed.strip_prefix("capability:") {
+            bss.privacy = capability.contains("Privacy");
+        } else if trimmed.starts_with("RSN:") {
+            bss.rsn = true;
+        } else if trimmed.starts_with("WPA:") {
+            bss.wpa = true;
+        }
+        // "RSN:\t * Version: 1" keeps its first field on the same line
+        if let Some((_, suites)) = trimmed.split_once("Authentication suites:") {
+            bss.auth_suites.push(' ');
+            bss.auth_suites.push_str(&suites.to_lowercase());
+        }
+    }
+    networks.extend(current.map(IwBss::into_network));
+    networks
+}
+
+/// Fields gathered from one BSS block of `iw scan` output
+#[derive(Default)]
+struct IwBss {
+    bssid: String,
+    ssid: String,
+    freq_mhz: u32,
+    signal_dbm: Option<i32>,
+    privacy: bool,
+    rsn: bool,
+    wpa: bool,
+    auth_suites: String,
+}
+
+impl IwBss {
+    fn into_network(self) -> Network {
+        let (channel, frequency_band) = channel_from_freq(self.freq_mhz);
+        // Hidden networks show up with an empty SSID or one of escaped NULs
+        let hidden = self.ssid.split("\\x00").all(str::is_empty);
+        let security = self.security();
+
+        Network {
+            ssid: if hidden { HIDDEN_SSID.to_string() } else { self.ssid },
+            mac: self.bssid,
+            channel,
+            signal_dbm: self.signal_dbm.unwrap_or(-100),
+            security,
+            frequency_band,
+            score: 0,
+            last_seen: Utc::now(),
+        }
+    }
+
+    fn security(&self) -> SecurityType {
+        // Suites read like "PSK", "FT/SAE", "IEEE 802.1X" or "PSK/SHA-256"
+        let has = |suite: &str| self.auth_suites.split_whitespace().any(|s| s.contains(suite));
+        let personal = has("psk") || has("sae");
+        if has("owe") {
+            SecurityType::OWE
+        } else if has("802.1x") && !personal {
+            SecurityType::Enterprise
+        } else if self.rsn && has("sae") && has("psk") {
+            SecurityType::WPA2WPA3
+        } else if self.rsn && has("sae") {
+            SecurityType::WPA3
+        } else if self.rsn {
+            SecurityType::WPA2
+        } else if self.wpa {
+            SecurityType::WPA
+        } else if self.privacy {
+            SecurityType::WEP
+        } else {
+            SecurityType::Open
+        }
+    }
+}
+
+/// Channel number and band of a center frequency in MHz
+fn channel_from_freq(mhz: u32) -> (u8, FrequencyBand) {
+    match mhz {
+        2484 => (14, FrequencyBand::Band2_4GHz),
+        2412..=2472 => (((mhz - 2407) / 5) as u8, FrequencyBand::Band2_4GHz),
+        5150..=5895 => (((mhz - 5000) / 5) as u8, FrequencyBand::Band5GHz),
+        5955..=7115 => (((mhz - 5950) / 5) as u8, FrequencyBand::Band6GHz),
+        _ => (0, FrequencyBand::Unknown),
+    }
 }
 
 /// Current connection info detected during scan
@@ -80,10 +277,9 @@ pub fn get_scan_detected_connection() -> Option<CurrentConnectionInfo> {
         .and_then(|guard| guard.clone())
 }
 
-/// Parse Swift scanner output: SSID|BSSID|CHANNEL|RSSI|SECURITY
+/// Parse Swift scanner output: SSID|BSSID|CHANNEL|RSSI|SECURITY[|BAND]
 /// Also looks for CONNECTED|SSID|BSSID line for current connection
-#[cfg(target_os = "macos")]
-fn parse_swift_scanner_output(output: &str) -> Result<Vec<Network>> {
+pub fn parse_swift_scanner_output(output: &str) -> Vec<Network> {
     let mut networks = Vec::new();
 
     for line in output.lines() {
@@ -93,7 +289,7 @@ fn parse_swift_scanner_output(output: &str) -> Result<Vec<Network>> {
         if parts.len() >= 2 && parts[0] == "CONNECTED" {
             let ssid = parts[1].to_string();
             let bssid = if parts.len() >= 3 && !parts[2].is_empty() {
-                Some(parts[2].to_uppercase())
+                Some(canonical_mac(parts[2]))
             } else {
                 None
             };
@@ -105,29 +301,25 @@ fn parse_swift_scanner_output(output: &str) -> Result<Vec<Network>> {
             continue;
         }
 
-        // Parse network line: SSID|BSSID|CHANNEL|RSSI|SECURITY
+        // Parse network line: SSID|BSSID|CHANNEL|RSSI|SECURITY[|BAND]
         if parts.len() >= 5 {
-            let ssid = if parts[0].is_empty() || parts[0] == "<Hidden>" {
-                "<Hidden>".to_string()
+            let ssid = if parts[0].is_empty() || parts[0] == HIDDEN_SSID {
+                HIDDEN_SSID.to_string()
             } else {
                 parts[0].to_string()
             };
             let channel = parts[2].parse::<u8>().unwrap_or(0);
             let signal_dbm = parts[3].parse::<i32>().unwrap_or(-100);
             let security = parse_security(parts[4]);
-            let frequency_band = FrequencyBand::from_channel(channel);
-
-            // Use BSSID if available, otherwise generate synthetic one from SSID+channel
-            // (macOS Sonoma+ doesn't return BSSID due to privacy restrictions)
-            let mac = if parts[1].is_empty() {
-                generate_synthetic_mac(&ssid, channel)
-            } else {
-                parts[1].to_string()
-            };
+            let frequency_band = parts
+                .get(5)
+                .map(|band| FrequencyBand::from_str(band))
+                .filter(|band| *band != FrequencyBand::Unknown)
+                .unwrap_or_else(|| FrequencyBand::from_channel(channel));
 
             networks.push(Network {
                 ssid,
-                mac,
+                mac: parts[1].to_string(),
                 channel,
                 signal_dbm,
                 security,
@@ -138,18 +330,87 @@ fn parse_swift_scanner_output(output: &str) -> Result<Vec<Network>> {
         }
     }
 
-    Ok(networks)
+    fill_missing_bssids(&mut networks);
+    networks
+}
+
+/// Channel and band of the current network in `system_profiler SPAirPortDataType`
+/// output, from a line like "Channel: 37 (6GHz, 160MHz)"
+pub fn parse_current_channel(profiler_output: &str) -> Option<(u8, FrequencyBand)> {
+    let mut in_current_network = false;
+
+    for line in profiler_output.lines() {
+        let trimmed = line.trim();
+
+        if trimmed.contains("Current Network Information:") {
+            in_current_network = true;
+            continue;
+        }
+
+        if in_current_network && let Some(channel_part) = trimmed.strip_prefix("Channel:") {
+            let (number, details) = channel_part.trim().split_once(' ').unwrap_or((channel_part.trim(), ""));
+            let channel = number.parse::<u8>().ok()?;
+            // 5 GHz and 6 GHz share channel numbers, so prefer the band macOS reports
+            let band = details
+                .trim_start_matches('(')
+                .split(',')
+                .next()
+                .map(FrequencyBand::from_str)
+                .filter(|band| *band != FrequencyBand::Unknown)
+                .unwrap_or_else(|| FrequencyBand::from_channel(channel));
+            return Some((channel, band));
+        }
+
+        // Stop if we've moved past the current network section
+        if in_current_network && (trimmed.starts_with("Other Local") || trimmed.is_empty() && line.len() < 10) {
+            break;
+        }
+    }
+
+    None
+}
+
+/// Strongest network on `channel` in `band`; the likely connected network
+/// when macOS withholds the SSID
+pub fn strongest_on_channel(networks: &[Network], channel: u8, band: FrequencyBand) -> Option<&Network> {
+    networks
+        .iter()
+        .filter(|n| n.channel == channel && n.frequency_band == band)
+        .max_by_key(|n| n.signal_dbm)
+}
+
+/// Give access points without a BSSID a synthetic one derived from SSID, channel
+/// and band (macOS Sonoma+ doesn't return BSSID due to privacy restrictions). Hidden
+/// networks all share one SSID, so they're numbered per channel to keep distinct APs apart.
+fn fill_missing_bssids(networks: &mut [Network]) {
+    let mut hidden_per_channel: HashMap<(u8, FrequencyBand), usize> = HashMap::new();
+
+    for network in networks.iter_mut().filter(|n| n.mac.is_empty()) {
+        let (channel, band) = (network.channel, network.frequency_band);
+        network.mac = if network.ssid == HIDDEN_SSID {
+            let index = hidden_per_channel.entry((channel, band)).or_insert(0);
+            *index += 1;
+            generate_synthetic_mac(&format!("{}#{}", HIDDEN_SSID, index), channel, band)
+        } else {
+            generate_synthetic_mac(&network.ssid, channel, band)
+        };
+    }
 }
 
 /// Generate a synthetic MAC address from SSID and channel for consistent tracking
-/// when real BSSID is not available (macOS privacy restrictions)
-fn generate_synthetic_mac(ssid: &str, channel: u8) -> String {
+/// when real BSSID is not available (macOS privacy restrictions). 6 GHz channel
+/// numbers repeat 2.4 and 5 GHz ones, so that band is hashed in too; the others
+/// are left out to keep the IDs already stored for them.
+fn generate_synthetic_mac(ssid: &str, channel: u8, band: FrequencyBand) -> String {
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
 
     let mut hasher = DefaultHasher::new();
     ssid.hash(&mut hasher);
     channel.hash(&mut hasher);
+    if band == FrequencyBand::Band6GHz {
+        band.hash(&mut hasher);
+    }
     let hash = hasher.finish();
 
     // Generate MAC-like string: XX:XX:XX:XX:XX:XX
@@ -166,35 +427,42 @@ fn generate_synthetic_mac(ssid: &str, channel: u8) -> String {
 
 /// Perform a multi-pass WiFi scan with deduplication.
 /// Runs multiple scan passes and merges results, keeping the strongest signal per access point.
-pub async fn scan_networks() -> Result<Vec<Network>> {
+pub async fn scan_networks() -> Result<Vec<Network>, ScanError> {
     // If demo mode is enabled, return simulated networks (no multi-pass needed)
     if is_demo_mode() {
         return Ok(generate_demo_networks());
     }
 
+    let options = SCAN_OPTIONS.get().copied().unwrap_or_default();
+    scan_passes(&options, single_scan_with_retry).await
+}
+
+/// Run `options.passes` scans (at least one), sleeping between passes but
+/// not before the first, and merge the results
+async fn scan_passes<F, Fut>(options: &ScanOptions, mut scan: F) -> Result<Vec<Network>, ScanError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Vec<Network>, ScanError>>,
+{
     let mut all_networks: HashMap<String, Network> = HashMap::new();
 
     // Perform multiple scan passes to catch all networks
-    for pass in 0..SCAN_PASSES {
+    for pass in 0..options.passes.max(1) {
         // Add delay between passes (but not before the first one)
         if pass > 0 {
-            tokio::time::sleep(Duration::from_millis(SCAN_DELAY_MS)).await;
+            tokio::time::sleep(options.delay).await;
         }
 
         // Perform a single scan
-        let networks = single_scan().await?;
-
-        // Merge results: keep the strongest signal per unique network
-        for network in networks {
-            // Use BSSID if available, otherwise fall back to SSID+channel
-            // (macOS Sonoma+ doesn't return BSSID due to privacy restrictions)
-            let key = if network.mac.is_empty() {
-                format!("{}:{}", network.ssid, network.channel)
-            } else {
-                network.mac.to_uppercase()
-            };
-
-            match all_networks.entry(key) {
+        let networks = scan().await?;
+
+        // Merge results: keep the strongest signal per access point. Every network
+        // has a BSSID here, real or synthetic (see `fill_missing_bssids`). Backends
+        // differ in case and padding ("a:b:c:d:e:f"), so BSSIDs are made canonical
+        // here and everything downstream can compare them as plain strings.
+        for mut network in networks {
+            network.mac = canonical_mac(&network.mac);
+            match all_networks.entry(network.mac.clone()) {
                 Entry::Vacant(e) => {
                     e.insert(network);
                 }
@@ -211,23 +479,47 @@ pub async fn scan_networks() -> Result<Vec<Network>> {
     Ok(all_networks.into_values().collect())
 }
 
+/// Run a scan pass, retrying with exponential backoff while it times out
+async fn single_scan_with_retry() -> Result<Vec<Network>, ScanError> {
+    let mut backoff = Duration::from_millis(SCAN_RETRY_BACKOFF_MS);
+    let mut retries = 0;
+
+    loop {
+        match single_scan().await {
+            Err(e) if e.is_transient() && retries < SCAN_RETRIES => {
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+                retries += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
 /// Perform a single WiFi scan pass
-async fn single_scan() -> Result<Vec<Network>> {
+async fn single_scan() -> Result<Vec<Network>, ScanError> {
     // Try Swift CoreWLAN scanner first (works on Sonoma/Sequoia/Tahoe)
     #[cfg(target_os = "macos")]
-    {
-        match scan_macos_swift().await {
-            Ok(networks) if !networks.is_empty() => return Ok(networks),
-            _ => {}
-        }
-    }
+    let native = Some(scan_macos_swift().await);
+    // iw scans without an association, so it goes before wifiscanner
+    #[cfg(target_os = "linux")]
+    let native = scan_linux_iw().await;
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    let native = None;
+
+    let native_error = match native_scan_outcome(native) {
+        ControlFlow::Break(networks) => return Ok(networks),
+        ControlFlow::Continue(error) => error,
+    };
 
     // Fallback to wifiscanner crate (works on older macOS, Linux, Windows)
-    let result = tokio::task::spawn_blocking(wifiscanner::scan).await?;
+    let result = tokio::task::spawn_blocking(wifiscanner::scan)
+        .await
+        .map_err(|e| ScanError::Backend(e.to_string()))?;
 
     match result {
         Ok(wifi_networks) => {
-            let networks: Vec<Network> = wifi_networks
+            let mut networks: Vec<Network> = wifi_networks
                 .into_iter()
                 .map(|wifi| {
                     let channel = wifi.channel.parse::<u8>().unwrap_or(0);
@@ -237,7 +529,7 @@ async fn single_scan() -> Result<Vec<Network>> {
 
                     Network {
                         ssid: if wifi.ssid.is_empty() {
-                            "<Hidden>".to_string()
+                            HIDDEN_SSID.to_string()
                         } else {
                             wifi.ssid
                         },
@@ -252,12 +544,31 @@ async fn single_scan() -> Result<Vec<Network>> {
                 })
                 .collect();
 
+            fill_missing_bssids(&mut networks);
             Ok(networks)
         }
-        Err(e) => Err(color_eyre::eyre::eyre!(
-            "WiFi scan failed: {:?}\n\nTry running with --demo flag for simulated data.",
-            e
-        )),
+        Err(e) => Err(prefer_native_error(native_error, format!("{:?}", e))),
+    }
+}
+
+/// Networks from the native scanner (CoreWLAN, iw) end the pass; anything
+/// else falls through to wifiscanner, carrying the native error along in
+/// case the fallback fails too
+fn native_scan_outcome(result: Option<Result<Vec<Network>, ScanError>>) -> ControlFlow<Vec<Network>, Option<ScanError>> {
+    match result {
+        Some(Ok(networks)) if !networks.is_empty() => ControlFlow::Break(networks),
+        Some(Err(e)) => ControlFlow::Continue(Some(e)),
+        _ => ControlFlow::Continue(None),
+    }
+}
+
+/// The error to report when wifiscanner failed too. The native failure is
+/// the more telling one: on modern macOS the fallback fails simply because
+/// the `airport` tool is gone, and on Linux it needs an association
+fn prefer_native_error(native: Option<ScanError>, fallback: String) -> ScanError {
+    match native {
+        Some(native) if !matches!(native, ScanError::Backend(_)) => native,
+        _ => ScanError::Backend(fallback),
     }
 }
 
@@ -269,19 +580,23 @@ fn generate_demo_networks() -> Vec<Network> {
         .duration_since(UNIX_EPOCH)
         .unwrap()
         .as_nanos() as u64;
+    demo_networks(seed)
+}
 
+/// The demo networks, with signal jitter (±3 dB) picked by `seed`
+pub fn demo_networks(seed: u64) -> Vec<Network> {
     let base_networks = vec![
         ("CoffeeShop_Free", SecurityType::Open, 36, -42, "A1:B2:C3:D4:E5:F6"),
         ("Airport_WiFi", SecurityType::Open, 6, -55, "11:22:33:44:55:66"),
         ("Starbucks_WiFi", SecurityType::WPA2, 11, -62, "AA:BB:CC:DD:EE:FF"),
         ("Hotel_Guest", SecurityType::Open, 1, -48, "12:34:56:78:9A:BC"),
-        ("Library_Public", SecurityType::Open, 149, -58, "DE:AD:BE:EF:CA:FE"),
+        ("Library_Public", SecurityType::Open, 149, -58, "00:1A:6D:EF:CA:FE"),
         ("FastFood_Free", SecurityType::Open, 6, -70, "FE:ED:FA:CE:00:11"),
         ("Mall_WiFi", SecurityType::WPA2, 44, -65, "22:33:44:55:66:77"),
         ("Neighbor_5G", SecurityType::WPA3, 36, -78, "88:99:AA:BB:CC:DD"),
         ("xfinitywifi", SecurityType::Open, 1, -72, "EE:FF:00:11:22:33"),
         ("ATT_WiFi", SecurityType::WPA2, 11, -80, "44:55:66:77:88:99"),
-        ("<Hidden>", SecurityType::WPA2, 6, -85, "00:11:22:33:44:55"),
+        (HIDDEN_SSID, SecurityType::WPA2, 6, -85, "00:11:22:33:44:55"),
     ];
 
     base_networks
@@ -320,8 +635,18 @@ fn parse_signal(signal: &str) -> i32 {
 fn parse_security(security: &str) -> SecurityType {
     let security_lower = security.to_lowercase();
 
-    if security_lower.is_empty() || security_lower.contains("none") || security_lower.contains("open") {
+    // Checked before "open", which "Enhanced Open" also contains. "owe" must
+    // be a whole token, so names that merely contain it aren't OWE.
+    let owe = security_lower.split(|c: char| !c.is_ascii_alphanumeric()).any(|t| t == "owe");
+    if owe || security_lower.contains("enhanced open") {
+        SecurityType::OWE
+    } else if security_lower.is_empty() || security_lower.contains("none") || security_lower.contains("open") {
         SecurityType::Open
+    } else if ["802.1x", "enterprise", "eap"].iter().any(|s| security_lower.contains(s)) {
+        SecurityType::Enterprise
+    } else if security_lower.contains("wpa3") && security_lower.contains("wpa2") {
+        // Transition mode: accepts both WPA2 and WPA3 clients
+        SecurityType::WPA2WPA3
     } else if security_lower.contains("wpa3") {
         SecurityType::WPA3
     } else if security_lower.contains("wpa2") {
@@ -334,3 +659,297 @@ fn parse_security(security: &str) -> SecurityType {
         SecurityType::Unknown
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn network(ssid: &str, channel: u8) -> Network {
+        Network { mac: String::new(), ..Network::test(ssid, channel, -60) }
+    }
+
+    #[test]
+    fn test_hidden_networks_on_same_channel_stay_separate() {
+        let mut networks = vec![
+            network(HIDDEN_SSID, 6),
+            network(HIDDEN_SSID, 6),
+            network(HIDDEN_SSID, 11),
+            network("CoffeeShop", 6),
+        ];
+        fill_missing_bssids(&mut networks);
+
+        let macs: std::collections::HashSet<_> = networks.iter().map(|n| n.mac.clone()).collect();
+        assert_eq!(macs.len(), 4);
+        assert!(networks.iter().all(|n| n.mac.starts_with("02:")));
+
+        // The same scan again yields the same BSSIDs, so passes merge instead of piling up
+        let mut again = vec![network(HIDDEN_SSID, 6), network(HIDDEN_SSID, 6)];
+        fill_missing_bssids(&mut again);
+        assert_eq!(again[0].mac, networks[0].mac);
+        assert_eq!(again[1].mac, networks[1].mac);
+    }
+
+    #[test]
+    fn test_scanner_band_field() {
+        let networks = parse_swift_scanner_output(
+            "Lab6E|aa:bb:cc:00:00:01|37|-55|WPA3|6GHz\n\
+             Lab5|aa:bb:cc:00:00:02|36|-60|WPA2|5GHz\n\
+             Old|aa:bb:cc:00:00:03|6|-70|WPA2\n",
+        );
+        let bands: Vec<_> = networks.iter().map(|n| n.frequency_band).collect();
+        assert_eq!(bands, [FrequencyBand::Band6GHz, FrequencyBand::Band5GHz, FrequencyBand::Band2_4GHz]);
+    }
+
+    #[test]
+    fn test_scanner_transition_mode() {
+        let networks = parse_swift_scanner_output(
+            "Home|aa:bb:cc:00:00:01|36|-55|WPA2/WPA3|5GHz\n\
+             Lab|aa:bb:cc:00:00:02|37|-60|WPA3|6GHz\n",
+        );
+        assert_eq!(networks[0].security, SecurityType::WPA2WPA3);
+        assert_eq!(networks[1].security, SecurityType::WPA3);
+    }
+
+    #[test]
+    fn test_parse_current_channel_band() {
+        let output = "\
+Wi-Fi:
+      Interfaces:
+        en0:
+          Status: Connected
+          Current Network Information:
+            Lab6E:
+              PHY Mode: 802.11ax
+              Channel: 37 (6GHz, 160MHz)
+              Signal / Noise: -55 dBm / -95 dBm
+          Other Local Wi-Fi Networks:
+            Neighbor:
+              Channel: 149 (5GHz, 80MHz)
+";
+        assert_eq!(parse_current_channel(output), Some((37, FrequencyBand::Band6GHz)));
+        assert_eq!(
+            parse_current_channel("Current Network Information:\n  Channel: 6 (2GHz, 20MHz)\n"),
+            Some((6, FrequencyBand::Band2_4GHz))
+        );
+        assert_eq!(
+            parse_current_channel("Current Network Information:\n  Channel: 36\n"),
+            Some((36, FrequencyBand::Band5GHz))
+        );
+        assert_eq!(parse_current_channel("Other Local Wi-Fi Networks:\n  Channel: 36\n"), None);
+    }
+
+    #[test]
+    fn test_strongest_on_channel_matches_band() {
+        let networks = vec![
+            Network { signal_dbm: -45, ..network("Neighbor5", 37) },
+            Network { signal_dbm: -60, frequency_band: FrequencyBand::Band6GHz, ..network("Lab6E", 37) },
+            Network { signal_dbm: -70, frequency_band: FrequencyBand::Band6GHz, ..network("Far6E", 37) },
+        ];
+
+        let connected = strongest_on_channel(&networks, 37, FrequencyBand::Band6GHz).unwrap();
+        assert_eq!(connected.ssid, "Lab6E");
+        assert_eq!(strongest_on_channel(&networks, 37, FrequencyBand::Band5GHz).unwrap().ssid, "Neighbor5");
+        assert!(strongest_on_channel(&networks, 36, FrequencyBand::Band5GHz).is_none());
+    }
+
+    const IW_SCAN: &str = "\
+BSS aa:bb:cc:00:00:01(on wlan0)
+\tTSF: 1234567890 usec (0d, 00:20:34)
+\tfreq: 2437
+\tbeacon interval: 100 TUs
+\tcapability: ESS Privacy ShortSlotTime (0x0411)
+\tsignal: -52.00 dBm
+\tlast seen: 120 ms ago
+\tSSID: HomeNet
+\tDS Parameter set: channel 6
+\tRSN:\t * Version: 1
+\t\t * Group cipher: CCMP
+\t\t * Pairwise ciphers: CCMP
+\t\t * Authentication suites: PSK SAE
+\t\t * Capabilities: 16-PTKSA-RC 1-GTKSA-RC (0x000c)
+BSS aa:bb:cc:00:00:02(on wlan0)
+\tfreq: 5180.0
+\tcapability: ESS Privacy (0x0011)
+\tsignal: -67.50 dBm
+\tSSID: Corp
+\tRSN:\t * Version: 1
+\t\t * Authentication suites: IEEE 802.1X
+\tBSS Load:
+\t\t * station count: 3
+BSS aa:bb:cc:00:00:03(on wlan0)
+\tfreq: 6135
+\tcapability: ESS Privacy (0x0011)
+\tsignal: -71.00 dBm
+\tSSID: \\x00\\x00\\x00
+\tRSN:\t * Version: 1
+\t\t * Authentication suites: SAE
+BSS aa:bb:cc:00:00:04(on wlan0)
+\tfreq: 2412
+\tcapability: ESS ShortSlotTime (0x0401)
+\tsignal: -80.00 dBm
+\tSSID: Cafe Guest
+BSS aa:bb:cc:00:00:05(on wlan0)
+\tfreq: 2462
+\tcapability: ESS Privacy (0x0411)
+\tsignal: -85.00 dBm
+\tSSID: OldRouter
+\tWPA:\t * Version: 1
+\t\t * Authentication suites: PSK
+";
+
+    #[test]
+    fn test_parse_iw_scan_output() {
+        let networks = parse_iw_scan_output(IW_SCAN);
+        let summary: Vec<_> = networks
+            .iter()
+            .map(|n| (n.ssid.as_str(), n.mac.as_str(), n.channel, n.frequency_band, n.signal_dbm, n.security.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("HomeNet", "aa:bb:cc:00:00:01", 6, FrequencyBand::Band2_4GHz, -52, SecurityType::WPA2WPA3),
+                ("Corp", "aa:bb:cc:00:00:02", 36, FrequencyBand::Band5GHz, -68, SecurityType::Enterprise),
+                (HIDDEN_SSID, "aa:bb:cc:00:00:03", 37, FrequencyBand::Band6GHz, -71, SecurityType::WPA3),
+                ("Cafe Guest", "aa:bb:cc:00:00:04", 1, FrequencyBand::Band2_4GHz, -80, SecurityType::Open),
+                ("OldRouter", "aa:bb:cc:00:00:05", 11, FrequencyBand::Band2_4GHz, -85, SecurityType::WPA),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_iw_associated_and_empty() {
+        // The associated AP carries a suffix after the interface; no output means no networks
+        let networks = parse_iw_scan_output(
+            "BSS 11:22:33:44:55:66(on wlp2s0) -- associated\n\tfreq: 5745\n\tsignal: -40.00 dBm\n\tSSID: Lab\n",
+        );
+        assert_eq!(networks.len(), 1);
+        assert_eq!(networks[0].mac, "11:22:33:44:55:66");
+        assert_eq!(networks[0].channel, 149);
+        assert_eq!(networks[0].security, SecurityType::Open);
+        assert!(parse_iw_scan_output("").is_empty());
+        assert_eq!(channel_from_freq(2484), (14, FrequencyBand::Band2_4GHz));
+        assert_eq!(channel_from_freq(900), (0, FrequencyBand::Unknown));
+    }
+
+    #[test]
+    fn test_parse_security_owe_and_enterprise() {
+        assert_eq!(parse_security("OWE"), SecurityType::OWE);
+        assert_eq!(parse_security("OWE Transition"), SecurityType::OWE);
+        // "owe" inside another word isn't OWE
+        assert_eq!(parse_security("PowerWEP"), SecurityType::WEP);
+        assert_eq!(parse_security("lowenergy-wpa2"), SecurityType::WPA2);
+        assert_eq!(parse_security("Enhanced Open"), SecurityType::OWE);
+        assert_eq!(parse_security("Open"), SecurityType::Open);
+        assert_eq!(parse_security("WPA2 Enterprise"), SecurityType::Enterprise);
+        assert_eq!(parse_security("WPA2 802.1X"), SecurityType::Enterprise);
+        assert_eq!(parse_security("wpa3-eap"), SecurityType::Enterprise);
+        assert_eq!(parse_security("WPA2 Personal"), SecurityType::WPA2);
+    }
+
+    #[tokio::test]
+    async fn test_single_pass_does_not_sleep() {
+        let options = ScanOptions { passes: 1, delay: Duration::from_secs(5) };
+        let mut calls = 0;
+        let start = std::time::Instant::now();
+        let networks = scan_passes(&options, || {
+            calls += 1;
+            std::future::ready(Ok(vec![Network { mac: "AA:BB:CC:DD:EE:FF".to_string(), ..network("Cafe", 6) }]))
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(calls, 1);
+        assert_eq!(networks.len(), 1);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_passes_merge_strongest_signal() {
+        let options = ScanOptions { passes: 3, delay: Duration::from_millis(10) };
+        let mut calls = 0;
+        let start = std::time::Instant::now();
+        let networks = scan_passes(&options, || {
+            calls += 1;
+            let signal_dbm = -70 + calls * 5;
+            std::future::ready(Ok(vec![Network {
+                mac: "aa:bb:cc:dd:ee:ff".to_string(),
+                signal_dbm,
+                ..network("Cafe", 6)
+            }]))
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(calls, 3);
+        assert_eq!(networks.len(), 1);
+        assert_eq!(networks[0].signal_dbm, -55);
+        // Two delays between three passes
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[tokio::test]
+    async fn test_bssid_variants_merge_into_one_network() {
+        let options = ScanOptions { passes: 3, delay: Duration::ZERO };
+        let variants = ["a4:c3:61:b:2e:ff", "A4:C3:61:0B:2E:FF", "a4-c3-61-0b-2e-ff"];
+        let mut calls = 0;
+        let networks = scan_passes(&options, || {
+            let mac = variants[calls].to_string();
+            calls += 1;
+            std::future::ready(Ok(vec![Network { mac, signal_dbm: -70 + calls as i32, ..network("Cafe", 6) }]))
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(networks.len(), 1);
+        assert_eq!(networks[0].mac, "A4:C3:61:0B:2E:FF");
+        assert_eq!(networks[0].signal_dbm, -67);
+    }
+
+    #[test]
+    fn test_native_error_falls_back_to_wifiscanner() {
+        // Only a successful native scan skips the fallback
+        assert!(matches!(native_scan_outcome(Some(Ok(vec![network("Home", 6)]))), ControlFlow::Break(n) if n.len() == 1));
+        assert!(matches!(
+            native_scan_outcome(Some(Err(ScanError::Busy))),
+            ControlFlow::Continue(Some(ScanError::Busy))
+        ));
+        assert!(matches!(native_scan_outcome(Some(Ok(Vec::new()))), ControlFlow::Continue(None)));
+        assert!(matches!(native_scan_outcome(None), ControlFlow::Continue(None)));
+
+        // When the fallback fails too, the native error is the one reported
+        let unsupported = ScanError::Unsupported("wlan0: scan not supported".to_string());
+        assert_eq!(prefer_native_error(Some(unsupported.clone()), "no wifi".to_string()), unsupported);
+        assert_eq!(
+            prefer_native_error(Some(ScanError::Backend("spawn".to_string())), "no wifi".to_string()),
+            ScanError::Backend("no wifi".to_string())
+        );
+        assert_eq!(prefer_native_error(None, "no wifi".to_string()), ScanError::Backend("no wifi".to_string()));
+    }
+
+    #[test]
+    fn test_synthetic_bssid_tells_6ghz_apart() {
+        // "Lab" on 2.4 GHz channel 1 and on 6 GHz channel 1 are different APs
+        let mut networks = vec![
+            network("Lab", 1),
+            Network { frequency_band: FrequencyBand::Band6GHz, ..network("Lab", 1) },
+            network(HIDDEN_SSID, 5),
+            Network { frequency_band: FrequencyBand::Band6GHz, ..network(HIDDEN_SSID, 5) },
+        ];
+        fill_missing_bssids(&mut networks);
+        assert_ne!(networks[0].mac, networks[1].mac);
+        assert_ne!(networks[2].mac, networks[3].mac);
+
+        // Other bands hash as before: SSID and channel only
+        assert_eq!(
+            generate_synthetic_mac("Lab", 36, FrequencyBand::Band5GHz),
+            generate_synthetic_mac("Lab", 36, FrequencyBand::Band2_4GHz)
+        );
+    }
+
+    #[test]
+    fn test_real_bssid_is_kept() {
+        let mut networks = vec![Network { mac: "AA:BB:CC:DD:EE:FF".to_string(), ..network(HIDDEN_SSID, 6) }];
+        fill_missing_bssids(&mut networks);
+        assert_eq!(networks[0].mac, "AA:BB:CC:DD:EE:FF");
+    }
+}
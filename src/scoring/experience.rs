@@ -0,0 +1,105 @@
+use crate::db::ConnectionRecord;
+use crate::scanner::Network;
+use crate::scoring::score_signal;
+
+/// Download speed that feels fast for browsing and video calls
+const FAST_DOWNLOAD_MBPS: f64 = 50.0;
+/// Upload speed that comfortably carries a video call
+const FAST_UPLOAD_MBPS: f64 = 20.0;
+/// Latency at or below this is indistinguishable from a wired connection
+const GOOD_LATENCY_MS: f64 = 30.0;
+/// Latency at or above this makes calls and interactive use painful
+const BAD_LATENCY_MS: f64 = 300.0;
+
+/// Score measured download speed: 0 Mbps = 0, 50+ Mbps = 100
+pub fn score_download(mbps: f64) -> f32 {
+    ((mbps / FAST_DOWNLOAD_MBPS).clamp(0.0, 1.0) * 100.0) as f32
+}
+
+/// Score measured upload speed: 0 Mbps = 0, 20+ Mbps = 100
+pub fn score_upload(mbps: f64) -> f32 {
+    ((mbps / FAST_UPLOAD_MBPS).clamp(0.0, 1.0) * 100.0) as f32
+}
+
+/// Score round-trip latency: 30 ms or less = 100, 300 ms or more = 0
+pub fn score_latency(ms: f64) -> f32 {
+    let normalized = (BAD_LATENCY_MS - ms) / (BAD_LATENCY_MS - GOOD_LATENCY_MS);
+    (normalized.clamp(0.0, 1.0) * 100.0) as f32
+}
+
+/// How good a network actually was to use (0-100), from the measurements
+/// of a connection plus the current signal. Unlike the scan-time `score`
+/// this needs a speed test, so only call it for records that have one.
+/// Weights: Download 40%, Latency 25%, Signal 20%, Upload 15%; factors
+/// missing from the record are left out and the rest reweighted.
+pub fn experience_score(network: &Network, last_connection: &ConnectionRecord) -> u8 {
+    let factors = [
+        (last_connection.download_mbps.map(score_download), 0.40),
+        (last_connection.latency_ms.map(score_latency), 0.25),
+        (Some(score_signal(network.signal_dbm)), 0.20),
+        (last_connection.upload_mbps.map(score_upload), 0.15),
+    ];
+
+    let (weighted, total_weight) = factors
+        .iter()
+        .filter_map(|&(score, weight)| score.map(|s| (s * weight, weight)))
+        .fold((0.0, 0.0), |(sum, total), (s, w)| (sum + s, total + w));
+
+    (weighted / total_weight).round().clamp(0.0, 100.0) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn network(signal_dbm: i32) -> Network {
+        Network { mac: "00:11:22:33:44:55".to_string(), ..Network::test("Cafe", 36, signal_dbm) }
+    }
+
+    fn connection(download: Option<f64>, upload: Option<f64>, latency: Option<f64>) -> ConnectionRecord {
+        ConnectionRecord {
+            id: 1,
+            network_id: 1,
+            connected_at: Utc::now(),
+            disconnected_at: None,
+            local_ip: None,
+            public_ip: None,
+            download_mbps: download,
+            upload_mbps: upload,
+            captive_status: None,
+            local_ipv6: None,
+            public_ipv6: None,
+            latency_ms: latency,
+        }
+    }
+
+    #[test]
+    fn test_fast_connection_scores_high() {
+        let score = experience_score(&network(-45), &connection(Some(120.0), Some(40.0), Some(12.0)));
+        assert!(score >= 95, "got {}", score);
+    }
+
+    #[test]
+    fn test_slow_connection_scores_low_despite_signal() {
+        // Full bars but a congested backhaul
+        let score = experience_score(&network(-35), &connection(Some(1.5), Some(0.5), Some(450.0)));
+        assert!(score <= 25, "got {}", score);
+    }
+
+    #[test]
+    fn test_middling_connection() {
+        // 25 Mbps down (50), 10 up (50), 165 ms (50), -60 dBm (50)
+        let score = experience_score(&network(-60), &connection(Some(25.0), Some(10.0), Some(165.0)));
+        assert_eq!(score, 50);
+    }
+
+    #[test]
+    fn test_missing_latency_is_reweighted() {
+        // Older records have no latency; it must not count as 0
+        let with = experience_score(&network(-30), &connection(Some(50.0), Some(20.0), Some(10.0)));
+        let without = experience_score(&network(-30), &connection(Some(50.0), Some(20.0), None));
+        assert_eq!(with, 100);
+        assert_eq!(without, 100);
+    }
+}
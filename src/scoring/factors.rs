@@ -10,32 +10,89 @@ pub fn score_signal(dbm: i32) -> f32 {
 }
 
 /// Score channel congestion (25% weight)
-/// Fewer networks on same channel = higher score
+/// Fewer networks on same channel = higher score.
+/// 2.4GHz channels overlap, so neighbors within ±4 channels also count,
+/// weighted by how close they are. 5/6GHz channels don't overlap.
 pub fn score_congestion(channel: u8, all_networks: &[Network]) -> f32 {
     if channel == 0 {
         return 50.0; // Unknown channel, neutral score
     }
 
-    let networks_on_channel = all_networks.iter().filter(|n| n.channel == channel).count();
+    let interference = channel_interference(channel, all_networks);
 
     // 1 network (just us) = 100, each additional network subtracts 15
-    let score = 100.0 - ((networks_on_channel.saturating_sub(1)) as f32 * 15.0);
+    let score = 100.0 - ((interference - 1.0).max(0.0) * 15.0);
     score.max(0.0)
 }
 
+/// How many networks a channel competes with: each network on the same
+/// channel counts 1, and on 2.4GHz overlapping neighbors count partially
+pub fn channel_interference(channel: u8, all_networks: &[Network]) -> f32 {
+    if FrequencyBand::from_channel(channel) == FrequencyBand::Band2_4GHz {
+        all_networks
+            .iter()
+            .filter(|n| FrequencyBand::from_channel(n.channel) == FrequencyBand::Band2_4GHz)
+            .map(|n| channel_overlap_weight(channel, n.channel))
+            .sum()
+    } else {
+        all_networks.iter().filter(|n| n.channel == channel).count() as f32
+    }
+}
+
+/// Interference weight between two 2.4GHz channels.
+/// Same channel = 1.0, falling off linearly to 0.0 at 5+ channels apart.
+fn channel_overlap_weight(a: u8, b: u8) -> f32 {
+    let distance = a.abs_diff(b);
+    if distance > 4 {
+        0.0
+    } else {
+        1.0 - distance as f32 / 5.0
+    }
+}
+
 /// Score security type (20% weight)
 /// For public WiFi use case: Open is preferred (easier to connect)
 pub fn score_security(security: &SecurityType) -> f32 {
     match security {
         SecurityType::Open => 100.0,
+        SecurityType::OWE => 95.0, // Joins like Open, but traffic is encrypted
+        SecurityType::WPA2WPA3 => 85.0, // Works with any client, WPA3 where supported
         SecurityType::WPA2 => 80.0,
         SecurityType::WPA3 => 70.0, // Newer but less compatible
         SecurityType::WPA => 60.0,
+        SecurityType::Enterprise => 40.0, // Needs an account, can't join ad hoc
         SecurityType::WEP => 30.0, // Insecure
         SecurityType::Unknown => 50.0,
     }
 }
 
+/// Security score once a captive portal check is known.
+/// A portal-gated open network is no easier to use than a password one,
+/// so it drops below WPA2.
+pub fn score_security_with_portal(security: &SecurityType, captive_portal: bool) -> f32 {
+    if captive_portal && matches!(security, SecurityType::Open | SecurityType::OWE) {
+        40.0
+    } else {
+        score_security(security)
+    }
+}
+
+/// Score security for places where protection matters more than convenience
+/// (the office scoring profile): encrypted networks first, open last
+pub fn score_security_strict(security: &SecurityType) -> f32 {
+    match security {
+        SecurityType::WPA3 => 100.0,
+        SecurityType::Enterprise => 95.0,
+        SecurityType::WPA2WPA3 => 90.0,
+        SecurityType::WPA2 => 80.0,
+        SecurityType::OWE => 60.0, // Encrypted, but anyone can join
+        SecurityType::WPA => 40.0,
+        SecurityType::Unknown => 30.0,
+        SecurityType::WEP => 10.0,
+        SecurityType::Open => 0.0,
+    }
+}
+
 /// Score frequency band (15% weight)
 /// 5GHz typically has less congestion and higher speeds
 pub fn score_band(band: FrequencyBand) -> f32 {
@@ -47,6 +104,28 @@ pub fn score_band(band: FrequencyBand) -> f32 {
     }
 }
 
+/// Newest signal samples used for the stability metric
+pub const STABILITY_WINDOW: usize = 10;
+
+/// Standard deviation of signal samples in dB. An AP whose signal jitters
+/// a lot between scans is often loaded or interfered with. None with fewer
+/// than 3 samples.
+pub fn signal_std_dev(samples: &[i32]) -> Option<f32> {
+    if samples.len() < 3 {
+        return None;
+    }
+    let n = samples.len() as f32;
+    let mean = samples.iter().map(|&dbm| dbm as f32).sum::<f32>() / n;
+    let variance = samples.iter().map(|&dbm| (dbm as f32 - mean).powi(2)).sum::<f32>() / n;
+    Some(variance.sqrt())
+}
+
+/// Points taken off the overall score for an unstable signal:
+/// none up to ±3 dB, then 1 per dB, at most 10
+pub fn stability_penalty(std_dev: f32) -> u8 {
+    (std_dev - 3.0).clamp(0.0, 10.0).round() as u8
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -66,6 +145,79 @@ mod tests {
         assert_eq!(score_security(&SecurityType::Open), 100.0);
         assert_eq!(score_security(&SecurityType::WPA2), 80.0);
         assert_eq!(score_security(&SecurityType::WEP), 30.0);
+        // Transition mode scores at least as well as plain WPA2
+        assert!(score_security(&SecurityType::WPA2WPA3) >= score_security(&SecurityType::WPA2));
+        assert!(score_security(&SecurityType::OWE) > score_security(&SecurityType::WPA2WPA3));
+        assert!(score_security(&SecurityType::Enterprise) < score_security(&SecurityType::WPA));
+    }
+
+    #[test]
+    fn test_strict_security_scoring() {
+        assert!(score_security_strict(&SecurityType::WPA3) > score_security_strict(&SecurityType::WPA2));
+        assert!(score_security_strict(&SecurityType::OWE) > score_security_strict(&SecurityType::Open));
+        assert_eq!(score_security_strict(&SecurityType::Open), 0.0);
+    }
+
+    #[test]
+    fn test_captive_portal_penalty() {
+        assert_eq!(score_security_with_portal(&SecurityType::Open, false), 100.0);
+        assert!(
+            score_security_with_portal(&SecurityType::Open, true)
+                < score_security(&SecurityType::WPA2)
+        );
+        // Only open networks are penalized
+        assert_eq!(score_security_with_portal(&SecurityType::WPA2, true), 80.0);
+    }
+
+    fn network_on(channel: u8) -> Network {
+        Network {
+            mac: format!("00:00:00:00:00:{:02X}", channel),
+            ..Network::test(&format!("Net{}", channel), channel, -60)
+        }
+    }
+
+    #[test]
+    fn test_congestion_non_overlapping_beats_adjacent() {
+        let spread: Vec<Network> = [1, 6, 11].into_iter().map(network_on).collect();
+        let packed: Vec<Network> = [1, 2, 3].into_iter().map(network_on).collect();
+
+        for (s, p) in spread.iter().zip(&packed) {
+            let spread_score = score_congestion(s.channel, &spread);
+            let packed_score = score_congestion(p.channel, &packed);
+            assert!(
+                spread_score > packed_score,
+                "ch {} ({}) should beat ch {} ({})",
+                s.channel, spread_score, p.channel, packed_score
+            );
+        }
+        assert_eq!(score_congestion(6, &spread), 100.0);
+    }
+
+    #[test]
+    fn test_congestion_5ghz_exact_match_only() {
+        let networks: Vec<Network> = [36, 40, 44].into_iter().map(network_on).collect();
+        assert_eq!(score_congestion(40, &networks), 100.0);
+
+        let same: Vec<Network> = [36, 36].into_iter().map(network_on).collect();
+        assert_eq!(score_congestion(36, &same), 85.0);
+    }
+
+    #[test]
+    fn test_signal_std_dev() {
+        assert_eq!(signal_std_dev(&[-60, -61]), None);
+        assert_eq!(signal_std_dev(&[-60, -60, -60]), Some(0.0));
+        // Mean -60, squared deviations 0+4+4+16+16 = 40, variance 8
+        let sd = signal_std_dev(&[-60, -62, -58, -64, -56]).unwrap();
+        assert!((sd - 8f32.sqrt()).abs() < 1e-4, "{}", sd);
+        assert_eq!(signal_std_dev(&[-50, -70, -50, -70]), Some(10.0));
+    }
+
+    #[test]
+    fn test_stability_penalty() {
+        assert_eq!(stability_penalty(0.0), 0);
+        assert_eq!(stability_penalty(3.0), 0);
+        assert_eq!(stability_penalty(10.0), 7);
+        assert_eq!(stability_penalty(40.0), 10);
     }
 
     #[test]
@@ -1,30 +1,183 @@
+mod experience;
 mod factors;
+mod profile;
+mod recommend;
 
 use crate::scanner::Network;
+pub use experience::*;
 pub use factors::*;
+pub use profile::*;
+pub use recommend::*;
+use std::collections::HashSet;
+use std::fmt;
+
+/// One factor's 0-100 value and the weight it counts with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FactorScore {
+    pub name: &'static str,
+    pub value: f32,
+    pub weight: f32,
+}
+
+impl FactorScore {
+    /// Points this factor adds to the overall score
+    pub fn contribution(&self) -> f32 {
+        self.value * self.weight
+    }
+}
+
+impl fmt::Display for FactorScore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:.0} ×{:.2}", self.name, self.value, self.weight)
+    }
+}
+
+/// The factors behind a network's score: signal, congestion, security, band
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoreBreakdown {
+    pub factors: [FactorScore; 4],
+}
+
+impl ScoreBreakdown {
+    /// Weighted sum of the factors, the score `calculate_score_weighted` gives
+    pub fn total(&self) -> u8 {
+        let sum: f32 = self.factors.iter().map(FactorScore::contribution).sum();
+        sum.round().clamp(0.0, 100.0) as u8
+    }
+}
+
+impl fmt::Display for ScoreBreakdown {
+    /// "Signal 62 ×0.40, Congestion 85 ×0.25, Security 100 ×0.20, Band 60 ×0.15 → 75"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let factors: Vec<String> = self.factors.iter().map(|factor| factor.to_string()).collect();
+        write!(f, "{} → {}", factors.join(", "), self.total())
+    }
+}
 
 /// Calculate the overall score for a network (0-100)
 /// Weights: Signal 40%, Congestion 25%, Security 20%, Band 15%
 pub fn calculate_score(network: &Network, all_networks: &[Network]) -> u8 {
-    let signal_score = score_signal(network.signal_dbm);
-    let congestion_score = score_congestion(network.channel, all_networks);
-    let security_score = score_security(&network.security);
-    let band_score = score_band(network.frequency_band);
+    calculate_score_with_portal(network, all_networks, false)
+}
 
-    let weighted_score = (signal_score * 0.40)
-        + (congestion_score * 0.25)
-        + (security_score * 0.20)
-        + (band_score * 0.15);
+/// Calculate the overall score, penalizing open networks known to sit
+/// behind a captive portal
+pub fn calculate_score_with_portal(
+    network: &Network,
+    all_networks: &[Network],
+    captive_portal: bool,
+) -> u8 {
+    calculate_score_weighted(network, all_networks, captive_portal, &ScoringWeights::default())
+}
+
+/// Calculate the overall score with a location's scoring profile weights
+pub fn calculate_score_weighted(
+    network: &Network,
+    all_networks: &[Network],
+    captive_portal: bool,
+    weights: &ScoringWeights,
+) -> u8 {
+    score_breakdown_weighted(network, all_networks, captive_portal, weights).total()
+}
 
-    weighted_score.round().clamp(0.0, 100.0) as u8
+/// Per-factor values and weights behind `calculate_score`
+pub fn score_breakdown(network: &Network, all_networks: &[Network]) -> ScoreBreakdown {
+    score_breakdown_weighted(network, all_networks, false, &ScoringWeights::default())
+}
+
+/// Per-factor values and weights behind `calculate_score_weighted`
+pub fn score_breakdown_weighted(
+    network: &Network,
+    all_networks: &[Network],
+    captive_portal: bool,
+    weights: &ScoringWeights,
+) -> ScoreBreakdown {
+    let security_score = if weights.prefer_secure {
+        score_security_strict(&network.security)
+    } else {
+        score_security_with_portal(&network.security, captive_portal)
+    };
+    let factor = |name, value, weight| FactorScore { name, value, weight };
+
+    ScoreBreakdown {
+        factors: [
+            factor("Signal", score_signal(network.signal_dbm), weights.signal),
+            factor("Congestion", score_congestion(network.channel, all_networks), weights.congestion),
+            factor("Security", security_score, weights.security),
+            factor("Band", score_band(network.frequency_band), weights.band),
+        ],
+    }
 }
 
 /// Calculate scores for all networks
 pub fn calculate_all_scores(networks: &mut [Network]) {
+    calculate_all_scores_with_portals(networks, &HashSet::new());
+}
+
+/// Calculate scores for all networks, given the (uppercase) BSSIDs known to
+/// have a captive portal
+pub fn calculate_all_scores_with_portals(networks: &mut [Network], captive_bssids: &HashSet<String>) {
+    calculate_all_scores_weighted(networks, captive_bssids, &ScoringWeights::default());
+}
+
+/// Calculate scores for all networks with a location's scoring profile weights
+pub fn calculate_all_scores_weighted(
+    networks: &mut [Network],
+    captive_bssids: &HashSet<String>,
+    weights: &ScoringWeights,
+) {
     // Need to clone for the borrow checker since we're reading and writing
     let networks_ref: Vec<Network> = networks.to_vec();
 
     for network in networks.iter_mut() {
-        network.score = calculate_score(network, &networks_ref);
+        let captive = captive_bssids.contains(&network.mac.to_uppercase());
+        network.score = calculate_score_weighted(network, &networks_ref, captive, weights);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::SecurityType;
+
+    fn network(ssid: &str, channel: u8, signal_dbm: i32, security: SecurityType) -> Network {
+        Network {
+            mac: format!("AA:BB:CC:00:00:{:02X}", channel),
+            security,
+            ..Network::test(ssid, channel, signal_dbm)
+        }
+    }
+
+    #[test]
+    fn test_breakdown_sums_to_score() {
+        let networks = vec![
+            network("Cafe", 6, -53, SecurityType::Open),
+            network("Neighbor", 6, -80, SecurityType::WPA2),
+            network("Office", 36, -67, SecurityType::WPA3),
+            network("Hidden", 0, -90, SecurityType::Unknown),
+        ];
+
+        for n in &networks {
+            let breakdown = score_breakdown(n, &networks);
+            assert_eq!(breakdown.total(), calculate_score(n, &networks), "{}", n.ssid);
+
+            let weights = ScoringProfile::Office.weights();
+            let weighted = score_breakdown_weighted(n, &networks, true, &weights);
+            assert_eq!(weighted.total(), calculate_score_weighted(n, &networks, true, &weights), "{}", n.ssid);
+        }
+    }
+
+    #[test]
+    fn test_breakdown_display() {
+        let networks = vec![network("Cafe", 6, -53, SecurityType::Open), network("Neighbor", 6, -80, SecurityType::WPA2)];
+        let breakdown = score_breakdown(&networks[0], &networks);
+
+        let weights: Vec<f32> = breakdown.factors.iter().map(|f| f.weight).collect();
+        assert_eq!(weights, [0.40, 0.25, 0.20, 0.15]);
+        // Signal 62 (-53 dBm), congestion 85 (one neighbor), open 100, 2.4 GHz 60
+        assert_eq!(
+            breakdown.to_string(),
+            "Signal 62 ×0.40, Congestion 85 ×0.25, Security 100 ×0.20, Band 60 ×0.15 → 75"
+        );
     }
 }
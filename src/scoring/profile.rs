@@ -0,0 +1,94 @@
+/// How much each factor counts toward a network's score
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoringWeights {
+    pub signal: f32,
+    pub congestion: f32,
+    pub security: f32,
+    pub band: f32,
+    /// Rank encrypted networks above open ones instead of favoring easy-to-join ones
+    pub prefer_secure: bool,
+}
+
+impl Default for ScoringWeights {
+    fn default() -> Self {
+        ScoringProfile::default().weights()
+    }
+}
+
+/// Named set of weights for a kind of place, remembered per location
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScoringProfile {
+    /// Signal 40%, congestion 25%, security 20%, band 15%
+    #[default]
+    Balanced,
+    /// Open and fast: easy-to-join networks with a strong, uncrowded signal
+    Cafe,
+    /// Secure first: WPA3/WPA2 above open networks
+    Office,
+}
+
+impl ScoringProfile {
+    pub const ALL: [ScoringProfile; 3] = [ScoringProfile::Balanced, ScoringProfile::Cafe, ScoringProfile::Office];
+
+    /// Name stored in the database and accepted by --profile
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScoringProfile::Balanced => "balanced",
+            ScoringProfile::Cafe => "cafe",
+            ScoringProfile::Office => "office",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|profile| profile.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    pub fn weights(&self) -> ScoringWeights {
+        match self {
+            ScoringProfile::Balanced => ScoringWeights {
+                signal: 0.40,
+                congestion: 0.25,
+                security: 0.20,
+                band: 0.15,
+                prefer_secure: false,
+            },
+            ScoringProfile::Cafe => ScoringWeights {
+                signal: 0.40,
+                congestion: 0.20,
+                security: 0.30,
+                band: 0.10,
+                prefer_secure: false,
+            },
+            ScoringProfile::Office => ScoringWeights {
+                signal: 0.30,
+                congestion: 0.20,
+                security: 0.40,
+                band: 0.10,
+                prefer_secure: true,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weights_sum_to_one() {
+        for profile in ScoringProfile::ALL {
+            let w = profile.weights();
+            let total = w.signal + w.congestion + w.security + w.band;
+            assert!((total - 1.0).abs() < 1e-6, "{} sums to {}", profile.name(), total);
+        }
+    }
+
+    #[test]
+    fn test_profile_names_round_trip() {
+        for profile in ScoringProfile::ALL {
+            assert_eq!(ScoringProfile::from_name(profile.name()), Some(profile));
+        }
+        assert_eq!(ScoringProfile::from_name("Office"), Some(ScoringProfile::Office));
+        assert_eq!(ScoringProfile::from_name("library"), None);
+    }
+}
@@ -0,0 +1,101 @@
+use crate::scanner::{FrequencyBand, Network};
+use crate::scoring::channel_interference;
+
+/// Channels usable worldwide on 2.4GHz (12-14 are region-restricted)
+pub const RECOMMENDABLE_2_4GHZ: &[u8] = &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+
+/// Commonly used 5GHz channels (UNII-1, UNII-2 and UNII-3)
+pub const CHANNELS_5GHZ: &[u8] = &[36, 40, 44, 48, 52, 56, 60, 64, 149, 153, 157, 161, 165];
+
+/// The only three 2.4GHz channels that don't overlap each other
+const NON_OVERLAPPING_2_4GHZ: &[u8] = &[1, 6, 11];
+
+/// Least congested channel(s) in a band for setting up your own AP, using
+/// the same overlap-aware interference as the congestion score. Ties are
+/// all returned, best first: on 2.4GHz the non-overlapping 1/6/11 lead.
+/// Empty for bands without a channel list (6GHz, unknown).
+pub fn recommend_channel(all_networks: &[Network], band: FrequencyBand) -> Vec<u8> {
+    let candidates = match band {
+        FrequencyBand::Band2_4GHz => RECOMMENDABLE_2_4GHZ,
+        FrequencyBand::Band5GHz => CHANNELS_5GHZ,
+        FrequencyBand::Band6GHz | FrequencyBand::Unknown => return Vec::new(),
+    };
+
+    let scored: Vec<(u8, f32)> = candidates
+        .iter()
+        .map(|&channel| (channel, channel_interference(channel, all_networks)))
+        .collect();
+    let least = scored.iter().map(|&(_, i)| i).fold(f32::INFINITY, f32::min);
+
+    let mut best: Vec<u8> = scored
+        .into_iter()
+        .filter(|&(_, interference)| interference - least < 0.001)
+        .map(|(channel, _)| channel)
+        .collect();
+    best.sort_by_key(|channel| (!NON_OVERLAPPING_2_4GHZ.contains(channel), *channel));
+    best
+}
+
+/// One-line summary, e.g. "Recommended: 2.4 GHz ch 11, 5 GHz ch 149"
+pub fn recommendation_summary(all_networks: &[Network]) -> String {
+    let picks: Vec<String> = [FrequencyBand::Band2_4GHz, FrequencyBand::Band5GHz]
+        .into_iter()
+        .filter_map(|band| {
+            recommend_channel(all_networks, band)
+                .first()
+                .map(|channel| format!("{} ch {}", band, channel))
+        })
+        .collect();
+    format!("Recommended: {}", picks.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn networks_on(channels: &[u8]) -> Vec<Network> {
+        channels
+            .iter()
+            .enumerate()
+            .map(|(i, &channel)| Network {
+                mac: format!("00:00:00:00:{:02X}:{:02X}", i, channel),
+                ..Network::test(&format!("Net{}", i), channel, -60)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_empty_environment_prefers_non_overlapping() {
+        let channels = recommend_channel(&[], FrequencyBand::Band2_4GHz);
+        assert_eq!(channels.len(), RECOMMENDABLE_2_4GHZ.len());
+        assert_eq!(&channels[..3], &[1, 6, 11]);
+        assert_eq!(recommend_channel(&[], FrequencyBand::Band5GHz)[0], 36);
+    }
+
+    #[test]
+    fn test_crowded_low_channels_recommend_11() {
+        // Typical apartment: everyone on 1 and 6
+        let networks = networks_on(&[1, 1, 1, 6, 6, 6, 6]);
+        assert_eq!(recommend_channel(&networks, FrequencyBand::Band2_4GHz), vec![11]);
+    }
+
+    #[test]
+    fn test_overlap_counts_neighbors() {
+        // 1 and 11 are taken; channel 6 is 5 away from both, so it's clear
+        let networks = networks_on(&[1, 11]);
+        assert_eq!(recommend_channel(&networks, FrequencyBand::Band2_4GHz), vec![6]);
+    }
+
+    #[test]
+    fn test_5ghz_ignores_2_4ghz_networks() {
+        let networks = networks_on(&[36, 36, 40, 44, 48, 52, 56, 60, 64, 153, 157, 161, 165, 1, 6]);
+        assert_eq!(recommend_channel(&networks, FrequencyBand::Band5GHz), vec![149]);
+    }
+
+    #[test]
+    fn test_summary() {
+        let networks = networks_on(&[1, 1, 6, 6, 36, 40, 44, 48, 52, 56, 60, 64, 153, 157, 161, 165]);
+        assert_eq!(recommendation_summary(&networks), "Recommended: 2.4 GHz ch 11, 5 GHz ch 149");
+        assert!(recommend_channel(&networks, FrequencyBand::Band6GHz).is_empty());
+    }
+}
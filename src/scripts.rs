@@ -0,0 +1,195 @@
+//! Embedded Swift helper scripts
+//!
+//! The CoreWLAN helpers are compiled into the binary and extracted to a temp
+//! directory on first use, so the tool works regardless of install location.
+//! The directory is private to the user: anyone able to swap a script out
+//! would have it run as us.
+
+use color_eyre::Result;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+/// Whether the `swift` interpreter runs, looked up once per process
+static SWIFT_AVAILABLE: OnceLock<bool> = OnceLock::new();
+
+/// Logged once at startup when the helpers can't run
+pub const SWIFT_MISSING_WARNING: &str =
+    "swift not found: BSSIDs and CoreWLAN scans unavailable (install with xcode-select --install)";
+
+/// A Swift helper script embedded at build time
+pub struct SwiftScript {
+    pub name: &'static str,
+    pub source: &'static str,
+}
+
+/// CoreWLAN WiFi scanner
+pub const WIFI_SCAN: SwiftScript = SwiftScript {
+    name: "wifi_scan.swift",
+    source: include_str!("../scripts/wifi_scan.swift"),
+};
+
+/// Current connection (SSID/BSSID) lookup
+pub const WIFI_CURRENT: SwiftScript = SwiftScript {
+    name: "wifi_current.swift",
+    source: include_str!("../scripts/wifi_current.swift"),
+};
+
+/// CoreWLAN WiFi connector
+pub const WIFI_CONNECT: SwiftScript = SwiftScript {
+    name: "wifi_connect.swift",
+    source: include_str!("../scripts/wifi_connect.swift"),
+};
+
+impl SwiftScript {
+    /// Path to an extracted copy of the script, writing it if missing or stale
+    pub fn path(&self) -> Result<PathBuf> {
+        let dir = script_dir()?;
+        let path = dir.join(self.name);
+        let up_to_date = std::fs::read_to_string(&path)
+            .map(|existing| existing == self.source)
+            .unwrap_or(false);
+
+        if !up_to_date {
+            // Write to a per-process file and rename so concurrent runs never see a partial script
+            let tmp = dir.join(format!("{}.{}", self.name, std::process::id()));
+            // Left over from a crashed run with the same pid
+            let _ = std::fs::remove_file(&tmp);
+            std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&tmp)?
+                .write_all(self.source.as_bytes())?;
+            std::fs::rename(&tmp, &path)?;
+        }
+
+        Ok(path)
+    }
+}
+
+/// This user's script directory in the temp dir, created 0700
+#[cfg(unix)]
+fn script_dir() -> Result<PathBuf> {
+    use std::os::unix::fs::DirBuilderExt;
+
+    // SAFETY: geteuid has no preconditions and can't fail
+    let uid = unsafe { libc::geteuid() };
+    let dir = std::env::temp_dir().join(format!("wifi-analyzer-scripts-{}", uid));
+    match std::fs::DirBuilder::new().mode(0o700).create(&dir) {
+        Err(e) if e.kind() != std::io::ErrorKind::AlreadyExists => return Err(e.into()),
+        _ => {}
+    }
+    check_private_dir(&dir, uid)?;
+    Ok(dir)
+}
+
+#[cfg(not(unix))]
+fn script_dir() -> Result<PathBuf> {
+    let dir = std::env::temp_dir().join("wifi-analyzer-scripts");
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Refuse a directory someone else planted: it must be a real directory
+/// (not a symlink), owned by `uid`, that no one else can write to
+#[cfg(unix)]
+fn check_private_dir(dir: &std::path::Path, uid: u32) -> Result<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = std::fs::symlink_metadata(dir)?;
+    if !metadata.is_dir() || metadata.uid() != uid || metadata.mode() & 0o077 != 0 {
+        return Err(color_eyre::eyre::eyre!(
+            "{} is not a private directory of this user; remove it to let the Swift helpers run",
+            dir.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Whether `swift` is installed and runs. Checked on first use and cached, so
+/// without the Xcode Command Line Tools every scan skips the Swift helpers
+/// instead of spawning a process that fails.
+pub fn swift_available() -> bool {
+    cached_check(&SWIFT_AVAILABLE, || command_runs("swift"))
+}
+
+fn cached_check(cell: &OnceLock<bool>, check: impl FnOnce() -> bool) -> bool {
+    *cell.get_or_init(check)
+}
+
+/// Whether `program --version` starts and exits successfully. On macOS the
+/// `/usr/bin/swift` stub exists even without the developer tools, but fails.
+fn command_runs(program: &str) -> bool {
+    Command::new(program)
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedded_scripts_not_empty() {
+        for script in [WIFI_SCAN, WIFI_CURRENT, WIFI_CONNECT] {
+            assert!(!script.source.trim().is_empty(), "{} is empty", script.name);
+        }
+    }
+
+    #[test]
+    fn test_extracted_script_exists() {
+        let path = WIFI_CURRENT.path().unwrap();
+        assert!(path.exists());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), WIFI_CURRENT.source);
+
+        // Extracting again reuses the same file
+        assert_eq!(WIFI_CURRENT.path().unwrap(), path);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_script_dir_must_be_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = script_dir().unwrap();
+        let mode = std::fs::metadata(&dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+
+        // SAFETY: geteuid has no preconditions and can't fail
+        let uid = unsafe { libc::geteuid() };
+        let shared = std::env::temp_dir().join(format!("wifi-analyzer-shared-{}", std::process::id()));
+        std::fs::create_dir_all(&shared).unwrap();
+        std::fs::set_permissions(&shared, std::fs::Permissions::from_mode(0o777)).unwrap();
+        assert!(check_private_dir(&shared, uid).is_err());
+        std::fs::set_permissions(&shared, std::fs::Permissions::from_mode(0o700)).unwrap();
+        assert!(check_private_dir(&shared, uid).is_ok());
+        // Someone else's directory
+        assert!(check_private_dir(&shared, uid.wrapping_add(1)).is_err());
+        std::fs::remove_dir(&shared).unwrap();
+    }
+
+    #[test]
+    fn test_availability_checked_once() {
+        let cell = OnceLock::new();
+        let mut checks = 0;
+        assert!(!cached_check(&cell, || {
+            checks += 1;
+            false
+        }));
+        // Later calls reuse the first answer without checking again
+        assert!(!cached_check(&cell, || {
+            checks += 1;
+            true
+        }));
+        assert_eq!(checks, 1);
+    }
+
+    #[test]
+    fn test_missing_command_not_available() {
+        assert!(!command_runs("wifi-analyzer-no-such-program"));
+    }
+}
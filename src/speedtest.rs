@@ -1,6 +1,6 @@
 //! Speed test module
 //!
-//! Measures download and upload speeds by transferring data to/from test servers.
+//! Measures latency plus download and upload speeds by transferring data to/from test servers.
 
 use color_eyre::Result;
 use std::time::Instant;
@@ -10,6 +10,67 @@ use std::time::Instant;
 pub struct SpeedTestResult {
     pub download_mbps: f64,
     pub upload_mbps: f64,
+    /// Round-trip time of a tiny request; None if every probe failed
+    pub latency_ms: Option<f64>,
+}
+
+/// Units speeds are shown in; results are always measured and stored in Mbps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpeedUnits {
+    /// Megabits per second
+    #[default]
+    Mbps,
+    /// Megabytes per second (Mbps / 8)
+    MBps,
+}
+
+impl SpeedUnits {
+    pub const ALL: [SpeedUnits; 2] = [SpeedUnits::Mbps, SpeedUnits::MBps];
+
+    /// Name accepted by --speed-units
+    pub fn name(&self) -> &'static str {
+        match self {
+            SpeedUnits::Mbps => "Mbps",
+            SpeedUnits::MBps => "MBps",
+        }
+    }
+
+    /// Look up units by name or by the "megabits"/"megabytes" aliases. The
+    /// names differ only in case, so they are matched exactly.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim() {
+            alias if alias.eq_ignore_ascii_case("megabits") => Some(SpeedUnits::Mbps),
+            alias if alias.eq_ignore_ascii_case("megabytes") => Some(SpeedUnits::MBps),
+            name => Self::ALL.into_iter().find(|units| units.name() == name),
+        }
+    }
+
+    /// Convert a speed in Mbps to these units
+    pub fn convert(&self, mbps: f64) -> f64 {
+        match self {
+            SpeedUnits::Mbps => mbps,
+            SpeedUnits::MBps => mbps / 8.0,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SpeedUnits::Mbps => "Mbps",
+            SpeedUnits::MBps => "MB/s",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            SpeedUnits::Mbps => SpeedUnits::MBps,
+            SpeedUnits::MBps => SpeedUnits::Mbps,
+        }
+    }
+}
+
+/// Format a speed measured in Mbps, e.g. "48.0 Mbps" or "6.0 MB/s"
+pub fn format_speed(mbps: f64, units: SpeedUnits) -> String {
+    format!("{:.1} {}", units.convert(mbps), units.label())
 }
 
 /// Run a speed test and return download/upload speeds in Mbps
@@ -24,15 +85,51 @@ pub fn run_speed_test() -> Result<SpeedTestResult> {
 
 /// Internal blocking implementation of speed test
 fn run_speed_test_blocking() -> Result<SpeedTestResult> {
+    let latency = measure_latency()?;
     let download = measure_download_speed()?;
     let upload = measure_upload_speed()?;
 
     Ok(SpeedTestResult {
         download_mbps: download,
         upload_mbps: upload,
+        latency_ms: latency,
     })
 }
 
+/// Zero-byte download used for latency probes and the reachability check
+const LATENCY_URL: &str = "https://speed.cloudflare.com/__down?bytes=0";
+
+/// Number of timed latency probes (after one untimed warm-up request)
+const LATENCY_PROBES: usize = 5;
+
+/// Measure round-trip latency as the fastest of several zero-byte requests
+/// over a kept-alive connection, so DNS and TLS setup aren't counted
+fn measure_latency() -> Result<Option<f64>> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(std::time::Duration::from_secs(5))
+        .build()?;
+
+    let test_url = LATENCY_URL;
+
+    // Warm-up: opens the connection
+    if client.get(test_url).send().is_err() {
+        return Ok(None);
+    }
+
+    let mut best: Option<f64> = None;
+    for _ in 0..LATENCY_PROBES {
+        let start = Instant::now();
+        if let Ok(resp) = client.get(test_url).send()
+            && resp.status().is_success()
+        {
+            let ms = start.elapsed().as_secs_f64() * 1000.0;
+            best = Some(best.map_or(ms, |b: f64| b.min(ms)));
+        }
+    }
+
+    Ok(best)
+}
+
 /// Measure download speed for approximately 5 seconds
 fn measure_download_speed() -> Result<f64> {
     let client = reqwest::blocking::Client::builder()
@@ -115,6 +212,19 @@ fn measure_upload_speed() -> Result<f64> {
     Ok(mbps)
 }
 
+/// Whether the speed test server answers, with the round trip in milliseconds
+pub fn check_reachable() -> Result<f64> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(std::time::Duration::from_secs(5))
+        .build()?;
+    let start = Instant::now();
+    let response = client.get(LATENCY_URL).send()?;
+    if !response.status().is_success() {
+        return Err(color_eyre::eyre::eyre!("speed test server answered {}", response.status()));
+    }
+    Ok(start.elapsed().as_secs_f64() * 1000.0)
+}
+
 /// Run just the download portion of the speed test (in separate thread)
 pub fn measure_download_only() -> Result<f64> {
     let handle = std::thread::spawn(measure_download_speed);
@@ -135,4 +245,34 @@ pub fn measure_upload_only() -> Result<f64> {
 mod tests {
     // Speed tests require network access, so we skip them in unit tests
     // They can be tested manually
+    use super::*;
+
+    #[test]
+    fn test_convert() {
+        assert_eq!(SpeedUnits::Mbps.convert(100.0), 100.0);
+        assert_eq!(SpeedUnits::MBps.convert(100.0), 12.5);
+        assert_eq!(SpeedUnits::MBps.convert(0.0), 0.0);
+    }
+
+    #[test]
+    fn test_format_speed() {
+        assert_eq!(format_speed(48.0, SpeedUnits::Mbps), "48.0 Mbps");
+        assert_eq!(format_speed(48.0, SpeedUnits::MBps), "6.0 MB/s");
+        assert_eq!(format_speed(12.34, SpeedUnits::Mbps), "12.3 Mbps");
+        assert_eq!(format_speed(1.0, SpeedUnits::MBps), "0.1 MB/s");
+    }
+
+    #[test]
+    fn test_units_by_name() {
+        assert_eq!(SpeedUnits::from_name("Mbps"), Some(SpeedUnits::Mbps));
+        assert_eq!(SpeedUnits::from_name("MBps"), Some(SpeedUnits::MBps));
+        assert_eq!(SpeedUnits::from_name("megabytes"), Some(SpeedUnits::MBps));
+        assert_eq!(SpeedUnits::from_name("mbps"), None);
+    }
+
+    #[test]
+    fn test_cycle() {
+        assert_eq!(SpeedUnits::default().next(), SpeedUnits::MBps);
+        assert_eq!(SpeedUnits::MBps.next(), SpeedUnits::Mbps);
+    }
 }
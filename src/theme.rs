@@ -1,8 +1,47 @@
+use crate::network_map::PortRisk;
 use ratatui::style::{Color, Modifier, Style};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Global switch for `--no-color` / `NO_COLOR`
+static COLORS_DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turn colors on or off for every Theme style (modifiers like bold are kept)
+pub fn set_colors_enabled(enabled: bool) {
+    COLORS_DISABLED.store(!enabled, Ordering::SeqCst);
+}
+
+pub fn colors_enabled() -> bool {
+    !COLORS_DISABLED.load(Ordering::SeqCst)
+}
+
+/// Whether the `NO_COLOR` convention (https://no-color.org) asks for no color:
+/// the variable is set to anything but an empty string
+pub fn no_color_requested() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty())
+}
 
 pub struct Theme;
 
 impl Theme {
+    /// Drop foreground and background colors when colors are disabled
+    pub fn plain(style: Style) -> Style {
+        if colors_enabled() {
+            style
+        } else {
+            Style {
+                fg: None,
+                bg: None,
+                ..style
+            }
+        }
+    }
+
+    /// A plain foreground color, for one-off accents in components and
+    /// popups; dropped like every other Theme color under `--no-color`
+    pub fn fg(color: Color) -> Style {
+        Self::plain(Style::default().fg(color))
+    }
+
     /// Get color for score value
     pub fn score_color(score: u8) -> Color {
         match score {
@@ -15,13 +54,18 @@ impl Theme {
 
     /// Style for score display
     pub fn score_style(score: u8) -> Style {
-        Style::default()
-            .fg(Self::score_color(score))
-            .add_modifier(Modifier::BOLD)
+        Self::plain(
+            Style::default()
+                .fg(Self::score_color(score))
+                .add_modifier(Modifier::BOLD),
+        )
     }
 
-    /// Style for selected row
+    /// Style for selected row (reversed without colors, so it stays visible)
     pub fn selected_style() -> Style {
+        if !colors_enabled() {
+            return Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD);
+        }
         Style::default()
             .bg(Color::DarkGray)
             .add_modifier(Modifier::BOLD)
@@ -29,50 +73,58 @@ impl Theme {
 
     /// Style for header
     pub fn header_style() -> Style {
-        Style::default()
-            .fg(Color::Cyan)
-            .add_modifier(Modifier::BOLD)
+        Self::plain(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
     }
 
     /// Style for borders
     pub fn border_style() -> Style {
-        Style::default().fg(Color::Gray)
+        Self::plain(Style::default().fg(Color::Gray))
     }
 
     /// Style for title
     pub fn title_style() -> Style {
-        Style::default()
-            .fg(Color::Cyan)
-            .add_modifier(Modifier::BOLD)
+        Self::plain(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
     }
 
     /// Style for help text
     pub fn help_style() -> Style {
-        Style::default().fg(Color::DarkGray)
+        Self::plain(Style::default().fg(Color::DarkGray))
     }
 
     /// Style for auto mode indicator
     pub fn auto_mode_style() -> Style {
-        Style::default()
-            .fg(Color::Green)
-            .add_modifier(Modifier::BOLD)
+        Self::plain(
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        )
     }
 
     /// Style for manual mode indicator
     pub fn manual_mode_style() -> Style {
-        Style::default()
-            .fg(Color::Yellow)
-            .add_modifier(Modifier::BOLD)
+        Self::plain(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )
     }
 
     /// Style for security type: Open
     pub fn security_open_style() -> Style {
-        Style::default().fg(Color::Green)
+        Self::plain(Style::default().fg(Color::Green))
     }
 
     /// Style for security type: Secured
     pub fn security_secured_style() -> Style {
-        Style::default().fg(Color::Yellow)
+        Self::plain(Style::default().fg(Color::Yellow))
     }
 
     /// Style for signal bars
@@ -82,23 +134,83 @@ impl Theme {
             s if s >= -70 => Color::Yellow,
             _ => Color::Red,
         };
-        Style::default().fg(color)
+        Self::plain(Style::default().fg(color))
     }
 
     /// Style for connected network indicator
     pub fn connected_style() -> Style {
-        Style::default()
-            .fg(Color::Green)
-            .add_modifier(Modifier::BOLD)
+        Self::plain(
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        )
     }
 
     /// Style for known network indicator
     pub fn known_style() -> Style {
-        Style::default().fg(Color::DarkGray)
+        Self::plain(Style::default().fg(Color::DarkGray))
     }
 
     /// Style for status message
     pub fn status_style() -> Style {
-        Style::default().fg(Color::Cyan)
+        Self::plain(Style::default().fg(Color::Cyan))
+    }
+
+    /// Style for warnings that replace the normal view, like a too-small terminal
+    pub fn warning_style() -> Style {
+        Self::plain(Style::default().fg(Color::Yellow))
+    }
+
+    /// Style for devices seen for the first time in the last scan
+    pub fn new_device_style() -> Style {
+        Self::plain(
+            Style::default()
+                .fg(Color::LightRed)
+                .add_modifier(Modifier::BOLD),
+        )
+    }
+
+    /// Style for networks flagged as a possible evil twin (and devices with an IP conflict)
+    pub fn ssid_conflict_style() -> Style {
+        Self::plain(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+    }
+
+    /// Signal change since the last scan: green when stronger, red when weaker
+    pub fn signal_delta_style(delta: i32) -> Style {
+        match delta.signum() {
+            1 => Self::plain(Style::default().fg(Color::Green)),
+            -1 => Self::plain(Style::default().fg(Color::Red)),
+            _ => Self::plain(Style::default().fg(Color::DarkGray)),
+        }
+    }
+
+    /// Style for an open port by risk: red for risky, yellow for management
+    pub fn port_risk_style(risk: PortRisk) -> Style {
+        match risk {
+            PortRisk::Risky => Self::plain(Style::default().fg(Color::Red)),
+            PortRisk::Management => Self::plain(Style::default().fg(Color::Yellow)),
+            PortRisk::Neutral => Style::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_styles_without_color() {
+        set_colors_enabled(false);
+        let score = Theme::score_style(90);
+        let selected = Theme::selected_style();
+        let accent = Theme::fg(Color::Cyan);
+        set_colors_enabled(true);
+
+        assert_eq!(score, Style::default().add_modifier(Modifier::BOLD));
+        assert!(selected.fg.is_none() && selected.bg.is_none());
+        assert!(selected.add_modifier.contains(Modifier::REVERSED));
+        assert_eq!(accent, Style::default());
+        assert_eq!(Theme::fg(Color::Cyan).fg, Some(Color::Cyan));
+        assert_eq!(Theme::score_style(90).fg, Some(Color::Green));
     }
 }